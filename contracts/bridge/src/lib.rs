@@ -0,0 +1,488 @@
+//! Cross-Chain Bridge for Massa Blockchain
+//!
+//! Moves an MRC20 token between Massa and other chains with off-chain relayers.
+//! `lock` holds the caller's tokens in the bridge and emits an event that
+//! relayers pick up; `mintFromBridge` mints tokens on Massa for a transfer from
+//! another chain, once enough validators signed it.
+//!
+//! The bridge needs the token's `BRIDGE_MINTER` role to mint.
+//!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes.
+//!
+//! # Storage Keys
+//! - `OWNER`: Owner address as raw string bytes
+//! - `TOKEN`: Bridged token address as raw string bytes
+//! - `THRESHOLD`: Validator signatures required per mint, u32 as 4 bytes (little-endian)
+//! - `VALIDATOR_COUNT`: Number of validators, u32 as 4 bytes (little-endian)
+//! - `VALIDATOR{len}{publicKey}`: Single byte [u8], 1 while the public key is a validator, deleted on removal;
+//!   `len` is the key's byte length as u32 (little-endian)
+//! - `LOCK_COUNT`: Number of locks, u64 as 8 bytes (little-endian)
+//! - `PROCESSED{sourceTxId}`: Single byte [u8], 1 once the source transaction was minted
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args, U256};
+
+use mrc20_core::approvals;
+use mrc20_core::calls;
+use mrc20_core::errors::{self, OrFail};
+use mrc20_core::store::{self, Key};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const OWNER_KEY: &[u8] = b"OWNER";
+const TOKEN_KEY: &[u8] = b"TOKEN";
+const THRESHOLD_KEY: &[u8] = b"THRESHOLD";
+const VALIDATOR_COUNT_KEY: &[u8] = b"VALIDATOR_COUNT";
+const VALIDATOR_KEY_PREFIX: &[u8] = b"VALIDATOR";
+const LOCK_COUNT_KEY: &[u8] = b"LOCK_COUNT";
+const PROCESSED_KEY_PREFIX: &[u8] = b"PROCESSED";
+
+/// Type tag of the message signed by validators
+const BRIDGE_MINT_TYPE: &str = "BRIDGE_MINT";
+
+const LOCK_EVENT: &str = "BRIDGE_LOCK";
+const MINT_EVENT: &str = "BRIDGE_MINT";
+const ADD_VALIDATOR_EVENT: &str = "BRIDGE_ADD_VALIDATOR";
+const REMOVE_VALIDATOR_EVENT: &str = "BRIDGE_REMOVE_VALIDATOR";
+const THRESHOLD_EVENT: &str = "BRIDGE_THRESHOLD";
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+/// Build validator key: "VALIDATOR" + length-prefixed public key, so a key
+/// like "_COUNT" cannot land on `VALIDATOR_COUNT`.
+fn validator_key(public_key: &str) -> Vec<u8> {
+    Key::new(VALIDATOR_KEY_PREFIX).push(public_key).into_bytes()
+}
+
+/// Build processed key: "PROCESSED" + source transaction id
+fn processed_key(source_tx_id: &str) -> Vec<u8> {
+    let mut key = PROCESSED_KEY_PREFIX.to_vec();
+    key.extend_from_slice(source_tx_id.as_bytes());
+    key
+}
+
+fn read_u64(key: &[u8]) -> u64 {
+    store::read(key).unwrap_or(0)
+}
+
+fn read_u32(key: &[u8]) -> u32 {
+    store::read(key).unwrap_or(0)
+}
+
+fn read_flag(key: &[u8]) -> bool {
+    store::read(key).unwrap_or(false)
+}
+
+fn read_string(key: &[u8]) -> String {
+    let data = store::get_opt(key).or_fail(errors::INVALID_STATE, "Bridge is not initialized");
+    core::str::from_utf8(&data).ok().map(String::from).or_fail(errors::INVALID_STATE, "Corrupted address")
+}
+
+fn only_owner() {
+    let owner = read_string(OWNER_KEY);
+    let caller = context::caller();
+    ensure!(caller == owner, errors::UNAUTHORIZED, fields("caller={}", caller), "Caller is not the owner");
+}
+
+fn is_validator(public_key: &str) -> bool {
+    read_flag(&validator_key(public_key))
+}
+
+fn add_validator(public_key: &str) {
+    if public_key.is_empty() {
+        errors::invalid_argument("publicKey");
+    }
+    ensure!(
+        !is_validator(public_key),
+        errors::ALREADY_EXISTS,
+        fields("publicKey={}", public_key),
+        "Public key is already a validator"
+    );
+    storage::set(&validator_key(public_key), &[1u8]);
+    storage::set(VALIDATOR_COUNT_KEY, &(read_u32(VALIDATOR_COUNT_KEY) + 1).to_le_bytes());
+}
+
+fn set_threshold(threshold: u32) {
+    let validator_count = read_u32(VALIDATOR_COUNT_KEY);
+    ensure!(
+        approvals::is_valid_threshold(threshold, validator_count),
+        errors::INVALID_ARGUMENT,
+        fields("name=threshold,threshold={},validators={}", threshold, validator_count),
+        "threshold argument must be between 1 and the number of validators"
+    );
+    storage::set(THRESHOLD_KEY, &threshold.to_le_bytes());
+}
+
+/// Message signed by validators for `mintFromBridge`:
+/// Args("BRIDGE_MINT", bridge, recipient, amount, sourceTxId)
+fn mint_message(recipient: &str, amount: U256, source_tx_id: &str) -> Vec<u8> {
+    let mut message = Args::new();
+    message
+        .add_string(BRIDGE_MINT_TYPE)
+        .add_string(&context::callee())
+        .add_string(recipient)
+        .add_u256(amount)
+        .add_string(source_tx_id);
+    message.into_bytes()
+}
+
+/// Fail unless `signatures` holds valid signatures of `message` from at least `threshold` distinct validators.
+fn verify_signatures(message: &[u8], signatures: Vec<u8>) {
+    let mut signatures = Args::from_bytes(signatures);
+    let count = signatures.next_u32().expect_arg("signatures");
+
+    let mut signers: Vec<String> = Vec::new();
+    for _ in 0..count {
+        let public_key = signatures.next_string().expect_arg("signatures");
+        let signature = signatures.next_string().expect_arg("signatures");
+        ensure!(
+            is_validator(&public_key),
+            errors::INVALID_SIGNATURE,
+            fields("publicKey={}", public_key),
+            "Signer is not a validator"
+        );
+        ensure!(
+            !signers.contains(&public_key),
+            errors::INVALID_SIGNATURE,
+            fields("publicKey={}", public_key),
+            "Duplicate validator signature"
+        );
+        ensure!(
+            abi::signature_verify(message, &signature, &public_key),
+            errors::INVALID_SIGNATURE,
+            fields("publicKey={}", public_key),
+            "Invalid signature"
+        );
+        signers.push(public_key);
+    }
+
+    let threshold = read_u32(THRESHOLD_KEY);
+    ensure!(
+        signers.len() as u32 >= threshold,
+        errors::INVALID_SIGNATURE,
+        fields("needed={},have={}", threshold, signers.len()),
+        "Not enough validator signatures"
+    );
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize the bridge. The caller becomes the owner.
+///
+/// # Arguments
+/// - `token`: Bridged token address (string)
+/// - `validators`: Args(count: u32, then `count` public keys as strings) (bytes)
+/// - `threshold`: Validator signatures required per mint (u32)
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token = args.next_string().expect_arg("token");
+    let validators = args.next_bytes().expect_arg("validators");
+    let threshold = args.next_u32().expect_arg("threshold");
+    if token.is_empty() {
+        errors::invalid_argument("token");
+    }
+
+    let mut validators = Args::from_bytes(validators);
+    let count = validators.next_u32().expect_arg("validators");
+    for _ in 0..count {
+        add_validator(&validators.next_string().expect_arg("validators"));
+    }
+    set_threshold(threshold);
+
+    storage::set(OWNER_KEY, context::caller().as_bytes());
+    storage::set(TOKEN_KEY, token.as_bytes());
+
+    Vec::new()
+}
+
+// ============================================================================
+// Bridging
+// ============================================================================
+
+/// Lock tokens in the bridge for a transfer to another chain.
+///
+/// The caller must first approve the bridge for `amount` on the token.
+///
+/// # Arguments
+/// - `amount`: Amount to bridge (U256, non-zero)
+/// - `destinationChain`: Destination chain id (string)
+/// - `destinationAddress`: Recipient on the destination chain (string)
+///
+/// # Returns
+/// - Lock nonce (u64, 8 bytes LE)
+///
+/// # Events
+/// - `BRIDGE_LOCK:nonce=...,from=...,amount=...,destinationChain=...,destinationAddress=...`
+#[massa_export]
+pub fn lock(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let amount = args.next_u256().expect_arg("amount");
+    let destination_chain = args.next_string().expect_arg("destinationChain");
+    let destination_address = args.next_string().expect_arg("destinationAddress");
+    if amount == U256::ZERO {
+        errors::invalid_argument("amount");
+    }
+    if destination_chain.is_empty() {
+        errors::invalid_argument("destinationChain");
+    }
+    if destination_address.is_empty() {
+        errors::invalid_argument("destinationAddress");
+    }
+
+    let from = context::caller();
+    let nonce = read_u64(LOCK_COUNT_KEY);
+    storage::set(LOCK_COUNT_KEY, &(nonce + 1).to_le_bytes());
+
+    let mut transfer_args = Args::new();
+    transfer_args
+        .add_string(&from)
+        .add_string(&context::callee())
+        .add_u256(amount);
+    calls::call(&read_string(TOKEN_KEY), "transferFrom", transfer_args);
+
+    abi::generate_event(&alloc::format!(
+        "{}:nonce={},from={},amount={},destinationChain={},destinationAddress={}",
+        LOCK_EVENT,
+        nonce,
+        from,
+        amount,
+        destination_chain,
+        destination_address
+    ));
+
+    nonce.to_le_bytes().to_vec()
+}
+
+/// Mint tokens for a transfer from another chain. Anyone can relay it, once.
+///
+/// Each validator signs the Args-serialized message
+/// `("BRIDGE_MINT", bridgeAddress, recipient, amount, sourceTxId)`.
+///
+/// # Arguments
+/// - `recipient`: Recipient address (string)
+/// - `amount`: Amount to mint (U256)
+/// - `sourceTxId`: Transaction id on the source chain (string, non-empty)
+/// - `signatures`: Args(count: u32, then `count` (publicKey: string, signature: string) pairs) (bytes)
+///
+/// # Events
+/// - `BRIDGE_MINT:to=...,amount=...,sourceTxId=...`
+#[massa_export]
+pub fn mintFromBridge(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let recipient = args.next_string().expect_arg("recipient");
+    let amount = args.next_u256().expect_arg("amount");
+    let source_tx_id = args.next_string().expect_arg("sourceTxId");
+    let signatures = args.next_bytes().expect_arg("signatures");
+    if recipient.is_empty() {
+        errors::invalid_argument("recipient");
+    }
+    if source_tx_id.is_empty() {
+        errors::invalid_argument("sourceTxId");
+    }
+
+    let key = processed_key(&source_tx_id);
+    ensure!(
+        !read_flag(&key),
+        errors::ALREADY_USED,
+        fields("sourceTxId={}", source_tx_id),
+        "Source transaction already processed"
+    );
+    verify_signatures(&mint_message(&recipient, amount, &source_tx_id), signatures);
+
+    // Mark as processed before the call so it cannot be replayed from within it
+    storage::set(&key, &[1u8]);
+    let mut mint_args = Args::new();
+    mint_args.add_string(&recipient).add_u256(amount).add_string(&source_tx_id);
+    calls::call(&read_string(TOKEN_KEY), "bridgeMint", mint_args);
+
+    abi::generate_event(&alloc::format!(
+        "{}:to={},amount={},sourceTxId={}",
+        MINT_EVENT,
+        recipient,
+        amount,
+        source_tx_id
+    ));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Validators (owner only)
+// ============================================================================
+
+/// Add a validator (owner only).
+///
+/// # Arguments
+/// - `publicKey`: Validator public key (string)
+///
+/// # Events
+/// - `BRIDGE_ADD_VALIDATOR:publicKey`
+#[massa_export]
+pub fn addValidator(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let public_key = args.next_string().expect_arg("publicKey");
+    add_validator(&public_key);
+
+    abi::generate_event(&alloc::format!("{}:{}", ADD_VALIDATOR_EVENT, public_key));
+
+    Vec::new()
+}
+
+/// Remove a validator (owner only). The remaining validators must still reach the threshold.
+///
+/// # Arguments
+/// - `publicKey`: Validator public key (string)
+///
+/// # Events
+/// - `BRIDGE_REMOVE_VALIDATOR:publicKey`
+#[massa_export]
+pub fn removeValidator(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let public_key = args.next_string().expect_arg("publicKey");
+    ensure!(
+        is_validator(&public_key),
+        errors::NOT_FOUND,
+        fields("publicKey={}", public_key),
+        "Public key is not a validator"
+    );
+
+    let validator_count = read_u32(VALIDATOR_COUNT_KEY);
+    ensure!(
+        approvals::allows_removal(read_u32(THRESHOLD_KEY), validator_count),
+        errors::INVALID_STATE,
+        "Removing the validator would make the threshold unreachable"
+    );
+    storage::delete(&validator_key(&public_key));
+    storage::set(VALIDATOR_COUNT_KEY, &(validator_count - 1).to_le_bytes());
+
+    abi::generate_event(&alloc::format!("{}:{}", REMOVE_VALIDATOR_EVENT, public_key));
+
+    Vec::new()
+}
+
+/// Change the number of validator signatures required per mint (owner only).
+///
+/// # Arguments
+/// - `threshold`: Required signatures (u32)
+///
+/// # Events
+/// - `BRIDGE_THRESHOLD:threshold`
+#[massa_export]
+pub fn setThreshold(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let threshold = args.next_u32().expect_arg("threshold");
+    set_threshold(threshold);
+
+    abi::generate_event(&alloc::format!("{}:{}", THRESHOLD_EVENT, threshold));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Returns true (1) if the public key is a validator, false (0) otherwise.
+///
+/// # Arguments
+/// - `publicKey`: Public key to check (string)
+#[massa_export]
+pub fn isValidator(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let public_key = args.next_string().expect_arg("publicKey");
+
+    if is_validator(&public_key) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+/// Returns true (1) if the source transaction was minted, false (0) otherwise.
+///
+/// # Arguments
+/// - `sourceTxId`: Transaction id on the source chain (string)
+#[massa_export]
+pub fn isProcessed(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let source_tx_id = args.next_string().expect_arg("sourceTxId");
+
+    if read_flag(&processed_key(&source_tx_id)) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+/// Returns the number of validator signatures required per mint (u32, 4 bytes LE).
+#[massa_export]
+pub fn threshold(_binary_args: &[u8]) -> Vec<u8> {
+    read_u32(THRESHOLD_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the number of validators (u32, 4 bytes LE).
+#[massa_export]
+pub fn validatorCount(_binary_args: &[u8]) -> Vec<u8> {
+    read_u32(VALIDATOR_COUNT_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns a page of validators as Args(count: u32, then `count` public keys as strings).
+///
+/// Validators are listed from the datastore in key order, so pages stay
+/// stable until the validator set changes.
+///
+/// # Arguments
+/// - `offset`: Index of the first validator (u64)
+/// - `limit`: Maximum number of validators to return (u32)
+#[massa_export]
+pub fn validators(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let offset = args.next_u64().expect_arg("offset");
+    let limit = args.next_u32().expect_arg("limit");
+
+    let offset = usize::try_from(offset).unwrap_or(usize::MAX);
+    let page = store::segments_with_prefix(VALIDATOR_KEY_PREFIX, offset, limit as usize);
+
+    let mut result = Args::new();
+    result.add_u32(page.len() as u32);
+    for public_key in page {
+        result.add_string(&String::from_utf8(public_key).or_fail(errors::INVALID_STATE, "Corrupted public key"));
+    }
+    result.into_bytes()
+}
+
+/// Returns the number of locks, which is also the next lock nonce (u64, 8 bytes LE).
+#[massa_export]
+pub fn lockCount(_binary_args: &[u8]) -> Vec<u8> {
+    read_u64(LOCK_COUNT_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the bridged token address (raw string bytes).
+#[massa_export]
+pub fn token(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(TOKEN_KEY)
+}
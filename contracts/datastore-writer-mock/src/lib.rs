@@ -0,0 +1,82 @@
+//! Mock Datastore Writer
+//!
+//! Minimal contract that writes and inspects raw datastore entries of the
+//! address it runs at. The test suite runs it at a token's address to seed
+//! layouts older contract builds left behind, to check the keys a
+//! migration rewrote, to read stored bytes and keys exactly as they are, and
+//! to set up period-dependent state relative to the current period. Used by
+//! the test suite only.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{context, storage, Args};
+
+/// Write a raw datastore entry.
+///
+/// # Arguments
+/// - `key`: Entry key (bytes)
+/// - `value`: Entry value (bytes)
+#[massa_export]
+pub fn setEntry(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let key = args.next_bytes().expect("key argument is missing or invalid");
+    let value = args.next_bytes().expect("value argument is missing or invalid");
+
+    storage::set(&key, &value);
+
+    Vec::new()
+}
+
+/// Returns whether a raw datastore entry exists ([0] or [1]).
+///
+/// # Arguments
+/// - `key`: Entry key (bytes)
+#[massa_export]
+pub fn hasEntry(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let key = args.next_bytes().expect("key argument is missing or invalid");
+
+    vec![storage::has(&key) as u8]
+}
+
+/// Returns a raw datastore entry's value as stored. Panics if it does not exist.
+///
+/// # Arguments
+/// - `key`: Entry key (bytes)
+#[massa_export]
+pub fn getEntry(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let key = args.next_bytes().expect("key argument is missing or invalid");
+
+    assert!(storage::has(&key), "Entry does not exist");
+    storage::get(&key)
+}
+
+/// Returns the keys starting with `prefix` as Args(count: u32, then `count` keys as bytes).
+///
+/// # Arguments
+/// - `prefix`: Key prefix, empty for every key (bytes)
+#[massa_export]
+pub fn listKeys(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let prefix = args.next_bytes().expect("prefix argument is missing or invalid");
+
+    let keys = storage::get_keys(&prefix);
+    let mut result = Args::new();
+    result.add_u32(keys.len() as u32);
+    for key in &keys {
+        result.add_bytes(key);
+    }
+    result.into_bytes()
+}
+
+/// Returns the current period (u64, 8 bytes LE).
+#[massa_export]
+pub fn currentPeriod(_binary_args: &[u8]) -> Vec<u8> {
+    context::current_period().to_le_bytes().to_vec()
+}
@@ -4,148 +4,965 @@
 //! compatibility with the AssemblyScript reference implementation from massa-standards.
 //!
 //! # Compatibility
-//! - Storage format matches AS implementation exactly
+//! - Storage format of the AS keys (`NAME` to `ALLOWANCE`, `OWNER`) matches AS implementation exactly
 //! - Function signatures match AS implementation
-//! - Event names and formats match AS implementation
+//! - Event names match AS implementation; transfer, approval, mint and burn
+//!   events append `key=value` fields after the name (e.g. `TRANSFER SUCCESS:from=...,to=...,amount=...`)
 //! - Can be deployed using the same deployer as AS contracts
 //! - Uses U256 for all token amounts (256-bit integers)
 //!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, see `mrc20_core::errors`.
+//!
 //! # Storage Keys
+//! `BALANCE` and `ALLOWANCE` keep the AS layout: the addresses are appended as
+//! raw bytes. Every other `{segment}` is built with `mrc20_core::key::Key::new`,
+//! which writes the segment after its length (u32 LE).
+//!
+//! - `STORAGE_VERSION`: Storage layout version, u32 as 4 bytes (little-endian), missing on deployments predating versioning
+//! - `DECOMMISSIONED`: Single byte [u8], 1 once the contract is permanently disabled (the only key left)
 //! - `NAME`: Token name as raw bytes
 //! - `SYMBOL`: Token symbol as raw bytes
 //! - `DECIMALS`: Single byte [u8]
+//! - `TOKEN_URI`: Token metadata URI as raw bytes
+//! - `DESCRIPTION`: Token description as raw bytes
 //! - `TOTAL_SUPPLY`: u256 as 32 bytes (little-endian)
-//! - `BALANCE{address}`: Balance for address, value is u256
-//! - `ALLOWANCE{owner}{spender}`: Allowance, value is u256
+//! - `MAX_SUPPLY`: Optional supply cap, u256 as 32 bytes (little-endian)
+//! - `FLASH_FEE`: Flat flash loan fee, u256 as 32 bytes (little-endian)
+//! - `FLASH_LOAN_ACTIVE`: Single byte [u8], present while a flash loan's callback runs
+//! - `BALANCE{address}`: Balance for address, value is u256 (deleted when zero)
+//! - `ALLOWANCE{owner}{spender}`: Allowance, value is u256 (deleted when zero)
+//! - `HOLDER_COUNT`: Number of addresses with a non-zero balance, u64 as 8 bytes (little-endian)
+//! - `HOLDER_AT{index}`: Holder address at index (u64 LE) as raw string bytes
+//! - `HOLDER_INDEX{address}`: Position of the holder in the list, u64 as 8 bytes (little-endian)
+//! - `SPENDER_COUNT{owner}`: Number of spenders with an allowance from owner, u64 as 8 bytes (little-endian)
+//! - `SPENDER_AT{owner}{index}`: Spender address at index (u64 LE) as raw string bytes
+//! - `SPENDER_INDEX{owner}{spender}`: Position of the spender in the owner's list, u64 as 8 bytes (little-endian)
+//! - `SPENDING_LIMIT{owner}{spender}`: Recurring allowance, limit (u256 LE) + period length (u64 LE) + window start (u64 LE) + spent in window (u256 LE)
+//! - `ALLOWANCE_EXPIRY{owner}{spender}`: Period at which the allowance expires, u64 as 8 bytes (little-endian), missing if it never expires
 //! - `OWNER`: Owner address as raw string bytes
+//! - `PENDING_OWNER`: Proposed owner address as raw string bytes (empty once accepted)
+//! - `MULTISIG_THRESHOLD`: Confirmations required per proposal, u32 as 4 bytes (little-endian), 0 = single-owner mode
+//! - `MULTISIG_OWNER_COUNT`: Number of multisig owners, u32 as 4 bytes (little-endian)
+//! - `MULTISIG_OWNER{address}`: Single byte [u8], 1 while the address is a multisig owner
+//! - `MULTISIG_EPOCH`: Owner set version, u64 as 8 bytes (little-endian), bumped on every owner or threshold change
+//! - `MULTISIG_PROPOSAL_COUNT`: Number of proposals created, u64 as 8 bytes (little-endian)
+//! - `MULTISIG_PROPOSAL{id}`: Epoch (u64 LE) + confirmations (u32 LE) + executed (u8) + operation length (u32 LE) + operation + args
+//! - `MULTISIG_CONFIRMATION{id}{address}`: Single byte [u8], 1 once the owner confirmed the proposal
+//! - `PAUSED`: Single byte [u8], 1 while the token is paused
+//! - `FROZEN{address}`: Single byte [u8], 1 while the address is frozen
+//! - `ALLOWLIST_MODE`: Single byte [u8], 1 when transfers are restricted to allowlisted addresses
+//! - `ALLOWLISTED{address}`: Single byte [u8], 1 while the address is allowlisted
+//! - `MAX_TX_AMOUNT`: Largest transfer allowed, u256 as 32 bytes (little-endian), 0 = unlimited
+//! - `MAX_WALLET_BALANCE`: Largest balance a transfer may create, u256 as 32 bytes (little-endian), 0 = unlimited
+//! - `TRANSFER_COOLDOWN`: Periods between two transfers from the same sender, u64 as 8 bytes (little-endian)
+//! - `LAST_TRANSFER{address}`: Period of the sender's last transfer, u64 as 8 bytes (little-endian)
+//! - `LIMIT_EXEMPT{address}`: Single byte [u8], 1 while the address bypasses transfer limits
+//! - `ROLE{role}{address}`: Single byte [u8], 1 while the address holds the role
+//! - `NONCE{owner}`: Permit and relayed transfer nonce for owner, u64 as 8 bytes (little-endian)
+//! - `AUTHORIZATION{from}{nonce}`: Single byte [u8], 1 once the authorization nonce is used
+//! - `SNAPSHOT_ID`: Current snapshot id, u64 as 8 bytes (little-endian)
+//! - `VESTING{beneficiary}`: Vesting schedule, start/cliff/duration periods (3 x u64 LE) + amount/released (2 x u256 LE)
+//! - `LOCK{address}`: Token lock, amount (u256 LE) + until period (u64 LE)
+//! - `CHECKPOINT_COUNT{address}`: Number of balance checkpoints, u64 as 8 bytes (little-endian)
+//! - `CHECKPOINT{address}{index}`: Balance checkpoint, snapshot id (u64 LE) + u256 value
+//! - `SUPPLY_CHECKPOINT_COUNT`: Number of total supply checkpoints, u64 as 8 bytes (little-endian)
+//! - `SUPPLY_CHECKPOINT{index}`: Total supply checkpoint, snapshot id (u64 LE) + u256 value
+//! - `SUBSCRIPTION_COUNT`: Number of subscriptions created, u64 as 8 bytes (little-endian)
+//! - `SUBSCRIPTION{id}`: Subscription (u64 LE id), Args-serialized subscriber, payee, amount, interval, next period, payments, fee budget, active flag and deferred call id
+//! - `SUBSCRIPTION_FEE_RESERVE`: nanoMAS held for subscription deferred call fees, u64 as 8 bytes (little-endian)
+//! - `STREAM_COUNT`: Number of streams created, u64 as 8 bytes (little-endian)
+//! - `STREAM{id}`: Stream (u64 LE id), Args-serialized sender, recipient, total amount, start and end periods, withdrawn amount and cancelled flag
+//! - `STREAM_ESCROW`: Tokens the contract holds for open streams, u256 as 32 bytes (little-endian)
 
 #![no_std]
 
 extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
 
 use alloc::string::String;
 use alloc::vec::Vec;
 use massa_export::massa_export;
 use massa_sc_sdk::{abi, context, storage, Args, U256};
 
+use mrc20_core::callbacks;
+use mrc20_core::calls;
+use mrc20_core::errors::{self, OrFail};
+use mrc20_core::math;
+use mrc20_core::store::{self, StorageCell, StorageDoubleMap, StorageMap, StorageValue};
+use mrc20_core::token::{
+    self, approval_event, mint_event, transfer_event, Hooks, DECIMALS_KEY, NAME_KEY, SYMBOL_KEY,
+    TOTAL_SUPPLY_KEY,
+};
+
 // ============================================================================
 // Constants - Storage Keys (matching AS implementation exactly)
 // ============================================================================
 
 const VERSION: &[u8] = b"0.0.1";
-const NAME_KEY: &[u8] = b"NAME";
-const SYMBOL_KEY: &[u8] = b"SYMBOL";
-const DECIMALS_KEY: &[u8] = b"DECIMALS";
-const TOTAL_SUPPLY_KEY: &[u8] = b"TOTAL_SUPPLY";
-const BALANCE_KEY_PREFIX: &[u8] = b"BALANCE";
-const ALLOWANCE_KEY_PREFIX: &[u8] = b"ALLOWANCE";
+const STORAGE_VERSION_KEY: &[u8] = b"STORAGE_VERSION";
+const DECOMMISSIONED_KEY: &[u8] = b"DECOMMISSIONED";
+const TOKEN_URI_KEY: &[u8] = b"TOKEN_URI";
+const DESCRIPTION_KEY: &[u8] = b"DESCRIPTION";
+const MAX_SUPPLY_KEY: &[u8] = b"MAX_SUPPLY";
+const FLASH_FEE_KEY: &[u8] = b"FLASH_FEE";
+const FLASH_LOAN_ACTIVE_KEY: &[u8] = b"FLASH_LOAN_ACTIVE";
+const HOLDER_COUNT_KEY: &[u8] = b"HOLDER_COUNT";
+const HOLDER_AT_KEY_PREFIX: &[u8] = b"HOLDER_AT";
+const HOLDER_INDEX_KEY_PREFIX: &[u8] = b"HOLDER_INDEX";
+const SPENDER_COUNT_KEY_PREFIX: &[u8] = b"SPENDER_COUNT";
+const SPENDER_AT_KEY_PREFIX: &[u8] = b"SPENDER_AT";
+const SPENDER_INDEX_KEY_PREFIX: &[u8] = b"SPENDER_INDEX";
+const ALLOWANCE_EXPIRY_KEY_PREFIX: &[u8] = b"ALLOWANCE_EXPIRY";
+const SPENDING_LIMIT_KEY_PREFIX: &[u8] = b"SPENDING_LIMIT";
 const OWNER_KEY: &[u8] = b"OWNER";
+const PENDING_OWNER_KEY: &[u8] = b"PENDING_OWNER";
+const PAUSED_KEY: &[u8] = b"PAUSED";
+const MULTISIG_THRESHOLD_KEY: &[u8] = b"MULTISIG_THRESHOLD";
+const MULTISIG_OWNER_COUNT_KEY: &[u8] = b"MULTISIG_OWNER_COUNT";
+const MULTISIG_OWNER_KEY_PREFIX: &[u8] = b"MULTISIG_OWNER";
+const MULTISIG_EPOCH_KEY: &[u8] = b"MULTISIG_EPOCH";
+const MULTISIG_PROPOSAL_COUNT_KEY: &[u8] = b"MULTISIG_PROPOSAL_COUNT";
+const MULTISIG_PROPOSAL_KEY_PREFIX: &[u8] = b"MULTISIG_PROPOSAL";
+const MULTISIG_CONFIRMATION_KEY_PREFIX: &[u8] = b"MULTISIG_CONFIRMATION";
+const FROZEN_KEY_PREFIX: &[u8] = b"FROZEN";
+const ROLE_KEY_PREFIX: &[u8] = b"ROLE";
+const ALLOWLIST_MODE_KEY: &[u8] = b"ALLOWLIST_MODE";
+const ALLOWLISTED_KEY_PREFIX: &[u8] = b"ALLOWLISTED";
+const MAX_TX_AMOUNT_KEY: &[u8] = b"MAX_TX_AMOUNT";
+const MAX_WALLET_BALANCE_KEY: &[u8] = b"MAX_WALLET_BALANCE";
+const TRANSFER_COOLDOWN_KEY: &[u8] = b"TRANSFER_COOLDOWN";
+const LAST_TRANSFER_KEY_PREFIX: &[u8] = b"LAST_TRANSFER";
+const LIMIT_EXEMPT_KEY_PREFIX: &[u8] = b"LIMIT_EXEMPT";
+const NONCE_KEY_PREFIX: &[u8] = b"NONCE";
+const AUTHORIZATION_KEY_PREFIX: &[u8] = b"AUTHORIZATION";
+const SNAPSHOT_ID_KEY: &[u8] = b"SNAPSHOT_ID";
+const VESTING_KEY_PREFIX: &[u8] = b"VESTING";
+const LOCK_KEY_PREFIX: &[u8] = b"LOCK";
+const CHECKPOINT_COUNT_KEY_PREFIX: &[u8] = b"CHECKPOINT_COUNT";
+const CHECKPOINT_KEY_PREFIX: &[u8] = b"CHECKPOINT";
+const SUPPLY_CHECKPOINT_COUNT_KEY: &[u8] = b"SUPPLY_CHECKPOINT_COUNT";
+const SUPPLY_CHECKPOINT_KEY_PREFIX: &[u8] = b"SUPPLY_CHECKPOINT";
+const SUBSCRIPTION_COUNT_KEY: &[u8] = b"SUBSCRIPTION_COUNT";
+const SUBSCRIPTION_KEY_PREFIX: &[u8] = b"SUBSCRIPTION";
+const SUBSCRIPTION_FEE_RESERVE_KEY: &[u8] = b"SUBSCRIPTION_FEE_RESERVE";
+const STREAM_COUNT_KEY: &[u8] = b"STREAM_COUNT";
+const STREAM_KEY_PREFIX: &[u8] = b"STREAM";
+const STREAM_ESCROW_KEY: &[u8] = b"STREAM_ESCROW";
+
+// Typed fields over the keys above
+const STORED_VERSION: StorageCell<u32> = StorageCell::new(STORAGE_VERSION_KEY);
+const DECOMMISSIONED: StorageCell<bool> = StorageCell::new(DECOMMISSIONED_KEY);
+const MAX_SUPPLY: StorageCell<U256> = StorageCell::new(MAX_SUPPLY_KEY);
+const FLASH_FEE: StorageCell<U256> = StorageCell::new(FLASH_FEE_KEY);
+const FLASH_LOAN_ACTIVE: StorageCell<bool> = StorageCell::new(FLASH_LOAN_ACTIVE_KEY);
+const HOLDER_COUNT: StorageCell<u64> = StorageCell::new(HOLDER_COUNT_KEY);
+const HOLDER_AT: StorageMap<u64, String> = StorageMap::new(HOLDER_AT_KEY_PREFIX);
+const HOLDER_INDEX: StorageMap<str, u64> = StorageMap::new(HOLDER_INDEX_KEY_PREFIX);
+const SPENDER_COUNT: StorageMap<str, u64> = StorageMap::new(SPENDER_COUNT_KEY_PREFIX);
+const SPENDER_AT: StorageDoubleMap<str, u64, String> = StorageDoubleMap::new(SPENDER_AT_KEY_PREFIX);
+const SPENDER_INDEX: StorageDoubleMap<str, str, u64> = StorageDoubleMap::new(SPENDER_INDEX_KEY_PREFIX);
+const ALLOWANCE_EXPIRIES: StorageDoubleMap<str, str, u64> = StorageDoubleMap::new(ALLOWANCE_EXPIRY_KEY_PREFIX);
+const SPENDING_LIMITS: StorageDoubleMap<str, str, SpendingLimit> = StorageDoubleMap::new(SPENDING_LIMIT_KEY_PREFIX);
+const OWNER: StorageCell<String> = StorageCell::new(OWNER_KEY);
+const PENDING_OWNER: StorageCell<String> = StorageCell::new(PENDING_OWNER_KEY);
+const PAUSED: StorageCell<bool> = StorageCell::new(PAUSED_KEY);
+const MULTISIG_THRESHOLD: StorageCell<u32> = StorageCell::new(MULTISIG_THRESHOLD_KEY);
+const MULTISIG_OWNER_COUNT: StorageCell<u32> = StorageCell::new(MULTISIG_OWNER_COUNT_KEY);
+const MULTISIG_OWNERS: StorageMap<str, bool> = StorageMap::new(MULTISIG_OWNER_KEY_PREFIX);
+const MULTISIG_EPOCH: StorageCell<u64> = StorageCell::new(MULTISIG_EPOCH_KEY);
+const MULTISIG_PROPOSAL_COUNT: StorageCell<u64> = StorageCell::new(MULTISIG_PROPOSAL_COUNT_KEY);
+const PROPOSALS: StorageMap<u64, Proposal> = StorageMap::new(MULTISIG_PROPOSAL_KEY_PREFIX);
+const CONFIRMATIONS: StorageDoubleMap<u64, str, bool> = StorageDoubleMap::new(MULTISIG_CONFIRMATION_KEY_PREFIX);
+const FROZEN: StorageMap<str, bool> = StorageMap::new(FROZEN_KEY_PREFIX);
+const ROLE_GRANTS: StorageDoubleMap<str, str, bool> = StorageDoubleMap::new(ROLE_KEY_PREFIX);
+const ALLOWLIST_MODE: StorageCell<bool> = StorageCell::new(ALLOWLIST_MODE_KEY);
+const ALLOWLISTED: StorageMap<str, bool> = StorageMap::new(ALLOWLISTED_KEY_PREFIX);
+const MAX_TX_AMOUNT: StorageCell<U256> = StorageCell::new(MAX_TX_AMOUNT_KEY);
+const MAX_WALLET_BALANCE: StorageCell<U256> = StorageCell::new(MAX_WALLET_BALANCE_KEY);
+const TRANSFER_COOLDOWN: StorageCell<u64> = StorageCell::new(TRANSFER_COOLDOWN_KEY);
+const LAST_TRANSFERS: StorageMap<str, u64> = StorageMap::new(LAST_TRANSFER_KEY_PREFIX);
+const LIMIT_EXEMPT: StorageMap<str, bool> = StorageMap::new(LIMIT_EXEMPT_KEY_PREFIX);
+const NONCES: StorageMap<str, u64> = StorageMap::new(NONCE_KEY_PREFIX);
+const USED_AUTHORIZATIONS: StorageDoubleMap<str, str, bool> = StorageDoubleMap::new(AUTHORIZATION_KEY_PREFIX);
+const SNAPSHOT_ID: StorageCell<u64> = StorageCell::new(SNAPSHOT_ID_KEY);
+const VESTINGS: StorageMap<str, VestingSchedule> = StorageMap::new(VESTING_KEY_PREFIX);
+const LOCKS: StorageMap<str, TokenLock> = StorageMap::new(LOCK_KEY_PREFIX);
+const CHECKPOINT_COUNTS: StorageMap<str, u64> = StorageMap::new(CHECKPOINT_COUNT_KEY_PREFIX);
+const CHECKPOINTS: StorageDoubleMap<str, u64, Checkpoint> = StorageDoubleMap::new(CHECKPOINT_KEY_PREFIX);
+const SUPPLY_CHECKPOINT_COUNT: StorageCell<u64> = StorageCell::new(SUPPLY_CHECKPOINT_COUNT_KEY);
+const SUPPLY_CHECKPOINTS: StorageMap<u64, Checkpoint> = StorageMap::new(SUPPLY_CHECKPOINT_KEY_PREFIX);
+const SUBSCRIPTION_COUNT: StorageCell<u64> = StorageCell::new(SUBSCRIPTION_COUNT_KEY);
+const SUBSCRIPTIONS: StorageMap<u64, Subscription> = StorageMap::new(SUBSCRIPTION_KEY_PREFIX);
+const SUBSCRIPTION_FEE_RESERVE: StorageCell<u64> = StorageCell::new(SUBSCRIPTION_FEE_RESERVE_KEY);
+const STREAM_COUNT: StorageCell<u64> = StorageCell::new(STREAM_COUNT_KEY);
+const STREAMS: StorageMap<u64, Stream> = StorageMap::new(STREAM_KEY_PREFIX);
+const STREAM_ESCROW: StorageCell<U256> = StorageCell::new(STREAM_ESCROW_KEY);
+
+// Storage layout written by this build. Bump it and add a step to
+// `migrate_storage_step` whenever the layout changes.
+const STORAGE_VERSION: u32 = 1;
+
+// Optional extensions compiled into this contract, reported by `features` and `supportsInterface`
+const INTERFACES: [&str; 18] = [
+    "MRC20",
+    "metadata",
+    "mintable",
+    "burnable",
+    "capped",
+    "pausable",
+    "permit",
+    "transfer-with-authorization",
+    "transfer-and-call",
+    "flash-mint",
+    "snapshots",
+    "vesting",
+    "locks",
+    "access-control",
+    "allowlist",
+    "multisig",
+    "subscriptions",
+    "streams",
+];
+
+// Entrypoint the deferred calls of subscriptions run, and the gas they may use
+const PROCESS_SUBSCRIPTION_FUNCTION: &str = "processSubscription";
+const SUBSCRIPTION_MAX_GAS: u64 = 100_000_000;
+
+// Entrypoint called on the recipient by `transferAndCall`
+const TOKEN_RECEIVED_HOOK: &str = "onTokenReceived";
+
+// Entrypoint called on the borrower by `flashLoan`, and the value it must return
+const FLASH_LOAN_HOOK: &str = "onFlashLoan";
+const FLASH_LOAN_CALLBACK_SUCCESS: &[u8] = b"ERC3156FlashBorrower.onFlashLoan";
+
+// Domain tags prepended to every signed message
+const PERMIT_TYPE: &str = "PERMIT";
+const TRANSFER_AUTHORIZATION_TYPE: &str = "TRANSFER_WITH_AUTHORIZATION";
+const RELAYED_TRANSFER_TYPE: &str = "RELAYED_TRANSFER";
+
+// Roles
+const MINTER_ROLE: &str = "MINTER";
+const BURNER_ROLE: &str = "BURNER";
+const COMPLIANCE_ROLE: &str = "COMPLIANCE";
+const BRIDGE_MINTER_ROLE: &str = "BRIDGE_MINTER";
+const PAUSER_ROLE: &str = "PAUSER";
+const SNAPSHOT_ROLE: &str = "SNAPSHOT";
+const ROLES: [&str; 6] = [MINTER_ROLE, BURNER_ROLE, PAUSER_ROLE, COMPLIANCE_ROLE, BRIDGE_MINTER_ROLE, SNAPSHOT_ROLE];
 
 // Event names (matching AS implementation exactly)
-const TRANSFER_EVENT: &str = "TRANSFER SUCCESS";
-const APPROVAL_EVENT: &str = "APPROVAL SUCCESS";
-const MINT_EVENT: &str = "MINT SUCCESS";
-const BURN_EVENT: &str = "BURN_SUCCESS";
 const CHANGE_OWNER_EVENT: &str = "CHANGE_OWNER";
+const PROPOSE_OWNER_EVENT: &str = "PROPOSE_OWNER";
+const ACCEPT_OWNER_EVENT: &str = "ACCEPT_OWNER";
+const PAUSE_EVENT: &str = "PAUSED";
+const UNPAUSE_EVENT: &str = "UNPAUSED";
+const FREEZE_EVENT: &str = "FREEZE_ACCOUNT";
+const UNFREEZE_EVENT: &str = "UNFREEZE_ACCOUNT";
+const GRANT_ROLE_EVENT: &str = "GRANT_ROLE";
+const REVOKE_ROLE_EVENT: &str = "REVOKE_ROLE";
+const AUTHORIZATION_USED_EVENT: &str = "AUTHORIZATION_USED";
+const RELAYED_TRANSFER_EVENT: &str = "RELAYED_TRANSFER";
+const SNAPSHOT_EVENT: &str = "SNAPSHOT";
+const SET_TOKEN_URI_EVENT: &str = "SET_TOKEN_URI";
+const SPENDING_LIMIT_EVENT: &str = "SPENDING_LIMIT";
+const APPROVE_IF_CURRENT_EVENT: &str = "APPROVE_IF_CURRENT";
+const ALLOWLIST_ADD_EVENT: &str = "ALLOWLIST_ADD";
+const ALLOWLIST_REMOVE_EVENT: &str = "ALLOWLIST_REMOVE";
+const FLASH_LOAN_EVENT: &str = "FLASH_LOAN SUCCESS";
+const BRIDGE_MINT_EVENT: &str = "BRIDGE_MINT";
+const VESTING_CREATED_EVENT: &str = "VESTING_CREATED";
+const VESTING_RELEASED_EVENT: &str = "VESTING_RELEASED";
+const LOCK_EVENT: &str = "LOCK_TOKENS";
+const UNLOCK_EVENT: &str = "UNLOCK_TOKENS";
+const RESCUE_TOKEN_EVENT: &str = "RESCUE_TOKEN";
+const RESCUE_COINS_EVENT: &str = "RESCUE_COINS";
+const MIGRATE_EVENT: &str = "MIGRATE";
+const DECOMMISSION_EVENT: &str = "DECOMMISSIONED";
+const ADD_OWNER_EVENT: &str = "MULTISIG_ADD_OWNER";
+const REMOVE_OWNER_EVENT: &str = "MULTISIG_REMOVE_OWNER";
+const THRESHOLD_EVENT: &str = "MULTISIG_THRESHOLD";
+const PROPOSE_EVENT: &str = "MULTISIG_PROPOSE";
+const CONFIRM_EVENT: &str = "MULTISIG_CONFIRM";
+const EXECUTE_EVENT: &str = "MULTISIG_EXECUTE";
+const SUBSCRIPTION_CREATED_EVENT: &str = "SUBSCRIPTION_CREATED";
+const SUBSCRIPTION_PAYMENT_EVENT: &str = "SUBSCRIPTION_PAYMENT";
+const SUBSCRIPTION_CANCELLED_EVENT: &str = "SUBSCRIPTION_CANCELLED";
+const SUBSCRIPTION_LAPSED_EVENT: &str = "SUBSCRIPTION_LAPSED";
+const STREAM_CREATED_EVENT: &str = "STREAM_CREATED";
+const STREAM_WITHDRAW_EVENT: &str = "STREAM_WITHDRAW";
+const STREAM_CANCELLED_EVENT: &str = "STREAM_CANCELLED";
 
 // ============================================================================
-// Storage Key Builders
+// Return Value Builders
 // ============================================================================
 
-/// Build balance key: "BALANCE" + address
-fn balance_key(address: &str) -> Vec<u8> {
-    let mut key = BALANCE_KEY_PREFIX.to_vec();
-    key.extend_from_slice(address.as_bytes());
-    key
+/// Args(true), returned by state-changing calls so composing contracts can branch on it.
+fn success_result() -> Vec<u8> {
+    let mut ret = Args::new();
+    ret.add_bool(true);
+    ret.into_bytes()
 }
 
-/// Build allowance key: "ALLOWANCE" + owner + spender
-fn allowance_key(owner: &str, spender: &str) -> Vec<u8> {
-    let mut key = ALLOWANCE_KEY_PREFIX.to_vec();
-    key.extend_from_slice(owner.as_bytes());
-    key.extend_from_slice(spender.as_bytes());
-    key
+/// Args(true, allowance), returned by allowance mutations.
+fn allowance_result(allowance: U256) -> Vec<u8> {
+    let mut ret = Args::new();
+    ret.add_bool(true).add_u256(allowance);
+    ret.into_bytes()
 }
 
 // ============================================================================
 // Internal Storage Helpers
 // ============================================================================
 
-fn get_balance(address: &str) -> U256 {
-    let key = balance_key(address);
-    if !storage::has(&key) {
-        return U256::ZERO;
+/// Recurring allowance that refreshes every `period_length` Massa periods.
+struct SpendingLimit {
+    amount_per_period: U256,
+    period_length: u64,
+    window_start: u64,
+    spent: U256,
+}
+
+impl StorageValue for SpendingLimit {
+    fn to_storage(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(80);
+        data.extend_from_slice(&self.amount_per_period.to_le_bytes());
+        data.extend_from_slice(&self.period_length.to_le_bytes());
+        data.extend_from_slice(&self.window_start.to_le_bytes());
+        data.extend_from_slice(&self.spent.to_le_bytes());
+        data
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        if data.len() < 80 {
+            return None;
+        }
+        let mut amount_bytes = [0u8; 32];
+        amount_bytes.copy_from_slice(&data[..32]);
+        let mut length_bytes = [0u8; 8];
+        length_bytes.copy_from_slice(&data[32..40]);
+        let mut start_bytes = [0u8; 8];
+        start_bytes.copy_from_slice(&data[40..48]);
+        let mut spent_bytes = [0u8; 32];
+        spent_bytes.copy_from_slice(&data[48..80]);
+        Some(SpendingLimit {
+            amount_per_period: U256::from_le_bytes(amount_bytes),
+            period_length: u64::from_le_bytes(length_bytes),
+            window_start: u64::from_le_bytes(start_bytes),
+            spent: U256::from_le_bytes(spent_bytes),
+        })
+    }
+}
+
+impl SpendingLimit {
+    /// Move the window forward to the one containing `period`, resetting the spent amount.
+    fn roll_window(&mut self, period: u64) {
+        if period >= self.window_start.saturating_add(self.period_length) {
+            let elapsed_windows = (period - self.window_start) / self.period_length;
+            self.window_start += elapsed_windows * self.period_length;
+            self.spent = U256::ZERO;
+        }
+    }
+
+    fn remaining(&self) -> U256 {
+        self.amount_per_period.saturating_sub(self.spent)
+    }
+}
+
+/// An admin operation waiting for multisig confirmations.
+struct Proposal {
+    epoch: u64,
+    confirmations: u32,
+    executed: bool,
+    operation: String,
+    args: Vec<u8>,
+}
+
+impl StorageValue for Proposal {
+    fn to_storage(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(17 + self.operation.len() + self.args.len());
+        data.extend_from_slice(&self.epoch.to_le_bytes());
+        data.extend_from_slice(&self.confirmations.to_le_bytes());
+        data.push(self.executed as u8);
+        data.extend_from_slice(&(self.operation.len() as u32).to_le_bytes());
+        data.extend_from_slice(self.operation.as_bytes());
+        data.extend_from_slice(&self.args);
+        data
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        if data.len() < 17 {
+            return None;
+        }
+        let mut epoch_bytes = [0u8; 8];
+        epoch_bytes.copy_from_slice(&data[..8]);
+        let mut confirmations_bytes = [0u8; 4];
+        confirmations_bytes.copy_from_slice(&data[8..12]);
+        let mut operation_len_bytes = [0u8; 4];
+        operation_len_bytes.copy_from_slice(&data[13..17]);
+        let operation_end = 17 + u32::from_le_bytes(operation_len_bytes) as usize;
+        if data.len() < operation_end {
+            return None;
+        }
+        let operation = core::str::from_utf8(&data[17..operation_end]).ok()?;
+        Some(Proposal {
+            epoch: u64::from_le_bytes(epoch_bytes),
+            confirmations: u32::from_le_bytes(confirmations_bytes),
+            executed: data[12] == 1,
+            operation: String::from(operation),
+            args: data[operation_end..].to_vec(),
+        })
+    }
+}
+
+/// Recurring payment pulled from the subscriber by self-rescheduling deferred calls.
+struct Subscription {
+    subscriber: String,
+    payee: String,
+    amount_per_interval: U256,
+    interval_periods: u64,
+    next_period: u64,
+    payments: u64,
+    fee_budget: u64,
+    active: bool,
+    call_id: String,
+}
+
+impl StorageValue for Subscription {
+    fn to_storage(&self) -> Vec<u8> {
+        let mut data = Args::new();
+        data.add_string(&self.subscriber)
+            .add_string(&self.payee)
+            .add_u256(self.amount_per_interval)
+            .add_u64(self.interval_periods)
+            .add_u64(self.next_period)
+            .add_u64(self.payments)
+            .add_u64(self.fee_budget)
+            .add_bool(self.active)
+            .add_string(&self.call_id);
+        data.into_bytes()
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        let mut data = Args::from_bytes(data.to_vec());
+        Some(Subscription {
+            subscriber: data.next_string().ok()?,
+            payee: data.next_string().ok()?,
+            amount_per_interval: data.next_u256().ok()?,
+            interval_periods: data.next_u64().ok()?,
+            next_period: data.next_u64().ok()?,
+            payments: data.next_u64().ok()?,
+            fee_budget: data.next_u64().ok()?,
+            active: data.next_bool().ok()?,
+            call_id: data.next_string().ok()?,
+        })
+    }
+}
+
+/// Tokens escrowed by the contract and paid out linearly between two periods.
+struct Stream {
+    sender: String,
+    recipient: String,
+    total_amount: U256,
+    start_period: u64,
+    end_period: u64,
+    withdrawn: U256,
+    cancelled: bool,
+}
+
+impl StorageValue for Stream {
+    fn to_storage(&self) -> Vec<u8> {
+        let mut data = Args::new();
+        data.add_string(&self.sender)
+            .add_string(&self.recipient)
+            .add_u256(self.total_amount)
+            .add_u64(self.start_period)
+            .add_u64(self.end_period)
+            .add_u256(self.withdrawn)
+            .add_bool(self.cancelled);
+        data.into_bytes()
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        let mut data = Args::from_bytes(data.to_vec());
+        Some(Stream {
+            sender: data.next_string().ok()?,
+            recipient: data.next_string().ok()?,
+            total_amount: data.next_u256().ok()?,
+            start_period: data.next_u64().ok()?,
+            end_period: data.next_u64().ok()?,
+            withdrawn: data.next_u256().ok()?,
+            cancelled: data.next_bool().ok()?,
+        })
+    }
+}
+
+impl Stream {
+    fn streamed_amount(&self, period: u64) -> U256 {
+        if period <= self.start_period {
+            U256::ZERO
+        } else if period >= self.end_period {
+            self.total_amount
+        } else {
+            mul_div_u64(self.total_amount, period - self.start_period, self.end_period - self.start_period)
+        }
+    }
+
+    /// Streamed tokens the recipient has not withdrawn yet, zero once cancelled.
+    fn withdrawable_amount(&self, period: u64) -> U256 {
+        if self.cancelled {
+            return U256::ZERO;
+        }
+        self.streamed_amount(period).saturating_sub(self.withdrawn)
+    }
+}
+
+fn get_spending_limit(owner: &str, spender: &str) -> Option<SpendingLimit> {
+    SPENDING_LIMITS.get(owner, spender).filter(|limit| limit.amount_per_period > U256::ZERO)
+}
+
+fn set_spending_limit(owner: &str, spender: &str, limit: &SpendingLimit) {
+    SPENDING_LIMITS.set(owner, spender, limit);
+}
+
+/// Consume `amount` of what `spender` may spend from `owner`.
+///
+/// A recurring spending limit takes precedence over the one-shot allowance.
+fn spend_allowance(owner: &str, spender: &str, amount: U256, operation: &str) {
+    if let Some(mut limit) = get_spending_limit(owner, spender) {
+        limit.roll_window(context::current_period());
+        ensure!(
+            amount <= limit.remaining(),
+            errors::SPENDING_LIMIT_EXCEEDED,
+            fields("needed={},have={}", amount, limit.remaining()),
+            "{} failed: spending limit exceeded",
+            operation
+        );
+        limit.spent = limit.spent.checked_add(amount).or_fail(errors::OVERFLOW, "Spending limit overflow");
+        set_spending_limit(owner, spender, &limit);
+        return;
+    }
+
+    let spender_allowance = get_allowance(owner, spender);
+    ensure!(
+        spender_allowance >= amount,
+        errors::INSUFFICIENT_ALLOWANCE,
+        fields("needed={},have={}", amount, spender_allowance),
+        "{} failed: insufficient allowance",
+        operation
+    );
+    let new_allowance = spender_allowance.checked_sub(amount).or_fail(errors::OVERFLOW, "Allowance underflow");
+    set_allowance(owner, spender, new_allowance);
+}
+
+/// Linear vesting schedule, in Massa periods.
+struct VestingSchedule {
+    start: u64,
+    cliff: u64,
+    duration: u64,
+    amount: U256,
+    released: U256,
+}
+
+impl StorageValue for VestingSchedule {
+    fn to_storage(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(88);
+        data.extend_from_slice(&self.start.to_le_bytes());
+        data.extend_from_slice(&self.cliff.to_le_bytes());
+        data.extend_from_slice(&self.duration.to_le_bytes());
+        data.extend_from_slice(&self.amount.to_le_bytes());
+        data.extend_from_slice(&self.released.to_le_bytes());
+        data
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        if data.len() < 88 {
+            return None;
+        }
+        let read_u64 = |offset: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data[offset..offset + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        let read_u256 = |offset: usize| {
+            let mut bytes = [0u8; 32];
+            bytes.copy_from_slice(&data[offset..offset + 32]);
+            U256::from_le_bytes(bytes)
+        };
+        Some(VestingSchedule {
+            start: read_u64(0),
+            cliff: read_u64(8),
+            duration: read_u64(16),
+            amount: read_u256(24),
+            released: read_u256(56),
+        })
     }
-    let data = storage::get(&key);
-    if data.len() >= 32 {
-        let mut bytes = [0u8; 32];
-        bytes.copy_from_slice(&data[..32]);
-        U256::from_le_bytes(bytes)
+}
+
+impl VestingSchedule {
+    /// Amount vested at `period`: nothing before the cliff, then linear until `start + duration`.
+    fn vested_amount(&self, period: u64) -> U256 {
+        if period < self.start.saturating_add(self.cliff) {
+            U256::ZERO
+        } else if period >= self.start.saturating_add(self.duration) {
+            self.amount
+        } else {
+            mul_div_u64(self.amount, period - self.start, self.duration)
+        }
+    }
+
+    fn releasable_amount(&self, period: u64) -> U256 {
+        self.vested_amount(period).saturating_sub(self.released)
+    }
+
+    /// Granted tokens that are not released yet and cannot be moved.
+    fn locked_amount(&self) -> U256 {
+        self.amount.saturating_sub(self.released)
+    }
+}
+
+/// Compute `value * numerator / denominator` (rounded down) for `numerator <= denominator`.
+fn mul_div_u64(value: U256, numerator: u64, denominator: u64) -> U256 {
+    ensure!(numerator <= denominator, errors::INVALID_STATE, "Ratio above one");
+    math::mul_div(value, U256::from(numerator), U256::from(denominator))
+        .or_fail(errors::INVALID_STATE, "Division by zero")
+}
+
+fn get_vesting(beneficiary: &str) -> Option<VestingSchedule> {
+    VESTINGS.get(beneficiary)
+}
+
+fn set_vesting(beneficiary: &str, schedule: &VestingSchedule) {
+    VESTINGS.set(beneficiary, schedule);
+}
+
+/// Tokens an account cannot move before `until_period`.
+struct TokenLock {
+    amount: U256,
+    until_period: u64,
+}
+
+impl StorageValue for TokenLock {
+    fn to_storage(&self) -> Vec<u8> {
+        let mut data = self.amount.to_le_bytes().to_vec();
+        data.extend_from_slice(&self.until_period.to_le_bytes());
+        data
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        if data.len() < 40 {
+            return None;
+        }
+        let mut amount_bytes = [0u8; 32];
+        amount_bytes.copy_from_slice(&data[..32]);
+        let mut until_bytes = [0u8; 8];
+        until_bytes.copy_from_slice(&data[32..40]);
+        Some(TokenLock {
+            amount: U256::from_le_bytes(amount_bytes),
+            until_period: u64::from_le_bytes(until_bytes),
+        })
+    }
+}
+
+/// The token lock of `address`, an empty one if it never had any.
+fn get_lock(address: &str) -> TokenLock {
+    LOCKS.get(address).unwrap_or(TokenLock {
+        amount: U256::ZERO,
+        until_period: 0,
+    })
+}
+
+fn set_lock(address: &str, lock: &TokenLock) {
+    LOCKS.set(address, lock);
+}
+
+/// Amount locked by `lockTokens` that is still active at the current period.
+fn active_lock(address: &str) -> U256 {
+    let lock = get_lock(address);
+    if context::current_period() < lock.until_period {
+        lock.amount
     } else {
         U256::ZERO
     }
 }
 
+/// Part of the balance of `address` that cannot be transferred or burned.
+fn locked_balance(address: &str) -> U256 {
+    let vesting_locked = match get_vesting(address) {
+        Some(schedule) => schedule.locked_amount(),
+        None => U256::ZERO,
+    };
+    vesting_locked.saturating_add(active_lock(address))
+}
+
+/// The token's rules and bookkeeping around the shared `token` ledger.
+struct Erc20;
+
+impl Hooks for Erc20 {
+    fn before_transfer(&self, from: &str, to: &str) {
+        when_not_decommissioned();
+        when_not_frozen(from);
+        when_not_frozen(to);
+        when_allowlisted(from);
+        when_allowlisted(to);
+    }
+
+    fn check_transfer(&self, from: &str, to: &str, amount: U256, new_from_balance: U256, new_to_balance: U256) {
+        ensure!(new_from_balance >= locked_balance(from), errors::TOKENS_LOCKED, "Transfer failed: tokens are locked");
+        enforce_transfer_limits(from, to, amount, new_to_balance);
+    }
+
+    fn check_mint(&self, _to: &str, new_supply: U256) {
+        if let Some(cap) = get_cap() {
+            ensure!(
+                new_supply <= cap,
+                errors::CAP_EXCEEDED,
+                fields("needed={},max={}", new_supply, cap),
+                "Requested mint amount exceeds the supply cap"
+            );
+        }
+    }
+
+    fn check_burn(&self, from: &str, new_balance: U256) {
+        ensure!(new_balance >= locked_balance(from), errors::TOKENS_LOCKED, "Burn failed: tokens are locked");
+    }
+
+    /// Accounts enter the holder list when their balance becomes non-zero and leave it when it drops to zero.
+    fn balance_changed(&self, address: &str, previous: U256, new: U256) {
+        Checkpoints::Balance(address).update(previous);
+        if previous == U256::ZERO && new > U256::ZERO {
+            AddressList::Holders.add(address);
+        } else if previous > U256::ZERO && new == U256::ZERO {
+            AddressList::Holders.remove(address);
+        }
+    }
+
+    fn supply_changed(&self, previous: U256, _new: U256) {
+        when_not_decommissioned();
+        Checkpoints::Supply.update(previous);
+    }
+
+    /// The spender index follows the allowance key, which only exists while non-zero.
+    fn allowance_changed(&self, owner: &str, spender: &str, previous: U256, new: U256) {
+        if previous == U256::ZERO && new > U256::ZERO {
+            AddressList::Spenders(owner).add(spender);
+        } else if previous > U256::ZERO && new == U256::ZERO {
+            AddressList::Spenders(owner).remove(spender);
+        }
+    }
+}
+
+fn get_balance(address: &str) -> U256 {
+    token::balance_of(address)
+}
+
 fn set_balance(address: &str, amount: U256) {
-    let key = balance_key(address);
-    storage::set(&key, &amount.to_le_bytes());
+    token::set_balance(&Erc20, address, amount);
+}
+
+/// An enumerable set of addresses stored as a count, an index -> address
+/// mapping and an address -> index mapping.
+enum AddressList<'a> {
+    /// Addresses with a non-zero balance.
+    Holders,
+    /// Spenders holding an allowance key from the owner.
+    Spenders(&'a str),
+}
+
+impl AddressList<'_> {
+    fn len(&self) -> u64 {
+        match self {
+            AddressList::Holders => HOLDER_COUNT.get_or(0),
+            AddressList::Spenders(owner) => SPENDER_COUNT.get_or(owner, 0),
+        }
+    }
+
+    fn set_len(&self, count: u64) {
+        match self {
+            AddressList::Holders => HOLDER_COUNT.set(&count),
+            AddressList::Spenders(owner) => SPENDER_COUNT.set(owner, &count),
+        }
+    }
+
+    fn at(&self, index: u64) -> String {
+        let address = match self {
+            AddressList::Holders => HOLDER_AT.get(&index),
+            AddressList::Spenders(owner) => SPENDER_AT.get(owner, &index),
+        };
+        address.or_fail(errors::INVALID_STATE, "Listed address is missing or not valid UTF-8")
+    }
+
+    fn set_at(&self, index: u64, address: &str) {
+        let address = String::from(address);
+        match self {
+            AddressList::Holders => HOLDER_AT.set(&index, &address),
+            AddressList::Spenders(owner) => SPENDER_AT.set(owner, &index, &address),
+        }
+    }
+
+    fn remove_at(&self, index: u64) {
+        match self {
+            AddressList::Holders => HOLDER_AT.remove(&index),
+            AddressList::Spenders(owner) => SPENDER_AT.remove(owner, &index),
+        }
+    }
+
+    fn index_of(&self, address: &str) -> Option<u64> {
+        match self {
+            AddressList::Holders => HOLDER_INDEX.get(address),
+            AddressList::Spenders(owner) => SPENDER_INDEX.get(owner, address),
+        }
+    }
+
+    fn set_index(&self, address: &str, index: u64) {
+        match self {
+            AddressList::Holders => HOLDER_INDEX.set(address, &index),
+            AddressList::Spenders(owner) => SPENDER_INDEX.set(owner, address, &index),
+        }
+    }
+
+    fn remove_index(&self, address: &str) {
+        match self {
+            AddressList::Holders => HOLDER_INDEX.remove(address),
+            AddressList::Spenders(owner) => SPENDER_INDEX.remove(owner, address),
+        }
+    }
+
+    fn contains(&self, address: &str) -> bool {
+        match self {
+            AddressList::Holders => HOLDER_INDEX.contains(address),
+            AddressList::Spenders(owner) => SPENDER_INDEX.contains(owner, address),
+        }
+    }
+
+    fn add(&self, address: &str) {
+        let count = self.len();
+        self.set_at(count, address);
+        self.set_index(address, count);
+        self.set_len(count + 1);
+    }
+
+    /// Swap-remove: the last address takes the removed address's slot.
+    /// Does nothing if the address is not listed.
+    fn remove(&self, address: &str) {
+        let Some(index) = self.index_of(address) else {
+            return;
+        };
+        let last = self.len().checked_sub(1).or_fail(errors::INVALID_STATE, "Address list count is out of sync");
+        if index != last {
+            let moved = self.at(last);
+            self.set_at(index, &moved);
+            self.set_index(&moved, index);
+        }
+        self.remove_at(last);
+        self.remove_index(address);
+        self.set_len(last);
+    }
+
+    /// Returns the indexes of the page starting at `offset`, clamped to the list.
+    fn page(&self, offset: u64, limit: u32) -> core::ops::Range<u64> {
+        let end = offset.saturating_add(limit as u64).min(self.len());
+        offset.min(end)..end
+    }
 }
 
 fn get_allowance(owner: &str, spender: &str) -> U256 {
-    let key = allowance_key(owner, spender);
-    if !storage::has(&key) {
+    if is_allowance_expired(owner, spender) {
         return U256::ZERO;
     }
-    let data = storage::get(&key);
-    if data.len() >= 32 {
-        let mut bytes = [0u8; 32];
-        bytes.copy_from_slice(&data[..32]);
-        U256::from_le_bytes(bytes)
-    } else {
-        U256::ZERO
-    }
+    token::allowance(owner, spender)
 }
 
+/// Spending or decreasing an allowance keeps its expiry. Writing over an expired
+/// allowance or reaching zero drops it, so a later approval does not inherit it.
 fn set_allowance(owner: &str, spender: &str, amount: U256) {
-    let key = allowance_key(owner, spender);
-    storage::set(&key, &amount.to_le_bytes());
+    when_not_decommissioned();
+    if amount == U256::ZERO || is_allowance_expired(owner, spender) {
+        set_allowance_expiry(owner, spender, 0);
+    }
+    token::set_allowance(&Erc20, owner, spender, amount);
 }
 
-fn get_total_supply() -> U256 {
-    if !storage::has(TOTAL_SUPPLY_KEY) {
-        return U256::ZERO;
+/// Replace the allowance and its expiry period, 0 for an allowance that never expires.
+fn approve(owner: &str, spender: &str, amount: U256, expiry_period: u64) {
+    set_allowance(owner, spender, amount);
+    if amount > U256::ZERO {
+        set_allowance_expiry(owner, spender, expiry_period);
     }
-    let data = storage::get(TOTAL_SUPPLY_KEY);
-    if data.len() >= 32 {
-        let mut bytes = [0u8; 32];
-        bytes.copy_from_slice(&data[..32]);
-        U256::from_le_bytes(bytes)
+}
+
+fn get_allowance_expiry(owner: &str, spender: &str) -> u64 {
+    ALLOWANCE_EXPIRIES.get_or(owner, spender, 0)
+}
+
+fn set_allowance_expiry(owner: &str, spender: &str, expiry_period: u64) {
+    if expiry_period == 0 {
+        ALLOWANCE_EXPIRIES.remove(owner, spender);
     } else {
-        U256::ZERO
+        ALLOWANCE_EXPIRIES.set(owner, spender, &expiry_period);
     }
 }
 
+fn is_allowance_expired(owner: &str, spender: &str) -> bool {
+    let expiry_period = get_allowance_expiry(owner, spender);
+    expiry_period != 0 && context::current_period() >= expiry_period
+}
+
+fn get_total_supply() -> U256 {
+    token::total_supply()
+}
+
 fn set_total_supply(amount: U256) {
-    storage::set(TOTAL_SUPPLY_KEY, &amount.to_le_bytes());
+    token::set_total_supply(&Erc20, amount);
+}
+
+fn get_flash_fee() -> U256 {
+    FLASH_FEE.get_or(U256::ZERO)
+}
+
+fn set_flash_fee(fee: U256) {
+    FLASH_FEE.set(&fee);
+}
+
+/// Largest amount that can be minted without exceeding the cap or overflowing.
+fn max_flash_loan() -> U256 {
+    callbacks::max_flash_loan(get_cap(), get_total_supply())
+}
+
+fn get_cap() -> Option<U256> {
+    MAX_SUPPLY.get()
+}
+
+fn set_cap(cap: U256) {
+    ensure!(cap >= get_total_supply(), errors::INVALID_ARGUMENT, "Cap is lower than the total supply");
+    MAX_SUPPLY.set(&cap);
 }
 
 fn get_owner() -> Option<String> {
-    if !storage::has(OWNER_KEY) {
-        return None;
-    }
-    let data = storage::get(OWNER_KEY);
-    core::str::from_utf8(&data).ok().map(String::from)
+    OWNER.get()
 }
 
 fn set_owner_internal(owner: &str) {
-    storage::set(OWNER_KEY, owner.as_bytes());
+    OWNER.set(&String::from(owner));
+}
+
+/// An empty pending owner means none.
+fn get_pending_owner() -> Option<String> {
+    PENDING_OWNER.get().filter(|pending_owner| !pending_owner.is_empty())
+}
+
+fn set_pending_owner(pending_owner: &str) {
+    PENDING_OWNER.set(&String::from(pending_owner));
 }
 
 fn only_owner() {
-    let owner = get_owner();
-    assert!(owner.is_some(), "Owner is not set");
+    if is_multisig_mode() {
+        only_multisig_execution();
+        return;
+    }
+    let owner = get_owner().or_fail(errors::INVALID_STATE, "Owner is not set");
     let caller = context::caller();
-    assert!(caller == owner.unwrap(), "Caller is not the owner");
+    ensure!(caller == owner, errors::UNAUTHORIZED, fields("caller={}", caller), "Caller is not the owner");
 }
 
 fn is_owner_check(address: &str) -> bool {
@@ -155,414 +972,3464 @@ fn is_owner_check(address: &str) -> bool {
     }
 }
 
-// ============================================================================
-// Constructor
-// ============================================================================
+fn has_role(role: &str, address: &str) -> bool {
+    ROLE_GRANTS.get_or(role, address, false)
+}
 
-/// Constructor - Initialize the MRC20 token.
+fn set_role(role: &str, address: &str, granted: bool) {
+    ROLE_GRANTS.set(role, address, &granted);
+}
+
+/// Whether the caller acts as the owner: the owner itself, or in multisig mode
+/// the contract executing a confirmed proposal.
+fn is_owner_caller(caller: &str) -> bool {
+    if is_multisig_mode() {
+        caller == context::callee()
+    } else {
+        is_owner_check(caller)
+    }
+}
+
+/// Role holders act in their own right, multisig mode only gates the owner's
+/// actions. In multisig mode a confirmed proposal can also use the role.
+fn only_role(role: &str) {
+    let caller = context::caller();
+    ensure!(
+        has_role(role, &caller) || (is_multisig_mode() && is_owner_caller(&caller)),
+        errors::UNAUTHORIZED,
+        fields("caller={},role={}", caller, role),
+        "Caller does not have the {} role",
+        role
+    );
+}
+
+fn only_owner_or_role(role: &str) {
+    let caller = context::caller();
+    ensure!(
+        is_owner_caller(&caller) || has_role(role, &caller),
+        errors::UNAUTHORIZED,
+        fields("caller={},role={}", caller, role),
+        "Caller is neither the owner nor has the {} role",
+        role
+    );
+}
+
+fn get_threshold() -> u32 {
+    MULTISIG_THRESHOLD.get_or(0)
+}
+
+fn get_multisig_owner_count() -> u32 {
+    MULTISIG_OWNER_COUNT.get_or(0)
+}
+
+/// A non-zero threshold switches admin functions to N-of-M proposals.
+fn is_multisig_mode() -> bool {
+    get_threshold() > 0
+}
+
+fn is_multisig_owner(address: &str) -> bool {
+    MULTISIG_OWNERS.get_or(address, false)
+}
+
+fn only_multisig_owner() {
+    let caller = context::caller();
+    ensure!(is_multisig_owner(&caller), errors::UNAUTHORIZED, fields("caller={}", caller), "Caller is not a multisig owner");
+}
+
+/// In multisig mode, admin calls must come from the contract itself, executing a confirmed proposal.
+fn only_multisig_execution() {
+    ensure!(
+        context::caller() == context::callee(),
+        errors::UNAUTHORIZED,
+        "Admin functions require a confirmed multisig proposal"
+    );
+}
+
+/// Invalidate pending proposals after the owner set or threshold changed.
+fn bump_multisig_epoch() {
+    MULTISIG_EPOCH.set(&(get_multisig_epoch() + 1));
+}
+
+fn get_multisig_epoch() -> u64 {
+    MULTISIG_EPOCH.get_or(0)
+}
+
+fn get_proposal(id: u64) -> Option<Proposal> {
+    PROPOSALS.get(&id)
+}
+
+fn set_proposal(id: u64, proposal: &Proposal) {
+    PROPOSALS.set(&id, proposal);
+}
+
+fn get_subscription(id: u64) -> Option<Subscription> {
+    SUBSCRIPTIONS.get(&id)
+}
+
+fn set_subscription(id: u64, subscription: &Subscription) {
+    SUBSCRIPTIONS.set(&id, subscription);
+}
+
+/// nanoMAS held for subscription fees, which `rescueCoins` cannot touch.
+fn get_subscription_fee_reserve() -> u64 {
+    SUBSCRIPTION_FEE_RESERVE.get_or(0)
+}
+
+fn set_subscription_fee_reserve(reserve: u64) {
+    SUBSCRIPTION_FEE_RESERVE.set(&reserve);
+}
+
+/// Register the next payment as a deferred call on this contract, paid from the fee budget.
 ///
-/// # Arguments (Args serialized)
-/// - `name`: Token name (string)
-/// - `symbol`: Token symbol (string)
-/// - `decimals`: Token decimals (u8)
-/// - `totalSupply`: Initial supply as U256 (32 bytes)
+/// Returns false, leaving the subscription untouched, when the budget cannot cover the fee.
+fn schedule_subscription(id: u64, subscription: &mut Subscription) -> bool {
+    let mut call_args = Args::new();
+    call_args.add_u64(id);
+    let call_args = call_args.into_bytes();
+
+    let thread = context::current_thread();
+    let fee = abi::deferred_call_quote(subscription.next_period, thread, SUBSCRIPTION_MAX_GAS, call_args.len() as u64);
+    if fee > subscription.fee_budget {
+        return false;
+    }
+
+    subscription.call_id = abi::deferred_call_register(
+        &context::callee(),
+        PROCESS_SUBSCRIPTION_FUNCTION,
+        subscription.next_period,
+        thread,
+        SUBSCRIPTION_MAX_GAS,
+        &call_args,
+        0,
+    );
+    subscription.fee_budget -= fee;
+    set_subscription_fee_reserve(get_subscription_fee_reserve().saturating_sub(fee));
+    true
+}
+
+/// Deactivate a subscription and send its unused fee budget back to the subscriber.
+fn close_subscription(subscription: &mut Subscription) {
+    subscription.active = false;
+    if subscription.fee_budget > 0 {
+        set_subscription_fee_reserve(get_subscription_fee_reserve().saturating_sub(subscription.fee_budget));
+        abi::transfer_coins(&subscription.subscriber, subscription.fee_budget);
+        subscription.fee_budget = 0;
+    }
+}
+
+/// Make the due payment, then schedule the next one or end the subscription.
+fn pay_subscription(id: u64, subscription: &mut Subscription) {
+    move_balance(&subscription.subscriber, &subscription.payee, subscription.amount_per_interval);
+    abi::generate_event(&transfer_event(
+        &subscription.subscriber,
+        &subscription.payee,
+        subscription.amount_per_interval,
+    ));
+
+    subscription.payments += 1;
+    abi::generate_event(&alloc::format!("{}:id={},payment={}", SUBSCRIPTION_PAYMENT_EVENT, id, subscription.payments));
+
+    subscription.next_period = subscription
+        .next_period
+        .checked_add(subscription.interval_periods)
+        .or_fail(errors::OVERFLOW, "Subscription period overflow");
+    if !schedule_subscription(id, subscription) {
+        lapse_subscription(id, subscription, "fees");
+    }
+}
+
+fn lapse_subscription(id: u64, subscription: &mut Subscription, reason: &str) {
+    close_subscription(subscription);
+    abi::generate_event(&alloc::format!("{}:id={},reason={}", SUBSCRIPTION_LAPSED_EVENT, id, reason));
+}
+
+/// Why the due payment cannot be made, checking everything a transfer would
+/// fail on, so that the deferred call ends the subscription instead of failing.
+fn subscription_lapse_reason(subscription: &Subscription) -> Option<&'static str> {
+    let subscriber = subscription.subscriber.as_str();
+    let payee = subscription.payee.as_str();
+    let amount = subscription.amount_per_interval;
+
+    if is_paused() {
+        return Some("paused");
+    }
+    if is_frozen(subscriber) || is_frozen(payee) {
+        return Some("frozen");
+    }
+    if is_allowlist_mode() && !(is_allowlisted(subscriber) && is_allowlisted(payee)) {
+        return Some("allowlist");
+    }
+    if get_balance(subscriber).saturating_sub(locked_balance(subscriber)) < amount {
+        return Some("balance");
+    }
+    let new_payee_balance = get_balance(payee).saturating_add(amount);
+    if exceeded_transfer_limit(subscriber, payee, amount, new_payee_balance).is_some() {
+        return Some("limits");
+    }
+    None
+}
+
+/// Cancel the subscription's pending deferred call, if it has not run yet.
+fn cancel_subscription_call(subscription: &Subscription) {
+    if !subscription.call_id.is_empty() && abi::deferred_call_exists(&subscription.call_id) {
+        abi::deferred_call_cancel(&subscription.call_id);
+    }
+}
+
+fn get_stream(id: u64) -> Option<Stream> {
+    STREAMS.get(&id)
+}
+
+fn set_stream(id: u64, stream: &Stream) {
+    STREAMS.set(&id, stream);
+}
+
+/// Tokens the contract holds for open streams.
+fn get_stream_escrow() -> U256 {
+    STREAM_ESCROW.get_or(U256::ZERO)
+}
+
+/// Pay `amount` of escrowed stream tokens from the contract to `to`.
+fn release_stream_escrow(to: &str, amount: U256) {
+    let this = context::callee();
+    move_balance(&this, to, amount);
+    abi::generate_event(&transfer_event(&this, to, amount));
+    let escrow = get_stream_escrow().checked_sub(amount).or_fail(errors::OVERFLOW, "Stream escrow underflow");
+    STREAM_ESCROW.set(&escrow);
+}
+
+fn is_proposal_confirmed(id: u64, owner: &str) -> bool {
+    CONFIRMATIONS.get_or(&id, owner, false)
+}
+
+/// Record the caller's confirmation of a pending proposal from the current epoch.
+fn confirm_proposal(id: u64, proposal: &mut Proposal) {
+    let caller = context::caller();
+    ensure!(!proposal.executed, errors::ALREADY_USED, fields("id={}", id), "Proposal is already executed");
+    ensure!(
+        proposal.epoch == get_multisig_epoch(),
+        errors::EXPIRED,
+        fields("id={}", id),
+        "Proposal is stale: owners changed"
+    );
+    ensure!(
+        !is_proposal_confirmed(id, &caller),
+        errors::ALREADY_USED,
+        fields("id={}", id),
+        "Proposal is already confirmed by caller"
+    );
+
+    CONFIRMATIONS.set(&id, &caller, &true);
+    proposal.confirmations += 1;
+    set_proposal(id, proposal);
+
+    abi::generate_event(&alloc::format!("{}:id={},owner={}", CONFIRM_EVENT, id, caller));
+}
+
+fn assert_known_role(role: &str) {
+    ensure!(ROLES.contains(&role), errors::INVALID_ARGUMENT, fields("role={}", role), "Unknown role");
+}
+
+/// Move `amount` from `from` to `to`, enforcing freeze, allowlist, lock, limit and balance rules.
+fn move_balance(from: &str, to: &str, amount: U256) {
+    token::move_balance(&Erc20, from, to, amount);
+}
+
+fn get_nonce(owner: &str) -> u64 {
+    NONCES.get_or(owner, 0)
+}
+
+fn set_nonce(owner: &str, nonce: u64) {
+    NONCES.set(owner, &nonce);
+}
+
+/// Check that `signature` over `message` was produced by the key behind `address`.
+fn verify_signed_by(address: &str, public_key: &str, message: &[u8], signature: &str) {
+    ensure!(
+        abi::address_from_public_key(public_key) == address,
+        errors::INVALID_SIGNATURE,
+        "Public key does not match the signer address"
+    );
+    ensure!(
+        abi::signature_verify(message, signature, public_key),
+        errors::INVALID_SIGNATURE,
+        "Invalid signature"
+    );
+}
+
+/// Message signed by the owner for `permit`:
+/// Args("PERMIT", contract, owner, spender, amount, nonce, deadline)
+fn permit_message(owner: &str, spender: &str, amount: U256, nonce: u64, deadline: u64) -> Vec<u8> {
+    let mut message = Args::new();
+    message
+        .add_string(PERMIT_TYPE)
+        .add_string(&context::callee())
+        .add_string(owner)
+        .add_string(spender)
+        .add_u256(amount)
+        .add_u64(nonce)
+        .add_u64(deadline);
+    message.into_bytes()
+}
+
+fn is_authorization_used(from: &str, nonce: &str) -> bool {
+    USED_AUTHORIZATIONS.get_or(from, nonce, false)
+}
+
+fn set_authorization_used(from: &str, nonce: &str) {
+    USED_AUTHORIZATIONS.set(from, nonce, &true);
+}
+
+/// Message signed by the sender for `transferWithAuthorization`:
+/// Args("TRANSFER_WITH_AUTHORIZATION", contract, from, to, amount, validAfter, validBefore, nonce)
+fn transfer_authorization_message(
+    from: &str,
+    to: &str,
+    amount: U256,
+    valid_after: u64,
+    valid_before: u64,
+    nonce: &str,
+) -> Vec<u8> {
+    let mut message = Args::new();
+    message
+        .add_string(TRANSFER_AUTHORIZATION_TYPE)
+        .add_string(&context::callee())
+        .add_string(from)
+        .add_string(to)
+        .add_u256(amount)
+        .add_u64(valid_after)
+        .add_u64(valid_before)
+        .add_string(nonce);
+    message.into_bytes()
+}
+
+/// Message signed by the sender for `relayedTransfer`:
+/// Args("RELAYED_TRANSFER", contract, from, to, amount, relayerFee, nonce, deadline)
+fn relayed_transfer_message(from: &str, to: &str, amount: U256, relayer_fee: U256, nonce: u64, deadline: u64) -> Vec<u8> {
+    let mut message = Args::new();
+    message
+        .add_string(RELAYED_TRANSFER_TYPE)
+        .add_string(&context::callee())
+        .add_string(from)
+        .add_string(to)
+        .add_u256(amount)
+        .add_u256(relayer_fee)
+        .add_u64(nonce)
+        .add_u64(deadline);
+    message.into_bytes()
+}
+
+fn get_snapshot_id() -> u64 {
+    SNAPSHOT_ID.get_or(0)
+}
+
+fn set_snapshot_id(id: u64) {
+    SNAPSHOT_ID.set(&id);
+}
+
+/// A value recorded for a snapshot.
+struct Checkpoint {
+    snapshot_id: u64,
+    value: U256,
+}
+
+impl StorageValue for Checkpoint {
+    fn to_storage(&self) -> Vec<u8> {
+        let mut data = self.snapshot_id.to_le_bytes().to_vec();
+        data.extend_from_slice(&self.value.to_le_bytes());
+        data
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        if data.len() < 40 {
+            return None;
+        }
+        let mut id_bytes = [0u8; 8];
+        id_bytes.copy_from_slice(&data[..8]);
+        let mut value_bytes = [0u8; 32];
+        value_bytes.copy_from_slice(&data[8..40]);
+        Some(Checkpoint {
+            snapshot_id: u64::from_le_bytes(id_bytes),
+            value: U256::from_le_bytes(value_bytes),
+        })
+    }
+}
+
+/// The checkpoints of a value tracked across snapshots, ordered by snapshot id.
+enum Checkpoints<'a> {
+    /// The total supply.
+    Supply,
+    /// The balance of an account.
+    Balance(&'a str),
+}
+
+impl Checkpoints<'_> {
+    fn len(&self) -> u64 {
+        match self {
+            Checkpoints::Supply => SUPPLY_CHECKPOINT_COUNT.get_or(0),
+            Checkpoints::Balance(address) => CHECKPOINT_COUNTS.get_or(address, 0),
+        }
+    }
+
+    fn get(&self, index: u64) -> Checkpoint {
+        let checkpoint = match self {
+            Checkpoints::Supply => SUPPLY_CHECKPOINTS.get(&index),
+            Checkpoints::Balance(address) => CHECKPOINTS.get(address, &index),
+        };
+        checkpoint.or_fail(errors::INVALID_STATE, "Checkpoint is missing")
+    }
+
+    fn push(&self, checkpoint: &Checkpoint) {
+        let count = self.len();
+        match self {
+            Checkpoints::Supply => {
+                SUPPLY_CHECKPOINTS.set(&count, checkpoint);
+                SUPPLY_CHECKPOINT_COUNT.set(&(count + 1));
+            }
+            Checkpoints::Balance(address) => {
+                CHECKPOINTS.set(address, &count, checkpoint);
+                CHECKPOINT_COUNTS.set(address, &(count + 1));
+            }
+        }
+    }
+
+    /// Record `current_value` for the current snapshot if it has not been recorded yet.
+    ///
+    /// Must be called before the value changes, so each checkpoint holds the value
+    /// as it was when its snapshot was taken.
+    fn update(&self, current_value: U256) {
+        let snapshot_id = get_snapshot_id();
+        if snapshot_id == 0 {
+            return;
+        }
+        let count = self.len();
+        if count > 0 && self.get(count - 1).snapshot_id >= snapshot_id {
+            return;
+        }
+        self.push(&Checkpoint {
+            snapshot_id,
+            value: current_value,
+        });
+    }
+
+    /// Value at `snapshot_id`: the first checkpoint recorded at or after that
+    /// snapshot, or `current_value` if the value has not changed since.
+    fn value_at(&self, snapshot_id: u64, current_value: U256) -> U256 {
+        ensure!(snapshot_id > 0, errors::INVALID_ARGUMENT, "Snapshot id is 0");
+        ensure!(
+            snapshot_id <= get_snapshot_id(),
+            errors::NOT_FOUND,
+            fields("id={}", snapshot_id),
+            "Nonexistent snapshot id"
+        );
+
+        let count = self.len();
+        let mut low = 0;
+        let mut high = count;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.get(mid).snapshot_id < snapshot_id {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        if low == count {
+            current_value
+        } else {
+            self.get(low).value
+        }
+    }
+}
+
+fn is_decommissioned() -> bool {
+    DECOMMISSIONED.get_or(false)
+}
+
+fn when_not_decommissioned() {
+    ensure!(!is_decommissioned(), errors::DECOMMISSIONED, "Contract is decommissioned");
+}
+
+fn is_paused() -> bool {
+    PAUSED.get_or(false)
+}
+
+fn set_paused(paused: bool) {
+    PAUSED.set(&paused);
+}
+
+fn when_not_paused() {
+    ensure!(!is_paused(), errors::PAUSED, "Token is paused");
+}
+
+fn is_frozen(address: &str) -> bool {
+    FROZEN.get_or(address, false)
+}
+
+fn set_frozen(address: &str, frozen: bool) {
+    FROZEN.set(address, &frozen);
+}
+
+fn when_not_frozen(address: &str) {
+    ensure!(!is_frozen(address), errors::FROZEN, fields("account={}", address), "Account is frozen");
+}
+
+fn is_allowlist_mode() -> bool {
+    ALLOWLIST_MODE.get_or(false)
+}
+
+fn is_allowlisted(address: &str) -> bool {
+    ALLOWLISTED.get_or(address, false)
+}
+
+fn set_allowlisted(address: &str, allowlisted: bool) {
+    ALLOWLISTED.set(address, &allowlisted);
+}
+
+/// The contract itself is always exempt, so the tokens it escrows can be paid out.
+fn is_limit_exempt(address: &str) -> bool {
+    if address == context::callee() {
+        return true;
+    }
+    LIMIT_EXEMPT.get_or(address, false)
+}
+
+fn set_limit_exempt(address: &str, exempt: bool) {
+    LIMIT_EXEMPT.set(address, &exempt);
+}
+
+fn get_max_tx_amount() -> U256 {
+    MAX_TX_AMOUNT.get_or(U256::ZERO)
+}
+
+fn get_max_wallet_balance() -> U256 {
+    MAX_WALLET_BALANCE.get_or(U256::ZERO)
+}
+
+fn get_transfer_cooldown() -> u64 {
+    TRANSFER_COOLDOWN.get_or(0)
+}
+
+/// Launch limit a transfer would break.
+enum TransferLimit {
+    MaxTxAmount(U256),
+    MaxWalletBalance(U256),
+    Cooldown(u64),
+}
+
+/// The first launch limit the transfer breaks, if any: max transaction amount,
+/// max resulting wallet balance or per-sender cooldown. Exempt addresses bypass them.
+fn exceeded_transfer_limit(from: &str, to: &str, amount: U256, new_to_balance: U256) -> Option<TransferLimit> {
+    let from_exempt = is_limit_exempt(from);
+    let to_exempt = is_limit_exempt(to);
+
+    let max_tx_amount = get_max_tx_amount();
+    if max_tx_amount > U256::ZERO && !from_exempt && !to_exempt && amount > max_tx_amount {
+        return Some(TransferLimit::MaxTxAmount(max_tx_amount));
+    }
+
+    let max_wallet_balance = get_max_wallet_balance();
+    if max_wallet_balance > U256::ZERO && !to_exempt && new_to_balance > max_wallet_balance {
+        return Some(TransferLimit::MaxWalletBalance(max_wallet_balance));
+    }
+
+    let cooldown = get_transfer_cooldown();
+    if cooldown > 0
+        && !from_exempt
+        && let Some(last_transfer) = LAST_TRANSFERS.get(from)
+    {
+        let until = last_transfer.saturating_add(cooldown);
+        if context::current_period() < until {
+            return Some(TransferLimit::Cooldown(until));
+        }
+    }
+    None
+}
+
+/// Enforce launch limits on a transfer and record the sender's transfer period
+/// for the cooldown.
+fn enforce_transfer_limits(from: &str, to: &str, amount: U256, new_to_balance: U256) {
+    match exceeded_transfer_limit(from, to, amount, new_to_balance) {
+        Some(TransferLimit::MaxTxAmount(max)) => fail!(
+            errors::TRANSFER_LIMIT,
+            fields("limit=maxTxAmount,needed={},max={}", amount, max),
+            "Transfer failed: amount exceeds maxTxAmount"
+        ),
+        Some(TransferLimit::MaxWalletBalance(max)) => fail!(
+            errors::TRANSFER_LIMIT,
+            fields("limit=maxWalletBalance,needed={},max={}", new_to_balance, max),
+            "Transfer failed: recipient balance exceeds maxWalletBalance"
+        ),
+        Some(TransferLimit::Cooldown(until)) => fail!(
+            errors::TRANSFER_LIMIT,
+            fields("limit=cooldown,until={}", until),
+            "Transfer failed: sender is in cooldown"
+        ),
+        None => {}
+    }
+
+    if get_transfer_cooldown() > 0 && !is_limit_exempt(from) {
+        LAST_TRANSFERS.set(from, &context::current_period());
+    }
+}
+
+/// In allowlist mode, both ends of a transfer must be allowlisted.
+fn when_allowlisted(address: &str) {
+    if is_allowlist_mode() {
+        ensure!(is_allowlisted(address), errors::NOT_ALLOWLISTED, fields("account={}", address), "Account is not allowlisted");
+    }
+}
+
+/// Deployments predating versioning have no `STORAGE_VERSION` key and are version 0.
+fn get_storage_version() -> u32 {
+    STORED_VERSION.get_or(0)
+}
+
+fn set_storage_version(version: u32) {
+    STORED_VERSION.set(&version);
+}
+
+/// Upgrade the storage layout from `from_version` to `from_version + 1`.
+fn migrate_storage_step(from_version: u32) {
+    match from_version {
+        // Version 0 is the AS reference layout, which keeps emptied balances and
+        // allowances as zero values and has no holder or spender lists. Version 1
+        // stores them as missing keys and lists every non-zero entry.
+        0 => {
+            let known = stored_addresses(token::BALANCE_KEY_PREFIX);
+            delete_zero_entries(token::BALANCE_KEY_PREFIX, &[]);
+            delete_zero_entries(token::ALLOWANCE_KEY_PREFIX, &[ALLOWANCE_EXPIRY_KEY_PREFIX]);
+            index_holders();
+            index_spenders(&known);
+        }
+        _ => fail!(
+            errors::INVALID_STATE,
+            fields("version={}", from_version),
+            "No migration from storage version {}",
+            from_version
+        ),
+    }
+}
+
+/// Addresses following `prefix` in the stored keys.
+fn stored_addresses(prefix: &[u8]) -> Vec<String> {
+    storage::get_keys(prefix)
+        .into_iter()
+        .filter_map(|key| String::from_utf8(key[prefix.len()..].to_vec()).ok())
+        .collect()
+}
+
+/// List every address with a balance key in the holder list.
+fn index_holders() {
+    let list = AddressList::Holders;
+    for address in stored_addresses(token::BALANCE_KEY_PREFIX) {
+        if !list.contains(&address) {
+            list.add(&address);
+        }
+    }
+}
+
+/// List every spender with an allowance key in its owner's spender list.
+///
+/// Version 0 keys are `ALLOWANCE{owner}{spender}` with no separator, so the
+/// owner is the address of `known` the key starts with. Allowances of owners
+/// that never held a balance are not listed; `remove` ignores them once they
+/// reach zero.
+fn index_spenders(known: &[String]) {
+    for key in storage::get_keys(token::ALLOWANCE_KEY_PREFIX) {
+        if key.starts_with(ALLOWANCE_EXPIRY_KEY_PREFIX) {
+            continue;
+        }
+        let pair = &key[token::ALLOWANCE_KEY_PREFIX.len()..];
+        let split = known
+            .iter()
+            .find(|owner| pair.starts_with(owner.as_bytes()) && pair.len() > owner.len())
+            .and_then(|owner| Some((owner, core::str::from_utf8(&pair[owner.len()..]).ok()?)));
+        if let Some((owner, spender)) = split {
+            let list = AddressList::Spenders(owner);
+            if !list.contains(spender) {
+                list.add(spender);
+            }
+        }
+    }
+}
+
+/// Delete the U256 entries under `prefix` that hold zero, skipping keys of the
+/// sibling maps in `excluded` that share the prefix.
+fn delete_zero_entries(prefix: &[u8], excluded: &[&[u8]]) {
+    for key in storage::get_keys(prefix) {
+        if excluded.iter().any(|other| key.starts_with(other)) {
+            continue;
+        }
+        if store::read::<U256>(&key) == Some(U256::ZERO) {
+            storage::delete(&key);
+        }
+    }
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Token parameters given at deployment.
+struct TokenConfig {
+    name: String,
+    symbol: String,
+    decimals: u8,
+    total_supply: U256,
+    cap: Option<U256>,
+    token_uri: String,
+    description: String,
+    allowlist_mode: bool,
+}
+
+/// Size of the required constructor arguments once Args-serialized: two
+/// length-prefixed strings, a u8 and a U256.
+fn required_args_len(name: &str, symbol: &str) -> usize {
+    4 + name.len() + 4 + symbol.len() + 1 + 32
+}
+
+/// Read the optional trailing constructor arguments, which default to no cap,
+/// empty metadata and an open token.
+///
+/// `Args` does not expose how many bytes are left, so `remaining` is the
+/// size of the arguments after the required ones. An argument is absent only
+/// when no bytes are left; bytes that do not decode fail instead of being
+/// ignored.
+fn read_optional_config(
+    args: &mut Args,
+    mut remaining: usize,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    total_supply: U256,
+) -> TokenConfig {
+    let mut config = TokenConfig {
+        name,
+        symbol,
+        decimals,
+        total_supply,
+        cap: None,
+        token_uri: String::new(),
+        description: String::new(),
+        allowlist_mode: false,
+    };
+    if remaining == 0 {
+        return config;
+    }
+    let cap = args.next_u256().unwrap_or_else(|_| errors::invalid_argument("cap"));
+    config.cap = Some(cap).filter(|cap| *cap > U256::ZERO);
+    remaining = remaining.saturating_sub(32);
+    if remaining == 0 {
+        return config;
+    }
+    let token_uri = args.next_string().unwrap_or_else(|_| errors::invalid_argument("tokenURI"));
+    remaining = remaining.saturating_sub(4 + token_uri.len());
+    config.token_uri = token_uri;
+    if remaining == 0 {
+        return config;
+    }
+    let description = args.next_string().unwrap_or_else(|_| errors::invalid_argument("description"));
+    remaining = remaining.saturating_sub(4 + description.len());
+    config.description = description;
+    if remaining == 0 {
+        return config;
+    }
+    let allowlist_mode = args.next_bool().unwrap_or_else(|_| errors::invalid_argument("allowlistMode"));
+    config.allowlist_mode = allowlist_mode;
+    config
+}
+
+/// Write the initial state shared by `constructor` and `constructorWithDefaults`.
+fn initialize(config: &TokenConfig) {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+    ensure!(!config.name.is_empty(), errors::INVALID_ARGUMENT, fields("name=name"), "name argument must not be empty");
+    ensure!(!config.symbol.is_empty(), errors::INVALID_ARGUMENT, fields("name=symbol"), "symbol argument must not be empty");
+    if let Some(cap) = config.cap {
+        ensure!(
+            config.total_supply <= cap,
+            errors::CAP_EXCEEDED,
+            fields("needed={},max={}", config.total_supply, cap),
+            "totalSupply argument exceeds the supply cap"
+        );
+    }
+
+    set_storage_version(STORAGE_VERSION);
+
+    // Store token metadata (raw bytes, matching AS format)
+    storage::set(NAME_KEY, config.name.as_bytes());
+    storage::set(SYMBOL_KEY, config.symbol.as_bytes());
+    storage::set(DECIMALS_KEY, &[config.decimals]);
+    storage::set(TOKEN_URI_KEY, config.token_uri.as_bytes());
+    storage::set(DESCRIPTION_KEY, config.description.as_bytes());
+    set_total_supply(config.total_supply);
+    if let Some(cap) = config.cap {
+        set_cap(cap);
+    }
+
+    // Set owner and mint initial supply to caller
+    let caller = context::caller();
+    set_owner_internal(&caller);
+    set_balance(&caller, config.total_supply);
+    for role in ROLES {
+        set_role(role, &caller, true);
+    }
+    set_limit_exempt(&caller, true);
+    if config.allowlist_mode {
+        ALLOWLIST_MODE.set(&true);
+        set_allowlisted(&caller, true);
+    }
+
+    // Emit CHANGE_OWNER event (matching AS format: "CHANGE_OWNER:address")
+    abi::generate_event(&alloc::format!("{}:{}", CHANGE_OWNER_EVENT, caller));
+    if config.total_supply > U256::ZERO {
+        abi::generate_event(&mint_event(&caller, config.total_supply));
+    }
+}
+
+/// Constructor - Initialize the MRC20 token.
+///
+/// # Arguments (Args serialized)
+/// - `name`: Token name (string, non-empty)
+/// - `symbol`: Token symbol (string, non-empty)
+/// - `decimals`: Token decimals (u8)
+/// - `totalSupply`: Initial supply as U256 (32 bytes)
+/// - `cap`: Optional maximum supply as U256 (32 bytes), uncapped if omitted or zero
+/// - `tokenURI`: Optional metadata URI (string), e.g. a logo or JSON metadata location
+/// - `description`: Optional project description (string)
+/// - `allowlistMode`: Optional flag restricting transfers to allowlisted addresses (bool)
+///
+/// Fails if a required argument is missing or invalid, or if `totalSupply`
+/// exceeds `cap`. Use `constructorWithDefaults` to fall back to default values.
+///
+/// The caller becomes the owner, receives all initial tokens and is granted
+/// every role. It is exempt from transfer limits, and allowlisted in allowlist mode.
+///
+/// # Events
+/// - `CHANGE_OWNER:caller`
+/// - `MINT SUCCESS` for the initial supply, if non-zero
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let name = args.next_string().expect_arg("name");
+    let symbol = args.next_string().expect_arg("symbol");
+    let decimals = args.next_u8().expect_arg("decimals");
+    let total_supply = args.next_u256().expect_arg("totalSupply");
+    let remaining = binary_args.len().saturating_sub(required_args_len(&name, &symbol));
+    let config = read_optional_config(&mut args, remaining, name, symbol, decimals, total_supply);
+
+    initialize(&config);
+
+    Vec::new()
+}
+
+/// Constructor variant that falls back to "MassaToken", "MT", 18 decimals and
+/// a supply of 1e18 for missing or invalid arguments. Optional arguments are
+/// read as in `constructor` once all four required ones decoded.
+///
+/// Takes the same arguments as `constructor`. Deployers must call it
+/// explicitly instead of `constructor`.
+#[massa_export]
+pub fn constructorWithDefaults(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let name = args.next_string().ok();
+    let symbol = args.next_string().ok();
+    let decimals = args.next_u8().ok();
+    let total_supply = args.next_u256().ok();
+
+    // Optional arguments are only read after a complete set of required ones
+    let remaining = match (&name, &symbol, decimals, total_supply) {
+        (Some(name), Some(symbol), Some(_), Some(_)) => {
+            binary_args.len().saturating_sub(required_args_len(name, symbol))
+        }
+        _ => 0,
+    };
+    let config = read_optional_config(
+        &mut args,
+        remaining,
+        name.unwrap_or_else(|| String::from("MassaToken")),
+        symbol.unwrap_or_else(|| String::from("MT")),
+        decimals.unwrap_or(18),
+        total_supply.unwrap_or_else(|| U256::from(1_000_000_000_000_000_000u64)),
+    );
+
+    initialize(&config);
+
+    Vec::new()
+}
+
+// ============================================================================
+// Token Attributes (read-only)
+// ============================================================================
+
+/// Returns the version of this smart contract.
+#[massa_export]
+pub fn version(_binary_args: &[u8]) -> Vec<u8> {
+    VERSION.to_vec()
+}
+
+/// Returns the optional extensions supported by this contract as Args(count: u32, then `count` ids as strings).
+#[massa_export]
+pub fn features(_binary_args: &[u8]) -> Vec<u8> {
+    let mut result = Args::new();
+    result.add_u32(INTERFACES.len() as u32);
+    for interface in INTERFACES {
+        result.add_string(interface);
+    }
+    result.into_bytes()
+}
+
+/// Returns true (1) if the contract supports the extension, false (0) otherwise.
+///
+/// # Arguments
+/// - `interfaceId`: Extension id as listed by `features`, e.g. `permit` (string)
+#[massa_export]
+pub fn supportsInterface(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let interface_id = args.next_string().expect_arg("interfaceId");
+
+    if INTERFACES.contains(&interface_id.as_str()) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+/// Returns the name of the token (raw bytes, not Args-wrapped).
+#[massa_export]
+pub fn name(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(NAME_KEY)
+}
+
+/// Returns the symbol of the token (raw bytes, not Args-wrapped).
+#[massa_export]
+pub fn symbol(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(SYMBOL_KEY)
+}
+
+/// Returns the decimals of the token (raw bytes, not Args-wrapped).
+#[massa_export]
+pub fn decimals(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(DECIMALS_KEY)
+}
+
+/// Returns the token metadata URI (raw bytes, not Args-wrapped), empty if unset.
+#[massa_export]
+pub fn tokenURI(_binary_args: &[u8]) -> Vec<u8> {
+    store::get_opt(TOKEN_URI_KEY).unwrap_or_default()
+}
+
+/// Returns the token description (raw bytes, not Args-wrapped), empty if unset.
+#[massa_export]
+pub fn description(_binary_args: &[u8]) -> Vec<u8> {
+    store::get_opt(DESCRIPTION_KEY).unwrap_or_default()
+}
+
+/// Set the token metadata URI (owner only).
+///
+/// # Arguments
+/// - `tokenURI`: New metadata URI (string)
+///
+/// # Events
+/// - `SET_TOKEN_URI:tokenURI`
+#[massa_export]
+pub fn setTokenURI(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token_uri = args.next_string().expect_arg("tokenURI");
+    storage::set(TOKEN_URI_KEY, token_uri.as_bytes());
+
+    abi::generate_event(&alloc::format!("{}:{}", SET_TOKEN_URI_EVENT, token_uri));
+
+    Vec::new()
+}
+
+/// Returns the total supply (raw u256 bytes, not Args-wrapped).
+#[massa_export]
+pub fn totalSupply(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(TOTAL_SUPPLY_KEY)
+}
+
+// ============================================================================
+// Balance
+// ============================================================================
+
+/// Returns the balance of an account (u256 bytes).
+///
+/// # Arguments
+/// - `address`: Account address (string)
+#[massa_export]
+pub fn balanceOf(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("Address");
+    let balance = get_balance(&address);
+    balance.to_le_bytes().to_vec()
+}
+
+/// Returns the number of addresses with a non-zero balance (u64, 8 bytes LE).
+#[massa_export]
+pub fn holderCount(_binary_args: &[u8]) -> Vec<u8> {
+    AddressList::Holders.len().to_le_bytes().to_vec()
+}
+
+/// Returns a page of holders as Args(count: u32, then `count` addresses as strings).
+///
+/// The order is unspecified and changes when holders leave, since the last
+/// holder takes the slot of a removed one.
+///
+/// # Arguments
+/// - `offset`: Index of the first holder (u64)
+/// - `limit`: Maximum number of holders to return (u32)
+#[massa_export]
+pub fn holders(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let offset = args.next_u64().expect_arg("offset");
+    let limit = args.next_u32().expect_arg("limit");
+
+    let list = AddressList::Holders;
+    let page = list.page(offset, limit);
+
+    let mut result = Args::new();
+    result.add_u32((page.end - page.start) as u32);
+    for index in page {
+        result.add_string(&list.at(index));
+    }
+    result.into_bytes()
+}
+
+// ============================================================================
+// Transfer
+// ============================================================================
+
+/// Transfers tokens from caller to recipient.
+///
+/// # Arguments
+/// - `to`: Recipient address (string)
+/// - `amount`: Amount to transfer (U256)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+///
+/// # Events
+/// - `TRANSFER SUCCESS`
+#[massa_export]
+pub fn transfer(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let to = args.next_string().expect_arg("receiverAddress");
+    let amount = args.next_u256().expect_arg("amount");
+
+    when_not_paused();
+
+    let from = context::caller();
+    move_balance(&from, &to, amount);
+
+    abi::generate_event(&transfer_event(&from, &to, amount));
+
+    success_result()
+}
+
+/// Executes a transfer signed off-chain by the sender and submitted by anyone.
+///
+/// The sender signs `Args("TRANSFER_WITH_AUTHORIZATION", contract, from, to, amount,
+/// validAfter, validBefore, nonce)`. The nonce is chosen by the sender and can
+/// only be used once per sender, so the relayer paying the fees cannot replay it.
+///
+/// # Arguments
+/// - `from`: Sender address (string)
+/// - `to`: Recipient address (string)
+/// - `amount`: Amount to transfer (U256)
+/// - `validAfter`: Timestamp in milliseconds after which the authorization is valid (u64)
+/// - `validBefore`: Timestamp in milliseconds before which the authorization is valid (u64)
+/// - `nonce`: Unique authorization nonce (string)
+/// - `publicKey`: Sender public key (string)
+/// - `signature`: Sender signature over the authorization message (string)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+///
+/// # Events
+/// - `AUTHORIZATION_USED:from:nonce`
+/// - `TRANSFER SUCCESS`
+#[massa_export]
+pub fn transferWithAuthorization(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let from = args.next_string().expect_arg("from");
+    let to = args.next_string().expect_arg("to");
+    let amount = args.next_u256().expect_arg("amount");
+    let valid_after = args.next_u64().expect_arg("validAfter");
+    let valid_before = args.next_u64().expect_arg("validBefore");
+    let nonce = args.next_string().expect_arg("nonce");
+    let public_key = args.next_string().expect_arg("publicKey");
+    let signature = args.next_string().expect_arg("signature");
+
+    when_not_paused();
+
+    let now = context::timestamp();
+    ensure!(now > valid_after, errors::NOT_YET_VALID, "Authorization is not yet valid");
+    ensure!(now < valid_before, errors::EXPIRED, "Authorization expired");
+    ensure!(!is_authorization_used(&from, &nonce), errors::ALREADY_USED, fields("nonce={}", nonce), "Authorization already used");
+
+    let message = transfer_authorization_message(&from, &to, amount, valid_after, valid_before, &nonce);
+    verify_signed_by(&from, &public_key, &message, &signature);
+
+    set_authorization_used(&from, &nonce);
+    move_balance(&from, &to, amount);
+
+    abi::generate_event(&alloc::format!("{}:{}:{}", AUTHORIZATION_USED_EVENT, from, nonce));
+    abi::generate_event(&transfer_event(&from, &to, amount));
+
+    success_result()
+}
+
+/// Executes a transfer signed off-chain by the sender and pays the relayer in tokens.
+///
+/// The sender signs `Args("RELAYED_TRANSFER", contract, from, to, amount, relayerFee,
+/// nonce, deadline)` where `nonce` is the current value of `nonces(from)`, shared
+/// with `permit`. The caller submitting it receives `relayerFee` from `from`, so
+/// users without MAS can still move tokens.
+///
+/// # Arguments
+/// - `from`: Sender address (string)
+/// - `to`: Recipient address (string)
+/// - `amount`: Amount to transfer (U256)
+/// - `relayerFee`: Fee paid to the caller, in tokens (U256)
+/// - `nonce`: Sender nonce (u64)
+/// - `deadline`: Expiration timestamp in milliseconds (u64)
+/// - `publicKey`: Sender public key (string)
+/// - `signature`: Sender signature over the relayed transfer message (string)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+///
+/// # Events
+/// - `TRANSFER SUCCESS` for the transfer, then for the fee if non-zero
+/// - `RELAYED_TRANSFER:from=...,relayer=...,nonce=...`
+#[massa_export]
+pub fn relayedTransfer(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let from = args.next_string().expect_arg("from");
+    let to = args.next_string().expect_arg("to");
+    let amount = args.next_u256().expect_arg("amount");
+    let relayer_fee = args.next_u256().expect_arg("relayerFee");
+    let nonce = args.next_u64().expect_arg("nonce");
+    let deadline = args.next_u64().expect_arg("deadline");
+    let public_key = args.next_string().expect_arg("publicKey");
+    let signature = args.next_string().expect_arg("signature");
+
+    when_not_paused();
+
+    ensure!(context::timestamp() <= deadline, errors::EXPIRED, "Relayed transfer expired");
+    let expected_nonce = get_nonce(&from);
+    ensure!(
+        nonce == expected_nonce,
+        errors::ALREADY_USED,
+        fields("nonce={},expected={}", nonce, expected_nonce),
+        "Invalid relayed transfer nonce"
+    );
+
+    let message = relayed_transfer_message(&from, &to, amount, relayer_fee, nonce, deadline);
+    verify_signed_by(&from, &public_key, &message, &signature);
+
+    set_nonce(&from, nonce + 1);
+    move_balance(&from, &to, amount);
+    abi::generate_event(&transfer_event(&from, &to, amount));
+
+    let relayer = context::caller();
+    if relayer_fee > U256::ZERO {
+        move_balance(&from, &relayer, relayer_fee);
+        abi::generate_event(&transfer_event(&from, &relayer, relayer_fee));
+    }
+
+    abi::generate_event(&alloc::format!(
+        "{}:from={},relayer={},nonce={}",
+        RELAYED_TRANSFER_EVENT,
+        from,
+        relayer,
+        nonce
+    ));
+
+    success_result()
+}
+
+/// Returns true (1) if the authorization nonce of `from` was used, false (0) otherwise.
+///
+/// # Arguments
+/// - `from`: Sender address (string)
+/// - `nonce`: Authorization nonce (string)
+#[massa_export]
+pub fn authorizationState(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let from = args.next_string().expect_arg("from");
+    let nonce = args.next_string().expect_arg("nonce");
+
+    if is_authorization_used(&from, &nonce) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+/// Transfers tokens from caller to a recipient contract, then calls its
+/// `onTokenReceived(from, amount, data)` entrypoint.
+///
+/// If the recipient does not implement the hook or the hook panics, the whole
+/// call reverts and the transfer is undone.
+///
+/// # Arguments
+/// - `to`: Recipient contract address (string, must start with `AS`)
+/// - `amount`: Amount to transfer (U256)
+/// - `data`: Opaque bytes forwarded to the recipient (bytes)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+///
+/// # Events
+/// - `TRANSFER SUCCESS`
+#[massa_export]
+pub fn transferAndCall(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let to = args.next_string().expect_arg("receiverAddress");
+    let amount = args.next_u256().expect_arg("amount");
+    let data = args.next_bytes().expect_arg("data");
+    if !callbacks::is_contract_address(&to) {
+        errors::invalid_argument("receiverAddress");
+    }
+
+    when_not_paused();
+
+    let from = context::caller();
+    move_balance(&from, &to, amount);
+
+    abi::generate_event(&transfer_event(&from, &to, amount));
+
+    let mut hook_args = Args::new();
+    hook_args.add_string(&from).add_u256(amount).add_bytes(&data);
+    calls::call(&to, TOKEN_RECEIVED_HOOK, hook_args);
+
+    success_result()
+}
+
+// ============================================================================
+// Allowance
+// ============================================================================
+
+/// Returns the allowance for owner/spender (u256 bytes).
+///
+/// # Arguments
+/// - `owner`: Owner address (string)
+/// - `spender`: Spender address (string)
+#[massa_export]
+pub fn allowance(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("owner");
+    let spender = args.next_string().expect_arg("spenderAddress");
+    
+    let amount = get_allowance(&owner, &spender);
+    amount.to_le_bytes().to_vec()
+}
+
+/// Returns a page of the owner's approvals as Args(count: u32, then `count` (spender: string, amount: U256) pairs).
+///
+/// Expired allowances are listed with a zero amount until they are overwritten.
+///
+/// # Arguments
+/// - `owner`: Owner address (string)
+/// - `offset`: Index of the first spender (u64)
+/// - `limit`: Maximum number of spenders to return (u32)
+#[massa_export]
+pub fn allowancesOf(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("owner");
+    let offset = args.next_u64().expect_arg("offset");
+    let limit = args.next_u32().expect_arg("limit");
+
+    let list = AddressList::Spenders(&owner);
+    let page = list.page(offset, limit);
+
+    let mut result = Args::new();
+    result.add_u32((page.end - page.start) as u32);
+    for index in page {
+        let spender = list.at(index);
+        result.add_string(&spender).add_u256(get_allowance(&owner, &spender));
+    }
+    result.into_bytes()
+}
+
+/// Increases the allowance of the spender on the caller's account.
+///
+/// # Arguments
+/// - `spender`: Spender address (string)
+/// - `amount`: Amount to increase (U256)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+/// - `allowance`: New allowance (U256, Args-serialized)
+///
+/// # Events
+/// - `APPROVAL SUCCESS`
+#[massa_export]
+pub fn increaseAllowance(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let spender = args.next_string().expect_arg("spenderAddress");
+    let amount = args.next_u256().expect_arg("amount");
+
+    let owner = context::caller();
+    let current = get_allowance(&owner, &spender);
+    
+    // If overflow, set to max (matching AS behavior)
+    let new_allowance = current.saturating_add(amount);
+    
+    approve(&owner, &spender, new_allowance, 0);
+
+    abi::generate_event(&approval_event(&owner, &spender, new_allowance));
+
+    allowance_result(new_allowance)
+}
+
+/// Decreases the allowance of the spender on the caller's account.
+///
+/// # Arguments
+/// - `spender`: Spender address (string)
+/// - `amount`: Amount to decrease (U256)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+/// - `allowance`: New allowance (U256, Args-serialized)
+///
+/// # Events
+/// - `APPROVAL SUCCESS`
+#[massa_export]
+pub fn decreaseAllowance(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let spender = args.next_string().expect_arg("spenderAddress");
+    let amount = args.next_u256().expect_arg("amount");
+
+    let owner = context::caller();
+    let current = get_allowance(&owner, &spender);
+    
+    // If underflow, set to zero (matching AS behavior)
+    let new_allowance = current.saturating_sub(amount);
+    
+    set_allowance(&owner, &spender, new_allowance);
+
+    abi::generate_event(&approval_event(&owner, &spender, new_allowance));
+
+    allowance_result(new_allowance)
+}
+
+/// Sets the allowance of the spender only if it currently equals `expectedCurrent`.
+///
+/// Replaces the allowance atomically, so a spender front-running the change
+/// cannot spend both the old and the new allowance. A mismatch does not fail:
+/// the allowance is left untouched and the result reports it.
+///
+/// # Arguments
+/// - `spender`: Spender address (string)
+/// - `expectedCurrent`: Allowance the caller expects to replace (U256)
+/// - `newAmount`: New allowance (U256)
+///
+/// # Returns
+/// - `success`: Whether the allowance was updated (bool, Args-serialized)
+/// - `allowance`: Allowance after the call (U256, Args-serialized)
+///
+/// # Events
+/// - `APPROVE_IF_CURRENT:owner=...,spender=...,expected=...,current=...,success=...`
+/// - `APPROVAL SUCCESS` if the allowance was updated
+#[massa_export]
+pub fn approveIfCurrent(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let spender = args.next_string().expect_arg("spenderAddress");
+    let expected_current = args.next_u256().expect_arg("expectedCurrent");
+    let new_amount = args.next_u256().expect_arg("newAmount");
+
+    let owner = context::caller();
+    let current = get_allowance(&owner, &spender);
+    let success = current == expected_current;
+
+    abi::generate_event(&alloc::format!(
+        "{}:owner={},spender={},expected={},current={},success={}",
+        APPROVE_IF_CURRENT_EVENT,
+        owner,
+        spender,
+        expected_current,
+        current,
+        success
+    ));
+
+    if !success {
+        let mut ret = Args::new();
+        ret.add_bool(false).add_u256(current);
+        return ret.into_bytes();
+    }
+
+    approve(&owner, &spender, new_amount, 0);
+
+    abi::generate_event(&approval_event(&owner, &spender, new_amount));
+
+    allowance_result(new_amount)
+}
+
+/// Sets the allowance of spender on owner's account from an off-chain signature.
+///
+/// The owner signs `Args("PERMIT", contract, owner, spender, amount, nonce, deadline)`
+/// where `nonce` is the current value of `nonces(owner)`. Anyone can submit the
+/// signed permit; each signature can only be used once.
+///
+/// # Arguments
+/// - `owner`: Owner address (string)
+/// - `spender`: Spender address (string)
+/// - `amount`: New allowance (U256)
+/// - `deadline`: Expiration timestamp in milliseconds (u64)
+/// - `publicKey`: Owner public key (string)
+/// - `signature`: Owner signature over the permit message (string)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+/// - `allowance`: New allowance (U256, Args-serialized)
+///
+/// # Events
+/// - `APPROVAL SUCCESS`
+#[massa_export]
+pub fn permit(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("owner");
+    let spender = args.next_string().expect_arg("spenderAddress");
+    let amount = args.next_u256().expect_arg("amount");
+    let deadline = args.next_u64().expect_arg("deadline");
+    let public_key = args.next_string().expect_arg("publicKey");
+    let signature = args.next_string().expect_arg("signature");
+
+    ensure!(context::timestamp() <= deadline, errors::EXPIRED, "Permit expired");
+
+    let nonce = get_nonce(&owner);
+    let message = permit_message(&owner, &spender, amount, nonce, deadline);
+    verify_signed_by(&owner, &public_key, &message, &signature);
+
+    set_nonce(&owner, nonce + 1);
+    approve(&owner, &spender, amount, 0);
+
+    abi::generate_event(&approval_event(&owner, &spender, amount));
+
+    allowance_result(amount)
+}
+
+/// Returns the current permit and relayed transfer nonce of owner (u64, 8 bytes LE).
+///
+/// # Arguments
+/// - `owner`: Owner address (string)
+#[massa_export]
+pub fn nonces(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("owner");
+    get_nonce(&owner).to_le_bytes().to_vec()
+}
+
+/// Sets the allowance of the spender on the caller's account until a given period.
+///
+/// From `expiryPeriod` on, `allowance` returns zero and `transferFrom`/`burnFrom`
+/// reject spends. An `expiryPeriod` of 0 sets an allowance that never expires.
+/// Spends and `decreaseAllowance` keep the expiry until the allowance reaches
+/// zero; `increaseAllowance`, `approveIfCurrent` and `permit` drop it.
+///
+/// # Arguments
+/// - `spender`: Spender address (string)
+/// - `amount`: New allowance (U256)
+/// - `expiryPeriod`: Period at which the allowance expires, 0 for none (u64)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+/// - `allowance`: New allowance (U256, Args-serialized)
+///
+/// # Events
+/// - `APPROVAL SUCCESS`
+#[massa_export]
+pub fn approveWithDeadline(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let spender = args.next_string().expect_arg("spenderAddress");
+    let amount = args.next_u256().expect_arg("amount");
+    let expiry_period = args.next_u64().expect_arg("expiryPeriod");
+
+    ensure!(
+        expiry_period == 0 || expiry_period > context::current_period(),
+        errors::INVALID_ARGUMENT,
+        "Expiry period is in the past"
+    );
+
+    let owner = context::caller();
+    approve(&owner, &spender, amount, expiry_period);
+
+    abi::generate_event(&approval_event(&owner, &spender, amount));
+
+    allowance_result(amount)
+}
+
+/// Returns the period at which the allowance expires (u64, 8 bytes LE), 0 if it never expires.
+///
+/// # Arguments
+/// - `owner`: Owner address (string)
+/// - `spender`: Spender address (string)
+#[massa_export]
+pub fn allowanceExpiry(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("owner");
+    let spender = args.next_string().expect_arg("spenderAddress");
+    get_allowance_expiry(&owner, &spender).to_le_bytes().to_vec()
+}
+
+/// Grants the spender a recurring allowance on the caller's account.
+///
+/// The spender may move up to `amountPerPeriod` tokens in each window of
+/// `periodLength` Massa periods, starting at the current period. While set, the
+/// limit replaces the one-shot allowance for `transferFrom` and `burnFrom`.
+/// Passing a zero `amountPerPeriod` removes the limit.
+///
+/// # Arguments
+/// - `spender`: Spender address (string)
+/// - `amountPerPeriod`: Amount spendable per window (U256)
+/// - `periodLength`: Window length in periods (u64)
+///
+/// # Events
+/// - `SPENDING_LIMIT:owner=...,spender=...,amount=...,periodLength=...`
+#[massa_export]
+pub fn setSpendingLimit(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let spender = args.next_string().expect_arg("spenderAddress");
+    let amount_per_period = args.next_u256().expect_arg("amountPerPeriod");
+    let period_length = args.next_u64().expect_arg("periodLength");
+
+    when_not_decommissioned();
+    ensure!(period_length > 0, errors::INVALID_ARGUMENT, "Period length is 0");
+
+    let owner = context::caller();
+    set_spending_limit(
+        &owner,
+        &spender,
+        &SpendingLimit {
+            amount_per_period,
+            period_length,
+            window_start: context::current_period(),
+            spent: U256::ZERO,
+        },
+    );
+
+    abi::generate_event(&alloc::format!(
+        "{}:owner={},spender={},amount={},periodLength={}",
+        SPENDING_LIMIT_EVENT, owner, spender, amount_per_period, period_length
+    ));
+
+    Vec::new()
+}
+
+/// Returns what the spender can still spend in the current window (u256 bytes).
+///
+/// Returns zero if no spending limit is set.
+///
+/// # Arguments
+/// - `owner`: Owner address (string)
+/// - `spender`: Spender address (string)
+#[massa_export]
+pub fn remainingSpendingLimit(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("owner");
+    let spender = args.next_string().expect_arg("spenderAddress");
+
+    let remaining = match get_spending_limit(&owner, &spender) {
+        Some(mut limit) => {
+            limit.roll_window(context::current_period());
+            limit.remaining()
+        }
+        None => U256::ZERO,
+    };
+    remaining.to_le_bytes().to_vec()
+}
+
+/// Transfers tokens from owner to recipient using spender's allowance.
+///
+/// # Arguments
+/// - `owner`: Owner address (string)
+/// - `recipient`: Recipient address (string)
+/// - `amount`: Amount to transfer (U256)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+///
+/// # Events
+/// - `TRANSFER SUCCESS`
+#[massa_export]
+pub fn transferFrom(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("ownerAddress");
+    let recipient = args.next_string().expect_arg("recipientAddress");
+    let amount = args.next_u256().expect_arg("amount");
+
+    when_not_paused();
+
+    let spender = context::caller();
+    spend_allowance(&owner, &spender, amount, "transferFrom");
+    move_balance(&owner, &recipient, amount);
+
+    abi::generate_event(&transfer_event(&owner, &recipient, amount));
+
+    success_result()
+}
+
+// ============================================================================
+// Mintable (MINTER role)
+// ============================================================================
+
+/// Mint `amount` to `recipient`, enforcing the supply cap.
+fn mint_to(recipient: &str, amount: U256) {
+    token::mint(&Erc20, recipient, amount);
+}
+
+/// Mint tokens to recipient (`MINTER` role only).
+///
+/// # Arguments
+/// - `recipient`: Recipient address (string)
+/// - `amount`: Amount to mint (U256)
+///
+/// # Events
+/// - `MINT SUCCESS`
+#[massa_export]
+pub fn mint(binary_args: &[u8]) -> Vec<u8> {
+    only_role(MINTER_ROLE);
+    when_not_paused();
+    
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let recipient = args.next_string().expect_arg("recipient");
+    let amount = args.next_u256().expect_arg("amount");
+
+    mint_to(&recipient, amount);
+
+    Vec::new()
+}
+
+/// Mint tokens released by a bridge (`BRIDGE_MINTER` role only).
+///
+/// The bridge contract checks the validator signatures and that `sourceTxId`
+/// was not processed before; the token only records it in the event.
+///
+/// # Arguments
+/// - `recipient`: Recipient address (string)
+/// - `amount`: Amount to mint (U256)
+/// - `sourceTxId`: Transaction id on the source chain (string)
+///
+/// # Events
+/// - `MINT SUCCESS`
+/// - `BRIDGE_MINT:to=...,amount=...,sourceTxId=...`
+#[massa_export]
+pub fn bridgeMint(binary_args: &[u8]) -> Vec<u8> {
+    only_role(BRIDGE_MINTER_ROLE);
+    when_not_paused();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let recipient = args.next_string().expect_arg("recipient");
+    let amount = args.next_u256().expect_arg("amount");
+    let source_tx_id = args.next_string().expect_arg("sourceTxId");
+
+    mint_to(&recipient, amount);
+    abi::generate_event(&alloc::format!(
+        "{}:to={},amount={},sourceTxId={}",
+        BRIDGE_MINT_EVENT,
+        recipient,
+        amount,
+        source_tx_id
+    ));
+
+    Vec::new()
+}
+
+/// Mint tokens to several recipients in one call (`MINTER` role only).
+///
+/// Each pair is minted in turn with the same checks as `mint`. A pair that
+/// would exceed the cap fails the whole batch.
+///
+/// # Arguments
+/// - `count`: Number of (recipient, amount) pairs (u32)
+/// - then `count` times:
+///   - `recipient`: Recipient address (string)
+///   - `amount`: Amount to mint (U256)
+///
+/// # Events
+/// - `MINT SUCCESS` for each recipient
+#[massa_export]
+pub fn mintBatch(binary_args: &[u8]) -> Vec<u8> {
+    only_role(MINTER_ROLE);
+    when_not_paused();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let count = args.next_u32().expect_arg("count");
+    ensure!(count > 0, errors::INVALID_ARGUMENT, "Empty mint batch");
+
+    for _ in 0..count {
+        let recipient = args.next_string().expect_arg("recipient");
+        let amount = args.next_u256().expect_arg("amount");
+        mint_to(&recipient, amount);
+    }
+
+    Vec::new()
+}
+
+/// Set the supply cap (owner only, once).
+///
+/// Fails if a cap is already set or if the cap is below the current total supply.
+///
+/// # Arguments
+/// - `cap`: Maximum supply (U256)
+#[massa_export]
+pub fn setCap(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let cap = args.next_u256().expect_arg("cap");
+
+    ensure!(get_cap().is_none(), errors::ALREADY_EXISTS, "Cap is already set");
+    set_cap(cap);
+
+    Vec::new()
+}
+
+/// Returns the supply cap (raw u256 bytes), or empty bytes if uncapped.
+#[massa_export]
+pub fn cap(_binary_args: &[u8]) -> Vec<u8> {
+    match get_cap() {
+        Some(cap) => cap.to_le_bytes().to_vec(),
+        None => Vec::new(),
+    }
+}
+
+// ============================================================================
+// Flash Mint
+// ============================================================================
+
+/// Flash mint `amount` tokens to a borrower contract.
+///
+/// The tokens are minted to `receiver`, then `onFlashLoan(initiator, amount, fee, data)`
+/// is called on it and must return `ERC3156FlashBorrower.onFlashLoan`. Before returning,
+/// the receiver must allow this contract to spend `amount + fee` of its tokens; that
+/// amount is then burned. Any failure reverts the whole loan.
+///
+/// # Arguments
+/// - `receiver`: Borrower contract address (string)
+/// - `amount`: Amount to lend (U256)
+/// - `data`: Opaque bytes forwarded to the receiver (bytes)
+///
+/// # Events
+/// - `FLASH_LOAN SUCCESS`
+#[massa_export]
+pub fn flashLoan(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let receiver = args.next_string().expect_arg("receiver");
+    let amount = args.next_u256().expect_arg("amount");
+    let data = args.next_bytes().expect_arg("data");
+
+    when_not_paused();
+    ensure!(amount <= max_flash_loan(), errors::CAP_EXCEEDED, "Flash loan amount exceeds maxFlashLoan");
+
+    let fee = get_flash_fee();
+    let repayment = callbacks::flash_repayment(amount, fee).or_fail(errors::OVERFLOW, "Flash loan repayment overflow");
+    let initiator = context::caller();
+    let token = context::callee();
+
+    mint_to(&receiver, amount);
+
+    let mut hook_args = Args::new();
+    hook_args
+        .add_string(&initiator)
+        .add_u256(amount)
+        .add_u256(fee)
+        .add_bytes(&data);
+    // Snapshots taken from within the callback would count the loan
+    FLASH_LOAN_ACTIVE.set(&true);
+    let result = calls::call(&receiver, FLASH_LOAN_HOOK, hook_args);
+    FLASH_LOAN_ACTIVE.remove();
+    ensure!(result == FLASH_LOAN_CALLBACK_SUCCESS, errors::CALLBACK_FAILED, "Flash loan callback failed");
+
+    // Spend the receiver's allowance and burn the repayment
+    let allowance = get_allowance(&receiver, &token);
+    ensure!(
+        allowance >= repayment,
+        errors::INSUFFICIENT_ALLOWANCE,
+        fields("needed={},have={}", repayment, allowance),
+        "Flash loan repayment not approved"
+    );
+    set_allowance(&receiver, &token, allowance.checked_sub(repayment).or_fail(errors::OVERFLOW, "Allowance underflow"));
+    token::burn(&Erc20, &receiver, repayment);
+
+    abi::generate_event(FLASH_LOAN_EVENT);
+
+    Vec::new()
+}
+
+/// Returns the maximum flash loan amount (u256 bytes).
+#[massa_export]
+pub fn maxFlashLoan(_binary_args: &[u8]) -> Vec<u8> {
+    max_flash_loan().to_le_bytes().to_vec()
+}
+
+/// Returns the fee charged for a flash loan of `amount` (u256 bytes).
+///
+/// # Arguments
+/// - `amount`: Loan amount (U256)
+#[massa_export]
+pub fn flashFee(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let amount = args.next_u256().expect_arg("amount");
+    ensure!(amount <= max_flash_loan(), errors::CAP_EXCEEDED, "Flash loan amount exceeds maxFlashLoan");
+    get_flash_fee().to_le_bytes().to_vec()
+}
+
+/// Set the flat flash loan fee (owner only).
+///
+/// # Arguments
+/// - `fee`: Fee charged per loan (U256)
+#[massa_export]
+pub fn setFlashFee(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let fee = args.next_u256().expect_arg("fee");
+    set_flash_fee(fee);
+
+    Vec::new()
+}
+
+// ============================================================================
+// Burnable
+// ============================================================================
+
+/// Burn tokens from caller's balance.
+///
+/// # Arguments
+/// - `amount`: Amount to burn (U256)
+///
+/// # Events
+/// - `BURN_SUCCESS`
+#[massa_export]
+pub fn burn(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let amount = args.next_u256().expect_arg("amount");
+
+    when_not_paused();
+
+    let caller = context::caller();
+    token::burn(&Erc20, &caller, amount);
+
+    Vec::new()
+}
+
+/// Burn tokens from owner using spender's allowance.
+///
+/// # Arguments
+/// - `owner`: Owner address (string)
+/// - `amount`: Amount to burn (U256)
+///
+/// # Events
+/// - `BURN_SUCCESS`
+#[massa_export]
+pub fn burnFrom(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("owner");
+    let amount = args.next_u256().expect_arg("amount");
+
+    when_not_paused();
+
+    let spender = context::caller();
+    spend_allowance(&owner, &spender, amount, "burnFrom");
+    token::burn(&Erc20, &owner, amount);
+
+    Vec::new()
+}
+
+// ============================================================================
+// Vesting
+// ============================================================================
+
+/// Grant tokens that vest linearly over Massa periods (owner only).
+///
+/// `amount` is moved from the owner to the beneficiary immediately but stays
+/// locked until released. Nothing vests before `start + cliff`; everything is
+/// vested at `start + duration`.
+///
+/// # Arguments
+/// - `beneficiary`: Grant recipient (string)
+/// - `start`: Period at which vesting starts (u64)
+/// - `cliff`: Number of periods after `start` before anything vests (u64)
+/// - `duration`: Number of periods after `start` until fully vested (u64)
+/// - `amount`: Granted amount (U256)
+///
+/// # Events
+/// - `VESTING_CREATED:beneficiary`
+#[massa_export]
+pub fn createVestingSchedule(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let beneficiary = args.next_string().expect_arg("beneficiary");
+    let start = args.next_u64().expect_arg("start");
+    let cliff = args.next_u64().expect_arg("cliff");
+    let duration = args.next_u64().expect_arg("duration");
+    let amount = args.next_u256().expect_arg("amount");
+
+    ensure!(duration > 0, errors::INVALID_ARGUMENT, "Vesting duration is 0");
+    ensure!(cliff <= duration, errors::INVALID_ARGUMENT, "Vesting cliff is longer than its duration");
+    ensure!(get_vesting(&beneficiary).is_none(), errors::ALREADY_EXISTS, "Vesting schedule already exists");
+
+    let owner = context::caller();
+    move_balance(&owner, &beneficiary, amount);
+    abi::generate_event(&transfer_event(&owner, &beneficiary, amount));
+    set_vesting(
+        &beneficiary,
+        &VestingSchedule {
+            start,
+            cliff,
+            duration,
+            amount,
+            released: U256::ZERO,
+        },
+    );
+
+    abi::generate_event(&alloc::format!("{}:{}", VESTING_CREATED_EVENT, beneficiary));
+
+    Vec::new()
+}
+
+/// Returns the amount the beneficiary can release now (u256 bytes).
+///
+/// # Arguments
+/// - `beneficiary`: Grant recipient (string)
+#[massa_export]
+pub fn releasableAmount(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let beneficiary = args.next_string().expect_arg("beneficiary");
+
+    let releasable = match get_vesting(&beneficiary) {
+        Some(schedule) => schedule.releasable_amount(context::current_period()),
+        None => U256::ZERO,
+    };
+    releasable.to_le_bytes().to_vec()
+}
+
+/// Unlock the caller's vested tokens.
+///
+/// # Events
+/// - `VESTING_RELEASED:beneficiary`
+#[massa_export]
+pub fn release(_binary_args: &[u8]) -> Vec<u8> {
+    let beneficiary = context::caller();
+    let mut schedule = get_vesting(&beneficiary).or_fail(errors::NOT_FOUND, "No vesting schedule");
+
+    let releasable = schedule.releasable_amount(context::current_period());
+    ensure!(releasable > U256::ZERO, errors::INVALID_STATE, "No tokens to release");
+
+    schedule.released = schedule.released.checked_add(releasable).or_fail(errors::OVERFLOW, "Release overflow");
+    set_vesting(&beneficiary, &schedule);
+
+    abi::generate_event(&alloc::format!("{}:{}", VESTING_RELEASED_EVENT, beneficiary));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Token Locks
+// ============================================================================
+
+/// Lock part of a balance until a given period (owner, or the account itself).
+///
+/// Locking an account that already has an active lock adds to the locked amount
+/// and keeps the later of the two unlock periods.
+///
+/// # Arguments
+/// - `address`: Account to lock (string)
+/// - `amount`: Amount to lock (U256)
+/// - `untilPeriod`: Period from which the tokens are transferable again (u64)
+///
+/// # Events
+/// - `LOCK_TOKENS:address`
+#[massa_export]
+pub fn lockTokens(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    let amount = args.next_u256().expect_arg("amount");
+    let until_period = args.next_u64().expect_arg("untilPeriod");
+
+    when_not_decommissioned();
+    let caller = context::caller();
+    ensure!(
+        caller == address || is_owner_caller(&caller),
+        errors::UNAUTHORIZED,
+        fields("caller={}", caller),
+        "Caller is neither the owner nor the account"
+    );
+    ensure!(until_period > context::current_period(), errors::INVALID_ARGUMENT, "Lock period is in the past");
+
+    let new_amount = active_lock(&address).checked_add(amount).or_fail(errors::OVERFLOW, "Lock amount overflow");
+    let new_until = until_period.max(get_lock(&address).until_period);
+
+    let balance = get_balance(&address);
+    let unlocked = balance.saturating_sub(locked_balance(&address));
+    ensure!(
+        amount <= unlocked,
+        errors::INSUFFICIENT_BALANCE,
+        fields("needed={},have={}", amount, unlocked),
+        "Lock amount exceeds the transferable balance"
+    );
+
+    set_lock(
+        &address,
+        &TokenLock {
+            amount: new_amount,
+            until_period: new_until,
+        },
+    );
+
+    abi::generate_event(&alloc::format!("{}:{}", LOCK_EVENT, address));
+
+    Vec::new()
+}
+
+/// Release part of an active lock early (owner only).
+///
+/// # Arguments
+/// - `address`: Locked account (string)
+/// - `amount`: Amount to unlock (U256)
+///
+/// # Events
+/// - `UNLOCK_TOKENS:address`
+#[massa_export]
+pub fn unlockTokens(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    let amount = args.next_u256().expect_arg("amount");
+
+    let new_amount = active_lock(&address)
+        .checked_sub(amount)
+        .or_fail(errors::INVALID_ARGUMENT, "Unlock amount exceeds the locked amount");
+    set_lock(
+        &address,
+        &TokenLock {
+            amount: new_amount,
+            until_period: get_lock(&address).until_period,
+        },
+    );
+
+    abi::generate_event(&alloc::format!("{}:{}", UNLOCK_EVENT, address));
+
+    Vec::new()
+}
+
+/// Returns the non-transferable part of a balance (u256 bytes), including unreleased vesting.
+///
+/// # Arguments
+/// - `address`: Account address (string)
+#[massa_export]
+pub fn lockedBalanceOf(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("Address");
+    locked_balance(&address).to_le_bytes().to_vec()
+}
+
+/// Returns the transferable part of a balance (u256 bytes).
+///
+/// # Arguments
+/// - `address`: Account address (string)
+#[massa_export]
+pub fn transferableBalanceOf(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("Address");
+    let transferable = get_balance(&address).saturating_sub(locked_balance(&address));
+    transferable.to_le_bytes().to_vec()
+}
+
+// ============================================================================
+// Snapshots (owner or SNAPSHOT role)
+// ============================================================================
+
+/// Take a snapshot of all balances and the total supply (owner or `SNAPSHOT` role).
+///
+/// Returns the new snapshot id (u64, 8 bytes LE). Snapshot ids start at 1.
+/// Fails while a flash loan's callback runs, so loans never show in a snapshot.
+///
+/// # Events
+/// - `SNAPSHOT:id`
+#[massa_export]
+pub fn snapshot(_binary_args: &[u8]) -> Vec<u8> {
+    only_owner_or_role(SNAPSHOT_ROLE);
+    ensure!(!FLASH_LOAN_ACTIVE.get_or(false), errors::INVALID_STATE, "Snapshots cannot be taken during a flash loan");
+
+    let id = get_snapshot_id() + 1;
+    set_snapshot_id(id);
+
+    abi::generate_event(&alloc::format!("{}:{}", SNAPSHOT_EVENT, id));
+
+    id.to_le_bytes().to_vec()
+}
+
+/// Returns the latest snapshot id (u64, 8 bytes LE), 0 if none was taken.
+#[massa_export]
+pub fn currentSnapshotId(_binary_args: &[u8]) -> Vec<u8> {
+    get_snapshot_id().to_le_bytes().to_vec()
+}
+
+/// Returns the balance of an account at a snapshot (u256 bytes).
+///
+/// # Arguments
+/// - `address`: Account address (string)
+/// - `snapshotId`: Snapshot id (u64)
+#[massa_export]
+pub fn balanceOfAt(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("Address");
+    let snapshot_id = args.next_u64().expect_arg("snapshotId");
+
+    let balance = Checkpoints::Balance(&address).value_at(snapshot_id, get_balance(&address));
+    balance.to_le_bytes().to_vec()
+}
+
+/// Returns the total supply at a snapshot (u256 bytes).
+///
+/// # Arguments
+/// - `snapshotId`: Snapshot id (u64)
+#[massa_export]
+pub fn totalSupplyAt(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let snapshot_id = args.next_u64().expect_arg("snapshotId");
+
+    let supply = Checkpoints::Supply.value_at(snapshot_id, get_total_supply());
+    supply.to_le_bytes().to_vec()
+}
+
+// ============================================================================
+// Pausable (PAUSER role)
+// ============================================================================
+
+/// Pause the token (`PAUSER` role only).
+///
+/// While paused, `transfer`, `transferFrom`, `mint`, `burn` and `burnFrom` panic.
+///
+/// # Events
+/// - `PAUSED`
+#[massa_export]
+pub fn pause(_binary_args: &[u8]) -> Vec<u8> {
+    only_role(PAUSER_ROLE);
+    ensure!(!is_paused(), errors::PAUSED, "Token is already paused");
+
+    set_paused(true);
+
+    abi::generate_event(PAUSE_EVENT);
+
+    Vec::new()
+}
+
+/// Unpause the token (`PAUSER` role only).
+///
+/// # Events
+/// - `UNPAUSED`
+#[massa_export]
+pub fn unpause(_binary_args: &[u8]) -> Vec<u8> {
+    only_role(PAUSER_ROLE);
+    ensure!(is_paused(), errors::NOT_PAUSED, "Token is not paused");
+
+    set_paused(false);
+
+    abi::generate_event(UNPAUSE_EVENT);
+
+    Vec::new()
+}
+
+/// Returns true (1) if the token is paused, false (0) otherwise.
+#[massa_export]
+pub fn paused(_binary_args: &[u8]) -> Vec<u8> {
+    if is_paused() {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+// ============================================================================
+// Access Control (owner only)
+// ============================================================================
+
+/// Grant a role to an address (owner only).
+///
+/// Known roles are the ones in `ROLES`: `MINTER`, `BURNER`, `PAUSER`, `COMPLIANCE`, `BRIDGE_MINTER`
+/// and `SNAPSHOT`. `BURNER` is reserved for integrations; `burn` and `burnFrom` stay permissionless as in MRC20.
+///
+/// # Arguments
+/// - `role`: Role name (string)
+/// - `address`: Account receiving the role (string)
+///
+/// # Events
+/// - `GRANT_ROLE:role:address`
+#[massa_export]
+pub fn grantRole(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let role = args.next_string().expect_arg("role");
+    let address = args.next_string().expect_arg("address");
+
+    assert_known_role(&role);
+    set_role(&role, &address, true);
+
+    abi::generate_event(&alloc::format!("{}:{}:{}", GRANT_ROLE_EVENT, role, address));
+
+    Vec::new()
+}
+
+/// Revoke a role from an address (owner only).
+///
+/// # Arguments
+/// - `role`: Role name (string)
+/// - `address`: Account losing the role (string)
+///
+/// # Events
+/// - `REVOKE_ROLE:role:address`
+#[massa_export]
+pub fn revokeRole(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let role = args.next_string().expect_arg("role");
+    let address = args.next_string().expect_arg("address");
+
+    assert_known_role(&role);
+    set_role(&role, &address, false);
+
+    abi::generate_event(&alloc::format!("{}:{}:{}", REVOKE_ROLE_EVENT, role, address));
+
+    Vec::new()
+}
+
+/// Returns true (1) if the address holds the role, false (0) otherwise.
+///
+/// # Arguments
+/// - `role`: Role name (string)
+/// - `address`: Address to check (string)
+#[massa_export]
+pub fn hasRole(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let role = args.next_string().expect_arg("role");
+    let address = args.next_string().expect_arg("address");
+
+    if has_role(&role, &address) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+// ============================================================================
+// Allowlist (owner or COMPLIANCE role)
+// ============================================================================
+
+/// Add an address to the transfer allowlist (owner or `COMPLIANCE` role).
+///
+/// # Arguments
+/// - `address`: Address to allow (string)
+///
+/// # Events
+/// - `ALLOWLIST_ADD:address`
+#[massa_export]
+pub fn addToAllowlist(binary_args: &[u8]) -> Vec<u8> {
+    only_owner_or_role(COMPLIANCE_ROLE);
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    set_allowlisted(&address, true);
+
+    abi::generate_event(&alloc::format!("{}:{}", ALLOWLIST_ADD_EVENT, address));
+
+    Vec::new()
+}
+
+/// Remove an address from the transfer allowlist (owner or `COMPLIANCE` role).
+///
+/// # Arguments
+/// - `address`: Address to remove (string)
+///
+/// # Events
+/// - `ALLOWLIST_REMOVE:address`
+#[massa_export]
+pub fn removeFromAllowlist(binary_args: &[u8]) -> Vec<u8> {
+    only_owner_or_role(COMPLIANCE_ROLE);
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    set_allowlisted(&address, false);
+
+    abi::generate_event(&alloc::format!("{}:{}", ALLOWLIST_REMOVE_EVENT, address));
+
+    Vec::new()
+}
+
+/// Returns true (1) if the address is allowlisted, false (0) otherwise.
+///
+/// # Arguments
+/// - `address`: Address to check (string)
+#[massa_export]
+pub fn isAllowlisted(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+
+    if is_allowlisted(&address) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+/// Returns true (1) if transfers are restricted to allowlisted addresses, false (0) otherwise.
+#[massa_export]
+pub fn allowlistMode(_binary_args: &[u8]) -> Vec<u8> {
+    if is_allowlist_mode() {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+// ============================================================================
+// Transfer Limits (owner only)
+// ============================================================================
+
+/// Set the largest amount a single transfer may move (owner only). Zero disables the limit.
+///
+/// # Arguments
+/// - `maxTxAmount`: Maximum transfer amount (U256)
+#[massa_export]
+pub fn setMaxTxAmount(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let max_tx_amount = args.next_u256().expect_arg("maxTxAmount");
+    MAX_TX_AMOUNT.set(&max_tx_amount);
+
+    Vec::new()
+}
+
+/// Set the largest balance a transfer may leave the recipient with (owner only). Zero disables the limit.
+///
+/// # Arguments
+/// - `maxWalletBalance`: Maximum recipient balance (U256)
+#[massa_export]
+pub fn setMaxWalletBalance(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let max_wallet_balance = args.next_u256().expect_arg("maxWalletBalance");
+    MAX_WALLET_BALANCE.set(&max_wallet_balance);
+
+    Vec::new()
+}
+
+/// Set the number of periods a sender must wait between transfers (owner only). Zero disables the cooldown.
+///
+/// # Arguments
+/// - `cooldownPeriods`: Cooldown in periods (u64)
+#[massa_export]
+pub fn setTransferCooldown(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let cooldown = args.next_u64().expect_arg("cooldownPeriods");
+    TRANSFER_COOLDOWN.set(&cooldown);
+
+    Vec::new()
+}
+
+/// Exempt an address from transfer limits, e.g. a DEX pool or the treasury (owner only).
+///
+/// # Arguments
+/// - `address`: Address to update (string)
+/// - `exempt`: Whether the address bypasses the limits (bool)
+#[massa_export]
+pub fn setLimitExempt(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    let exempt = args.next_bool().expect_arg("exempt");
+    set_limit_exempt(&address, exempt);
+
+    Vec::new()
+}
+
+/// Returns the maximum transfer amount (u256 bytes), zero if unlimited.
+#[massa_export]
+pub fn maxTxAmount(_binary_args: &[u8]) -> Vec<u8> {
+    get_max_tx_amount().to_le_bytes().to_vec()
+}
+
+/// Returns the maximum recipient balance (u256 bytes), zero if unlimited.
+#[massa_export]
+pub fn maxWalletBalance(_binary_args: &[u8]) -> Vec<u8> {
+    get_max_wallet_balance().to_le_bytes().to_vec()
+}
+
+/// Returns the transfer cooldown in periods (u64, 8 bytes LE), zero if disabled.
+#[massa_export]
+pub fn transferCooldownPeriods(_binary_args: &[u8]) -> Vec<u8> {
+    get_transfer_cooldown().to_le_bytes().to_vec()
+}
+
+/// Returns true (1) if the address bypasses transfer limits, false (0) otherwise.
+///
+/// # Arguments
+/// - `address`: Address to check (string)
+#[massa_export]
+pub fn isLimitExempt(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+
+    if is_limit_exempt(&address) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+// ============================================================================
+// Account Freeze (owner only)
+// ============================================================================
+
+/// Freeze an account (owner only).
+///
+/// Frozen accounts can neither send nor receive tokens through `transfer`
+/// or `transferFrom`.
+///
+/// # Arguments
+/// - `address`: Account to freeze (string)
+///
+/// # Events
+/// - `FREEZE_ACCOUNT:address`
+#[massa_export]
+pub fn freezeAccount(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+
+    ensure!(!is_frozen(&address), errors::FROZEN, fields("account={}", address), "Account is already frozen");
+    set_frozen(&address, true);
+
+    abi::generate_event(&alloc::format!("{}:{}", FREEZE_EVENT, address));
+
+    Vec::new()
+}
+
+/// Unfreeze an account (owner only).
+///
+/// # Arguments
+/// - `address`: Account to unfreeze (string)
+///
+/// # Events
+/// - `UNFREEZE_ACCOUNT:address`
+#[massa_export]
+pub fn unfreezeAccount(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+
+    ensure!(is_frozen(&address), errors::NOT_FROZEN, fields("account={}", address), "Account is not frozen");
+    set_frozen(&address, false);
+
+    abi::generate_event(&alloc::format!("{}:{}", UNFREEZE_EVENT, address));
+
+    Vec::new()
+}
+
+/// Returns true (1) if the address is frozen, false (0) otherwise.
+///
+/// # Arguments
+/// - `address`: Address to check (string)
+#[massa_export]
+pub fn isFrozen(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+
+    if is_frozen(&address) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+// ============================================================================
+// Rescue (owner only)
+// ============================================================================
+
+/// Recover MRC20 tokens sent to this contract's address (owner only).
+///
+/// When `tokenAddress` is this contract, the tokens held by the contract's own
+/// balance are moved directly; otherwise `transfer(to, amount)` is called on
+/// the foreign token, which must return true (a single byte 1, as `Args(true)`).
+/// Tokens whose `transfer` returns nothing or false fail with `CALLBACK_FAILED`.
+///
+/// # Arguments
+/// - `tokenAddress`: Token contract address (string)
+/// - `to`: Recipient address (string)
+/// - `amount`: Amount to recover (U256)
+///
+/// # Events
+/// - `RESCUE_TOKEN:tokenAddress:to`
+#[massa_export]
+pub fn rescueToken(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token = args.next_string().expect_arg("tokenAddress");
+    let to = args.next_string().expect_arg("to");
+    let amount = args.next_u256().expect_arg("amount");
+
+    let this = context::callee();
+    if token == this {
+        let available = get_balance(&this).saturating_sub(get_stream_escrow());
+        ensure!(
+            amount <= available,
+            errors::INSUFFICIENT_BALANCE,
+            fields("needed={},have={}", amount, available),
+            "Rescue amount exceeds the tokens not escrowed by streams"
+        );
+        move_balance(&this, &to, amount);
+        abi::generate_event(&transfer_event(&this, &to, amount));
+    } else {
+        let mut transfer_args = Args::new();
+        transfer_args.add_string(&to).add_u256(amount);
+        let transferred = calls::call_bool(&token, "transfer", transfer_args);
+        ensure!(
+            transferred,
+            errors::CALLBACK_FAILED,
+            fields("target={},function=transfer", token),
+            "Token transfer returned false"
+        );
+    }
+
+    abi::generate_event(&alloc::format!("{}:{}:{}", RESCUE_TOKEN_EVENT, token, to));
+
+    Vec::new()
+}
+
+/// Recover MAS coins held by this contract (owner only).
+///
+/// # Arguments
+/// - `to`: Recipient address (string)
+/// - `amount`: Amount in nanoMAS (u64)
+///
+/// # Events
+/// - `RESCUE_COINS:to`
+#[massa_export]
+pub fn rescueCoins(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let to = args.next_string().expect_arg("to");
+    let amount = args.next_u64().expect_arg("amount");
+
+    let available = context::balance().saturating_sub(get_subscription_fee_reserve());
+    ensure!(
+        amount <= available,
+        errors::INSUFFICIENT_BALANCE,
+        fields("needed={},have={}", amount, available),
+        "Rescue amount exceeds the contract balance"
+    );
+    abi::transfer_coins(&to, amount);
+
+    abi::generate_event(&alloc::format!("{}:{}", RESCUE_COINS_EVENT, to));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Storage Migration (owner only)
+// ============================================================================
+
+/// Upgrade the storage layout to the version written by this build (owner only).
+///
+/// Runs one step per version between the stored version and the current one.
+/// Does nothing if the storage is already up to date.
+///
+/// # Events
+/// - `MIGRATE:from=...,to=...` for each step
+#[massa_export]
+pub fn migrate(_binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut version = get_storage_version();
+    ensure!(
+        version <= STORAGE_VERSION,
+        errors::INVALID_STATE,
+        fields("version={}", version),
+        "Storage version {} is newer than this contract",
+        version
+    );
+
+    while version < STORAGE_VERSION {
+        migrate_storage_step(version);
+        set_storage_version(version + 1);
+        abi::generate_event(&alloc::format!("{}:from={},to={}", MIGRATE_EVENT, version, version + 1));
+        version += 1;
+    }
+
+    Vec::new()
+}
+
+/// Returns the storage layout version (u32, 4 bytes LE).
+#[massa_export]
+pub fn storageVersion(_binary_args: &[u8]) -> Vec<u8> {
+    get_storage_version().to_le_bytes().to_vec()
+}
+
+// ============================================================================
+// Decommission (owner only)
+// ============================================================================
+
+/// Permanently retire the contract (owner only), then delete its datastore page by page.
+///
+/// The first call ends the active subscriptions, refunding their fee budgets,
+/// sends the remaining MAS outside the subscription fee reserve to the owner
+/// and sets the `DECOMMISSIONED` flag, which blocks transfers, approvals,
+/// minting, burning and ownership changes. This cannot be undone.
+///
+/// Each call then deletes up to `limit` datastore keys. Deleted keys leave the
+/// listing, so the next call picks up where the previous one stopped. Once
+/// decommissioned, anyone can delete the next page until only the flag is left.
+///
+/// # Arguments
+/// - `limit`: Maximum number of keys to delete in this call (u32)
+///
+/// # Returns
+/// - Number of keys left to delete besides the flag (u64, 8 bytes LE)
+///
+/// # Events
+/// - `DECOMMISSIONED:owner` on the first call
+#[massa_export]
+pub fn decommission(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let limit = args.next_u32().expect_arg("limit");
+    ensure!(limit > 0, errors::INVALID_ARGUMENT, "Limit must be positive");
+
+    if is_decommissioned() {
+        let remaining = delete_datastore_page(limit);
+        return remaining.to_le_bytes().to_vec();
+    }
+
+    only_owner();
+
+    let owner = get_owner().unwrap_or_else(context::caller);
+    for id in 0..SUBSCRIPTION_COUNT.get_or(0) {
+        if let Some(mut subscription) = get_subscription(id).filter(|subscription| subscription.active) {
+            cancel_subscription_call(&subscription);
+            close_subscription(&mut subscription);
+        }
+    }
+    let coins = context::balance().saturating_sub(get_subscription_fee_reserve());
+
+    DECOMMISSIONED.set(&true);
+    let remaining = delete_datastore_page(limit);
+
+    if coins > 0 {
+        abi::transfer_coins(&owner, coins);
+    }
+
+    abi::generate_event(&alloc::format!("{}:{}", DECOMMISSION_EVENT, owner));
+
+    remaining.to_le_bytes().to_vec()
+}
+
+/// Delete up to `limit` keys other than the `DECOMMISSIONED` flag and return how many are left.
+///
+/// Fails once only the flag is left, so a finished decommission cannot be repeated.
+fn delete_datastore_page(limit: u32) -> u64 {
+    let keys: Vec<Vec<u8>> = storage::get_keys(&[])
+        .into_iter()
+        .filter(|key| key.as_slice() != DECOMMISSIONED.key())
+        .collect();
+    ensure!(!keys.is_empty(), errors::DECOMMISSIONED, "Contract is decommissioned");
+
+    let page = keys.len().min(limit as usize);
+    for key in &keys[..page] {
+        storage::delete(key);
+    }
+    (keys.len() - page) as u64
+}
+
+/// Returns true (1) if the contract is decommissioned, false (0) otherwise.
+#[massa_export]
+pub fn decommissioned(_binary_args: &[u8]) -> Vec<u8> {
+    if is_decommissioned() {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+// ============================================================================
+// Ownership
+// ============================================================================
+
+/// Set the contract owner (only current owner can call, or anyone if no owner set).
+///
+/// # Arguments
+/// - `newOwner`: New owner address (string)
+///
+/// # Events
+/// - `CHANGE_OWNER:newOwner`
+#[massa_export]
+pub fn setOwner(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let new_owner = args.next_string().expect_arg("newOwnerAddress");
+    when_not_decommissioned();
+
+    // If owner exists, only owner can change
+    if get_owner().is_some() {
+        only_owner();
+    }
+    
+    set_owner_internal(&new_owner);
+    
+    abi::generate_event(&alloc::format!("{}:{}", CHANGE_OWNER_EVENT, new_owner));
+
+    Vec::new()
+}
+
+/// Propose a new owner (owner only).
+///
+/// The proposed address becomes owner only once it calls `acceptOwnership`.
+/// A new proposal replaces any previous pending one.
+///
+/// # Arguments
+/// - `newOwner`: Proposed owner address (string)
+///
+/// # Events
+/// - `PROPOSE_OWNER:newOwner`
+#[massa_export]
+pub fn proposeOwner(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let new_owner = args.next_string().expect_arg("newOwnerAddress");
+    if new_owner.is_empty() {
+        errors::invalid_argument("newOwnerAddress");
+    }
+
+    set_pending_owner(&new_owner);
+
+    abi::generate_event(&alloc::format!("{}:{}", PROPOSE_OWNER_EVENT, new_owner));
+
+    Vec::new()
+}
+
+/// Accept a pending ownership proposal (pending owner only).
+///
+/// # Events
+/// - `ACCEPT_OWNER:newOwner`
+/// - `CHANGE_OWNER:newOwner`
+#[massa_export]
+pub fn acceptOwnership(_binary_args: &[u8]) -> Vec<u8> {
+    let new_owner = get_pending_owner().or_fail(errors::NOT_FOUND, "No pending owner");
+    ensure!(context::caller() == new_owner, errors::UNAUTHORIZED, "Caller is not the pending owner");
+
+    set_owner_internal(&new_owner);
+    set_pending_owner("");
+
+    abi::generate_event(&alloc::format!("{}:{}", ACCEPT_OWNER_EVENT, new_owner));
+    abi::generate_event(&alloc::format!("{}:{}", CHANGE_OWNER_EVENT, new_owner));
+
+    Vec::new()
+}
+
+/// Returns the pending owner address (raw bytes), or empty bytes if none.
+#[massa_export]
+pub fn pendingOwner(_binary_args: &[u8]) -> Vec<u8> {
+    match get_pending_owner() {
+        Some(pending_owner) => pending_owner.into_bytes(),
+        None => Vec::new(),
+    }
+}
+
+/// Returns the owner address (raw bytes).
+#[massa_export]
+pub fn ownerAddress(_binary_args: &[u8]) -> Vec<u8> {
+    if !OWNER.exists() {
+        return Vec::new();
+    }
+    storage::get(OWNER.key())
+}
+
+/// Returns true (1) if address is owner, false (0) otherwise.
+///
+/// # Arguments
+/// - `address`: Address to check (string)
+#[massa_export]
+pub fn isOwner(binary_args: &[u8]) -> Vec<u8> {
+    if !OWNER.exists() {
+        return alloc::vec![0u8];
+    }
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    
+    if is_owner_check(&address) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+// ============================================================================
+// Multisig Ownership
+// ============================================================================
+
+/// Add a multisig owner (owner only, or a confirmed proposal in multisig mode).
+///
+/// # Arguments
+/// - `address`: Owner to add (string)
+///
+/// # Events
+/// - `MULTISIG_ADD_OWNER:address`
+#[massa_export]
+pub fn addOwner(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    if address.is_empty() {
+        errors::invalid_argument("address");
+    }
+    ensure!(
+        !is_multisig_owner(&address),
+        errors::ALREADY_EXISTS,
+        fields("account={}", address),
+        "Address is already a multisig owner"
+    );
+
+    MULTISIG_OWNERS.set(&address, &true);
+    MULTISIG_OWNER_COUNT.set(&(get_multisig_owner_count() + 1));
+    bump_multisig_epoch();
+
+    abi::generate_event(&alloc::format!("{}:{}", ADD_OWNER_EVENT, address));
+
+    Vec::new()
+}
+
+/// Remove a multisig owner (owner only, or a confirmed proposal in multisig mode).
+///
+/// The remaining owners must still be able to reach the threshold.
+///
+/// # Arguments
+/// - `address`: Owner to remove (string)
+///
+/// # Events
+/// - `MULTISIG_REMOVE_OWNER:address`
+#[massa_export]
+pub fn removeOwner(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    ensure!(is_multisig_owner(&address), errors::NOT_FOUND, fields("account={}", address), "Address is not a multisig owner");
+
+    let owner_count = get_multisig_owner_count() - 1;
+    ensure!(
+        owner_count >= get_threshold(),
+        errors::INVALID_STATE,
+        "Removing the owner would make the threshold unreachable"
+    );
+
+    MULTISIG_OWNERS.set(&address, &false);
+    MULTISIG_OWNER_COUNT.set(&owner_count);
+    bump_multisig_epoch();
+
+    abi::generate_event(&alloc::format!("{}:{}", REMOVE_OWNER_EVENT, address));
+
+    Vec::new()
+}
+
+/// Set the number of confirmations a proposal needs (owner only, or a confirmed proposal in multisig mode).
+///
+/// A non-zero threshold enables multisig mode; zero returns to single-owner mode.
+///
+/// # Arguments
+/// - `threshold`: Required confirmations (u32)
 ///
-/// The caller becomes the owner and receives all initial tokens.
+/// # Events
+/// - `MULTISIG_THRESHOLD:threshold`
 #[massa_export]
-pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
-    assert!(context::is_deploying_contract(), "Can only be called during deployment");
+pub fn setThreshold(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
 
     let mut args = Args::from_bytes(binary_args.to_vec());
-    let name = args.next_string().unwrap_or_else(|_| String::from("MassaToken"));
-    let symbol = args.next_string().unwrap_or_else(|_| String::from("MT"));
-    let decimals = args.next_u8().unwrap_or(18);
-    let total_supply = args.next_u256().unwrap_or_else(|_| U256::from(1_000_000_000_000_000_000u64));
-
-    // Store token metadata (raw bytes, matching AS format)
-    storage::set(NAME_KEY, name.as_bytes());
-    storage::set(SYMBOL_KEY, symbol.as_bytes());
-    storage::set(DECIMALS_KEY, &[decimals]);
-    set_total_supply(total_supply);
+    let threshold = args.next_u32().expect_arg("threshold");
+    ensure!(
+        threshold <= get_multisig_owner_count(),
+        errors::INVALID_ARGUMENT,
+        fields("threshold={},owners={}", threshold, get_multisig_owner_count()),
+        "Threshold exceeds the number of multisig owners"
+    );
 
-    // Set owner and mint initial supply to caller
-    let caller = context::caller();
-    set_owner_internal(&caller);
-    set_balance(&caller, total_supply);
+    MULTISIG_THRESHOLD.set(&threshold);
+    bump_multisig_epoch();
 
-    // Emit CHANGE_OWNER event (matching AS format: "CHANGE_OWNER:address")
-    abi::generate_event(&alloc::format!("{}:{}", CHANGE_OWNER_EVENT, caller));
+    abi::generate_event(&alloc::format!("{}:{}", THRESHOLD_EVENT, threshold));
 
     Vec::new()
 }
 
-// ============================================================================
-// Token Attributes (read-only)
-// ============================================================================
-
-/// Returns the version of this smart contract.
+/// Propose an admin call on this contract (multisig owner only). The proposer confirms it.
+///
+/// # Arguments
+/// - `operation`: Entrypoint to call, e.g. `mint` or `pause` (string)
+/// - `args`: Serialized arguments for the entrypoint (bytes)
+///
+/// # Returns
+/// - Proposal id (u64, 8 bytes LE)
+///
+/// # Events
+/// - `MULTISIG_PROPOSE:id=...,operation=...`
+/// - `MULTISIG_CONFIRM:id=...,owner=...`
 #[massa_export]
-pub fn version(_binary_args: &[u8]) -> Vec<u8> {
-    VERSION.to_vec()
-}
+pub fn proposeOperation(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(is_multisig_mode(), errors::INVALID_STATE, "Multisig mode is not enabled");
+    only_multisig_owner();
 
-/// Returns the name of the token (raw bytes, not Args-wrapped).
-#[massa_export]
-pub fn name(_binary_args: &[u8]) -> Vec<u8> {
-    storage::get(NAME_KEY)
-}
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let operation = args.next_string().expect_arg("operation");
+    let operation_args = args.next_bytes().expect_arg("args");
+    if operation.is_empty() {
+        errors::invalid_argument("operation");
+    }
 
-/// Returns the symbol of the token (raw bytes, not Args-wrapped).
-#[massa_export]
-pub fn symbol(_binary_args: &[u8]) -> Vec<u8> {
-    storage::get(SYMBOL_KEY)
-}
+    let id = MULTISIG_PROPOSAL_COUNT.get_or(0);
+    MULTISIG_PROPOSAL_COUNT.set(&(id + 1));
 
-/// Returns the decimals of the token (raw bytes, not Args-wrapped).
-#[massa_export]
-pub fn decimals(_binary_args: &[u8]) -> Vec<u8> {
-    storage::get(DECIMALS_KEY)
-}
+    let mut proposal = Proposal {
+        epoch: get_multisig_epoch(),
+        confirmations: 0,
+        executed: false,
+        operation,
+        args: operation_args,
+    };
+    abi::generate_event(&alloc::format!("{}:id={},operation={}", PROPOSE_EVENT, id, proposal.operation));
+    confirm_proposal(id, &mut proposal);
 
-/// Returns the total supply (raw u256 bytes, not Args-wrapped).
-#[massa_export]
-pub fn totalSupply(_binary_args: &[u8]) -> Vec<u8> {
-    storage::get(TOTAL_SUPPLY_KEY)
+    id.to_le_bytes().to_vec()
 }
 
-// ============================================================================
-// Balance
-// ============================================================================
-
-/// Returns the balance of an account (u256 bytes).
+/// Confirm a pending proposal (multisig owner only).
 ///
 /// # Arguments
-/// - `address`: Account address (string)
+/// - `id`: Proposal id (u64)
+///
+/// # Events
+/// - `MULTISIG_CONFIRM:id=...,owner=...`
 #[massa_export]
-pub fn balanceOf(binary_args: &[u8]) -> Vec<u8> {
+pub fn confirmOperation(binary_args: &[u8]) -> Vec<u8> {
+    only_multisig_owner();
+
     let mut args = Args::from_bytes(binary_args.to_vec());
-    let address = args.next_string().expect("Address argument is missing or invalid");
-    let balance = get_balance(&address);
-    balance.to_le_bytes().to_vec()
-}
+    let id = args.next_u64().expect_arg("id");
+    let mut proposal = get_proposal(id).or_fail(errors::NOT_FOUND, "Proposal does not exist");
+    confirm_proposal(id, &mut proposal);
 
-// ============================================================================
-// Transfer
-// ============================================================================
+    Vec::new()
+}
 
-/// Transfers tokens from caller to recipient.
+/// Execute a proposal that reached the threshold (multisig owner only).
+///
+/// The contract calls the proposed entrypoint on itself, which passes the admin checks.
 ///
 /// # Arguments
-/// - `to`: Recipient address (string)
-/// - `amount`: Amount to transfer (U256)
+/// - `id`: Proposal id (u64)
+///
+/// # Returns
+/// - The return value of the executed entrypoint
 ///
 /// # Events
-/// - `TRANSFER SUCCESS`
+/// - `MULTISIG_EXECUTE:id=...,operation=...`
 #[massa_export]
-pub fn transfer(binary_args: &[u8]) -> Vec<u8> {
+pub fn executeOperation(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(is_multisig_mode(), errors::INVALID_STATE, "Multisig mode is not enabled");
+    only_multisig_owner();
+
     let mut args = Args::from_bytes(binary_args.to_vec());
-    let to = args.next_string().expect("receiverAddress argument is missing or invalid");
-    let amount = args.next_u256().expect("amount argument is missing or invalid");
+    let id = args.next_u64().expect_arg("id");
+    let mut proposal = get_proposal(id).or_fail(errors::NOT_FOUND, "Proposal does not exist");
+    ensure!(!proposal.executed, errors::ALREADY_USED, fields("id={}", id), "Proposal is already executed");
+    ensure!(
+        proposal.epoch == get_multisig_epoch(),
+        errors::EXPIRED,
+        fields("id={}", id),
+        "Proposal is stale: owners changed"
+    );
+    ensure!(
+        proposal.confirmations >= get_threshold(),
+        errors::INVALID_STATE,
+        fields("id={},confirmations={},threshold={}", id, proposal.confirmations, get_threshold()),
+        "Proposal does not have enough confirmations"
+    );
 
-    let from = context::caller();
-    
-    assert!(from != to, "Transfer failed: cannot send tokens to own account");
+    // Mark as executed before the call so it cannot be replayed from within it
+    proposal.executed = true;
+    set_proposal(id, &proposal);
 
-    let from_balance = get_balance(&from);
-    let to_balance = get_balance(&to);
-    
-    assert!(from_balance >= amount, "Transfer failed: insufficient funds");
-    
-    let new_to_balance = to_balance.checked_add(amount).expect("Transfer failed: overflow");
-    let new_from_balance = from_balance.checked_sub(amount).expect("Transfer failed: underflow");
-    
-    set_balance(&from, new_from_balance);
-    set_balance(&to, new_to_balance);
+    let result = abi::call(&context::callee(), &proposal.operation, &proposal.args, 0);
 
-    abi::generate_event(TRANSFER_EVENT);
+    abi::generate_event(&alloc::format!("{}:id={},operation={}", EXECUTE_EVENT, id, proposal.operation));
 
-    Vec::new()
+    result
 }
 
-// ============================================================================
-// Allowance
-// ============================================================================
+/// Returns a proposal as Args(operation: string, args: bytes, confirmations: u32, executed: bool).
+///
+/// # Arguments
+/// - `id`: Proposal id (u64)
+#[massa_export]
+pub fn proposal(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+    let proposal = get_proposal(id).or_fail(errors::NOT_FOUND, "Proposal does not exist");
 
-/// Returns the allowance for owner/spender (u256 bytes).
+    let mut result = Args::new();
+    result
+        .add_string(&proposal.operation)
+        .add_bytes(&proposal.args)
+        .add_u32(proposal.confirmations)
+        .add_bool(proposal.executed);
+    result.into_bytes()
+}
+
+/// Returns true (1) if the owner confirmed the proposal, false (0) otherwise.
 ///
 /// # Arguments
+/// - `id`: Proposal id (u64)
 /// - `owner`: Owner address (string)
-/// - `spender`: Spender address (string)
 #[massa_export]
-pub fn allowance(binary_args: &[u8]) -> Vec<u8> {
+pub fn isConfirmed(binary_args: &[u8]) -> Vec<u8> {
     let mut args = Args::from_bytes(binary_args.to_vec());
-    let owner = args.next_string().expect("owner argument is missing or invalid");
-    let spender = args.next_string().expect("spenderAddress argument is missing or invalid");
-    
-    let amount = get_allowance(&owner, &spender);
-    amount.to_le_bytes().to_vec()
+    let id = args.next_u64().expect_arg("id");
+    let owner = args.next_string().expect_arg("owner");
+
+    if is_proposal_confirmed(id, &owner) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
 }
 
-/// Increases the allowance of the spender on the caller's account.
+/// Returns true (1) if the address is a multisig owner, false (0) otherwise.
 ///
 /// # Arguments
-/// - `spender`: Spender address (string)
-/// - `amount`: Amount to increase (U256)
-///
-/// # Events
-/// - `APPROVAL SUCCESS`
+/// - `address`: Address to check (string)
 #[massa_export]
-pub fn increaseAllowance(binary_args: &[u8]) -> Vec<u8> {
+pub fn isMultisigOwner(binary_args: &[u8]) -> Vec<u8> {
     let mut args = Args::from_bytes(binary_args.to_vec());
-    let spender = args.next_string().expect("spenderAddress argument is missing or invalid");
-    let amount = args.next_u256().expect("amount argument is missing or invalid");
+    let address = args.next_string().expect_arg("address");
 
-    let owner = context::caller();
-    let current = get_allowance(&owner, &spender);
-    
-    // If overflow, set to max (matching AS behavior)
-    let new_allowance = current.saturating_add(amount);
-    
-    set_allowance(&owner, &spender, new_allowance);
+    if is_multisig_owner(&address) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
 
-    abi::generate_event(APPROVAL_EVENT);
+/// Returns the confirmation threshold (u32, 4 bytes LE), zero in single-owner mode.
+#[massa_export]
+pub fn multisigThreshold(_binary_args: &[u8]) -> Vec<u8> {
+    get_threshold().to_le_bytes().to_vec()
+}
 
-    Vec::new()
+/// Returns the number of multisig owners (u32, 4 bytes LE).
+#[massa_export]
+pub fn multisigOwnerCount(_binary_args: &[u8]) -> Vec<u8> {
+    get_multisig_owner_count().to_le_bytes().to_vec()
 }
 
-/// Decreases the allowance of the spender on the caller's account.
+// ============================================================================
+// Subscriptions
+// ============================================================================
+//
+// Each subscription pulls a fixed amount from the subscriber every interval.
+// Payments run as deferred calls to `processSubscription` that reschedule
+// themselves, so no keeper is needed. Their fees come from the MAS the
+// subscriber sends to `createSubscription`.
+
+/// Subscribe to pay `amountPerInterval` to `payee` every `intervalPeriods` periods.
+///
+/// The first payment is made immediately. The coins sent with the call fund the
+/// deferred call fees of the following payments; the unused part is refunded
+/// when the subscription ends.
 ///
 /// # Arguments
-/// - `spender`: Spender address (string)
-/// - `amount`: Amount to decrease (U256)
+/// - `payee`: Recipient of the payments (string)
+/// - `amountPerInterval`: Amount paid per interval (U256)
+/// - `intervalPeriods`: Number of periods between payments (u64)
+///
+/// # Returns
+/// - Subscription id (u64, 8 bytes LE)
 ///
 /// # Events
-/// - `APPROVAL SUCCESS`
+/// - `SUBSCRIPTION_CREATED:id=...,subscriber=...,payee=...,amount=...,interval=...`
+/// - `TRANSFER SUCCESS` and `SUBSCRIPTION_PAYMENT:id=...,payment=...` for the first payment
 #[massa_export]
-pub fn decreaseAllowance(binary_args: &[u8]) -> Vec<u8> {
+pub fn createSubscription(binary_args: &[u8]) -> Vec<u8> {
     let mut args = Args::from_bytes(binary_args.to_vec());
-    let spender = args.next_string().expect("spenderAddress argument is missing or invalid");
-    let amount = args.next_u256().expect("amount argument is missing or invalid");
+    let payee = args.next_string().expect_arg("payee");
+    let amount_per_interval = args.next_u256().expect_arg("amountPerInterval");
+    let interval_periods = args.next_u64().expect_arg("intervalPeriods");
 
-    let owner = context::caller();
-    let current = get_allowance(&owner, &spender);
-    
-    // If underflow, set to zero (matching AS behavior)
-    let new_allowance = current.saturating_sub(amount);
-    
-    set_allowance(&owner, &spender, new_allowance);
+    when_not_paused();
+    if amount_per_interval == U256::ZERO {
+        errors::invalid_argument("amountPerInterval");
+    }
+    if interval_periods == 0 {
+        errors::invalid_argument("intervalPeriods");
+    }
 
-    abi::generate_event(APPROVAL_EVENT);
+    let subscriber = context::caller();
+    let fee_budget = context::transferred_coins();
+    set_subscription_fee_reserve(
+        get_subscription_fee_reserve()
+            .checked_add(fee_budget)
+            .or_fail(errors::OVERFLOW, "Subscription fee reserve overflow"),
+    );
 
-    Vec::new()
+    let id = SUBSCRIPTION_COUNT.get_or(0);
+    SUBSCRIPTION_COUNT.set(&(id + 1));
+
+    let mut subscription = Subscription {
+        subscriber,
+        payee,
+        amount_per_interval,
+        interval_periods,
+        next_period: context::current_period(),
+        payments: 0,
+        fee_budget,
+        active: true,
+        call_id: String::new(),
+    };
+    abi::generate_event(&alloc::format!(
+        "{}:id={},subscriber={},payee={},amount={},interval={}",
+        SUBSCRIPTION_CREATED_EVENT,
+        id,
+        subscription.subscriber,
+        subscription.payee,
+        amount_per_interval,
+        interval_periods
+    ));
+
+    pay_subscription(id, &mut subscription);
+    set_subscription(id, &subscription);
+
+    id.to_le_bytes().to_vec()
 }
 
-/// Transfers tokens from owner to recipient using spender's allowance.
+/// Collect a due payment. Only callable by the contract itself, through the
+/// deferred call registered by the previous payment.
+///
+/// A payment that cannot be made ends the subscription instead of failing the
+/// deferred call: while the token is paused, when either party is frozen or
+/// not allowlisted, when the subscriber cannot cover it or when it breaks a
+/// transfer limit. Runs on a decommissioned contract, or for a subscription
+/// that no longer exists, do nothing.
 ///
 /// # Arguments
-/// - `owner`: Owner address (string)
-/// - `recipient`: Recipient address (string)
-/// - `amount`: Amount to transfer (U256)
+/// - `id`: Subscription id (u64)
 ///
 /// # Events
-/// - `TRANSFER SUCCESS`
+/// - `TRANSFER SUCCESS` and `SUBSCRIPTION_PAYMENT:id=...,payment=...`
+/// - `SUBSCRIPTION_LAPSED:id=...,reason=...` when the subscription ends
+///   (`paused`, `frozen`, `allowlist`, `balance`, `limits` or `fees`)
 #[massa_export]
-pub fn transferFrom(binary_args: &[u8]) -> Vec<u8> {
-    let mut args = Args::from_bytes(binary_args.to_vec());
-    let owner = args.next_string().expect("ownerAddress argument is missing or invalid");
-    let recipient = args.next_string().expect("recipientAddress argument is missing or invalid");
-    let amount = args.next_u256().expect("amount argument is missing or invalid");
+pub fn processSubscription(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(
+        context::caller() == context::callee(),
+        errors::UNAUTHORIZED,
+        "Subscriptions are only processed by their deferred calls"
+    );
 
-    let spender = context::caller();
-    
-    assert!(owner != recipient, "Transfer failed: cannot send tokens to own account");
-    
-    // Check allowance
-    let spender_allowance = get_allowance(&owner, &spender);
-    assert!(spender_allowance >= amount, "transferFrom failed: insufficient allowance");
-    
-    // Check balance
-    let owner_balance = get_balance(&owner);
-    let recipient_balance = get_balance(&recipient);
-    
-    assert!(owner_balance >= amount, "Transfer failed: insufficient funds");
-    
-    // Safe arithmetic
-    let new_recipient_balance = recipient_balance.checked_add(amount).expect("Transfer failed: overflow");
-    let new_owner_balance = owner_balance.checked_sub(amount).expect("Transfer failed: underflow");
-    let new_allowance = spender_allowance.checked_sub(amount).expect("Allowance underflow");
-    
-    set_balance(&owner, new_owner_balance);
-    set_balance(&recipient, new_recipient_balance);
-    set_allowance(&owner, &spender, new_allowance);
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+    if is_decommissioned() {
+        return Vec::new();
+    }
+    let Some(mut subscription) = get_subscription(id) else {
+        return Vec::new();
+    };
+    if !subscription.active || context::current_period() < subscription.next_period {
+        return Vec::new();
+    }
 
-    abi::generate_event(TRANSFER_EVENT);
+    match subscription_lapse_reason(&subscription) {
+        Some(reason) => lapse_subscription(id, &mut subscription, reason),
+        None => pay_subscription(id, &mut subscription),
+    }
+    set_subscription(id, &subscription);
 
     Vec::new()
 }
 
-// ============================================================================
-// Mintable (owner only)
-// ============================================================================
-
-/// Mint tokens to recipient (owner only).
+/// Cancel a subscription (subscriber or payee only), refunding the unused fee budget.
+///
+/// The pending deferred call is cancelled unless it already ran, e.g. a run
+/// that failed without rescheduling.
 ///
 /// # Arguments
-/// - `recipient`: Recipient address (string)
-/// - `amount`: Amount to mint (U256)
+/// - `id`: Subscription id (u64)
 ///
 /// # Events
-/// - `MINT SUCCESS`
+/// - `SUBSCRIPTION_CANCELLED:id=...,by=...`
 #[massa_export]
-pub fn mint(binary_args: &[u8]) -> Vec<u8> {
-    only_owner();
-    
+pub fn cancelSubscription(binary_args: &[u8]) -> Vec<u8> {
     let mut args = Args::from_bytes(binary_args.to_vec());
-    let recipient = args.next_string().expect("recipient argument is missing or invalid");
-    let amount = args.next_u256().expect("amount argument is missing or invalid");
+    let id = args.next_u64().expect_arg("id");
+    let mut subscription = get_subscription(id).or_fail(errors::NOT_FOUND, "Subscription does not exist");
 
-    // Increase total supply with overflow check
-    let old_supply = get_total_supply();
-    let new_supply = old_supply.checked_add(amount).expect("Requested mint amount causes an overflow");
-    set_total_supply(new_supply);
-    
-    // Increase recipient balance with overflow check
-    let old_balance = get_balance(&recipient);
-    let new_balance = old_balance.checked_add(amount).expect("Requested mint amount causes an overflow");
-    set_balance(&recipient, new_balance);
+    let caller = context::caller();
+    ensure!(
+        caller == subscription.subscriber || caller == subscription.payee,
+        errors::UNAUTHORIZED,
+        "Caller is neither the subscriber nor the payee"
+    );
+    ensure!(subscription.active, errors::INVALID_STATE, fields("id={}", id), "Subscription is not active");
 
-    abi::generate_event(MINT_EVENT);
+    cancel_subscription_call(&subscription);
+    close_subscription(&mut subscription);
+    set_subscription(id, &subscription);
+
+    abi::generate_event(&alloc::format!("{}:id={},by={}", SUBSCRIPTION_CANCELLED_EVENT, id, caller));
 
     Vec::new()
 }
 
+/// Returns a subscription as Args(subscriber: string, payee: string, amountPerInterval: U256,
+/// intervalPeriods: u64, nextPaymentPeriod: u64, payments: u64, feeBudget: u64, active: bool).
+///
+/// # Arguments
+/// - `id`: Subscription id (u64)
+#[massa_export]
+pub fn subscriptionStatus(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+    let subscription = get_subscription(id).or_fail(errors::NOT_FOUND, "Subscription does not exist");
+
+    let mut result = Args::new();
+    result
+        .add_string(&subscription.subscriber)
+        .add_string(&subscription.payee)
+        .add_u256(subscription.amount_per_interval)
+        .add_u64(subscription.interval_periods)
+        .add_u64(subscription.next_period)
+        .add_u64(subscription.payments)
+        .add_u64(subscription.fee_budget)
+        .add_bool(subscription.active);
+    result.into_bytes()
+}
+
 // ============================================================================
-// Burnable
+// Streams
 // ============================================================================
+//
+// The sender escrows the full amount in the contract's own balance up front.
+// It then becomes withdrawable by the recipient linearly, period by period,
+// between the start and end periods.
 
-/// Burn tokens from caller's balance.
+/// Stream `totalAmount` from the caller to `to` between `startPeriod` and `endPeriod`.
 ///
 /// # Arguments
-/// - `amount`: Amount to burn (U256)
+/// - `to`: Recipient (string)
+/// - `totalAmount`: Amount streamed in total (U256)
+/// - `startPeriod`: Period at which streaming starts (u64)
+/// - `endPeriod`: Period at which everything is streamed (u64)
+///
+/// # Returns
+/// - Stream id (u64, 8 bytes LE)
 ///
 /// # Events
-/// - `BURN_SUCCESS`
+/// - `TRANSFER SUCCESS` from the sender to the contract
+/// - `STREAM_CREATED:id=...,sender=...,recipient=...,amount=...,start=...,end=...`
 #[massa_export]
-pub fn burn(binary_args: &[u8]) -> Vec<u8> {
+pub fn createStream(binary_args: &[u8]) -> Vec<u8> {
     let mut args = Args::from_bytes(binary_args.to_vec());
-    let amount = args.next_u256().expect("amount argument is missing or invalid");
+    let recipient = args.next_string().expect_arg("to");
+    let total_amount = args.next_u256().expect_arg("totalAmount");
+    let start_period = args.next_u64().expect_arg("startPeriod");
+    let end_period = args.next_u64().expect_arg("endPeriod");
 
-    let caller = context::caller();
-    
-    // Decrease total supply with underflow check
-    let old_supply = get_total_supply();
-    let new_supply = old_supply.checked_sub(amount)
-        .expect("Requested burn amount causes an underflow of the total supply");
-    set_total_supply(new_supply);
-    
-    // Decrease caller balance with underflow check
-    let old_balance = get_balance(&caller);
-    let new_balance = old_balance.checked_sub(amount)
-        .expect("Requested burn amount causes an underflow of the recipient balance");
-    set_balance(&caller, new_balance);
+    when_not_paused();
+    if total_amount == U256::ZERO {
+        errors::invalid_argument("totalAmount");
+    }
+    ensure!(
+        end_period > start_period,
+        errors::INVALID_ARGUMENT,
+        fields("name=endPeriod"),
+        "endPeriod argument must be after startPeriod"
+    );
 
-    abi::generate_event(BURN_EVENT);
+    let sender = context::caller();
+    let this = context::callee();
+    move_balance(&sender, &this, total_amount);
+    abi::generate_event(&transfer_event(&sender, &this, total_amount));
+    let escrow = get_stream_escrow().checked_add(total_amount).or_fail(errors::OVERFLOW, "Stream escrow overflow");
+    STREAM_ESCROW.set(&escrow);
 
-    Vec::new()
+    let id = STREAM_COUNT.get_or(0);
+    STREAM_COUNT.set(&(id + 1));
+    set_stream(
+        id,
+        &Stream {
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            total_amount,
+            start_period,
+            end_period,
+            withdrawn: U256::ZERO,
+            cancelled: false,
+        },
+    );
+
+    abi::generate_event(&alloc::format!(
+        "{}:id={},sender={},recipient={},amount={},start={},end={}",
+        STREAM_CREATED_EVENT,
+        id,
+        sender,
+        recipient,
+        total_amount,
+        start_period,
+        end_period
+    ));
+
+    id.to_le_bytes().to_vec()
 }
 
-/// Burn tokens from owner using spender's allowance.
+/// Withdraw everything streamed so far (recipient only).
 ///
 /// # Arguments
-/// - `owner`: Owner address (string)
-/// - `amount`: Amount to burn (U256)
+/// - `streamId`: Stream id (u64)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+/// - `amount`: Amount withdrawn (U256, Args-serialized)
 ///
 /// # Events
-/// - `BURN_SUCCESS`
+/// - `TRANSFER SUCCESS` from the contract to the recipient
+/// - `STREAM_WITHDRAW:id=...,recipient=...,amount=...`
 #[massa_export]
-pub fn burnFrom(binary_args: &[u8]) -> Vec<u8> {
+pub fn withdrawFromStream(binary_args: &[u8]) -> Vec<u8> {
     let mut args = Args::from_bytes(binary_args.to_vec());
-    let owner = args.next_string().expect("owner argument is missing or invalid");
-    let amount = args.next_u256().expect("amount argument is missing or invalid");
+    let id = args.next_u64().expect_arg("streamId");
+    let mut stream = get_stream(id).or_fail(errors::NOT_FOUND, "Stream does not exist");
 
-    let spender = context::caller();
-    
-    // Check allowance
-    let spender_allowance = get_allowance(&owner, &spender);
-    assert!(spender_allowance >= amount, "burnFrom failed: insufficient allowance");
-    
-    // Decrease total supply with underflow check
-    let old_supply = get_total_supply();
-    let new_supply = old_supply.checked_sub(amount)
-        .expect("Requested burn amount causes an underflow of the total supply");
-    set_total_supply(new_supply);
-    
-    // Decrease owner balance with underflow check
-    let old_balance = get_balance(&owner);
-    let new_balance = old_balance.checked_sub(amount)
-        .expect("Requested burn amount causes an underflow of the recipient balance");
-    set_balance(&owner, new_balance);
-    
-    // Decrease allowance
-    let new_allowance = spender_allowance.checked_sub(amount).expect("Allowance underflow");
-    set_allowance(&owner, &spender, new_allowance);
+    when_not_paused();
+    ensure!(context::caller() == stream.recipient, errors::UNAUTHORIZED, "Caller is not the stream recipient");
+    ensure!(!stream.cancelled, errors::INVALID_STATE, fields("id={}", id), "Stream is cancelled");
 
-    abi::generate_event(BURN_EVENT);
+    let amount = stream.withdrawable_amount(context::current_period());
+    ensure!(amount > U256::ZERO, errors::INVALID_STATE, fields("id={}", id), "Nothing to withdraw");
 
-    Vec::new()
-}
+    stream.withdrawn = stream.withdrawn.checked_add(amount).or_fail(errors::OVERFLOW, "Stream withdrawal overflow");
+    set_stream(id, &stream);
+    release_stream_escrow(&stream.recipient, amount);
 
-// ============================================================================
-// Ownership
-// ============================================================================
+    abi::generate_event(&alloc::format!(
+        "{}:id={},recipient={},amount={}",
+        STREAM_WITHDRAW_EVENT,
+        id,
+        stream.recipient,
+        amount
+    ));
 
-/// Set the contract owner (only current owner can call, or anyone if no owner set).
+    let mut ret = Args::new();
+    ret.add_bool(true).add_u256(amount);
+    ret.into_bytes()
+}
+
+/// Stop a stream (sender or recipient only). The recipient gets what was
+/// streamed and not withdrawn yet, the sender gets the rest back.
 ///
 /// # Arguments
-/// - `newOwner`: New owner address (string)
+/// - `streamId`: Stream id (u64)
 ///
 /// # Events
-/// - `CHANGE_OWNER:newOwner`
+/// - `TRANSFER SUCCESS` for each non-zero payout
+/// - `STREAM_CANCELLED:id=...,by=...,recipientAmount=...,senderAmount=...`
 #[massa_export]
-pub fn setOwner(binary_args: &[u8]) -> Vec<u8> {
+pub fn cancelStream(binary_args: &[u8]) -> Vec<u8> {
     let mut args = Args::from_bytes(binary_args.to_vec());
-    let new_owner = args.next_string().expect("newOwnerAddress argument is missing or invalid");
-    
-    // If owner exists, only owner can change
-    if get_owner().is_some() {
-        only_owner();
+    let id = args.next_u64().expect_arg("streamId");
+    let mut stream = get_stream(id).or_fail(errors::NOT_FOUND, "Stream does not exist");
+
+    when_not_paused();
+    let caller = context::caller();
+    ensure!(
+        caller == stream.sender || caller == stream.recipient,
+        errors::UNAUTHORIZED,
+        "Caller is neither the stream sender nor the recipient"
+    );
+    ensure!(!stream.cancelled, errors::INVALID_STATE, fields("id={}", id), "Stream is already cancelled");
+
+    let recipient_amount = stream.withdrawable_amount(context::current_period());
+    let sender_amount = stream.total_amount.saturating_sub(stream.withdrawn).saturating_sub(recipient_amount);
+    ensure!(sender_amount > U256::ZERO, errors::INVALID_STATE, fields("id={}", id), "Stream is already complete");
+
+    stream.withdrawn = stream.withdrawn.saturating_add(recipient_amount);
+    stream.cancelled = true;
+    set_stream(id, &stream);
+
+    if recipient_amount > U256::ZERO {
+        release_stream_escrow(&stream.recipient, recipient_amount);
     }
-    
-    set_owner_internal(&new_owner);
-    
-    abi::generate_event(&alloc::format!("{}:{}", CHANGE_OWNER_EVENT, new_owner));
+    release_stream_escrow(&stream.sender, sender_amount);
+
+    abi::generate_event(&alloc::format!(
+        "{}:id={},by={},recipientAmount={},senderAmount={}",
+        STREAM_CANCELLED_EVENT,
+        id,
+        caller,
+        recipient_amount,
+        sender_amount
+    ));
 
     Vec::new()
 }
 
-/// Returns the owner address (raw bytes).
+/// Returns a stream as Args(sender: string, recipient: string, totalAmount: U256,
+/// startPeriod: u64, endPeriod: u64, withdrawn: U256, cancelled: bool).
+///
+/// # Arguments
+/// - `streamId`: Stream id (u64)
 #[massa_export]
-pub fn ownerAddress(_binary_args: &[u8]) -> Vec<u8> {
-    if !storage::has(OWNER_KEY) {
-        return Vec::new();
-    }
-    storage::get(OWNER_KEY)
+pub fn stream(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("streamId");
+    let stream = get_stream(id).or_fail(errors::NOT_FOUND, "Stream does not exist");
+
+    let mut result = Args::new();
+    result
+        .add_string(&stream.sender)
+        .add_string(&stream.recipient)
+        .add_u256(stream.total_amount)
+        .add_u64(stream.start_period)
+        .add_u64(stream.end_period)
+        .add_u256(stream.withdrawn)
+        .add_bool(stream.cancelled);
+    result.into_bytes()
 }
 
-/// Returns true (1) if address is owner, false (0) otherwise.
+/// Returns the amount the recipient can withdraw now (u256 bytes).
 ///
 /// # Arguments
-/// - `address`: Address to check (string)
+/// - `streamId`: Stream id (u64)
 #[massa_export]
-pub fn isOwner(binary_args: &[u8]) -> Vec<u8> {
-    if !storage::has(OWNER_KEY) {
-        return alloc::vec![0u8];
-    }
+pub fn streamWithdrawable(binary_args: &[u8]) -> Vec<u8> {
     let mut args = Args::from_bytes(binary_args.to_vec());
-    let address = args.next_string().expect("address argument is missing or invalid");
-    
-    if is_owner_check(&address) {
-        alloc::vec![1u8]
-    } else {
-        alloc::vec![0u8]
-    }
+    let id = args.next_u64().expect_arg("streamId");
+    let stream = get_stream(id).or_fail(errors::NOT_FOUND, "Stream does not exist");
+
+    stream.withdrawable_amount(context::current_period()).to_le_bytes().to_vec()
 }
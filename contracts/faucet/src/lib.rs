@@ -0,0 +1,295 @@
+//! Rate-Limited Token Faucet for Massa Blockchain
+//!
+//! Dispenses a fixed amount of an MRC20 token to any address, at most once per
+//! cooldown window of Massa periods. Meant for buildnet testing: the owner
+//! funds the faucet with `refill` and testers call `drip`.
+//!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes.
+//!
+//! # Storage Keys
+//! - `OWNER`: Owner address as raw string bytes
+//! - `TOKEN`: Dispensed token address as raw string bytes
+//! - `DRIP_AMOUNT`: Amount sent per drip, u256 as 32 bytes (little-endian)
+//! - `COOLDOWN`: Periods between two drips to the same address, u64 as 8 bytes (little-endian)
+//! - `RESERVE`: Tokens funded and not dispensed yet, u256 as 32 bytes (little-endian)
+//! - `LAST_DRIP{address}`: Period of the address's last drip, u64 as 8 bytes (little-endian)
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args, U256};
+
+use mrc20_core::calls;
+use mrc20_core::cooldown;
+use mrc20_core::errors::{self, OrFail};
+use mrc20_core::store;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const OWNER_KEY: &[u8] = b"OWNER";
+const TOKEN_KEY: &[u8] = b"TOKEN";
+const DRIP_AMOUNT_KEY: &[u8] = b"DRIP_AMOUNT";
+const COOLDOWN_KEY: &[u8] = b"COOLDOWN";
+const RESERVE_KEY: &[u8] = b"RESERVE";
+const LAST_DRIP_KEY_PREFIX: &[u8] = b"LAST_DRIP";
+
+const DRIP_EVENT: &str = "FAUCET_DRIP";
+const REFILL_EVENT: &str = "FAUCET_REFILL";
+const WITHDRAW_EVENT: &str = "FAUCET_WITHDRAW";
+const SETTINGS_EVENT: &str = "FAUCET_SETTINGS";
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+/// Build last drip key: "LAST_DRIP" + address
+fn last_drip_key(address: &str) -> Vec<u8> {
+    let mut key = LAST_DRIP_KEY_PREFIX.to_vec();
+    key.extend_from_slice(address.as_bytes());
+    key
+}
+
+fn read_u256(key: &[u8]) -> U256 {
+    store::read(key).unwrap_or(U256::ZERO)
+}
+
+fn write_u256(key: &[u8], value: U256) {
+    storage::set(key, &value.to_le_bytes());
+}
+
+fn read_u64(key: &[u8]) -> u64 {
+    store::read(key).unwrap_or(0)
+}
+
+fn read_string(key: &[u8]) -> String {
+    let data = store::get_opt(key).or_fail(errors::INVALID_STATE, "Faucet is not initialized");
+    core::str::from_utf8(&data).ok().map(String::from).or_fail(errors::INVALID_STATE, "Corrupted address")
+}
+
+fn only_owner() {
+    let owner = read_string(OWNER_KEY);
+    let caller = context::caller();
+    ensure!(caller == owner, errors::UNAUTHORIZED, fields("caller={}", caller), "Caller is not the owner");
+}
+
+/// Call `operation` on the dispensed token with `args`.
+fn call_token(operation: &str, args: Args) {
+    calls::call(&read_string(TOKEN_KEY), operation, args);
+}
+
+/// First period at which `address` can drip, 0 if it never did.
+fn next_drip_period(address: &str) -> u64 {
+    cooldown::next_period(store::read::<u64>(&last_drip_key(address)), read_u64(COOLDOWN_KEY))
+}
+
+fn set_settings(drip_amount: U256, cooldown: u64) {
+    if drip_amount == U256::ZERO {
+        errors::invalid_argument("dripAmount");
+    }
+    write_u256(DRIP_AMOUNT_KEY, drip_amount);
+    storage::set(COOLDOWN_KEY, &cooldown.to_le_bytes());
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize the faucet. The caller becomes the owner.
+///
+/// # Arguments
+/// - `token`: Dispensed token address (string)
+/// - `dripAmount`: Amount sent per drip (U256, non-zero)
+/// - `cooldownPeriods`: Periods between two drips to the same address (u64)
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token = args.next_string().expect_arg("token");
+    let drip_amount = args.next_u256().expect_arg("dripAmount");
+    let cooldown = args.next_u64().expect_arg("cooldownPeriods");
+    if token.is_empty() {
+        errors::invalid_argument("token");
+    }
+
+    set_settings(drip_amount, cooldown);
+    storage::set(OWNER_KEY, context::caller().as_bytes());
+    storage::set(TOKEN_KEY, token.as_bytes());
+
+    Vec::new()
+}
+
+// ============================================================================
+// Faucet
+// ============================================================================
+
+/// Send `dripAmount` tokens to the caller, at most once per cooldown window.
+///
+/// # Returns
+/// - Amount sent (u256 bytes)
+///
+/// # Events
+/// - `FAUCET_DRIP:to=...,amount=...`
+#[massa_export]
+pub fn drip(_binary_args: &[u8]) -> Vec<u8> {
+    let to = context::caller();
+    let period = context::current_period();
+    let next_period = next_drip_period(&to);
+    ensure!(
+        period >= next_period,
+        errors::NOT_YET_VALID,
+        fields("account={},next={}", to, next_period),
+        "Cooldown has not passed since the last drip"
+    );
+
+    let amount = read_u256(DRIP_AMOUNT_KEY);
+    let reserve = read_u256(RESERVE_KEY);
+    ensure!(
+        reserve >= amount,
+        errors::INSUFFICIENT_BALANCE,
+        fields("needed={},have={}", amount, reserve),
+        "Faucet is empty"
+    );
+
+    // Record the drip before the call so it cannot be repeated from within it
+    storage::set(&last_drip_key(&to), &period.to_le_bytes());
+    write_u256(RESERVE_KEY, reserve - amount);
+
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(&to).add_u256(amount);
+    call_token("transfer", transfer_args);
+
+    abi::generate_event(&alloc::format!("{}:to={},amount={}", DRIP_EVENT, to, amount));
+
+    amount.to_le_bytes().to_vec()
+}
+
+// ============================================================================
+// Administration (owner only)
+// ============================================================================
+
+/// Fund the faucet (owner only). The owner must have approved this contract for `amount`.
+///
+/// # Arguments
+/// - `amount`: Amount of tokens (U256, non-zero)
+///
+/// # Events
+/// - `FAUCET_REFILL:amount=...`
+#[massa_export]
+pub fn refill(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let amount = args.next_u256().expect_arg("amount");
+    if amount == U256::ZERO {
+        errors::invalid_argument("amount");
+    }
+
+    write_u256(
+        RESERVE_KEY,
+        read_u256(RESERVE_KEY).checked_add(amount).or_fail(errors::OVERFLOW, "Faucet reserve overflow"),
+    );
+
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(&context::caller()).add_string(&context::callee()).add_u256(amount);
+    call_token("transferFrom", transfer_args);
+
+    abi::generate_event(&alloc::format!("{}:amount={}", REFILL_EVENT, amount));
+
+    Vec::new()
+}
+
+/// Take tokens back from the reserve (owner only), e.g. once testing is over.
+///
+/// # Arguments
+/// - `amount`: Amount of tokens (U256)
+///
+/// # Events
+/// - `FAUCET_WITHDRAW:amount=...`
+#[massa_export]
+pub fn withdraw(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let amount = args.next_u256().expect_arg("amount");
+    let reserve = read_u256(RESERVE_KEY);
+    ensure!(
+        amount <= reserve,
+        errors::INSUFFICIENT_BALANCE,
+        fields("needed={},have={}", amount, reserve),
+        "Amount exceeds the faucet reserve"
+    );
+    write_u256(RESERVE_KEY, reserve - amount);
+
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(&context::caller()).add_u256(amount);
+    call_token("transfer", transfer_args);
+
+    abi::generate_event(&alloc::format!("{}:amount={}", WITHDRAW_EVENT, amount));
+
+    Vec::new()
+}
+
+/// Change the drip amount and cooldown (owner only). Past drips keep counting toward the new cooldown.
+///
+/// # Arguments
+/// - `dripAmount`: Amount sent per drip (U256, non-zero)
+/// - `cooldownPeriods`: Periods between two drips to the same address (u64)
+///
+/// # Events
+/// - `FAUCET_SETTINGS:dripAmount=...,cooldown=...`
+#[massa_export]
+pub fn setDripSettings(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let drip_amount = args.next_u256().expect_arg("dripAmount");
+    let cooldown = args.next_u64().expect_arg("cooldownPeriods");
+    set_settings(drip_amount, cooldown);
+
+    abi::generate_event(&alloc::format!("{}:dripAmount={},cooldown={}", SETTINGS_EVENT, drip_amount, cooldown));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Returns the first period at which the address can drip (u64, 8 bytes LE), 0 if it never did.
+///
+/// # Arguments
+/// - `address`: Address to check (string)
+#[massa_export]
+pub fn nextDripPeriod(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    next_drip_period(&address).to_le_bytes().to_vec()
+}
+
+/// Returns the amount sent per drip (u256 bytes).
+#[massa_export]
+pub fn dripAmount(_binary_args: &[u8]) -> Vec<u8> {
+    read_u256(DRIP_AMOUNT_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the cooldown in periods (u64, 8 bytes LE).
+#[massa_export]
+pub fn cooldown(_binary_args: &[u8]) -> Vec<u8> {
+    read_u64(COOLDOWN_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the tokens funded and not dispensed yet (u256 bytes).
+#[massa_export]
+pub fn reserve(_binary_args: &[u8]) -> Vec<u8> {
+    read_u256(RESERVE_KEY).to_le_bytes().to_vec()
+}
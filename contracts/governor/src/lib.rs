@@ -0,0 +1,557 @@
+//! Token Governance for Massa Blockchain
+//!
+//! Token holders propose and vote on calls to any contract. Voting power is
+//! the holder's token balance at the governor's latest checkpoint, a token
+//! snapshot taken in an earlier period than the proposal, so tokens borrowed
+//! or moved for the proposal cannot vote. The governor needs the token's
+//! `SNAPSHOT` role to take checkpoints.
+//!
+//! # Lifecycle
+//! - `checkpoint`: Anyone takes a snapshot, at most one per period
+//! - `propose`: Requires `proposalThreshold` voting power at a checkpoint from an earlier period
+//! - `castVote`: One vote per holder, from `votingDelay` periods after the proposal, for `votingPeriod` periods
+//! - `execute`: Anyone can run a succeeded proposal once
+//!
+//! A proposal succeeds when its for votes exceed its against votes and its for
+//! and abstain votes reach the quorum, a share of the total supply at the snapshot.
+//! The vote counting lives in `mrc20_core::governance`.
+//!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes.
+//!
+//! # Storage Keys
+//! - `TOKEN`: Governance token address as raw string bytes
+//! - `VOTING_DELAY`: Periods between a proposal and the start of its vote, u64 as 8 bytes (little-endian)
+//! - `VOTING_PERIOD`: Voting duration in periods, u64 as 8 bytes (little-endian)
+//! - `QUORUM_BPS`: Quorum in basis points of the total supply, u32 as 4 bytes (little-endian)
+//! - `PROPOSAL_THRESHOLD`: Voting power needed to propose, u256 as 32 bytes (little-endian)
+//! - `CHECKPOINT_SNAPSHOT`: Token snapshot id of the latest checkpoint, u64 as 8 bytes (little-endian)
+//! - `CHECKPOINT_PERIOD`: Period the latest checkpoint was taken in, u64 as 8 bytes (little-endian)
+//! - `PROPOSAL_COUNT`: Number of proposals created, u64 as 8 bytes (little-endian)
+//! - `PROPOSAL{id}`: Proposal (u64 LE id), Args-serialized
+//! - `VOTED{id}{address}`: Single byte [u8], the vote (1 + support) once the address voted
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args, U256};
+
+use mrc20_core::calls;
+use mrc20_core::errors::{self, OrFail};
+use mrc20_core::governance::{self, Tally, ABSTAIN, STATE_ACTIVE, STATE_EXECUTED, STATE_PENDING, STATE_SUCCEEDED};
+use mrc20_core::math;
+use mrc20_core::store;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const TOKEN_KEY: &[u8] = b"TOKEN";
+const VOTING_DELAY_KEY: &[u8] = b"VOTING_DELAY";
+const VOTING_PERIOD_KEY: &[u8] = b"VOTING_PERIOD";
+const QUORUM_BPS_KEY: &[u8] = b"QUORUM_BPS";
+const PROPOSAL_THRESHOLD_KEY: &[u8] = b"PROPOSAL_THRESHOLD";
+const CHECKPOINT_SNAPSHOT_KEY: &[u8] = b"CHECKPOINT_SNAPSHOT";
+const CHECKPOINT_PERIOD_KEY: &[u8] = b"CHECKPOINT_PERIOD";
+const PROPOSAL_COUNT_KEY: &[u8] = b"PROPOSAL_COUNT";
+const PROPOSAL_KEY_PREFIX: &[u8] = b"PROPOSAL";
+const VOTED_KEY_PREFIX: &[u8] = b"VOTED";
+
+const CHECKPOINT_EVENT: &str = "CHECKPOINT";
+const PROPOSAL_CREATED_EVENT: &str = "PROPOSAL_CREATED";
+const VOTE_CAST_EVENT: &str = "VOTE_CAST";
+const PROPOSAL_EXECUTED_EVENT: &str = "PROPOSAL_EXECUTED";
+const SETTINGS_EVENT: &str = "GOVERNOR_SETTINGS";
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+/// Build proposal key: "PROPOSAL" + id (u64 LE)
+fn proposal_key(id: u64) -> Vec<u8> {
+    let mut key = PROPOSAL_KEY_PREFIX.to_vec();
+    key.extend_from_slice(&id.to_le_bytes());
+    key
+}
+
+/// Build vote receipt key: "VOTED" + id (u64 LE) + address
+fn voted_key(id: u64, address: &str) -> Vec<u8> {
+    let mut key = VOTED_KEY_PREFIX.to_vec();
+    key.extend_from_slice(&id.to_le_bytes());
+    key.extend_from_slice(address.as_bytes());
+    key
+}
+
+fn read_u64(key: &[u8]) -> u64 {
+    store::read(key).unwrap_or(0)
+}
+
+fn read_u32(key: &[u8]) -> u32 {
+    store::read(key).unwrap_or(0)
+}
+
+fn read_u256(key: &[u8]) -> U256 {
+    store::read(key).unwrap_or(U256::ZERO)
+}
+
+fn token() -> String {
+    let data = store::get_opt(TOKEN_KEY).or_fail(errors::INVALID_STATE, "Governor is not initialized");
+    core::str::from_utf8(&data).ok().map(String::from).or_fail(errors::INVALID_STATE, "Corrupted token address")
+}
+
+/// Governance settings can only change through an executed proposal.
+fn only_governance() {
+    ensure!(
+        context::caller() == context::callee(),
+        errors::UNAUTHORIZED,
+        "Settings can only change through an executed proposal"
+    );
+}
+
+fn set_settings(voting_delay: u64, voting_period: u64, quorum_bps: u32, proposal_threshold: U256) {
+    if voting_delay == 0 {
+        errors::invalid_argument("votingDelay");
+    }
+    if voting_period == 0 {
+        errors::invalid_argument("votingPeriod");
+    }
+    if quorum_bps > math::MAX_BPS {
+        errors::invalid_argument("quorumBps");
+    }
+    storage::set(VOTING_DELAY_KEY, &voting_delay.to_le_bytes());
+    storage::set(VOTING_PERIOD_KEY, &voting_period.to_le_bytes());
+    storage::set(QUORUM_BPS_KEY, &quorum_bps.to_le_bytes());
+    storage::set(PROPOSAL_THRESHOLD_KEY, &proposal_threshold.to_le_bytes());
+}
+
+// ============================================================================
+// Token Queries
+// ============================================================================
+
+fn take_snapshot() -> u64 {
+    calls::call_u64(&token(), "snapshot", Args::new())
+}
+
+fn voting_power(address: &str, snapshot_id: u64) -> U256 {
+    let mut args = Args::new();
+    args.add_string(address).add_u64(snapshot_id);
+    calls::call_u256(&token(), "balanceOfAt", args)
+}
+
+fn supply_at(snapshot_id: u64) -> U256 {
+    let mut args = Args::new();
+    args.add_u64(snapshot_id);
+    calls::call_u256(&token(), "totalSupplyAt", args)
+}
+
+// ============================================================================
+// Proposals
+// ============================================================================
+
+/// A call voted on by token holders.
+struct Proposal {
+    proposer: String,
+    target: String,
+    entrypoint: String,
+    args: Vec<u8>,
+    description: String,
+    snapshot_id: u64,
+    start_period: u64,
+    end_period: u64,
+    quorum: U256,
+    tally: Tally,
+    executed: bool,
+}
+
+impl Proposal {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Args::new();
+        data.add_string(&self.proposer)
+            .add_string(&self.target)
+            .add_string(&self.entrypoint)
+            .add_bytes(&self.args)
+            .add_string(&self.description)
+            .add_u64(self.snapshot_id)
+            .add_u64(self.start_period)
+            .add_u64(self.end_period)
+            .add_u256(self.quorum)
+            .add_u256(self.tally.for_votes)
+            .add_u256(self.tally.against_votes)
+            .add_u256(self.tally.abstain_votes)
+            .add_bool(self.executed);
+        data.into_bytes()
+    }
+
+    fn from_bytes(data: &[u8]) -> Option<Self> {
+        let mut data = Args::from_bytes(data.to_vec());
+        Some(Proposal {
+            proposer: data.next_string().ok()?,
+            target: data.next_string().ok()?,
+            entrypoint: data.next_string().ok()?,
+            args: data.next_bytes().ok()?,
+            description: data.next_string().ok()?,
+            snapshot_id: data.next_u64().ok()?,
+            start_period: data.next_u64().ok()?,
+            end_period: data.next_u64().ok()?,
+            quorum: data.next_u256().ok()?,
+            tally: Tally {
+                for_votes: data.next_u256().ok()?,
+                against_votes: data.next_u256().ok()?,
+                abstain_votes: data.next_u256().ok()?,
+            },
+            executed: data.next_bool().ok()?,
+        })
+    }
+
+    fn state(&self, period: u64) -> u8 {
+        governance::proposal_state(period, self.start_period, self.end_period, self.executed, &self.tally, self.quorum)
+    }
+}
+
+fn get_proposal(id: u64) -> Proposal {
+    let key = proposal_key(id);
+    let Some(data) = store::get_opt(&key) else {
+        fail!(errors::NOT_FOUND, fields("id={}", id), "Proposal does not exist");
+    };
+    Proposal::from_bytes(&data).or_fail(errors::INVALID_STATE, "Corrupted proposal")
+}
+
+fn set_proposal(id: u64, proposal: &Proposal) {
+    storage::set(&proposal_key(id), &proposal.to_bytes());
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize the governor.
+///
+/// # Arguments
+/// - `token`: Governance token address (string)
+/// - `votingPeriod`: Voting duration in periods (u64)
+/// - `quorumBps`: Quorum in basis points of the total supply (u32)
+/// - `proposalThreshold`: Voting power needed to propose (U256)
+/// - `votingDelay`: Periods between a proposal and the start of its vote, at least 1 (u64)
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token = args.next_string().expect_arg("token");
+    let voting_period = args.next_u64().expect_arg("votingPeriod");
+    let quorum_bps = args.next_u32().expect_arg("quorumBps");
+    let proposal_threshold = args.next_u256().expect_arg("proposalThreshold");
+    let voting_delay = args.next_u64().expect_arg("votingDelay");
+    if token.is_empty() {
+        errors::invalid_argument("token");
+    }
+
+    storage::set(TOKEN_KEY, token.as_bytes());
+    set_settings(voting_delay, voting_period, quorum_bps, proposal_threshold);
+
+    Vec::new()
+}
+
+/// Take a token snapshot for later proposals to use. Anyone can call it.
+///
+/// Only the first call in a period takes a snapshot; later ones return it.
+///
+/// # Returns
+/// - Snapshot id of the checkpoint (u64, 8 bytes LE)
+///
+/// # Events
+/// - `CHECKPOINT:snapshot=...,period=...` when a snapshot is taken
+#[massa_export]
+pub fn checkpoint(_binary_args: &[u8]) -> Vec<u8> {
+    let period = context::current_period();
+    let snapshot_id = read_u64(CHECKPOINT_SNAPSHOT_KEY);
+    if snapshot_id != 0 && read_u64(CHECKPOINT_PERIOD_KEY) == period {
+        return snapshot_id.to_le_bytes().to_vec();
+    }
+
+    let snapshot_id = take_snapshot();
+    storage::set(CHECKPOINT_SNAPSHOT_KEY, &snapshot_id.to_le_bytes());
+    storage::set(CHECKPOINT_PERIOD_KEY, &period.to_le_bytes());
+
+    abi::generate_event(&alloc::format!("{}:snapshot={},period={}", CHECKPOINT_EVENT, snapshot_id, period));
+
+    snapshot_id.to_le_bytes().to_vec()
+}
+
+/// Create a proposal to call `entrypoint` on `target` with `args`.
+///
+/// Voting power is read at the latest checkpoint, which must be from an
+/// earlier period. Voting opens `votingDelay` periods later.
+///
+/// # Arguments
+/// - `target`: Contract to call (string)
+/// - `entrypoint`: Function to call (string)
+/// - `args`: Serialized arguments (bytes)
+/// - `description`: Human-readable description (string)
+///
+/// # Returns
+/// - Proposal id (u64, 8 bytes LE)
+///
+/// # Events
+/// - `PROPOSAL_CREATED:id=...,proposer=...,target=...,entrypoint=...,snapshot=...,start=...,end=...`
+#[massa_export]
+pub fn propose(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let target = args.next_string().expect_arg("target");
+    let entrypoint = args.next_string().expect_arg("entrypoint");
+    let call_args = args.next_bytes().expect_arg("args");
+    let description = args.next_string().expect_arg("description");
+    if target.is_empty() {
+        errors::invalid_argument("target");
+    }
+    if entrypoint.is_empty() {
+        errors::invalid_argument("entrypoint");
+    }
+
+    let period = context::current_period();
+    let snapshot_id = read_u64(CHECKPOINT_SNAPSHOT_KEY);
+    let checkpoint_period = read_u64(CHECKPOINT_PERIOD_KEY);
+    ensure!(
+        snapshot_id != 0 && checkpoint_period < period,
+        errors::NOT_YET_VALID,
+        fields("checkpointPeriod={}", checkpoint_period),
+        "Proposals need a checkpoint from an earlier period"
+    );
+
+    let proposer = context::caller();
+    let power = voting_power(&proposer, snapshot_id);
+    let threshold = read_u256(PROPOSAL_THRESHOLD_KEY);
+    ensure!(
+        power >= threshold,
+        errors::INSUFFICIENT_BALANCE,
+        fields("needed={},have={}", threshold, power),
+        "Voting power below the proposal threshold"
+    );
+
+    let quorum = governance::quorum(supply_at(snapshot_id), read_u32(QUORUM_BPS_KEY))
+        .or_fail(errors::OVERFLOW, "Quorum overflow");
+    let start_period = period
+        .checked_add(read_u64(VOTING_DELAY_KEY))
+        .or_fail(errors::OVERFLOW, "Voting delay overflow");
+    let end_period = start_period
+        .checked_add(read_u64(VOTING_PERIOD_KEY))
+        .or_fail(errors::OVERFLOW, "Voting period overflow");
+
+    let id = read_u64(PROPOSAL_COUNT_KEY);
+    storage::set(PROPOSAL_COUNT_KEY, &(id + 1).to_le_bytes());
+    let proposal = Proposal {
+        proposer,
+        target,
+        entrypoint,
+        args: call_args,
+        description,
+        snapshot_id,
+        start_period,
+        end_period,
+        quorum,
+        tally: Tally::EMPTY,
+        executed: false,
+    };
+    set_proposal(id, &proposal);
+
+    abi::generate_event(&alloc::format!(
+        "{}:id={},proposer={},target={},entrypoint={},snapshot={},start={},end={}",
+        PROPOSAL_CREATED_EVENT,
+        id,
+        proposal.proposer,
+        proposal.target,
+        proposal.entrypoint,
+        snapshot_id,
+        start_period,
+        end_period
+    ));
+
+    id.to_le_bytes().to_vec()
+}
+
+/// Vote on an active proposal with the caller's balance at its snapshot.
+///
+/// # Arguments
+/// - `proposalId`: Proposal id (u64)
+/// - `support`: 0 = against, 1 = for, 2 = abstain (u8)
+///
+/// # Events
+/// - `VOTE_CAST:id=...,voter=...,support=...,weight=...`
+#[massa_export]
+pub fn castVote(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("proposalId");
+    let support = args.next_u8().expect_arg("support");
+    if support > ABSTAIN {
+        errors::invalid_argument("support");
+    }
+
+    let mut proposal = get_proposal(id);
+    let state = proposal.state(context::current_period());
+    ensure!(
+        state != STATE_PENDING,
+        errors::NOT_YET_VALID,
+        fields("id={},start={}", id, proposal.start_period),
+        "Voting has not started"
+    );
+    ensure!(state == STATE_ACTIVE, errors::EXPIRED, fields("id={}", id), "Voting is closed");
+
+    let voter = context::caller();
+    let receipt_key = voted_key(id, &voter);
+    ensure!(!storage::has(&receipt_key), errors::ALREADY_USED, fields("id={}", id), "Already voted");
+
+    let weight = voting_power(&voter, proposal.snapshot_id);
+    ensure!(weight > U256::ZERO, errors::INSUFFICIENT_BALANCE, "No voting power at the proposal snapshot");
+
+    proposal.tally = proposal.tally.with_vote(support, weight).or_fail(errors::OVERFLOW, "Vote tally overflow");
+    set_proposal(id, &proposal);
+    storage::set(&receipt_key, &[1 + support]);
+
+    abi::generate_event(&alloc::format!(
+        "{}:id={},voter={},support={},weight={}",
+        VOTE_CAST_EVENT,
+        id,
+        voter,
+        support,
+        weight
+    ));
+
+    Vec::new()
+}
+
+/// Run a succeeded proposal. Anyone can call it, once.
+///
+/// # Arguments
+/// - `proposalId`: Proposal id (u64)
+///
+/// # Returns
+/// - The return value of the executed call
+///
+/// # Events
+/// - `PROPOSAL_EXECUTED:id=...`
+#[massa_export]
+pub fn execute(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("proposalId");
+
+    let mut proposal = get_proposal(id);
+    let state = proposal.state(context::current_period());
+    ensure!(state != STATE_EXECUTED, errors::ALREADY_USED, fields("id={}", id), "Proposal is already executed");
+    ensure!(
+        state == STATE_SUCCEEDED,
+        errors::INVALID_STATE,
+        fields("id={},state={}", id, state),
+        "Proposal has not succeeded"
+    );
+
+    // Mark as executed before the call so it cannot be replayed from within it
+    proposal.executed = true;
+    set_proposal(id, &proposal);
+
+    let result = abi::call(&proposal.target, &proposal.entrypoint, &proposal.args, 0);
+
+    abi::generate_event(&alloc::format!("{}:id={}", PROPOSAL_EXECUTED_EVENT, id));
+
+    result
+}
+
+/// Change the governance settings. Only callable by the governor itself, through a proposal.
+///
+/// # Arguments
+/// - `votingPeriod`: Voting duration in periods (u64)
+/// - `quorumBps`: Quorum in basis points of the total supply (u32)
+/// - `proposalThreshold`: Voting power needed to propose (U256)
+/// - `votingDelay`: Periods between a proposal and the start of its vote, at least 1 (u64)
+///
+/// # Events
+/// - `GOVERNOR_SETTINGS:votingDelay=...,votingPeriod=...,quorumBps=...,proposalThreshold=...`
+#[massa_export]
+pub fn updateSettings(binary_args: &[u8]) -> Vec<u8> {
+    only_governance();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let voting_period = args.next_u64().expect_arg("votingPeriod");
+    let quorum_bps = args.next_u32().expect_arg("quorumBps");
+    let proposal_threshold = args.next_u256().expect_arg("proposalThreshold");
+    let voting_delay = args.next_u64().expect_arg("votingDelay");
+    set_settings(voting_delay, voting_period, quorum_bps, proposal_threshold);
+
+    abi::generate_event(&alloc::format!(
+        "{}:votingDelay={},votingPeriod={},quorumBps={},proposalThreshold={}",
+        SETTINGS_EVENT,
+        voting_delay,
+        voting_period,
+        quorum_bps,
+        proposal_threshold
+    ));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Returns a proposal as Args(proposer: string, target: string, entrypoint: string,
+/// args: bytes, description: string, snapshotId: u64, startPeriod: u64, endPeriod: u64, quorum: U256,
+/// forVotes: U256, againstVotes: U256, abstainVotes: U256, executed: bool).
+///
+/// # Arguments
+/// - `proposalId`: Proposal id (u64)
+#[massa_export]
+pub fn proposal(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("proposalId");
+    get_proposal(id).to_bytes()
+}
+
+/// Returns the proposal state as a single byte: 0 = active, 1 = defeated, 2 = succeeded,
+/// 3 = executed, 4 = pending (voting has not started).
+///
+/// # Arguments
+/// - `proposalId`: Proposal id (u64)
+#[massa_export]
+pub fn proposalState(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("proposalId");
+    alloc::vec![get_proposal(id).state(context::current_period())]
+}
+
+/// Returns the address's vote as a single byte: 0 = not voted, otherwise 1 + support.
+///
+/// # Arguments
+/// - `proposalId`: Proposal id (u64)
+/// - `address`: Voter address (string)
+#[massa_export]
+pub fn getReceipt(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("proposalId");
+    let address = args.next_string().expect_arg("address");
+
+    let key = voted_key(id, &address);
+    store::get_opt(&key).unwrap_or_else(|| alloc::vec![0u8])
+}
+
+/// Returns the number of proposals created (u64, 8 bytes LE).
+#[massa_export]
+pub fn proposalCount(_binary_args: &[u8]) -> Vec<u8> {
+    read_u64(PROPOSAL_COUNT_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the settings as Args(votingPeriod: u64, quorumBps: u32, proposalThreshold: U256, votingDelay: u64).
+#[massa_export]
+pub fn settings(_binary_args: &[u8]) -> Vec<u8> {
+    let mut result = Args::new();
+    result
+        .add_u64(read_u64(VOTING_PERIOD_KEY))
+        .add_u32(read_u32(QUORUM_BPS_KEY))
+        .add_u256(read_u256(PROPOSAL_THRESHOLD_KEY))
+        .add_u64(read_u64(VOTING_DELAY_KEY));
+    result.into_bytes()
+}
@@ -0,0 +1,296 @@
+//! Merkle Airdrop Distributor for Massa Blockchain
+//!
+//! Distributes an MRC20 token to a list of (address, amount) pairs committed
+//! to by a merkle root. The contract holds the tokens; each entry can be
+//! claimed once by its address with a proof of inclusion.
+//!
+//! # Merkle Tree
+//! - Leaf: `keccak256(index (u64 LE) || amount (u256 LE) || address bytes)`
+//! - Node: `keccak256(min(a, b) || max(a, b))`, so proofs need no left/right flags
+//! - Proof: Concatenated 32-byte sibling hashes, from the leaf up
+//!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes.
+//!
+//! # Storage Keys
+//! - `OWNER`: Owner address as raw string bytes
+//! - `TOKEN`: Distributed token address as raw string bytes
+//! - `MERKLE_ROOT`: Merkle root, 32 bytes
+//! - `CLAIMED{word}`: Claimed bitmap for indices `word * 256 .. word * 256 + 255`, word as u64 LE, 32 bytes
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args, U256};
+
+use mrc20_core::calls;
+use mrc20_core::errors::{self, OrFail};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const OWNER_KEY: &[u8] = b"OWNER";
+const TOKEN_KEY: &[u8] = b"TOKEN";
+const MERKLE_ROOT_KEY: &[u8] = b"MERKLE_ROOT";
+const CLAIMED_KEY_PREFIX: &[u8] = b"CLAIMED";
+
+const HASH_SIZE: usize = 32;
+
+const CLAIMED_EVENT: &str = "AIRDROP_CLAIMED";
+const RECOVER_EVENT: &str = "AIRDROP_RECOVER";
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+/// Build claimed bitmap key: "CLAIMED" + word (u64 LE)
+fn claimed_key(word: u64) -> Vec<u8> {
+    let mut key = CLAIMED_KEY_PREFIX.to_vec();
+    key.extend_from_slice(&word.to_le_bytes());
+    key
+}
+
+fn read_string(key: &[u8]) -> String {
+    ensure!(storage::has(key), errors::INVALID_STATE, "Airdrop is not initialized");
+    let data = storage::get(key);
+    core::str::from_utf8(&data).ok().map(String::from).or_fail(errors::INVALID_STATE, "Corrupted address")
+}
+
+fn only_owner() {
+    let owner = read_string(OWNER_KEY);
+    let caller = context::caller();
+    ensure!(caller == owner, errors::UNAUTHORIZED, fields("caller={}", caller), "Caller is not the owner");
+}
+
+/// Returns the claimed bitmap word holding `index` and the bit of `index` in it.
+fn claimed_bit(index: u64) -> (Vec<u8>, [u8; 32], usize) {
+    let key = claimed_key(index / 256);
+    let mut word = [0u8; 32];
+    if storage::has(&key) {
+        let data = storage::get(&key);
+        if data.len() >= 32 {
+            word.copy_from_slice(&data[..32]);
+        }
+    }
+    (key, word, (index % 256) as usize)
+}
+
+fn is_claimed(index: u64) -> bool {
+    let (_, word, bit) = claimed_bit(index);
+    word[bit / 8] & (1 << (bit % 8)) != 0
+}
+
+fn set_claimed(index: u64) {
+    let (key, mut word, bit) = claimed_bit(index);
+    word[bit / 8] |= 1 << (bit % 8);
+    storage::set(&key, &word);
+}
+
+// ============================================================================
+// Merkle Proofs
+// ============================================================================
+
+fn leaf_hash(index: u64, account: &str, amount: U256) -> [u8; 32] {
+    let mut data = Vec::with_capacity(40 + account.len());
+    data.extend_from_slice(&index.to_le_bytes());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(account.as_bytes());
+    abi::keccak256(&data)
+}
+
+fn node_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(first);
+    data[32..].copy_from_slice(second);
+    abi::keccak256(&data)
+}
+
+/// Check that (index, account, amount) is a leaf of the stored tree.
+fn verify_claim(index: u64, account: &str, amount: U256, proof: &[u8]) -> bool {
+    ensure!(
+        proof.len() % HASH_SIZE == 0,
+        errors::INVALID_ARGUMENT,
+        fields("name=proof"),
+        "proof argument must be a multiple of 32 bytes"
+    );
+    let mut hash = leaf_hash(index, account, amount);
+    for sibling in proof.chunks_exact(HASH_SIZE) {
+        let mut sibling_hash = [0u8; 32];
+        sibling_hash.copy_from_slice(sibling);
+        hash = node_hash(&hash, &sibling_hash);
+    }
+    hash.as_slice() == storage::get(MERKLE_ROOT_KEY).as_slice()
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize the airdrop. The caller becomes the owner.
+///
+/// The owner then funds the contract by transferring the total airdropped
+/// amount of `token` to its address.
+///
+/// # Arguments
+/// - `token`: Distributed token address (string)
+/// - `merkleRoot`: Root of the (index, address, amount) tree (32 bytes)
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token = args.next_string().expect_arg("token");
+    let merkle_root = args.next_bytes().expect_arg("merkleRoot");
+    if token.is_empty() {
+        errors::invalid_argument("token");
+    }
+    if merkle_root.len() != HASH_SIZE {
+        errors::invalid_argument("merkleRoot");
+    }
+
+    storage::set(OWNER_KEY, context::caller().as_bytes());
+    storage::set(TOKEN_KEY, token.as_bytes());
+    storage::set(MERKLE_ROOT_KEY, &merkle_root);
+
+    Vec::new()
+}
+
+// ============================================================================
+// Claims
+// ============================================================================
+
+/// Claim the caller's airdrop entry.
+///
+/// # Arguments
+/// - `index`: Entry index in the tree (u64)
+/// - `amount`: Entry amount (U256)
+/// - `proof`: Concatenated 32-byte sibling hashes (bytes)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+///
+/// # Events
+/// - `AIRDROP_CLAIMED:index=...,account=...,amount=...`
+#[massa_export]
+pub fn claim(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let index = args.next_u64().expect_arg("index");
+    let amount = args.next_u256().expect_arg("amount");
+    let proof = args.next_bytes().expect_arg("proof");
+
+    let account = context::caller();
+    ensure!(!is_claimed(index), errors::ALREADY_USED, fields("index={}", index), "Airdrop entry already claimed");
+    ensure!(
+        verify_claim(index, &account, amount, &proof),
+        errors::INVALID_PROOF,
+        fields("index={}", index),
+        "Invalid merkle proof"
+    );
+
+    // Mark as claimed before the call so a reentrant claim fails
+    set_claimed(index);
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(&account).add_u256(amount);
+    calls::call(&read_string(TOKEN_KEY), "transfer", transfer_args);
+
+    abi::generate_event(&alloc::format!(
+        "{}:index={},account={},amount={}",
+        CLAIMED_EVENT,
+        index,
+        account,
+        amount
+    ));
+
+    let mut ret = Args::new();
+    ret.add_bool(true);
+    ret.into_bytes()
+}
+
+/// Returns true (1) if the entry can be claimed by `account` with `proof`, false (0) otherwise.
+///
+/// Does not check whether the entry was already claimed, see `isClaimed`.
+///
+/// # Arguments
+/// - `index`: Entry index in the tree (u64)
+/// - `account`: Entry address (string)
+/// - `amount`: Entry amount (U256)
+/// - `proof`: Concatenated 32-byte sibling hashes (bytes)
+#[massa_export]
+pub fn verifyClaim(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let index = args.next_u64().expect_arg("index");
+    let account = args.next_string().expect_arg("account");
+    let amount = args.next_u256().expect_arg("amount");
+    let proof = args.next_bytes().expect_arg("proof");
+
+    if verify_claim(index, &account, amount, &proof) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+/// Returns true (1) if the entry was claimed, false (0) otherwise.
+///
+/// # Arguments
+/// - `index`: Entry index in the tree (u64)
+#[massa_export]
+pub fn isClaimed(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let index = args.next_u64().expect_arg("index");
+
+    if is_claimed(index) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+/// Returns the merkle root (32 bytes).
+#[massa_export]
+pub fn merkleRoot(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(MERKLE_ROOT_KEY)
+}
+
+/// Returns the distributed token address (raw string bytes).
+#[massa_export]
+pub fn token(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(TOKEN_KEY)
+}
+
+// ============================================================================
+// Admin
+// ============================================================================
+
+/// Send unclaimed tokens held by the contract to `to` (owner only), e.g. once the claim window is over.
+///
+/// # Arguments
+/// - `to`: Recipient address (string)
+/// - `amount`: Amount to recover (U256)
+///
+/// # Events
+/// - `AIRDROP_RECOVER:to=...,amount=...`
+#[massa_export]
+pub fn recover(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let to = args.next_string().expect_arg("to");
+    let amount = args.next_u256().expect_arg("amount");
+
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(&to).add_u256(amount);
+    calls::call(&read_string(TOKEN_KEY), "transfer", transfer_args);
+
+    abi::generate_event(&alloc::format!("{}:to={},amount={}", RECOVER_EVENT, to, amount));
+
+    Vec::new()
+}
@@ -0,0 +1,560 @@
+//! MRC721 Non-Fungible Token Implementation for Massa Blockchain
+//!
+//! This contract implements the MRC721 standard (Massa's ERC721 equivalent) with
+//! the storage layout of the AssemblyScript NFT from massa-standards, so either
+//! implementation can read the other's state.
+//!
+//! # Compatibility
+//! - Storage format matches AS implementation exactly
+//! - Function signatures match AS implementation
+//! - Token ids are U256, serialized as 32 bytes (little-endian)
+//!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes.
+//!
+//! # Storage Keys
+//! - `NAME`: Collection name as raw bytes
+//! - `SYMBOL`: Collection symbol as raw bytes
+//! - `BASE_URI`: Prefix of token URIs as raw bytes
+//! - `OWNER`: Contract owner address as raw string bytes
+//! - `TOTAL_SUPPLY`: Number of existing tokens, u256 as 32 bytes (little-endian)
+//! - `BALANCE{address}`: Number of tokens held by address, u256 as 32 bytes (little-endian, deleted when zero)
+//! - `OWNER{tokenId}`: Token owner (u256 LE id) as raw string bytes
+//! - `ALLOWANCE{tokenId}`: Address approved for the token (u256 LE id) as raw string bytes
+//! - `OPERATOR_ALLOWANCE{owner}{operator}`: Single byte [u8], 1 while the operator manages all of the owner's tokens
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args, U256};
+
+use mrc20_core::errors::{self, OrFail};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const NAME_KEY: &[u8] = b"NAME";
+const SYMBOL_KEY: &[u8] = b"SYMBOL";
+const BASE_URI_KEY: &[u8] = b"BASE_URI";
+const OWNER_KEY: &[u8] = b"OWNER";
+const TOTAL_SUPPLY_KEY: &[u8] = b"TOTAL_SUPPLY";
+const BALANCE_KEY_PREFIX: &[u8] = b"BALANCE";
+const TOKEN_OWNER_KEY_PREFIX: &[u8] = b"OWNER";
+const ALLOWANCE_KEY_PREFIX: &[u8] = b"ALLOWANCE";
+const OPERATOR_ALLOWANCE_KEY_PREFIX: &[u8] = b"OPERATOR_ALLOWANCE";
+
+const TRANSFER_EVENT: &str = "TRANSFER";
+const APPROVAL_EVENT: &str = "APPROVAL";
+const APPROVAL_FOR_ALL_EVENT: &str = "APPROVAL_FOR_ALL";
+const CHANGE_OWNER_EVENT: &str = "CHANGE_OWNER";
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+/// Build balance key: "BALANCE" + address
+fn balance_key(address: &str) -> Vec<u8> {
+    let mut key = BALANCE_KEY_PREFIX.to_vec();
+    key.extend_from_slice(address.as_bytes());
+    key
+}
+
+/// Build token owner key: "OWNER" + tokenId (u256 LE)
+fn token_owner_key(token_id: U256) -> Vec<u8> {
+    let mut key = TOKEN_OWNER_KEY_PREFIX.to_vec();
+    key.extend_from_slice(&token_id.to_le_bytes());
+    key
+}
+
+/// Build token approval key: "ALLOWANCE" + tokenId (u256 LE)
+fn allowance_key(token_id: U256) -> Vec<u8> {
+    let mut key = ALLOWANCE_KEY_PREFIX.to_vec();
+    key.extend_from_slice(&token_id.to_le_bytes());
+    key
+}
+
+/// Build operator approval key: "OPERATOR_ALLOWANCE" + owner + operator
+fn operator_allowance_key(owner: &str, operator: &str) -> Vec<u8> {
+    let mut key = OPERATOR_ALLOWANCE_KEY_PREFIX.to_vec();
+    key.extend_from_slice(owner.as_bytes());
+    key.extend_from_slice(operator.as_bytes());
+    key
+}
+
+fn read_u256(key: &[u8]) -> U256 {
+    if !storage::has(key) {
+        return U256::ZERO;
+    }
+    let data = storage::get(key);
+    if data.len() >= 32 {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&data[..32]);
+        U256::from_le_bytes(bytes)
+    } else {
+        U256::ZERO
+    }
+}
+
+/// Store a u256, deleting the key when zero
+fn write_u256(key: &[u8], value: U256) {
+    if value == U256::ZERO {
+        if storage::has(key) {
+            storage::del(key);
+        }
+    } else {
+        storage::set(key, &value.to_le_bytes());
+    }
+}
+
+fn read_address(key: &[u8]) -> Option<String> {
+    if !storage::has(key) {
+        return None;
+    }
+    let data = storage::get(key);
+    core::str::from_utf8(&data).ok().map(String::from)
+}
+
+fn only_owner() {
+    let owner = read_address(OWNER_KEY).or_fail(errors::INVALID_STATE, "Owner is not set");
+    let caller = context::caller();
+    ensure!(caller == owner, errors::UNAUTHORIZED, fields("caller={}", caller), "Caller is not the owner");
+}
+
+fn owner_of(token_id: U256) -> String {
+    read_address(&token_owner_key(token_id))
+        .unwrap_or_else(|| fail!(errors::NOT_FOUND, fields("tokenId={}", token_id), "Token does not exist"))
+}
+
+fn get_approved(token_id: U256) -> String {
+    read_address(&allowance_key(token_id)).unwrap_or_default()
+}
+
+fn is_approved_for_all(owner: &str, operator: &str) -> bool {
+    storage::has(&operator_allowance_key(owner, operator))
+}
+
+/// Returns true if `spender` may transfer or burn the token held by `owner`.
+fn is_authorized(owner: &str, spender: &str, token_id: U256) -> bool {
+    spender == owner || get_approved(token_id) == spender || is_approved_for_all(owner, spender)
+}
+
+fn change_balance(address: &str, increase: bool) {
+    let key = balance_key(address);
+    let balance = read_u256(&key);
+    let balance = if increase {
+        balance.checked_add(U256::from(1u64)).or_fail(errors::OVERFLOW, "Balance overflow")
+    } else {
+        balance.checked_sub(U256::from(1u64)).or_fail(errors::INVALID_STATE, "Balance underflow")
+    };
+    write_u256(&key, balance);
+}
+
+/// Move a token, minting when `from` is empty and burning when `to` is empty.
+fn update(from: &str, to: &str, token_id: U256) {
+    let owner_key = token_owner_key(token_id);
+    let allowance_key = allowance_key(token_id);
+    if storage::has(&allowance_key) {
+        storage::del(&allowance_key);
+    }
+
+    if from.is_empty() {
+        let supply = read_u256(TOTAL_SUPPLY_KEY)
+            .checked_add(U256::from(1u64))
+            .or_fail(errors::OVERFLOW, "Supply overflow");
+        write_u256(TOTAL_SUPPLY_KEY, supply);
+    } else {
+        change_balance(from, false);
+    }
+
+    if to.is_empty() {
+        storage::del(&owner_key);
+        let supply = read_u256(TOTAL_SUPPLY_KEY)
+            .checked_sub(U256::from(1u64))
+            .or_fail(errors::INVALID_STATE, "Supply underflow");
+        write_u256(TOTAL_SUPPLY_KEY, supply);
+    } else {
+        storage::set(&owner_key, to.as_bytes());
+        change_balance(to, true);
+    }
+
+    abi::generate_event(&alloc::format!("{}:from={},to={},tokenId={}", TRANSFER_EVENT, from, to, token_id));
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize the collection. The caller becomes the owner.
+///
+/// # Arguments
+/// - `name`: Collection name (string, non-empty)
+/// - `symbol`: Collection symbol (string, non-empty)
+/// - `baseURI`: Optional prefix of token URIs (string)
+///
+/// # Events
+/// - `CHANGE_OWNER:caller`
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let name = args.next_string().expect_arg("name");
+    let symbol = args.next_string().expect_arg("symbol");
+    let base_uri = args.next_string().unwrap_or_default();
+    if name.is_empty() {
+        errors::invalid_argument("name");
+    }
+    if symbol.is_empty() {
+        errors::invalid_argument("symbol");
+    }
+
+    let owner = context::caller();
+    storage::set(NAME_KEY, name.as_bytes());
+    storage::set(SYMBOL_KEY, symbol.as_bytes());
+    storage::set(BASE_URI_KEY, base_uri.as_bytes());
+    storage::set(OWNER_KEY, owner.as_bytes());
+
+    abi::generate_event(&alloc::format!("{}:{}", CHANGE_OWNER_EVENT, owner));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Collection Attributes (read-only)
+// ============================================================================
+
+/// Returns the name of the collection (raw bytes, not Args-wrapped).
+#[massa_export]
+pub fn name(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(NAME_KEY)
+}
+
+/// Returns the symbol of the collection (raw bytes, not Args-wrapped).
+#[massa_export]
+pub fn symbol(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(SYMBOL_KEY)
+}
+
+/// Returns the prefix of token URIs (raw bytes, not Args-wrapped).
+#[massa_export]
+pub fn baseURI(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(BASE_URI_KEY)
+}
+
+/// Returns the URI of a token, `baseURI` followed by the decimal token id (raw bytes).
+///
+/// # Arguments
+/// - `tokenId`: Token id (U256)
+#[massa_export]
+pub fn tokenURI(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token_id = args.next_u256().expect_arg("tokenId");
+    owner_of(token_id);
+
+    let mut uri = storage::get(BASE_URI_KEY);
+    uri.extend_from_slice(alloc::format!("{}", token_id).as_bytes());
+    uri
+}
+
+/// Returns the number of existing tokens (u256 bytes).
+#[massa_export]
+pub fn totalSupply(_binary_args: &[u8]) -> Vec<u8> {
+    read_u256(TOTAL_SUPPLY_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the contract owner address (raw string bytes).
+#[massa_export]
+pub fn ownerAddress(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(OWNER_KEY)
+}
+
+// ============================================================================
+// Ownership and Approvals (read-only)
+// ============================================================================
+
+/// Returns the number of tokens held by an address (u256 bytes).
+///
+/// # Arguments
+/// - `address`: Holder address (string)
+#[massa_export]
+pub fn balanceOf(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    read_u256(&balance_key(&address)).to_le_bytes().to_vec()
+}
+
+/// Returns the owner of a token (raw string bytes). Fails if the token does not exist.
+///
+/// # Arguments
+/// - `tokenId`: Token id (U256)
+#[massa_export]
+pub fn ownerOf(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token_id = args.next_u256().expect_arg("tokenId");
+    owner_of(token_id).into_bytes()
+}
+
+/// Returns the address approved for a token (raw string bytes), empty if none.
+///
+/// # Arguments
+/// - `tokenId`: Token id (U256)
+#[massa_export]
+pub fn getApproved(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token_id = args.next_u256().expect_arg("tokenId");
+    owner_of(token_id);
+    get_approved(token_id).into_bytes()
+}
+
+/// Returns true (1) if the operator manages all of the owner's tokens, false (0) otherwise.
+///
+/// # Arguments
+/// - `owner`: Token owner (string)
+/// - `operator`: Operator address (string)
+#[massa_export]
+pub fn isApprovedForAll(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("owner");
+    let operator = args.next_string().expect_arg("operator");
+
+    if is_approved_for_all(&owner, &operator) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+// ============================================================================
+// Approvals
+// ============================================================================
+
+/// Approve an address to transfer a token, replacing any previous approval.
+///
+/// Only the token owner or one of its operators can approve. An empty `to` clears the approval.
+///
+/// # Arguments
+/// - `to`: Approved address (string)
+/// - `tokenId`: Token id (U256)
+///
+/// # Events
+/// - `APPROVAL:owner=...,approved=...,tokenId=...`
+#[massa_export]
+pub fn approve(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let to = args.next_string().expect_arg("to");
+    let token_id = args.next_u256().expect_arg("tokenId");
+
+    let owner = owner_of(token_id);
+    let caller = context::caller();
+    ensure!(
+        caller == owner || is_approved_for_all(&owner, &caller),
+        errors::UNAUTHORIZED,
+        fields("caller={}", caller),
+        "Caller is neither the token owner nor an operator"
+    );
+    ensure!(to != owner, errors::INVALID_ARGUMENT, fields("name=to"), "Cannot approve the token owner");
+
+    let key = allowance_key(token_id);
+    if to.is_empty() {
+        if storage::has(&key) {
+            storage::del(&key);
+        }
+    } else {
+        storage::set(&key, to.as_bytes());
+    }
+
+    abi::generate_event(&alloc::format!("{}:owner={},approved={},tokenId={}", APPROVAL_EVENT, owner, to, token_id));
+
+    Vec::new()
+}
+
+/// Let an operator transfer all of the caller's tokens, or revoke it.
+///
+/// # Arguments
+/// - `operator`: Operator address (string)
+/// - `approved`: Whether the operator is allowed (bool)
+///
+/// # Events
+/// - `APPROVAL_FOR_ALL:owner=...,operator=...,approved=...`
+#[massa_export]
+pub fn setApprovalForAll(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let operator = args.next_string().expect_arg("operator");
+    let approved = args.next_bool().expect_arg("approved");
+
+    let owner = context::caller();
+    if operator.is_empty() || operator == owner {
+        errors::invalid_argument("operator");
+    }
+
+    let key = operator_allowance_key(&owner, &operator);
+    if approved {
+        storage::set(&key, &[1u8]);
+    } else if storage::has(&key) {
+        storage::del(&key);
+    }
+
+    abi::generate_event(&alloc::format!(
+        "{}:owner={},operator={},approved={}",
+        APPROVAL_FOR_ALL_EVENT,
+        owner,
+        operator,
+        approved
+    ));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Transfers
+// ============================================================================
+
+/// Transfer a token. The caller must be the owner, approved for the token, or an operator of the owner.
+///
+/// Clears the token's approval.
+///
+/// # Arguments
+/// - `from`: Current token owner (string)
+/// - `to`: Recipient (string, non-empty)
+/// - `tokenId`: Token id (U256)
+///
+/// # Events
+/// - `TRANSFER:from=...,to=...,tokenId=...`
+#[massa_export]
+pub fn transferFrom(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let from = args.next_string().expect_arg("from");
+    let to = args.next_string().expect_arg("to");
+    let token_id = args.next_u256().expect_arg("tokenId");
+    if to.is_empty() {
+        errors::invalid_argument("to");
+    }
+
+    let owner = owner_of(token_id);
+    ensure!(
+        from == owner,
+        errors::INVALID_ARGUMENT,
+        fields("name=from,tokenId={}", token_id),
+        "from argument is not the token owner"
+    );
+    let caller = context::caller();
+    ensure!(
+        is_authorized(&owner, &caller, token_id),
+        errors::UNAUTHORIZED,
+        fields("caller={},tokenId={}", caller, token_id),
+        "Caller is not allowed to transfer the token"
+    );
+
+    update(&from, &to, token_id);
+
+    Vec::new()
+}
+
+// ============================================================================
+// Mint and Burn
+// ============================================================================
+
+/// Create a token (owner only).
+///
+/// # Arguments
+/// - `to`: Recipient (string, non-empty)
+/// - `tokenId`: Token id, unused so far (U256)
+///
+/// # Events
+/// - `TRANSFER:from=,to=...,tokenId=...`
+#[massa_export]
+pub fn mint(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let to = args.next_string().expect_arg("to");
+    let token_id = args.next_u256().expect_arg("tokenId");
+    if to.is_empty() {
+        errors::invalid_argument("to");
+    }
+    ensure!(
+        !storage::has(&token_owner_key(token_id)),
+        errors::ALREADY_EXISTS,
+        fields("tokenId={}", token_id),
+        "Token already exists"
+    );
+
+    update("", &to, token_id);
+
+    Vec::new()
+}
+
+/// Destroy a token. The caller must be the owner, approved for the token, or an operator of the owner.
+///
+/// # Arguments
+/// - `tokenId`: Token id (U256)
+///
+/// # Events
+/// - `TRANSFER:from=...,to=,tokenId=...`
+#[massa_export]
+pub fn burn(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token_id = args.next_u256().expect_arg("tokenId");
+
+    let owner = owner_of(token_id);
+    let caller = context::caller();
+    ensure!(
+        is_authorized(&owner, &caller, token_id),
+        errors::UNAUTHORIZED,
+        fields("caller={},tokenId={}", caller, token_id),
+        "Caller is not allowed to burn the token"
+    );
+
+    update(&owner, "", token_id);
+
+    Vec::new()
+}
+
+// ============================================================================
+// Admin
+// ============================================================================
+
+/// Change the prefix of token URIs (owner only).
+///
+/// # Arguments
+/// - `baseURI`: New prefix (string)
+#[massa_export]
+pub fn setBaseURI(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let base_uri = args.next_string().expect_arg("baseURI");
+    storage::set(BASE_URI_KEY, base_uri.as_bytes());
+
+    Vec::new()
+}
+
+/// Transfer the contract ownership (owner only).
+///
+/// # Arguments
+/// - `newOwner`: New owner address (string)
+///
+/// # Events
+/// - `CHANGE_OWNER:newOwner`
+#[massa_export]
+pub fn setOwner(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let new_owner = args.next_string().expect_arg("newOwner");
+    if new_owner.is_empty() {
+        errors::invalid_argument("newOwner");
+    }
+    storage::set(OWNER_KEY, new_owner.as_bytes());
+
+    abi::generate_event(&alloc::format!("{}:{}", CHANGE_OWNER_EVENT, new_owner));
+
+    Vec::new()
+}
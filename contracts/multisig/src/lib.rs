@@ -0,0 +1,557 @@
+//! N-of-M Multisig Wallet for Massa Blockchain
+//!
+//! Holds MRC20 tokens and MAS for a group of owners. Any owner submits a call
+//! on any contract, e.g. `transfer` on a token; it runs once `threshold`
+//! owners confirmed it.
+//!
+//! Changing the owners or the threshold goes through the wallet itself and
+//! bumps an epoch, which invalidates transactions confirmed under the old set.
+//!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes.
+//!
+//! # Storage Keys
+//! - `THRESHOLD`: Confirmations required per transaction, u32 as 4 bytes (little-endian)
+//! - `OWNER_COUNT`: Number of owners, u32 as 4 bytes (little-endian)
+//! - `OWNER{address}`: Single byte [u8], 1 while the address is an owner
+//! - `EPOCH`: Owner set version, u64 as 8 bytes (little-endian)
+//! - `TX_COUNT`: Number of transactions submitted, u64 as 8 bytes (little-endian)
+//! - `TX{id}`: Transaction (u64 LE id), Args-serialized target, entrypoint, args, coins, epoch, confirmations and executed flag
+//! - `CONFIRMATION{id}{address}`: Single byte [u8], 1 while the owner confirms the transaction
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args};
+
+use mrc20_core::approvals;
+use mrc20_core::errors::{self, OrFail};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const THRESHOLD_KEY: &[u8] = b"THRESHOLD";
+const OWNER_COUNT_KEY: &[u8] = b"OWNER_COUNT";
+const OWNER_KEY_PREFIX: &[u8] = b"OWNER";
+const EPOCH_KEY: &[u8] = b"EPOCH";
+const TX_COUNT_KEY: &[u8] = b"TX_COUNT";
+const TX_KEY_PREFIX: &[u8] = b"TX";
+const CONFIRMATION_KEY_PREFIX: &[u8] = b"CONFIRMATION";
+
+const SUBMIT_EVENT: &str = "MULTISIG_SUBMIT";
+const CONFIRM_EVENT: &str = "MULTISIG_CONFIRM";
+const REVOKE_EVENT: &str = "MULTISIG_REVOKE";
+const EXECUTE_EVENT: &str = "MULTISIG_EXECUTE";
+const ADD_OWNER_EVENT: &str = "MULTISIG_ADD_OWNER";
+const REMOVE_OWNER_EVENT: &str = "MULTISIG_REMOVE_OWNER";
+const THRESHOLD_EVENT: &str = "MULTISIG_THRESHOLD";
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+/// Build owner key: "OWNER" + address
+fn owner_key(address: &str) -> Vec<u8> {
+    let mut key = OWNER_KEY_PREFIX.to_vec();
+    key.extend_from_slice(address.as_bytes());
+    key
+}
+
+/// Build transaction key: "TX" + id (u64 LE)
+fn tx_key(id: u64) -> Vec<u8> {
+    let mut key = TX_KEY_PREFIX.to_vec();
+    key.extend_from_slice(&id.to_le_bytes());
+    key
+}
+
+/// Build confirmation key: "CONFIRMATION" + id (u64 LE) + owner
+fn confirmation_key(id: u64, owner: &str) -> Vec<u8> {
+    let mut key = CONFIRMATION_KEY_PREFIX.to_vec();
+    key.extend_from_slice(&id.to_le_bytes());
+    key.extend_from_slice(owner.as_bytes());
+    key
+}
+
+fn read_u64(key: &[u8]) -> u64 {
+    if !storage::has(key) {
+        return 0;
+    }
+    let data = storage::get(key);
+    if data.len() >= 8 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&data[..8]);
+        u64::from_le_bytes(bytes)
+    } else {
+        0
+    }
+}
+
+fn read_u32(key: &[u8]) -> u32 {
+    if !storage::has(key) {
+        return 0;
+    }
+    let data = storage::get(key);
+    if data.len() >= 4 {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&data[..4]);
+        u32::from_le_bytes(bytes)
+    } else {
+        0
+    }
+}
+
+fn read_flag(key: &[u8]) -> bool {
+    storage::has(key) && storage::get(key).first() == Some(&1)
+}
+
+fn is_owner(address: &str) -> bool {
+    read_flag(&owner_key(address))
+}
+
+fn only_owner() {
+    let caller = context::caller();
+    ensure!(is_owner(&caller), errors::UNAUTHORIZED, fields("caller={}", caller), "Caller is not an owner");
+}
+
+/// Owner set changes can only run as a confirmed transaction on the wallet itself.
+fn only_wallet() {
+    ensure!(
+        context::caller() == context::callee(),
+        errors::UNAUTHORIZED,
+        "Only callable through a confirmed transaction"
+    );
+}
+
+/// Invalidate pending transactions after the owner set or threshold changed.
+fn bump_epoch() {
+    storage::set(EPOCH_KEY, &(read_u64(EPOCH_KEY) + 1).to_le_bytes());
+}
+
+fn add_owner(address: &str) {
+    if address.is_empty() {
+        errors::invalid_argument("owner");
+    }
+    ensure!(
+        !is_owner(address),
+        errors::ALREADY_EXISTS,
+        fields("account={}", address),
+        "Address is already an owner"
+    );
+    storage::set(&owner_key(address), &[1u8]);
+    storage::set(OWNER_COUNT_KEY, &(read_u32(OWNER_COUNT_KEY) + 1).to_le_bytes());
+}
+
+fn set_threshold(threshold: u32) {
+    let owner_count = read_u32(OWNER_COUNT_KEY);
+    ensure!(
+        approvals::is_valid_threshold(threshold, owner_count),
+        errors::INVALID_ARGUMENT,
+        fields("name=threshold,threshold={},owners={}", threshold, owner_count),
+        "threshold argument must be between 1 and the number of owners"
+    );
+    storage::set(THRESHOLD_KEY, &threshold.to_le_bytes());
+}
+
+/// A call waiting for owner confirmations.
+struct Transaction {
+    target: String,
+    entrypoint: String,
+    args: Vec<u8>,
+    coins: u64,
+    epoch: u64,
+    confirmations: u32,
+    executed: bool,
+}
+
+impl Transaction {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Args::new();
+        data.add_string(&self.target)
+            .add_string(&self.entrypoint)
+            .add_bytes(&self.args)
+            .add_u64(self.coins)
+            .add_u64(self.epoch)
+            .add_u32(self.confirmations)
+            .add_bool(self.executed);
+        data.into_bytes()
+    }
+
+    fn from_bytes(data: &[u8]) -> Option<Self> {
+        let mut data = Args::from_bytes(data.to_vec());
+        Some(Transaction {
+            target: data.next_string().ok()?,
+            entrypoint: data.next_string().ok()?,
+            args: data.next_bytes().ok()?,
+            coins: data.next_u64().ok()?,
+            epoch: data.next_u64().ok()?,
+            confirmations: data.next_u32().ok()?,
+            executed: data.next_bool().ok()?,
+        })
+    }
+}
+
+fn get_transaction(id: u64) -> Transaction {
+    let key = tx_key(id);
+    ensure!(storage::has(&key), errors::NOT_FOUND, fields("id={}", id), "Transaction does not exist");
+    Transaction::from_bytes(&storage::get(&key)).or_fail(errors::INVALID_STATE, "Corrupted transaction")
+}
+
+fn set_transaction(id: u64, transaction: &Transaction) {
+    storage::set(&tx_key(id), &transaction.to_bytes());
+}
+
+/// Fail unless the transaction can still be confirmed or executed.
+fn ensure_open(id: u64, transaction: &Transaction) {
+    ensure!(!transaction.executed, errors::ALREADY_USED, fields("id={}", id), "Transaction is already executed");
+    ensure!(
+        transaction.epoch == read_u64(EPOCH_KEY),
+        errors::EXPIRED,
+        fields("id={}", id),
+        "Transaction is stale: owners changed"
+    );
+}
+
+fn confirm(id: u64, transaction: &mut Transaction) {
+    let owner = context::caller();
+    let key = confirmation_key(id, &owner);
+    ensure!(!read_flag(&key), errors::ALREADY_USED, fields("id={}", id), "Transaction already confirmed by caller");
+
+    storage::set(&key, &[1u8]);
+    transaction.confirmations += 1;
+    set_transaction(id, transaction);
+
+    abi::generate_event(&alloc::format!("{}:id={},owner={}", CONFIRM_EVENT, id, owner));
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize the wallet.
+///
+/// # Arguments
+/// - `owners`: Args(count: u32, then `count` addresses as strings) (bytes)
+/// - `threshold`: Confirmations required per transaction (u32)
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owners = args.next_bytes().expect_arg("owners");
+    let threshold = args.next_u32().expect_arg("threshold");
+
+    let mut owners = Args::from_bytes(owners);
+    let count = owners.next_u32().expect_arg("owners");
+    for _ in 0..count {
+        add_owner(&owners.next_string().expect_arg("owners"));
+    }
+    set_threshold(threshold);
+
+    Vec::new()
+}
+
+// ============================================================================
+// Transactions
+// ============================================================================
+
+/// Submit a call (owner only). The submitter confirms it.
+///
+/// # Arguments
+/// - `target`: Contract to call (string)
+/// - `entrypoint`: Function to call (string)
+/// - `args`: Serialized arguments (bytes)
+/// - `coins`: nanoMAS sent from the wallet with the call (u64)
+///
+/// # Returns
+/// - Transaction id (u64, 8 bytes LE)
+///
+/// # Events
+/// - `MULTISIG_SUBMIT:id=...,target=...,entrypoint=...`
+/// - `MULTISIG_CONFIRM:id=...,owner=...`
+#[massa_export]
+pub fn submitTransaction(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let target = args.next_string().expect_arg("target");
+    let entrypoint = args.next_string().expect_arg("entrypoint");
+    let call_args = args.next_bytes().expect_arg("args");
+    let coins = args.next_u64().expect_arg("coins");
+    if target.is_empty() {
+        errors::invalid_argument("target");
+    }
+    if entrypoint.is_empty() {
+        errors::invalid_argument("entrypoint");
+    }
+
+    let id = read_u64(TX_COUNT_KEY);
+    storage::set(TX_COUNT_KEY, &(id + 1).to_le_bytes());
+
+    let mut transaction = Transaction {
+        target,
+        entrypoint,
+        args: call_args,
+        coins,
+        epoch: read_u64(EPOCH_KEY),
+        confirmations: 0,
+        executed: false,
+    };
+    abi::generate_event(&alloc::format!(
+        "{}:id={},target={},entrypoint={}",
+        SUBMIT_EVENT,
+        id,
+        transaction.target,
+        transaction.entrypoint
+    ));
+    confirm(id, &mut transaction);
+
+    id.to_le_bytes().to_vec()
+}
+
+/// Confirm a pending transaction (owner only).
+///
+/// # Arguments
+/// - `id`: Transaction id (u64)
+///
+/// # Events
+/// - `MULTISIG_CONFIRM:id=...,owner=...`
+#[massa_export]
+pub fn confirmTransaction(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+    let mut transaction = get_transaction(id);
+    ensure_open(id, &transaction);
+    confirm(id, &mut transaction);
+
+    Vec::new()
+}
+
+/// Withdraw the caller's confirmation of a pending transaction (owner only).
+///
+/// # Arguments
+/// - `id`: Transaction id (u64)
+///
+/// # Events
+/// - `MULTISIG_REVOKE:id=...,owner=...`
+#[massa_export]
+pub fn revokeConfirmation(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+    let mut transaction = get_transaction(id);
+    ensure_open(id, &transaction);
+
+    let owner = context::caller();
+    let key = confirmation_key(id, &owner);
+    ensure!(read_flag(&key), errors::NOT_FOUND, fields("id={}", id), "Transaction not confirmed by caller");
+    storage::set(&key, &[0u8]);
+    transaction.confirmations -= 1;
+    set_transaction(id, &transaction);
+
+    abi::generate_event(&alloc::format!("{}:id={},owner={}", REVOKE_EVENT, id, owner));
+
+    Vec::new()
+}
+
+/// Run a transaction that reached the threshold (owner only).
+///
+/// # Arguments
+/// - `id`: Transaction id (u64)
+///
+/// # Returns
+/// - The return value of the executed call
+///
+/// # Events
+/// - `MULTISIG_EXECUTE:id=...`
+#[massa_export]
+pub fn executeTransaction(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+    let mut transaction = get_transaction(id);
+    ensure_open(id, &transaction);
+    let threshold = read_u32(THRESHOLD_KEY);
+    ensure!(
+        transaction.confirmations >= threshold,
+        errors::INVALID_STATE,
+        fields("id={},confirmations={},threshold={}", id, transaction.confirmations, threshold),
+        "Transaction does not have enough confirmations"
+    );
+
+    // Mark as executed before the call so it cannot be replayed from within it
+    transaction.executed = true;
+    set_transaction(id, &transaction);
+
+    let result = abi::call(&transaction.target, &transaction.entrypoint, &transaction.args, transaction.coins);
+
+    abi::generate_event(&alloc::format!("{}:id={}", EXECUTE_EVENT, id));
+
+    result
+}
+
+// ============================================================================
+// Owner Management (through the wallet only)
+// ============================================================================
+
+/// Add an owner. Only callable by the wallet itself, through a confirmed transaction.
+///
+/// # Arguments
+/// - `owner`: Owner to add (string)
+///
+/// # Events
+/// - `MULTISIG_ADD_OWNER:owner`
+#[massa_export]
+pub fn addOwner(binary_args: &[u8]) -> Vec<u8> {
+    only_wallet();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("owner");
+    add_owner(&owner);
+    bump_epoch();
+
+    abi::generate_event(&alloc::format!("{}:{}", ADD_OWNER_EVENT, owner));
+
+    Vec::new()
+}
+
+/// Remove an owner. Only callable by the wallet itself, through a confirmed transaction.
+///
+/// The remaining owners must still be able to reach the threshold.
+///
+/// # Arguments
+/// - `owner`: Owner to remove (string)
+///
+/// # Events
+/// - `MULTISIG_REMOVE_OWNER:owner`
+#[massa_export]
+pub fn removeOwner(binary_args: &[u8]) -> Vec<u8> {
+    only_wallet();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("owner");
+    ensure!(is_owner(&owner), errors::NOT_FOUND, fields("account={}", owner), "Address is not an owner");
+
+    let owner_count = read_u32(OWNER_COUNT_KEY);
+    ensure!(
+        approvals::allows_removal(read_u32(THRESHOLD_KEY), owner_count),
+        errors::INVALID_STATE,
+        "Removing the owner would make the threshold unreachable"
+    );
+    storage::set(&owner_key(&owner), &[0u8]);
+    storage::set(OWNER_COUNT_KEY, &(owner_count - 1).to_le_bytes());
+    bump_epoch();
+
+    abi::generate_event(&alloc::format!("{}:{}", REMOVE_OWNER_EVENT, owner));
+
+    Vec::new()
+}
+
+/// Change the confirmation threshold. Only callable by the wallet itself, through a confirmed transaction.
+///
+/// # Arguments
+/// - `threshold`: Required confirmations (u32)
+///
+/// # Events
+/// - `MULTISIG_THRESHOLD:threshold`
+#[massa_export]
+pub fn changeThreshold(binary_args: &[u8]) -> Vec<u8> {
+    only_wallet();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let threshold = args.next_u32().expect_arg("threshold");
+    set_threshold(threshold);
+    bump_epoch();
+
+    abi::generate_event(&alloc::format!("{}:{}", THRESHOLD_EVENT, threshold));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Returns a transaction as Args(target: string, entrypoint: string, args: bytes,
+/// coins: u64, confirmations: u32, executed: bool).
+///
+/// # Arguments
+/// - `id`: Transaction id (u64)
+#[massa_export]
+pub fn transaction(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+    let transaction = get_transaction(id);
+
+    let mut result = Args::new();
+    result
+        .add_string(&transaction.target)
+        .add_string(&transaction.entrypoint)
+        .add_bytes(&transaction.args)
+        .add_u64(transaction.coins)
+        .add_u32(transaction.confirmations)
+        .add_bool(transaction.executed);
+    result.into_bytes()
+}
+
+/// Returns true (1) if the owner confirms the transaction, false (0) otherwise.
+///
+/// # Arguments
+/// - `id`: Transaction id (u64)
+/// - `owner`: Owner address (string)
+#[massa_export]
+pub fn isConfirmed(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+    let owner = args.next_string().expect_arg("owner");
+
+    if read_flag(&confirmation_key(id, &owner)) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+/// Returns true (1) if the address is an owner, false (0) otherwise.
+///
+/// # Arguments
+/// - `address`: Address to check (string)
+#[massa_export]
+pub fn isOwner(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+
+    if is_owner(&address) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+/// Returns the confirmation threshold (u32, 4 bytes LE).
+#[massa_export]
+pub fn threshold(_binary_args: &[u8]) -> Vec<u8> {
+    read_u32(THRESHOLD_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the number of owners (u32, 4 bytes LE).
+#[massa_export]
+pub fn ownerCount(_binary_args: &[u8]) -> Vec<u8> {
+    read_u32(OWNER_COUNT_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the number of transactions submitted (u64, 8 bytes LE).
+#[massa_export]
+pub fn transactionCount(_binary_args: &[u8]) -> Vec<u8> {
+    read_u64(TX_COUNT_KEY).to_le_bytes().to_vec()
+}
@@ -0,0 +1,378 @@
+//! MRC20 Staking with Time-Weighted Rewards for Massa Blockchain
+//!
+//! Stakers deposit an MRC20 token and earn more of the same token. Every
+//! Massa period, `rewardRate` tokens are shared between stakers in proportion
+//! to their stake, using reward-per-token accounting: each stake only needs
+//! updating when its owner interacts with the contract.
+//!
+//! Rewards are paid from a reserve that anyone can fund with `fundRewards`.
+//! Accrual stops when the reserve is empty, so the contract never owes more
+//! than it holds. The accrual arithmetic lives in `mrc20_core::rewards`.
+//!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes.
+//!
+//! # Storage Keys
+//! - `OWNER`: Owner address as raw string bytes
+//! - `TOKEN`: Staked and reward token address as raw string bytes
+//! - `REWARD_RATE`: Rewards distributed per period, u256 as 32 bytes (little-endian)
+//! - `REWARD_RESERVE`: Funded rewards not yet distributed, u256 as 32 bytes (little-endian)
+//! - `REWARD_PER_TOKEN`: Rewards per staked token since deployment, scaled by 1e18, u256 as 32 bytes (little-endian)
+//! - `LAST_UPDATE`: Period of the last reward update, u64 as 8 bytes (little-endian)
+//! - `TOTAL_STAKED`: Sum of all stakes, u256 as 32 bytes (little-endian)
+//! - `STAKE{address}`: Staked amount, u256 as 32 bytes (little-endian)
+//! - `REWARD_PER_TOKEN_PAID{address}`: `REWARD_PER_TOKEN` at the account's last update, u256 as 32 bytes (little-endian)
+//! - `REWARDS{address}`: Rewards earned and not claimed at the last update, u256 as 32 bytes (little-endian)
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args, U256};
+
+use mrc20_core::calls;
+use mrc20_core::errors::{self, OrFail};
+use mrc20_core::rewards::{self, RewardPool};
+use mrc20_core::store;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const OWNER_KEY: &[u8] = b"OWNER";
+const TOKEN_KEY: &[u8] = b"TOKEN";
+const REWARD_RATE_KEY: &[u8] = b"REWARD_RATE";
+const REWARD_RESERVE_KEY: &[u8] = b"REWARD_RESERVE";
+const REWARD_PER_TOKEN_KEY: &[u8] = b"REWARD_PER_TOKEN";
+const LAST_UPDATE_KEY: &[u8] = b"LAST_UPDATE";
+const TOTAL_STAKED_KEY: &[u8] = b"TOTAL_STAKED";
+const STAKE_KEY_PREFIX: &[u8] = b"STAKE";
+const REWARD_PER_TOKEN_PAID_KEY_PREFIX: &[u8] = b"REWARD_PER_TOKEN_PAID";
+const REWARDS_KEY_PREFIX: &[u8] = b"REWARDS";
+
+const STAKE_EVENT: &str = "STAKE";
+const UNSTAKE_EVENT: &str = "UNSTAKE";
+const CLAIM_REWARDS_EVENT: &str = "CLAIM_REWARDS";
+const FUND_REWARDS_EVENT: &str = "FUND_REWARDS";
+const REWARD_RATE_EVENT: &str = "REWARD_RATE";
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+/// Build an account key: prefix + address
+fn account_key(prefix: &[u8], address: &str) -> Vec<u8> {
+    let mut key = prefix.to_vec();
+    key.extend_from_slice(address.as_bytes());
+    key
+}
+
+fn read_u256(key: &[u8]) -> U256 {
+    store::read(key).unwrap_or(U256::ZERO)
+}
+
+fn write_u256(key: &[u8], value: U256) {
+    storage::set(key, &value.to_le_bytes());
+}
+
+fn read_u64(key: &[u8]) -> u64 {
+    store::read(key).unwrap_or(0)
+}
+
+fn read_string(key: &[u8]) -> String {
+    let data = store::get_opt(key).or_fail(errors::INVALID_STATE, "Staking is not initialized");
+    core::str::from_utf8(&data).ok().map(String::from).or_fail(errors::INVALID_STATE, "Corrupted address")
+}
+
+fn only_owner() {
+    let owner = read_string(OWNER_KEY);
+    let caller = context::caller();
+    ensure!(caller == owner, errors::UNAUTHORIZED, fields("caller={}", caller), "Caller is not the owner");
+}
+
+/// Call `operation` on the staked token with `args`.
+fn call_token(operation: &str, args: Args) {
+    calls::call(&read_string(TOKEN_KEY), operation, args);
+}
+
+// ============================================================================
+// Reward Accounting
+// ============================================================================
+
+/// Reward per token and reserve after distributing the rewards due up to `period`.
+fn accrued_state(period: u64) -> (U256, U256) {
+    let pool = RewardPool {
+        reward_per_token: read_u256(REWARD_PER_TOKEN_KEY),
+        reserve: read_u256(REWARD_RESERVE_KEY),
+        rate: read_u256(REWARD_RATE_KEY),
+        last_update: read_u64(LAST_UPDATE_KEY),
+        total_staked: read_u256(TOTAL_STAKED_KEY),
+    };
+    pool.accrued(period).or_fail(errors::OVERFLOW, "Reward per token overflow")
+}
+
+/// Rewards of `account` given the current reward per token.
+fn earned_with(account: &str, reward_per_token: U256) -> U256 {
+    rewards::earned(
+        read_u256(&account_key(STAKE_KEY_PREFIX, account)),
+        reward_per_token,
+        read_u256(&account_key(REWARD_PER_TOKEN_PAID_KEY_PREFIX, account)),
+        read_u256(&account_key(REWARDS_KEY_PREFIX, account)),
+    )
+    .or_fail(errors::OVERFLOW, "Reward overflow")
+}
+
+/// Distribute the rewards due so far and settle `account`'s share.
+///
+/// Must run before any change to a stake, the total stake or the reward rate.
+fn update_rewards(account: Option<&str>) {
+    let period = context::current_period();
+    let (reward_per_token, reserve) = accrued_state(period);
+    write_u256(REWARD_PER_TOKEN_KEY, reward_per_token);
+    write_u256(REWARD_RESERVE_KEY, reserve);
+    storage::set(LAST_UPDATE_KEY, &period.to_le_bytes());
+
+    if let Some(account) = account {
+        write_u256(&account_key(REWARDS_KEY_PREFIX, account), earned_with(account, reward_per_token));
+        write_u256(&account_key(REWARD_PER_TOKEN_PAID_KEY_PREFIX, account), reward_per_token);
+    }
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize staking. The caller becomes the owner.
+///
+/// # Arguments
+/// - `token`: Staked and reward token address (string)
+/// - `rewardRate`: Rewards distributed per period (U256)
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token = args.next_string().expect_arg("token");
+    let reward_rate = args.next_u256().expect_arg("rewardRate");
+    if token.is_empty() {
+        errors::invalid_argument("token");
+    }
+
+    storage::set(OWNER_KEY, context::caller().as_bytes());
+    storage::set(TOKEN_KEY, token.as_bytes());
+    write_u256(REWARD_RATE_KEY, reward_rate);
+    storage::set(LAST_UPDATE_KEY, &context::current_period().to_le_bytes());
+
+    Vec::new()
+}
+
+// ============================================================================
+// Staking
+// ============================================================================
+
+/// Stake tokens. The caller must have approved this contract for `amount`.
+///
+/// # Arguments
+/// - `amount`: Amount to stake (U256)
+///
+/// # Events
+/// - `STAKE:account=...,amount=...`
+#[massa_export]
+pub fn stake(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let amount = args.next_u256().expect_arg("amount");
+    if amount == U256::ZERO {
+        errors::invalid_argument("amount");
+    }
+
+    let account = context::caller();
+    update_rewards(Some(&account));
+
+    let stake_key = account_key(STAKE_KEY_PREFIX, &account);
+    write_u256(&stake_key, read_u256(&stake_key).checked_add(amount).or_fail(errors::OVERFLOW, "Stake overflow"));
+    write_u256(
+        TOTAL_STAKED_KEY,
+        read_u256(TOTAL_STAKED_KEY).checked_add(amount).or_fail(errors::OVERFLOW, "Total stake overflow"),
+    );
+
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(&account).add_string(&context::callee()).add_u256(amount);
+    call_token("transferFrom", transfer_args);
+
+    abi::generate_event(&alloc::format!("{}:account={},amount={}", STAKE_EVENT, account, amount));
+
+    Vec::new()
+}
+
+/// Withdraw staked tokens. Earned rewards stay claimable.
+///
+/// # Arguments
+/// - `amount`: Amount to unstake (U256)
+///
+/// # Events
+/// - `UNSTAKE:account=...,amount=...`
+#[massa_export]
+pub fn unstake(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let amount = args.next_u256().expect_arg("amount");
+    if amount == U256::ZERO {
+        errors::invalid_argument("amount");
+    }
+
+    let account = context::caller();
+    let stake_key = account_key(STAKE_KEY_PREFIX, &account);
+    let staked = read_u256(&stake_key);
+    ensure!(
+        amount <= staked,
+        errors::INSUFFICIENT_BALANCE,
+        fields("needed={},have={}", amount, staked),
+        "Unstake amount exceeds the stake"
+    );
+
+    update_rewards(Some(&account));
+    write_u256(&stake_key, staked - amount);
+    write_u256(TOTAL_STAKED_KEY, read_u256(TOTAL_STAKED_KEY).saturating_sub(amount));
+
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(&account).add_u256(amount);
+    call_token("transfer", transfer_args);
+
+    abi::generate_event(&alloc::format!("{}:account={},amount={}", UNSTAKE_EVENT, account, amount));
+
+    Vec::new()
+}
+
+/// Send the caller's earned rewards.
+///
+/// # Returns
+/// - Amount claimed (u256 bytes)
+///
+/// # Events
+/// - `CLAIM_REWARDS:account=...,amount=...`
+#[massa_export]
+pub fn claimRewards(_binary_args: &[u8]) -> Vec<u8> {
+    let account = context::caller();
+    update_rewards(Some(&account));
+
+    let rewards_key = account_key(REWARDS_KEY_PREFIX, &account);
+    let rewards = read_u256(&rewards_key);
+    ensure!(rewards > U256::ZERO, errors::INVALID_STATE, "No rewards to claim");
+    write_u256(&rewards_key, U256::ZERO);
+
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(&account).add_u256(rewards);
+    call_token("transfer", transfer_args);
+
+    abi::generate_event(&alloc::format!("{}:account={},amount={}", CLAIM_REWARDS_EVENT, account, rewards));
+
+    rewards.to_le_bytes().to_vec()
+}
+
+// ============================================================================
+// Rewards Administration
+// ============================================================================
+
+/// Add `amount` to the reward reserve. The caller must have approved this contract for `amount`.
+///
+/// # Arguments
+/// - `amount`: Amount of reward tokens (U256)
+///
+/// # Events
+/// - `FUND_REWARDS:from=...,amount=...`
+#[massa_export]
+pub fn fundRewards(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let amount = args.next_u256().expect_arg("amount");
+    if amount == U256::ZERO {
+        errors::invalid_argument("amount");
+    }
+
+    // Rewards due before the funding were capped by the old reserve
+    update_rewards(None);
+    write_u256(
+        REWARD_RESERVE_KEY,
+        read_u256(REWARD_RESERVE_KEY).checked_add(amount).or_fail(errors::OVERFLOW, "Reward reserve overflow"),
+    );
+
+    let from = context::caller();
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(&from).add_string(&context::callee()).add_u256(amount);
+    call_token("transferFrom", transfer_args);
+
+    abi::generate_event(&alloc::format!("{}:from={},amount={}", FUND_REWARDS_EVENT, from, amount));
+
+    Vec::new()
+}
+
+/// Change the rewards distributed per period (owner only). Rewards due so far use the old rate.
+///
+/// # Arguments
+/// - `rewardRate`: Rewards per period (U256)
+///
+/// # Events
+/// - `REWARD_RATE:{rewardRate}`
+#[massa_export]
+pub fn setRewardRate(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let reward_rate = args.next_u256().expect_arg("rewardRate");
+
+    update_rewards(None);
+    write_u256(REWARD_RATE_KEY, reward_rate);
+
+    abi::generate_event(&alloc::format!("{}:{}", REWARD_RATE_EVENT, reward_rate));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Returns the staked amount of an account (u256 bytes).
+///
+/// # Arguments
+/// - `address`: Account address (string)
+#[massa_export]
+pub fn stakedBalance(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    read_u256(&account_key(STAKE_KEY_PREFIX, &address)).to_le_bytes().to_vec()
+}
+
+/// Returns the rewards an account can claim now (u256 bytes).
+///
+/// # Arguments
+/// - `address`: Account address (string)
+#[massa_export]
+pub fn earned(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+    let (reward_per_token, _) = accrued_state(context::current_period());
+    earned_with(&address, reward_per_token).to_le_bytes().to_vec()
+}
+
+/// Returns the sum of all stakes (u256 bytes).
+#[massa_export]
+pub fn totalStaked(_binary_args: &[u8]) -> Vec<u8> {
+    read_u256(TOTAL_STAKED_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the rewards distributed per period (u256 bytes).
+#[massa_export]
+pub fn rewardRate(_binary_args: &[u8]) -> Vec<u8> {
+    read_u256(REWARD_RATE_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the funded rewards not yet distributed (u256 bytes).
+#[massa_export]
+pub fn rewardReserve(_binary_args: &[u8]) -> Vec<u8> {
+    let (_, reserve) = accrued_state(context::current_period());
+    reserve.to_le_bytes().to_vec()
+}
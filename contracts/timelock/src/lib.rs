@@ -0,0 +1,383 @@
+//! Timelock Controller for Massa Blockchain
+//!
+//! Delays admin calls so users can react before they take effect. The owner
+//! schedules a call on any contract; it can run once its delay, at least
+//! `minDelay` periods, has passed, and the owner can cancel it until then.
+//!
+//! To put a token behind the timelock, its owner calls `setOwner` (or
+//! `proposeOwner`, then a scheduled `acceptOwnership`) with the timelock
+//! address. Token admin calls such as `mint` are then scheduled here.
+//!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes.
+//!
+//! # Storage Keys
+//! - `OWNER`: Owner address as raw string bytes
+//! - `MIN_DELAY`: Minimum delay in periods, u64 as 8 bytes (little-endian)
+//! - `OPERATION_COUNT`: Number of operations scheduled, u64 as 8 bytes (little-endian)
+//! - `OPERATION{id}`: Operation (u64 LE id), Args-serialized target, entrypoint, args, ready period and status
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args};
+
+use mrc20_core::errors::{self, OrFail};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const OWNER_KEY: &[u8] = b"OWNER";
+const MIN_DELAY_KEY: &[u8] = b"MIN_DELAY";
+const OPERATION_COUNT_KEY: &[u8] = b"OPERATION_COUNT";
+const OPERATION_KEY_PREFIX: &[u8] = b"OPERATION";
+
+// Operation statuses
+const STATUS_PENDING: u8 = 0;
+const STATUS_EXECUTED: u8 = 1;
+const STATUS_CANCELLED: u8 = 2;
+
+const SCHEDULED_EVENT: &str = "TIMELOCK_SCHEDULED";
+const EXECUTED_EVENT: &str = "TIMELOCK_EXECUTED";
+const CANCELLED_EVENT: &str = "TIMELOCK_CANCELLED";
+const MIN_DELAY_EVENT: &str = "TIMELOCK_MIN_DELAY";
+const CHANGE_OWNER_EVENT: &str = "CHANGE_OWNER";
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+/// Build operation key: "OPERATION" + id (u64 LE)
+fn operation_key(id: u64) -> Vec<u8> {
+    let mut key = OPERATION_KEY_PREFIX.to_vec();
+    key.extend_from_slice(&id.to_le_bytes());
+    key
+}
+
+fn read_u64(key: &[u8]) -> u64 {
+    if !storage::has(key) {
+        return 0;
+    }
+    let data = storage::get(key);
+    if data.len() >= 8 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&data[..8]);
+        u64::from_le_bytes(bytes)
+    } else {
+        0
+    }
+}
+
+fn get_owner() -> String {
+    ensure!(storage::has(OWNER_KEY), errors::INVALID_STATE, "Owner is not set");
+    let data = storage::get(OWNER_KEY);
+    core::str::from_utf8(&data).ok().map(String::from).or_fail(errors::INVALID_STATE, "Corrupted owner address")
+}
+
+fn only_owner() {
+    let caller = context::caller();
+    ensure!(caller == get_owner(), errors::UNAUTHORIZED, fields("caller={}", caller), "Caller is not the owner");
+}
+
+/// Timelock settings can only change through a scheduled operation on the timelock itself.
+fn only_timelock() {
+    ensure!(
+        context::caller() == context::callee(),
+        errors::UNAUTHORIZED,
+        "Only callable through a scheduled operation"
+    );
+}
+
+/// A delayed call on a contract.
+struct Operation {
+    target: String,
+    entrypoint: String,
+    args: Vec<u8>,
+    ready_period: u64,
+    status: u8,
+}
+
+impl Operation {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Args::new();
+        data.add_string(&self.target)
+            .add_string(&self.entrypoint)
+            .add_bytes(&self.args)
+            .add_u64(self.ready_period)
+            .add_u8(self.status);
+        data.into_bytes()
+    }
+
+    fn from_bytes(data: &[u8]) -> Option<Self> {
+        let mut data = Args::from_bytes(data.to_vec());
+        Some(Operation {
+            target: data.next_string().ok()?,
+            entrypoint: data.next_string().ok()?,
+            args: data.next_bytes().ok()?,
+            ready_period: data.next_u64().ok()?,
+            status: data.next_u8().ok()?,
+        })
+    }
+}
+
+fn get_operation(id: u64) -> Operation {
+    let key = operation_key(id);
+    ensure!(storage::has(&key), errors::NOT_FOUND, fields("id={}", id), "Operation does not exist");
+    Operation::from_bytes(&storage::get(&key)).or_fail(errors::INVALID_STATE, "Corrupted operation")
+}
+
+fn set_operation(id: u64, operation: &Operation) {
+    storage::set(&operation_key(id), &operation.to_bytes());
+}
+
+fn ensure_pending(id: u64, operation: &Operation) {
+    ensure!(
+        operation.status != STATUS_EXECUTED,
+        errors::ALREADY_USED,
+        fields("id={}", id),
+        "Operation is already executed"
+    );
+    ensure!(
+        operation.status == STATUS_PENDING,
+        errors::INVALID_STATE,
+        fields("id={}", id),
+        "Operation is cancelled"
+    );
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize the timelock. The caller becomes the owner.
+///
+/// # Arguments
+/// - `minDelay`: Minimum delay in periods between scheduling and execution (u64)
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let min_delay = args.next_u64().expect_arg("minDelay");
+
+    let owner = context::caller();
+    storage::set(OWNER_KEY, owner.as_bytes());
+    storage::set(MIN_DELAY_KEY, &min_delay.to_le_bytes());
+
+    abi::generate_event(&alloc::format!("{}:{}", CHANGE_OWNER_EVENT, owner));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Operations
+// ============================================================================
+
+/// Schedule a call (owner only).
+///
+/// # Arguments
+/// - `target`: Contract to call (string)
+/// - `entrypoint`: Function to call (string)
+/// - `args`: Serialized arguments (bytes)
+/// - `delay`: Periods to wait before execution, at least `minDelay` (u64)
+///
+/// # Returns
+/// - Operation id (u64, 8 bytes LE)
+///
+/// # Events
+/// - `TIMELOCK_SCHEDULED:id=...,target=...,entrypoint=...,ready=...`
+#[massa_export]
+pub fn schedule(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let target = args.next_string().expect_arg("target");
+    let entrypoint = args.next_string().expect_arg("entrypoint");
+    let call_args = args.next_bytes().expect_arg("args");
+    let delay = args.next_u64().expect_arg("delay");
+    if target.is_empty() {
+        errors::invalid_argument("target");
+    }
+    if entrypoint.is_empty() {
+        errors::invalid_argument("entrypoint");
+    }
+    let min_delay = read_u64(MIN_DELAY_KEY);
+    ensure!(
+        delay >= min_delay,
+        errors::INVALID_ARGUMENT,
+        fields("name=delay,needed={},have={}", min_delay, delay),
+        "delay argument is below the minimum delay"
+    );
+
+    let ready_period = context::current_period()
+        .checked_add(delay)
+        .or_fail(errors::OVERFLOW, "Delay overflow");
+    let id = read_u64(OPERATION_COUNT_KEY);
+    storage::set(OPERATION_COUNT_KEY, &(id + 1).to_le_bytes());
+    let operation = Operation {
+        target,
+        entrypoint,
+        args: call_args,
+        ready_period,
+        status: STATUS_PENDING,
+    };
+    set_operation(id, &operation);
+
+    abi::generate_event(&alloc::format!(
+        "{}:id={},target={},entrypoint={},ready={}",
+        SCHEDULED_EVENT,
+        id,
+        operation.target,
+        operation.entrypoint,
+        ready_period
+    ));
+
+    id.to_le_bytes().to_vec()
+}
+
+/// Run a scheduled call whose delay has passed. Anyone can call it, once.
+///
+/// # Arguments
+/// - `id`: Operation id (u64)
+///
+/// # Returns
+/// - The return value of the executed call
+///
+/// # Events
+/// - `TIMELOCK_EXECUTED:id=...`
+#[massa_export]
+pub fn execute(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+
+    let mut operation = get_operation(id);
+    ensure_pending(id, &operation);
+    ensure!(
+        context::current_period() >= operation.ready_period,
+        errors::NOT_YET_VALID,
+        fields("id={},ready={}", id, operation.ready_period),
+        "Operation delay has not passed"
+    );
+
+    // Mark as executed before the call so it cannot be replayed from within it
+    operation.status = STATUS_EXECUTED;
+    set_operation(id, &operation);
+
+    let result = abi::call(&operation.target, &operation.entrypoint, &operation.args, 0);
+
+    abi::generate_event(&alloc::format!("{}:id={}", EXECUTED_EVENT, id));
+
+    result
+}
+
+/// Cancel a pending operation (owner only).
+///
+/// # Arguments
+/// - `id`: Operation id (u64)
+///
+/// # Events
+/// - `TIMELOCK_CANCELLED:id=...`
+#[massa_export]
+pub fn cancel(binary_args: &[u8]) -> Vec<u8> {
+    only_owner();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+
+    let mut operation = get_operation(id);
+    ensure_pending(id, &operation);
+    operation.status = STATUS_CANCELLED;
+    set_operation(id, &operation);
+
+    abi::generate_event(&alloc::format!("{}:id={}", CANCELLED_EVENT, id));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Settings (through the timelock only)
+// ============================================================================
+
+/// Change the minimum delay. Only callable by the timelock itself, through a scheduled operation.
+///
+/// # Arguments
+/// - `minDelay`: Minimum delay in periods (u64)
+///
+/// # Events
+/// - `TIMELOCK_MIN_DELAY:{minDelay}`
+#[massa_export]
+pub fn updateDelay(binary_args: &[u8]) -> Vec<u8> {
+    only_timelock();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let min_delay = args.next_u64().expect_arg("minDelay");
+    storage::set(MIN_DELAY_KEY, &min_delay.to_le_bytes());
+
+    abi::generate_event(&alloc::format!("{}:{}", MIN_DELAY_EVENT, min_delay));
+
+    Vec::new()
+}
+
+/// Change the owner. Only callable by the timelock itself, through a scheduled operation.
+///
+/// # Arguments
+/// - `newOwner`: New owner address (string)
+///
+/// # Events
+/// - `CHANGE_OWNER:newOwner`
+#[massa_export]
+pub fn setOwner(binary_args: &[u8]) -> Vec<u8> {
+    only_timelock();
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let new_owner = args.next_string().expect_arg("newOwner");
+    if new_owner.is_empty() {
+        errors::invalid_argument("newOwner");
+    }
+    storage::set(OWNER_KEY, new_owner.as_bytes());
+
+    abi::generate_event(&alloc::format!("{}:{}", CHANGE_OWNER_EVENT, new_owner));
+
+    Vec::new()
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Returns an operation as Args(target: string, entrypoint: string, args: bytes,
+/// readyPeriod: u64, status: u8), status 0 = pending, 1 = executed, 2 = cancelled.
+///
+/// # Arguments
+/// - `id`: Operation id (u64)
+#[massa_export]
+pub fn operation(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+    get_operation(id).to_bytes()
+}
+
+/// Returns the number of operations scheduled (u64, 8 bytes LE).
+#[massa_export]
+pub fn operationCount(_binary_args: &[u8]) -> Vec<u8> {
+    read_u64(OPERATION_COUNT_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the minimum delay in periods (u64, 8 bytes LE).
+#[massa_export]
+pub fn minDelay(_binary_args: &[u8]) -> Vec<u8> {
+    read_u64(MIN_DELAY_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the owner address (raw string bytes).
+#[massa_export]
+pub fn ownerAddress(_binary_args: &[u8]) -> Vec<u8> {
+    get_owner().into_bytes()
+}
@@ -0,0 +1,266 @@
+//! Token Factory for Massa Blockchain
+//!
+//! Deploys new instances of the erc20-token contract in one call. The
+//! erc20-token bytecode is stored at deployment; `createToken` deploys a copy,
+//! runs its constructor, then hands the supply, the roles and the ownership
+//! over to the caller, so the token ends up as if the caller deployed it.
+//!
+//! Created tokens are recorded for enumeration.
+//!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes.
+//!
+//! # Storage Keys
+//! - `TOKEN_BYTECODE`: erc20-token WASM bytecode
+//! - `TOKEN_COUNT`: Number of tokens created, u64 as 8 bytes (little-endian)
+//! - `TOKEN{id}`: Created token (u64 LE id), Args-serialized address and creator
+//! - `CREATED{address}`: Set to 1 for addresses deployed by the factory
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args, U256};
+
+use mrc20_core::calls;
+use mrc20_core::errors::{self, OrFail};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const TOKEN_BYTECODE_KEY: &[u8] = b"TOKEN_BYTECODE";
+const TOKEN_COUNT_KEY: &[u8] = b"TOKEN_COUNT";
+const TOKEN_KEY_PREFIX: &[u8] = b"TOKEN";
+const CREATED_KEY_PREFIX: &[u8] = b"CREATED";
+
+/// Roles granted by the token constructor, handed over to the creator.
+const ROLES: [&str; 5] = ["MINTER", "BURNER", "PAUSER", "COMPLIANCE", "BRIDGE_MINTER"];
+
+const TOKEN_CREATED_EVENT: &str = "TOKEN_CREATED";
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+/// Build token key: "TOKEN" + id (u64 LE)
+fn token_key(id: u64) -> Vec<u8> {
+    let mut key = TOKEN_KEY_PREFIX.to_vec();
+    key.extend_from_slice(&id.to_le_bytes());
+    key
+}
+
+/// Build created flag key: "CREATED" + address
+fn created_key(address: &str) -> Vec<u8> {
+    let mut key = CREATED_KEY_PREFIX.to_vec();
+    key.extend_from_slice(address.as_bytes());
+    key
+}
+
+fn read_u64(key: &[u8]) -> u64 {
+    if !storage::has(key) {
+        return 0;
+    }
+    let data = storage::get(key);
+    if data.len() >= 8 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&data[..8]);
+        u64::from_le_bytes(bytes)
+    } else {
+        0
+    }
+}
+
+/// A token deployed by the factory.
+struct CreatedToken {
+    address: String,
+    creator: String,
+}
+
+impl CreatedToken {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Args::new();
+        data.add_string(&self.address).add_string(&self.creator);
+        data.into_bytes()
+    }
+
+    fn from_bytes(data: &[u8]) -> Option<Self> {
+        let mut data = Args::from_bytes(data.to_vec());
+        Some(CreatedToken {
+            address: data.next_string().ok()?,
+            creator: data.next_string().ok()?,
+        })
+    }
+}
+
+fn get_token(id: u64) -> CreatedToken {
+    let key = token_key(id);
+    ensure!(storage::has(&key), errors::NOT_FOUND, fields("id={}", id), "Token does not exist");
+    CreatedToken::from_bytes(&storage::get(&key)).or_fail(errors::INVALID_STATE, "Corrupted token record")
+}
+
+/// Call `entrypoint` on the new token with Args(`role`, `address`).
+fn call_role(token: &str, entrypoint: &str, role: &str, address: &str) {
+    let mut args = Args::new();
+    args.add_string(role).add_string(address);
+    calls::call(token, entrypoint, args);
+}
+
+fn call_limit_exempt(token: &str, address: &str, exempt: bool) {
+    let mut args = Args::new();
+    args.add_string(address).add_bool(exempt);
+    calls::call(token, "setLimitExempt", args);
+}
+
+/// Give the creator everything the token constructor gave the factory.
+fn hand_over(token: &str, creator: &str, supply: U256) {
+    let factory = context::callee();
+    for role in ROLES {
+        call_role(token, "grantRole", role, creator);
+        call_role(token, "revokeRole", role, &factory);
+    }
+    call_limit_exempt(token, creator, true);
+
+    if supply > U256::ZERO {
+        let mut transfer_args = Args::new();
+        transfer_args.add_string(creator).add_u256(supply);
+        calls::call(token, "transfer", transfer_args);
+    }
+    call_limit_exempt(token, &factory, false);
+
+    let mut owner_args = Args::new();
+    owner_args.add_string(creator);
+    calls::call(token, "setOwner", owner_args);
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize the factory.
+///
+/// # Arguments
+/// - `bytecode`: erc20-token WASM bytecode deployed by `createToken` (bytes)
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let bytecode = args.next_bytes().expect_arg("bytecode");
+    if bytecode.is_empty() {
+        errors::invalid_argument("bytecode");
+    }
+
+    storage::set(TOKEN_BYTECODE_KEY, &bytecode);
+
+    Vec::new()
+}
+
+// ============================================================================
+// Token Creation
+// ============================================================================
+
+/// Deploy a new token owned by the caller, who receives the whole supply and every role.
+///
+/// Coins sent with the call are forwarded to the token constructor to pay for its storage.
+///
+/// # Arguments
+/// - `name`: Token name (string, non-empty)
+/// - `symbol`: Token symbol (string, non-empty)
+/// - `decimals`: Token decimals (u8)
+/// - `supply`: Initial supply (U256)
+///
+/// # Returns
+/// - Token address (raw string bytes)
+///
+/// # Events
+/// - `TOKEN_CREATED:id=...,token=...,creator=...`
+#[massa_export]
+pub fn createToken(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let name = args.next_string().expect_arg("name");
+    let symbol = args.next_string().expect_arg("symbol");
+    let decimals = args.next_u8().expect_arg("decimals");
+    let supply = args.next_u256().expect_arg("supply");
+    if name.is_empty() {
+        errors::invalid_argument("name");
+    }
+    if symbol.is_empty() {
+        errors::invalid_argument("symbol");
+    }
+
+    let creator = context::caller();
+    let token = abi::create_sc(&storage::get(TOKEN_BYTECODE_KEY));
+
+    let mut constructor_args = Args::new();
+    constructor_args.add_string(&name).add_string(&symbol).add_u8(decimals).add_u256(supply);
+    calls::call_with_coins(&token, "constructor", constructor_args, context::transferred_coins());
+    hand_over(&token, &creator, supply);
+
+    let id = read_u64(TOKEN_COUNT_KEY);
+    storage::set(TOKEN_COUNT_KEY, &(id + 1).to_le_bytes());
+    let record = CreatedToken {
+        address: token,
+        creator,
+    };
+    storage::set(&token_key(id), &record.to_bytes());
+    storage::set(&created_key(&record.address), &[1u8]);
+
+    abi::generate_event(&alloc::format!(
+        "{}:id={},token={},creator={}",
+        TOKEN_CREATED_EVENT,
+        id,
+        record.address,
+        record.creator
+    ));
+
+    record.address.into_bytes()
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Returns a created token as Args(address: string, creator: string).
+///
+/// # Arguments
+/// - `id`: Token id, from 0 to `tokenCount() - 1` (u64)
+#[massa_export]
+pub fn tokenAt(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+    get_token(id).to_bytes()
+}
+
+/// Returns the number of tokens created (u64, 8 bytes LE).
+#[massa_export]
+pub fn tokenCount(_binary_args: &[u8]) -> Vec<u8> {
+    read_u64(TOKEN_COUNT_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns true (1) if the address was deployed by the factory, false (0) otherwise.
+///
+/// # Arguments
+/// - `address`: Address to check (string)
+#[massa_export]
+pub fn isFactoryToken(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("address");
+
+    if storage::has(&created_key(&address)) {
+        alloc::vec![1u8]
+    } else {
+        alloc::vec![0u8]
+    }
+}
+
+/// Returns the keccak256 hash of the deployed erc20-token bytecode (32 bytes).
+#[massa_export]
+pub fn bytecodeHash(_binary_args: &[u8]) -> Vec<u8> {
+    abi::keccak256(&storage::get(TOKEN_BYTECODE_KEY)).to_vec()
+}
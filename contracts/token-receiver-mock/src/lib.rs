@@ -0,0 +1,88 @@
+//! Mock MRC20 Token Receiver
+//!
+//! Minimal contract implementing the `onTokenReceived` hook called by the
+//! token's `transferAndCall` and the `onFlashLoan` hook called by `flashLoan`.
+//! Used by the test suite only.
+//!
+//! # Behavior
+//! - Records the last sender, amount and data under `LAST_SENDER`, `LAST_AMOUNT` and `LAST_DATA`
+//! - Emits `TOKEN_RECEIVED:from` and `FLASH_LOAN_RECEIVED:initiator`
+//! - Approves the token to take back flash loans plus their fee
+//! - Panics when the forwarded data is `reject`, so callers can test reverts
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args};
+
+const LAST_SENDER_KEY: &[u8] = b"LAST_SENDER";
+const LAST_AMOUNT_KEY: &[u8] = b"LAST_AMOUNT";
+const LAST_DATA_KEY: &[u8] = b"LAST_DATA";
+
+const TOKEN_RECEIVED_EVENT: &str = "TOKEN_RECEIVED";
+const FLASH_LOAN_RECEIVED_EVENT: &str = "FLASH_LOAN_RECEIVED";
+const FLASH_LOAN_CALLBACK_SUCCESS: &[u8] = b"ERC3156FlashBorrower.onFlashLoan";
+const REJECT_DATA: &[u8] = b"reject";
+
+/// Hook called by the token after a `transferAndCall`.
+///
+/// # Arguments
+/// - `from`: Token sender (string)
+/// - `amount`: Amount received (U256)
+/// - `data`: Opaque bytes forwarded by the sender (bytes)
+///
+/// # Events
+/// - `TOKEN_RECEIVED:from`
+#[massa_export]
+pub fn onTokenReceived(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let from = args.next_string().expect("from argument is missing or invalid");
+    let amount = args.next_u256().expect("amount argument is missing or invalid");
+    let data = args.next_bytes().expect("data argument is missing or invalid");
+
+    assert!(data != REJECT_DATA, "Tokens rejected by receiver");
+
+    storage::set(LAST_SENDER_KEY, from.as_bytes());
+    storage::set(LAST_AMOUNT_KEY, &amount.to_le_bytes());
+    storage::set(LAST_DATA_KEY, &data);
+
+    abi::generate_event(&alloc::format!("{}:{}", TOKEN_RECEIVED_EVENT, from));
+
+    Vec::new()
+}
+
+/// Hook called by the token during a `flashLoan`.
+///
+/// Approves the calling token to burn `amount + fee` from this contract.
+///
+/// # Arguments
+/// - `initiator`: Account that requested the loan (string)
+/// - `amount`: Amount lent (U256)
+/// - `fee`: Fee owed on top of the amount (U256)
+/// - `data`: Opaque bytes forwarded by the initiator (bytes)
+///
+/// # Events
+/// - `FLASH_LOAN_RECEIVED:initiator`
+#[massa_export]
+pub fn onFlashLoan(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let initiator = args.next_string().expect("initiator argument is missing or invalid");
+    let amount = args.next_u256().expect("amount argument is missing or invalid");
+    let fee = args.next_u256().expect("fee argument is missing or invalid");
+    let data = args.next_bytes().expect("data argument is missing or invalid");
+
+    assert!(data != REJECT_DATA, "Flash loan rejected by receiver");
+
+    let token = context::caller();
+    let repayment = amount.checked_add(fee).expect("Repayment overflow");
+    let mut approve_args = Args::new();
+    approve_args.add_string(&token).add_u256(repayment);
+    abi::call(&token, "increaseAllowance", &approve_args.into_bytes(), 0);
+
+    abi::generate_event(&alloc::format!("{}:{}", FLASH_LOAN_RECEIVED_EVENT, initiator));
+
+    FLASH_LOAN_CALLBACK_SUCCESS.to_vec()
+}
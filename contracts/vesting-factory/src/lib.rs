@@ -0,0 +1,297 @@
+//! Vesting Factory for Massa Blockchain
+//!
+//! Deploys one `contracts/vesting-wallet` contract per grant and funds it with
+//! `transferFrom` from the caller, so every grant can be audited on its own.
+//! The vesting-wallet bytecode is stored at deployment.
+//!
+//! Created wallets are recorded for enumeration, globally and per beneficiary.
+//!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes.
+//!
+//! # Storage Keys
+//! - `WALLET_BYTECODE`: vesting-wallet WASM bytecode
+//! - `VESTING_COUNT`: Number of wallets created, u64 as 8 bytes (little-endian)
+//! - `VESTING{id}`: Created wallet (u64 LE id), Args-serialized wallet, token, beneficiary, creator and amount
+//! - `BENEFICIARY_COUNT{beneficiary}`: Number of wallets for the beneficiary, u64 as 8 bytes (little-endian)
+//! - `BENEFICIARY{beneficiary}{index}`: Id of the beneficiary's wallet at index (u64 LE), u64 as 8 bytes (little-endian)
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args, U256};
+
+use mrc20_core::calls;
+use mrc20_core::errors::{self, OrFail};
+use mrc20_core::vesting::{Schedule, ScheduleError};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const WALLET_BYTECODE_KEY: &[u8] = b"WALLET_BYTECODE";
+const VESTING_COUNT_KEY: &[u8] = b"VESTING_COUNT";
+const VESTING_KEY_PREFIX: &[u8] = b"VESTING";
+const BENEFICIARY_COUNT_KEY_PREFIX: &[u8] = b"BENEFICIARY_COUNT";
+const BENEFICIARY_KEY_PREFIX: &[u8] = b"BENEFICIARY";
+
+const VESTING_CREATED_EVENT: &str = "VESTING_WALLET_CREATED";
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+/// Build vesting key: "VESTING" + id (u64 LE)
+fn vesting_key(id: u64) -> Vec<u8> {
+    let mut key = VESTING_KEY_PREFIX.to_vec();
+    key.extend_from_slice(&id.to_le_bytes());
+    key
+}
+
+/// Build beneficiary count key: "BENEFICIARY_COUNT" + beneficiary
+fn beneficiary_count_key(beneficiary: &str) -> Vec<u8> {
+    let mut key = BENEFICIARY_COUNT_KEY_PREFIX.to_vec();
+    key.extend_from_slice(beneficiary.as_bytes());
+    key
+}
+
+/// Build beneficiary entry key: "BENEFICIARY" + beneficiary + index (u64 LE)
+fn beneficiary_key(beneficiary: &str, index: u64) -> Vec<u8> {
+    let mut key = BENEFICIARY_KEY_PREFIX.to_vec();
+    key.extend_from_slice(beneficiary.as_bytes());
+    key.extend_from_slice(&index.to_le_bytes());
+    key
+}
+
+fn read_u64(key: &[u8]) -> u64 {
+    if !storage::has(key) {
+        return 0;
+    }
+    let data = storage::get(key);
+    if data.len() >= 8 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&data[..8]);
+        u64::from_le_bytes(bytes)
+    } else {
+        0
+    }
+}
+
+/// A vesting wallet deployed by the factory.
+struct Vesting {
+    wallet: String,
+    token: String,
+    beneficiary: String,
+    creator: String,
+    amount: U256,
+}
+
+impl Vesting {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Args::new();
+        data.add_string(&self.wallet)
+            .add_string(&self.token)
+            .add_string(&self.beneficiary)
+            .add_string(&self.creator)
+            .add_u256(self.amount);
+        data.into_bytes()
+    }
+
+    fn from_bytes(data: &[u8]) -> Option<Self> {
+        let mut data = Args::from_bytes(data.to_vec());
+        Some(Vesting {
+            wallet: data.next_string().ok()?,
+            token: data.next_string().ok()?,
+            beneficiary: data.next_string().ok()?,
+            creator: data.next_string().ok()?,
+            amount: data.next_u256().ok()?,
+        })
+    }
+}
+
+fn get_vesting(id: u64) -> Vesting {
+    let key = vesting_key(id);
+    ensure!(storage::has(&key), errors::NOT_FOUND, fields("id={}", id), "Vesting does not exist");
+    Vesting::from_bytes(&storage::get(&key)).or_fail(errors::INVALID_STATE, "Corrupted vesting record")
+}
+
+/// Record the wallet under a new id and in the beneficiary's list.
+fn record_vesting(vesting: &Vesting) -> u64 {
+    let id = read_u64(VESTING_COUNT_KEY);
+    storage::set(VESTING_COUNT_KEY, &(id + 1).to_le_bytes());
+    storage::set(&vesting_key(id), &vesting.to_bytes());
+
+    let count_key = beneficiary_count_key(&vesting.beneficiary);
+    let index = read_u64(&count_key);
+    storage::set(&count_key, &(index + 1).to_le_bytes());
+    storage::set(&beneficiary_key(&vesting.beneficiary, index), &id.to_le_bytes());
+    id
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize the factory.
+///
+/// # Arguments
+/// - `bytecode`: vesting-wallet WASM bytecode deployed by `createVesting` (bytes)
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let bytecode = args.next_bytes().expect_arg("bytecode");
+    if bytecode.is_empty() {
+        errors::invalid_argument("bytecode");
+    }
+
+    storage::set(WALLET_BYTECODE_KEY, &bytecode);
+
+    Vec::new()
+}
+
+// ============================================================================
+// Vesting Creation
+// ============================================================================
+
+/// Deploy a vesting wallet for `beneficiary` and fund it with `amount` from the caller.
+///
+/// The caller must have approved this contract for `amount` on `token`. Coins
+/// sent with the call are forwarded to the wallet constructor to pay for its storage.
+///
+/// # Arguments
+/// - `token`: Vested token address (string)
+/// - `beneficiary`: Grant recipient (string)
+/// - `amount`: Granted amount (U256, non-zero)
+/// - `start`: Period at which vesting starts (u64)
+/// - `cliff`: Number of periods after `start` before anything vests (u64)
+/// - `duration`: Number of periods after `start` until fully vested (u64, non-zero, at least `cliff`)
+///
+/// # Returns
+/// - Wallet address (raw string bytes)
+///
+/// # Events
+/// - `VESTING_WALLET_CREATED:id=...,wallet=...,beneficiary=...,amount=...`
+#[massa_export]
+pub fn createVesting(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token = args.next_string().expect_arg("token");
+    let beneficiary = args.next_string().expect_arg("beneficiary");
+    let amount = args.next_u256().expect_arg("amount");
+    let start = args.next_u64().expect_arg("start");
+    let cliff = args.next_u64().expect_arg("cliff");
+    let duration = args.next_u64().expect_arg("duration");
+    if token.is_empty() {
+        errors::invalid_argument("token");
+    }
+    if beneficiary.is_empty() {
+        errors::invalid_argument("beneficiary");
+    }
+    if amount == U256::ZERO {
+        errors::invalid_argument("amount");
+    }
+    let schedule = Schedule {
+        start,
+        cliff,
+        duration,
+    };
+    match schedule.validate() {
+        Ok(()) => {}
+        Err(ScheduleError::ZeroDuration) => {
+            fail!(errors::INVALID_ARGUMENT, fields("name=duration"), "Vesting duration is 0")
+        }
+        Err(ScheduleError::CliffAfterDuration) => {
+            fail!(errors::INVALID_ARGUMENT, fields("name=cliff"), "Vesting cliff is longer than its duration")
+        }
+    }
+
+    let creator = context::caller();
+    let wallet = abi::create_sc(&storage::get(WALLET_BYTECODE_KEY));
+
+    let mut constructor_args = Args::new();
+    constructor_args
+        .add_string(&token)
+        .add_string(&beneficiary)
+        .add_u64(start)
+        .add_u64(cliff)
+        .add_u64(duration)
+        .add_u256(amount);
+    calls::call_with_coins(&wallet, "constructor", constructor_args, context::transferred_coins());
+
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(&creator).add_string(&wallet).add_u256(amount);
+    calls::call(&token, "transferFrom", transfer_args);
+
+    let vesting = Vesting {
+        wallet,
+        token,
+        beneficiary,
+        creator,
+        amount,
+    };
+    let id = record_vesting(&vesting);
+
+    abi::generate_event(&alloc::format!(
+        "{}:id={},wallet={},beneficiary={},amount={}",
+        VESTING_CREATED_EVENT,
+        id,
+        vesting.wallet,
+        vesting.beneficiary,
+        vesting.amount
+    ));
+
+    vesting.wallet.into_bytes()
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Returns a created vesting as Args(wallet: string, token: string, beneficiary: string,
+/// creator: string, amount: U256).
+///
+/// # Arguments
+/// - `id`: Vesting id, from 0 to `vestingCount() - 1` (u64)
+#[massa_export]
+pub fn vestingAt(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let id = args.next_u64().expect_arg("id");
+    get_vesting(id).to_bytes()
+}
+
+/// Returns the number of vesting wallets created (u64, 8 bytes LE).
+#[massa_export]
+pub fn vestingCount(_binary_args: &[u8]) -> Vec<u8> {
+    read_u64(VESTING_COUNT_KEY).to_le_bytes().to_vec()
+}
+
+/// Returns the ids of the beneficiary's vestings as Args(count: u32, then `count` ids as u64).
+///
+/// # Arguments
+/// - `beneficiary`: Grant recipient (string)
+#[massa_export]
+pub fn vestingsOf(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let beneficiary = args.next_string().expect_arg("beneficiary");
+
+    let count = read_u64(&beneficiary_count_key(&beneficiary));
+    let mut result = Args::new();
+    result.add_u32(count as u32);
+    for index in 0..count {
+        result.add_u64(read_u64(&beneficiary_key(&beneficiary, index)));
+    }
+    result.into_bytes()
+}
+
+/// Returns the keccak256 hash of the deployed vesting-wallet bytecode (32 bytes).
+#[massa_export]
+pub fn bytecodeHash(_binary_args: &[u8]) -> Vec<u8> {
+    abi::keccak256(&storage::get(WALLET_BYTECODE_KEY)).to_vec()
+}
@@ -0,0 +1,228 @@
+//! Vesting Wallet for Massa Blockchain
+//!
+//! Holds an MRC20 grant for a single beneficiary and releases it linearly over
+//! Massa periods: nothing before `start + cliff`, everything at
+//! `start + duration`. Each grant lives in its own contract, so its schedule
+//! and balance can be audited independently. Deployed and funded by
+//! `contracts/vesting-factory`, which checks schedules with the same
+//! `mrc20_core::vesting` rules.
+//!
+//! # Errors
+//! Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes.
+//!
+//! # Storage Keys
+//! - `TOKEN`: Vested token address as raw string bytes
+//! - `BENEFICIARY`: Beneficiary address as raw string bytes
+//! - `START`: Period at which vesting starts, u64 as 8 bytes (little-endian)
+//! - `CLIFF`: Periods after `START` before anything vests, u64 as 8 bytes (little-endian)
+//! - `DURATION`: Periods after `START` until fully vested, u64 as 8 bytes (little-endian)
+//! - `AMOUNT`: Granted amount, u256 as 32 bytes (little-endian)
+//! - `RELEASED`: Amount released so far, u256 as 32 bytes (little-endian)
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args, U256};
+
+use mrc20_core::calls;
+use mrc20_core::errors::{self, OrFail};
+use mrc20_core::vesting::{Schedule, ScheduleError};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const TOKEN_KEY: &[u8] = b"TOKEN";
+const BENEFICIARY_KEY: &[u8] = b"BENEFICIARY";
+const START_KEY: &[u8] = b"START";
+const CLIFF_KEY: &[u8] = b"CLIFF";
+const DURATION_KEY: &[u8] = b"DURATION";
+const AMOUNT_KEY: &[u8] = b"AMOUNT";
+const RELEASED_KEY: &[u8] = b"RELEASED";
+
+const RELEASED_EVENT: &str = "VESTING_RELEASED";
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+fn read_u256(key: &[u8]) -> U256 {
+    if !storage::has(key) {
+        return U256::ZERO;
+    }
+    let data = storage::get(key);
+    if data.len() >= 32 {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&data[..32]);
+        U256::from_le_bytes(bytes)
+    } else {
+        U256::ZERO
+    }
+}
+
+fn write_u256(key: &[u8], value: U256) {
+    storage::set(key, &value.to_le_bytes());
+}
+
+fn read_u64(key: &[u8]) -> u64 {
+    if !storage::has(key) {
+        return 0;
+    }
+    let data = storage::get(key);
+    if data.len() >= 8 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&data[..8]);
+        u64::from_le_bytes(bytes)
+    } else {
+        0
+    }
+}
+
+fn read_string(key: &[u8]) -> String {
+    ensure!(storage::has(key), errors::INVALID_STATE, "Vesting wallet is not initialized");
+    let data = storage::get(key);
+    core::str::from_utf8(&data).ok().map(String::from).or_fail(errors::INVALID_STATE, "Corrupted address")
+}
+
+/// Amount vested at `period`: nothing before the cliff, then linear until `start + duration`.
+fn vested_amount(period: u64) -> U256 {
+    let schedule = Schedule {
+        start: read_u64(START_KEY),
+        cliff: read_u64(CLIFF_KEY),
+        duration: read_u64(DURATION_KEY),
+    };
+    schedule.vested(read_u256(AMOUNT_KEY), period)
+}
+
+fn releasable_amount(period: u64) -> U256 {
+    vested_amount(period).saturating_sub(read_u256(RELEASED_KEY))
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize the wallet. The grant is funded by transferring `amount` of `token` to the wallet.
+///
+/// # Arguments
+/// - `token`: Vested token address (string)
+/// - `beneficiary`: Grant recipient (string)
+/// - `start`: Period at which vesting starts (u64)
+/// - `cliff`: Number of periods after `start` before anything vests (u64)
+/// - `duration`: Number of periods after `start` until fully vested (u64, non-zero, at least `cliff`)
+/// - `amount`: Granted amount (U256)
+#[massa_export]
+pub fn constructor(binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let token = args.next_string().expect_arg("token");
+    let beneficiary = args.next_string().expect_arg("beneficiary");
+    let start = args.next_u64().expect_arg("start");
+    let cliff = args.next_u64().expect_arg("cliff");
+    let duration = args.next_u64().expect_arg("duration");
+    let amount = args.next_u256().expect_arg("amount");
+    if token.is_empty() {
+        errors::invalid_argument("token");
+    }
+    if beneficiary.is_empty() {
+        errors::invalid_argument("beneficiary");
+    }
+    let schedule = Schedule {
+        start,
+        cliff,
+        duration,
+    };
+    match schedule.validate() {
+        Ok(()) => {}
+        Err(ScheduleError::ZeroDuration) => {
+            fail!(errors::INVALID_ARGUMENT, fields("name=duration"), "Vesting duration is 0")
+        }
+        Err(ScheduleError::CliffAfterDuration) => {
+            fail!(errors::INVALID_ARGUMENT, fields("name=cliff"), "Vesting cliff is longer than its duration")
+        }
+    }
+
+    storage::set(TOKEN_KEY, token.as_bytes());
+    storage::set(BENEFICIARY_KEY, beneficiary.as_bytes());
+    storage::set(START_KEY, &start.to_le_bytes());
+    storage::set(CLIFF_KEY, &cliff.to_le_bytes());
+    storage::set(DURATION_KEY, &duration.to_le_bytes());
+    write_u256(AMOUNT_KEY, amount);
+    write_u256(RELEASED_KEY, U256::ZERO);
+
+    Vec::new()
+}
+
+// ============================================================================
+// Release
+// ============================================================================
+
+/// Send the vested tokens not released yet to the beneficiary. Anyone can call it.
+///
+/// # Returns
+/// - Amount released (u256 bytes)
+///
+/// # Events
+/// - `VESTING_RELEASED:beneficiary=...,amount=...`
+#[massa_export]
+pub fn release(_binary_args: &[u8]) -> Vec<u8> {
+    let releasable = releasable_amount(context::current_period());
+    ensure!(releasable > U256::ZERO, errors::INVALID_STATE, "No tokens to release");
+
+    // Record the release before the call so it cannot be repeated from within it
+    let released = read_u256(RELEASED_KEY).checked_add(releasable).or_fail(errors::OVERFLOW, "Release overflow");
+    write_u256(RELEASED_KEY, released);
+
+    let beneficiary = read_string(BENEFICIARY_KEY);
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(&beneficiary).add_u256(releasable);
+    calls::call(&read_string(TOKEN_KEY), "transfer", transfer_args);
+
+    abi::generate_event(&alloc::format!(
+        "{}:beneficiary={},amount={}",
+        RELEASED_EVENT,
+        beneficiary,
+        releasable
+    ));
+
+    releasable.to_le_bytes().to_vec()
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// Returns the schedule as Args(token: string, beneficiary: string, start: u64,
+/// cliff: u64, duration: u64, amount: U256, released: U256).
+#[massa_export]
+pub fn schedule(_binary_args: &[u8]) -> Vec<u8> {
+    let mut result = Args::new();
+    result
+        .add_string(&read_string(TOKEN_KEY))
+        .add_string(&read_string(BENEFICIARY_KEY))
+        .add_u64(read_u64(START_KEY))
+        .add_u64(read_u64(CLIFF_KEY))
+        .add_u64(read_u64(DURATION_KEY))
+        .add_u256(read_u256(AMOUNT_KEY))
+        .add_u256(read_u256(RELEASED_KEY));
+    result.into_bytes()
+}
+
+/// Returns the amount vested at the current period (u256 bytes).
+#[massa_export]
+pub fn vestedAmount(_binary_args: &[u8]) -> Vec<u8> {
+    vested_amount(context::current_period()).to_le_bytes().to_vec()
+}
+
+/// Returns the amount the beneficiary can release now (u256 bytes).
+#[massa_export]
+pub fn releasableAmount(_binary_args: &[u8]) -> Vec<u8> {
+    releasable_amount(context::current_period()).to_le_bytes().to_vec()
+}
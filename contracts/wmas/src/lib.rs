@@ -0,0 +1,344 @@
+//! Wrapped MAS (wMAS) for Massa Blockchain
+//!
+//! MRC20 token backed 1:1 by native MAS held by the contract. Sending coins
+//! to `deposit` mints the same amount of wMAS to the caller, minus the storage
+//! cost of their balance entry on a first deposit, and `withdraw` burns wMAS
+//! and sends the coins back. One wMAS unit is one nanoMAS, hence
+//! 9 decimals.
+//!
+//! # Compatibility
+//! - Balances, allowances, the supply and their events are the `mrc20_core::token` ledger `erc20-token` uses
+//! - Function signatures follow the `erc20-token` MRC20 core
+//! - Failures panic with `ERR:{CODE}:{fields}:{message}`, using the token's error codes
+//!
+//! # Invariant
+//! The total supply never exceeds the coin balance of the contract.
+//!
+//! # Storage Keys
+//! - `NAME`: Token name as raw bytes ("Wrapped MAS")
+//! - `SYMBOL`: Token symbol as raw bytes ("WMAS")
+//! - `DECIMALS`: Single byte [u8] (9)
+//! - `TOTAL_SUPPLY`: u256 as 32 bytes (little-endian)
+//! - `BALANCE{address}`: Balance for address, value is u256 (deleted when zero)
+//! - `ALLOWANCE{owner}{spender}`: Allowance, value is u256 (deleted when zero)
+
+#![no_std]
+
+extern crate alloc;
+#[macro_use]
+extern crate mrc20_core;
+
+use alloc::vec::Vec;
+use massa_export::massa_export;
+use massa_sc_sdk::{abi, context, storage, Args, U256};
+
+use mrc20_core::errors::{self, OrFail};
+use mrc20_core::token::{self, NoHooks, DECIMALS_KEY, NAME_KEY, SYMBOL_KEY};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const NAME: &str = "Wrapped MAS";
+const SYMBOL: &str = "WMAS";
+const DECIMALS: u8 = 9;
+
+const DEPOSIT_EVENT: &str = "DEPOSIT";
+const WITHDRAW_EVENT: &str = "WITHDRAW";
+
+// ============================================================================
+// Return Value Builders
+// ============================================================================
+
+/// Args(true), returned by state-changing calls so composing contracts can branch on it.
+fn success_result() -> Vec<u8> {
+    let mut ret = Args::new();
+    ret.add_bool(true);
+    ret.into_bytes()
+}
+
+/// Args(true, allowance), returned by allowance mutations.
+fn allowance_result(allowance: U256) -> Vec<u8> {
+    let mut ret = Args::new();
+    ret.add_bool(true).add_u256(allowance);
+    ret.into_bytes()
+}
+
+// ============================================================================
+// Storage Helpers
+// ============================================================================
+
+fn get_balance(address: &str) -> U256 {
+    token::balance_of(address)
+}
+
+fn get_allowance(owner: &str, spender: &str) -> U256 {
+    token::allowance(owner, spender)
+}
+
+fn get_total_supply() -> U256 {
+    token::total_supply()
+}
+
+/// Convert a token amount to nanoMAS, failing if it does not fit in a u64.
+fn to_coins(amount: U256) -> u64 {
+    ensure!(
+        amount <= U256::from(u64::MAX),
+        errors::INVALID_ARGUMENT,
+        fields("name=amount"),
+        "amount argument exceeds the coin range"
+    );
+    let bytes = amount.to_le_bytes();
+    let mut low = [0u8; 8];
+    low.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(low)
+}
+
+// ============================================================================
+// Constructor
+// ============================================================================
+
+/// Initialize the token metadata. Takes no arguments and mints nothing:
+/// the supply only grows through `deposit`.
+#[massa_export]
+pub fn constructor(_binary_args: &[u8]) -> Vec<u8> {
+    ensure!(context::is_deploying_contract(), errors::UNAUTHORIZED, "Can only be called during deployment");
+
+    storage::set(NAME_KEY, NAME.as_bytes());
+    storage::set(SYMBOL_KEY, SYMBOL.as_bytes());
+    storage::set(DECIMALS_KEY, &[DECIMALS]);
+    token::set_total_supply(&NoHooks, U256::ZERO);
+
+    Vec::new()
+}
+
+// ============================================================================
+// Token Attributes
+// ============================================================================
+
+/// Returns the name of the token (raw bytes, not Args-wrapped).
+#[massa_export]
+pub fn name(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(NAME_KEY)
+}
+
+/// Returns the symbol of the token (raw bytes, not Args-wrapped).
+#[massa_export]
+pub fn symbol(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(SYMBOL_KEY)
+}
+
+/// Returns the decimals of the token (raw bytes, not Args-wrapped).
+#[massa_export]
+pub fn decimals(_binary_args: &[u8]) -> Vec<u8> {
+    storage::get(DECIMALS_KEY)
+}
+
+/// Returns the total supply (raw u256 bytes, not Args-wrapped).
+#[massa_export]
+pub fn totalSupply(_binary_args: &[u8]) -> Vec<u8> {
+    get_total_supply().to_le_bytes().to_vec()
+}
+
+/// Returns the balance of an account (u256 bytes).
+///
+/// # Arguments
+/// - `address`: Account address (string)
+#[massa_export]
+pub fn balanceOf(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let address = args.next_string().expect_arg("Address");
+    get_balance(&address).to_le_bytes().to_vec()
+}
+
+// ============================================================================
+// Wrapping
+// ============================================================================
+
+/// Wrap the coins sent with the call into wMAS for the caller.
+///
+/// The first deposit of an account creates its balance entry, whose storage
+/// cost the contract pays from its coins. That cost is kept from the coins
+/// sent, so the caller receives `coins - storage cost` wMAS.
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+///
+/// # Events
+/// - `MINT SUCCESS`
+/// - `DEPOSIT:to=...,amount=...`
+#[massa_export]
+pub fn deposit(_binary_args: &[u8]) -> Vec<u8> {
+    let coins = context::transferred_coins();
+    ensure!(coins > 0, errors::INVALID_ARGUMENT, fields("name=coins"), "No coins sent with the deposit");
+
+    let to = context::caller();
+    let storage_cost = token::balance_entry_cost(&to);
+    ensure!(
+        coins > storage_cost,
+        errors::INVALID_ARGUMENT,
+        fields("name=coins"),
+        "Sent coins do not cover the {} nanoMAS storage cost of the deposit",
+        storage_cost
+    );
+    let amount = U256::from(coins - storage_cost);
+    token::mint(&NoHooks, &to, amount);
+
+    abi::generate_event(&alloc::format!("{}:to={},amount={}", DEPOSIT_EVENT, to, amount));
+
+    success_result()
+}
+
+/// Burn wMAS from the caller and send the same amount of coins back.
+///
+/// # Arguments
+/// - `amount`: Amount to unwrap, in nanoMAS (U256)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+///
+/// # Events
+/// - `BURN_SUCCESS`
+/// - `WITHDRAW:from=...,amount=...`
+#[massa_export]
+pub fn withdraw(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let amount = args.next_u256().expect_arg("amount");
+
+    let from = context::caller();
+    let coins = to_coins(amount);
+    token::burn(&NoHooks, &from, amount);
+    abi::transfer_coins(&from, coins);
+
+    abi::generate_event(&alloc::format!("{}:from={},amount={}", WITHDRAW_EVENT, from, amount));
+
+    success_result()
+}
+
+// ============================================================================
+// Transfer
+// ============================================================================
+
+/// Transfers tokens from caller to recipient.
+///
+/// # Arguments
+/// - `to`: Recipient address (string)
+/// - `amount`: Amount to transfer (U256)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+///
+/// # Events
+/// - `TRANSFER SUCCESS`
+#[massa_export]
+pub fn transfer(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let to = args.next_string().expect_arg("receiverAddress");
+    let amount = args.next_u256().expect_arg("amount");
+
+    let from = context::caller();
+    token::move_balance(&NoHooks, &from, &to, amount);
+
+    abi::generate_event(&token::transfer_event(&from, &to, amount));
+
+    success_result()
+}
+
+/// Transfers tokens from owner to recipient using spender's allowance.
+///
+/// # Arguments
+/// - `owner`: Owner address (string)
+/// - `recipient`: Recipient address (string)
+/// - `amount`: Amount to transfer (U256)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+///
+/// # Events
+/// - `TRANSFER SUCCESS`
+#[massa_export]
+pub fn transferFrom(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("ownerAddress");
+    let recipient = args.next_string().expect_arg("recipientAddress");
+    let amount = args.next_u256().expect_arg("amount");
+
+    let spender = context::caller();
+    token::spend_allowance(&NoHooks, &owner, &spender, amount, "transferFrom");
+    token::move_balance(&NoHooks, &owner, &recipient, amount);
+
+    abi::generate_event(&token::transfer_event(&owner, &recipient, amount));
+
+    success_result()
+}
+
+// ============================================================================
+// Allowance
+// ============================================================================
+
+/// Returns the allowance of the spender on the owner's account (u256 bytes).
+///
+/// # Arguments
+/// - `owner`: Owner address (string)
+/// - `spender`: Spender address (string)
+#[massa_export]
+pub fn allowance(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let owner = args.next_string().expect_arg("owner");
+    let spender = args.next_string().expect_arg("spenderAddress");
+    get_allowance(&owner, &spender).to_le_bytes().to_vec()
+}
+
+/// Increases the allowance of the spender on the caller's account.
+///
+/// # Arguments
+/// - `spender`: Spender address (string)
+/// - `amount`: Amount to increase (U256)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+/// - `allowance`: New allowance (U256, Args-serialized)
+///
+/// # Events
+/// - `APPROVAL SUCCESS`
+#[massa_export]
+pub fn increaseAllowance(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let spender = args.next_string().expect_arg("spenderAddress");
+    let amount = args.next_u256().expect_arg("amount");
+
+    let owner = context::caller();
+    let new_allowance = get_allowance(&owner, &spender).saturating_add(amount);
+    token::set_allowance(&NoHooks, &owner, &spender, new_allowance);
+
+    abi::generate_event(&token::approval_event(&owner, &spender, new_allowance));
+
+    allowance_result(new_allowance)
+}
+
+/// Decreases the allowance of the spender on the caller's account.
+///
+/// # Arguments
+/// - `spender`: Spender address (string)
+/// - `amount`: Amount to decrease (U256)
+///
+/// # Returns
+/// - `success`: Always true, the call panics on failure (bool, Args-serialized)
+/// - `allowance`: New allowance (U256, Args-serialized)
+///
+/// # Events
+/// - `APPROVAL SUCCESS`
+#[massa_export]
+pub fn decreaseAllowance(binary_args: &[u8]) -> Vec<u8> {
+    let mut args = Args::from_bytes(binary_args.to_vec());
+    let spender = args.next_string().expect_arg("spenderAddress");
+    let amount = args.next_u256().expect_arg("amount");
+
+    let owner = context::caller();
+    let new_allowance = get_allowance(&owner, &spender).saturating_sub(amount);
+    token::set_allowance(&NoHooks, &owner, &spender, new_allowance);
+
+    abi::generate_event(&token::approval_event(&owner, &spender, new_allowance));
+
+    allowance_result(new_allowance)
+}
@@ -0,0 +1,91 @@
+//! Runtime support for the typed contract bindings generated by `tools/abigen`.
+//!
+//! A generated method encodes its arguments and returns a [`ContractCall`],
+//! which either runs read-only and decodes the return value, or is signed and
+//! sent as a state-changing operation. The manifest does not say which
+//! entrypoints change state, so the caller picks.
+
+use std::marker::PhantomData;
+
+use massa_types::{Args, U256};
+
+use crate::{decode_error, CallReceipt, Erc20Client, GasEstimate, Result};
+
+/// A call to one entrypoint with its arguments encoded.
+#[must_use = "a contract call does nothing until it is read or sent"]
+pub struct ContractCall<'a, T> {
+    client: &'a Erc20Client,
+    function: &'static str,
+    args: Args,
+    expected: &'static str,
+    decode: fn(&[u8]) -> Option<T>,
+    returns: PhantomData<T>,
+}
+
+impl<'a, T> ContractCall<'a, T> {
+    /// `decode` turns the raw return value into `T`; `expected` names its encoding in errors.
+    pub fn new(
+        client: &'a Erc20Client,
+        function: &'static str,
+        args: Args,
+        expected: &'static str,
+        decode: fn(&[u8]) -> Option<T>,
+    ) -> Self {
+        ContractCall {
+            client,
+            function,
+            args,
+            expected,
+            decode,
+            returns: PhantomData,
+        }
+    }
+
+    pub fn function(&self) -> &'static str {
+        self.function
+    }
+
+    /// Run the call read-only and decode its return value.
+    pub fn read(self) -> Result<T> {
+        let ret = self.client.read(self.function, self.args)?;
+        (self.decode)(&ret).ok_or_else(|| decode_error(self.function, self.expected))
+    }
+
+    /// Simulate the call as the client's keypair, for its gas cost and events.
+    pub fn estimate(self) -> Result<GasEstimate> {
+        self.client.estimate_gas(self.function, self.args)
+    }
+
+    /// Sign and send the call, then wait for its execution.
+    ///
+    /// Operations do not hand back return values; the receipt carries the events.
+    pub fn send(self) -> Result<CallReceipt> {
+        self.client.call(self.function, self.args)
+    }
+}
+
+/// Decoders for the raw return encodings of the manifest.
+pub fn unit(_: &[u8]) -> Option<()> {
+    Some(())
+}
+
+pub fn bytes(ret: &[u8]) -> Option<Vec<u8>> {
+    Some(ret.to_vec())
+}
+
+pub fn u32(ret: &[u8]) -> Option<u32> {
+    ret.try_into().ok().map(u32::from_le_bytes)
+}
+
+pub fn u64(ret: &[u8]) -> Option<u64> {
+    ret.try_into().ok().map(u64::from_le_bytes)
+}
+
+pub fn u256(ret: &[u8]) -> Option<U256> {
+    ret.try_into().ok().map(U256::from_le_bytes)
+}
+
+/// The return value as `Args`, for values with repeated fields.
+pub fn args(ret: &[u8]) -> Option<Args> {
+    Some(Args::from_bytes(ret.to_vec()))
+}
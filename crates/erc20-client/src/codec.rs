@@ -0,0 +1,221 @@
+//! Decoding and encoding of `Args` bytes against a type signature.
+//!
+//! Meant for inspecting serialized calls, e.g. the constructor arguments of a
+//! failed deployment: `decode(&bytes, &parse_signature("string,string,u8,u256")?)`.
+//!
+//! Fields are laid out as `Args` writes them: `bool` and `u8` as one byte,
+//! integers little-endian, `string` and `bytes` behind a `u32` length.
+
+use std::fmt;
+use std::str::FromStr;
+
+use massa_types::{Args, U256};
+
+use crate::{units, Error, Result};
+
+/// The type of one `Args` field, named as in `next_<type>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArgType {
+    String,
+    Bool,
+    U8,
+    U32,
+    U64,
+    U256,
+    Bytes,
+}
+
+impl ArgType {
+    pub fn name(self) -> &'static str {
+        match self {
+            ArgType::String => "string",
+            ArgType::Bool => "bool",
+            ArgType::U8 => "u8",
+            ArgType::U32 => "u32",
+            ArgType::U64 => "u64",
+            ArgType::U256 => "u256",
+            ArgType::Bytes => "bytes",
+        }
+    }
+}
+
+impl FromStr for ArgType {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name {
+            "string" => Ok(ArgType::String),
+            "bool" => Ok(ArgType::Bool),
+            "u8" => Ok(ArgType::U8),
+            "u32" => Ok(ArgType::U32),
+            "u64" => Ok(ArgType::U64),
+            "u256" => Ok(ArgType::U256),
+            "bytes" => Ok(ArgType::Bytes),
+            _ => Err(Error::InvalidArgs(format!("unknown type {:?}", name))),
+        }
+    }
+}
+
+impl fmt::Display for ArgType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Parse a comma-separated signature such as `string,string,u256`.
+pub fn parse_signature(signature: &str) -> Result<Vec<ArgType>> {
+    if signature.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    signature.split(',').map(|name| name.trim().parse()).collect()
+}
+
+/// One decoded field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    Bool(bool),
+    U8(u8),
+    U32(u32),
+    U64(u64),
+    U256(U256),
+    Bytes(Vec<u8>),
+}
+
+impl Value {
+    /// Parse `text` as a value of `ty`; `bytes` are hex, with or without `0x`.
+    pub fn parse(ty: ArgType, text: &str) -> Result<Self> {
+        let invalid = || Error::InvalidArgs(format!("{:?} is not a valid {}", text, ty));
+        Ok(match ty {
+            ArgType::String => Value::String(String::from(text)),
+            ArgType::Bool => Value::Bool(text.parse().map_err(|_| invalid())?),
+            ArgType::U8 => Value::U8(text.parse().map_err(|_| invalid())?),
+            ArgType::U32 => Value::U32(text.parse().map_err(|_| invalid())?),
+            ArgType::U64 => Value::U64(text.parse().map_err(|_| invalid())?),
+            ArgType::U256 => Value::U256(units::parse_units(text, 0).ok_or_else(invalid)?),
+            ArgType::Bytes => Value::Bytes(hex::decode(text.trim_start_matches("0x")).map_err(|_| invalid())?),
+        })
+    }
+
+    pub fn arg_type(&self) -> ArgType {
+        match self {
+            Value::String(_) => ArgType::String,
+            Value::Bool(_) => ArgType::Bool,
+            Value::U8(_) => ArgType::U8,
+            Value::U32(_) => ArgType::U32,
+            Value::U64(_) => ArgType::U64,
+            Value::U256(_) => ArgType::U256,
+            Value::Bytes(_) => ArgType::Bytes,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::String(value) => write!(f, "{:?}", value),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::U8(value) => write!(f, "{}", value),
+            Value::U32(value) => write!(f, "{}", value),
+            Value::U64(value) => write!(f, "{}", value),
+            Value::U256(value) => write!(f, "{}", value),
+            Value::Bytes(value) => write!(f, "0x{}", hex::encode(value)),
+        }
+    }
+}
+
+/// Reads fields one at a time, keeping track of the offset for error reports.
+pub struct ArgsReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ArgsReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        ArgsReader { data, offset: 0 }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The bytes not read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[self.offset..]
+    }
+
+    fn take(&mut self, ty: ArgType, len: usize) -> Result<&'a [u8]> {
+        let remaining = self.remaining();
+        if remaining.len() < len {
+            return Err(Error::InvalidArgs(format!(
+                "{} at byte {} needs {} bytes, {} left",
+                ty,
+                self.offset,
+                len,
+                remaining.len()
+            )));
+        }
+        self.offset += len;
+        Ok(&remaining[..len])
+    }
+
+    fn take_array<const N: usize>(&mut self, ty: ArgType) -> Result<[u8; N]> {
+        Ok(self.take(ty, N)?.try_into().expect("Took N bytes"))
+    }
+
+    /// Read the next field as `ty`.
+    pub fn read(&mut self, ty: ArgType) -> Result<Value> {
+        let start = self.offset;
+        let value = match ty {
+            ArgType::Bool => match self.take_array::<1>(ty)? {
+                [0] => Value::Bool(false),
+                [1] => Value::Bool(true),
+                [byte] => return Err(Error::InvalidArgs(format!("bool at byte {} is {}, not 0 or 1", start, byte))),
+            },
+            ArgType::U8 => Value::U8(self.take_array::<1>(ty)?[0]),
+            ArgType::U32 => Value::U32(u32::from_le_bytes(self.take_array(ty)?)),
+            ArgType::U64 => Value::U64(u64::from_le_bytes(self.take_array(ty)?)),
+            ArgType::U256 => Value::U256(U256::from_le_bytes(self.take_array(ty)?)),
+            ArgType::String | ArgType::Bytes => {
+                let len = u32::from_le_bytes(self.take_array(ty)?) as usize;
+                let bytes = self.take(ty, len)?;
+                if ty == ArgType::Bytes {
+                    Value::Bytes(bytes.to_vec())
+                } else {
+                    let text = std::str::from_utf8(bytes).map_err(|_| {
+                        Error::InvalidArgs(format!("string at byte {} of length {} is not UTF-8", start, len))
+                    })?;
+                    Value::String(String::from(text))
+                }
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// Decode `data` as exactly the fields of `types`.
+pub fn decode(data: &[u8], types: &[ArgType]) -> Result<Vec<Value>> {
+    let mut reader = ArgsReader::new(data);
+    let values = types.iter().map(|ty| reader.read(*ty)).collect::<Result<Vec<_>>>()?;
+    match reader.remaining().len() {
+        0 => Ok(values),
+        trailing => Err(Error::InvalidArgs(format!("{} trailing bytes after the last field", trailing))),
+    }
+}
+
+/// Encode `values` with `Args`, as a contract call would.
+pub fn encode(values: &[Value]) -> Vec<u8> {
+    let mut args = Args::new();
+    for value in values {
+        match value {
+            Value::String(value) => args.add_string(value),
+            Value::Bool(value) => args.add_bool(*value),
+            Value::U8(value) => args.add_u8(*value),
+            Value::U32(value) => args.add_u32(*value),
+            Value::U64(value) => args.add_u64(*value),
+            Value::U256(value) => args.add_u256(*value),
+            Value::Bytes(value) => args.add_bytes(value),
+        };
+    }
+    args.into_bytes()
+}
@@ -0,0 +1,202 @@
+//! Named network profiles for the client and the command-line tools.
+//!
+//! `buildnet`, `mainnet` and `local` (a sandbox node on this machine) are
+//! built in. A TOML file can change them or add more, field by field:
+//!
+//! ```toml
+//! [profiles.buildnet]
+//! fee = "0.02"
+//!
+//! [profiles.buildnet.tokens]
+//! MCOIN = "AS12..."
+//!
+//! [profiles.staging]
+//! rpc_url = "https://staging.example.com/api/v2"
+//! chain_id = 77658366
+//! ```
+//!
+//! The file is `$MRC20_CONFIG`, or `mrc20/config.toml` under
+//! `$XDG_CONFIG_HOME` (`~/.config` by default) when it exists. On top of the
+//! selected profile, `MRC20_RPC_URL`, `MRC20_CHAIN_ID` and `MRC20_FEE` override
+//! its fields.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::network::Network;
+use crate::rpc::RpcClient;
+use crate::{units, Error, Result};
+
+/// Fee of the built-in public networks, in nanoMAS.
+const PUBLIC_FEE: u64 = 10_000_000;
+/// Public JSON-RPC port of a node started with the default settings.
+const LOCAL_RPC_URL: &str = "http://127.0.0.1:33035";
+
+/// One network's settings.
+#[derive(Clone, Debug)]
+pub struct Profile {
+    pub name: String,
+    pub rpc_url: String,
+    /// Chain id the node must report; `None` accepts any, e.g. for a sandbox.
+    pub chain_id: Option<u64>,
+    /// Default operation fee, in nanoMAS.
+    pub fee: u64,
+    /// Token addresses by name, usable wherever a token address is expected.
+    pub tokens: BTreeMap<String, String>,
+}
+
+impl Profile {
+    /// A client for the profile's node, refusing any other chain.
+    pub fn rpc(&self) -> RpcClient {
+        RpcClient::new(&self.rpc_url).with_chain_id(self.chain_id)
+    }
+
+    /// The address of the token named `token` in the profile, or `token` itself.
+    pub fn token_address<'a>(&'a self, token: &'a str) -> &'a str {
+        self.tokens.get(token).map_or(token, String::as_str)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    profiles: BTreeMap<String, ProfileFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileFile {
+    rpc_url: Option<String>,
+    chain_id: Option<u64>,
+    /// In MAS.
+    fee: Option<String>,
+    #[serde(default)]
+    tokens: BTreeMap<String, String>,
+}
+
+/// Every known profile.
+#[derive(Clone, Debug)]
+pub struct Config {
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl Config {
+    /// The built-in profiles only.
+    pub fn builtin() -> Self {
+        let public = |network: Network| Profile {
+            name: network.to_string(),
+            rpc_url: String::from(network.rpc_url()),
+            chain_id: Some(network.chain_id()),
+            fee: PUBLIC_FEE,
+            tokens: BTreeMap::new(),
+        };
+        let local = Profile {
+            name: String::from("local"),
+            rpc_url: String::from(LOCAL_RPC_URL),
+            chain_id: None,
+            fee: 0,
+            tokens: BTreeMap::new(),
+        };
+        let profiles = [public(Network::Buildnet), public(Network::Mainnet), local];
+        Config {
+            profiles: profiles.into_iter().map(|profile| (profile.name.clone(), profile)).collect(),
+        }
+    }
+
+    /// The built-in profiles with the file at `path` applied, else the one at
+    /// `$MRC20_CONFIG`, else the user's file if there is one.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let mut config = Config::builtin();
+        let path = match (path, std::env::var_os("MRC20_CONFIG")) {
+            (Some(path), _) => Some(PathBuf::from(path)),
+            (None, Some(path)) => Some(PathBuf::from(path)),
+            (None, None) => user_path().filter(|path| path.exists()),
+        };
+        if let Some(path) = path {
+            let text = std::fs::read_to_string(&path)
+                .map_err(|err| Error::Config(format!("reading {}: {}", path.display(), err)))?;
+            config.apply(&text).map_err(|err| match err {
+                Error::Config(reason) => Error::Config(format!("{}: {}", path.display(), reason)),
+                err => err,
+            })?;
+        }
+        Ok(config)
+    }
+
+    /// Apply the TOML `text`, changing existing profiles field by field.
+    pub fn apply(&mut self, text: &str) -> Result<()> {
+        let file: ConfigFile = toml::from_str(text).map_err(|err| Error::Config(err.to_string()))?;
+        for (name, changes) in file.profiles {
+            let profile = match self.profiles.remove(&name) {
+                Some(profile) => profile,
+                None => Profile {
+                    name: name.clone(),
+                    rpc_url: changes
+                        .rpc_url
+                        .clone()
+                        .ok_or_else(|| Error::Config(format!("profile {} has no rpc_url", name)))?,
+                    chain_id: None,
+                    fee: 0,
+                    tokens: BTreeMap::new(),
+                },
+            };
+            self.profiles.insert(name, changes.apply(profile)?);
+        }
+        Ok(())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// The profile `name` with the `MRC20_*` environment overrides applied.
+    pub fn profile(&self, name: &str) -> Result<Profile> {
+        let mut profile = self.profiles.get(name).cloned().ok_or_else(|| {
+            let names: Vec<&str> = self.names().collect();
+            Error::Config(format!("unknown network {}, expected one of {}", name, names.join(", ")))
+        })?;
+        if let Ok(rpc_url) = std::env::var("MRC20_RPC_URL") {
+            profile.rpc_url = rpc_url;
+        }
+        if let Ok(chain_id) = std::env::var("MRC20_CHAIN_ID") {
+            let chain_id = chain_id.parse().map_err(|_| Error::Config(format!("MRC20_CHAIN_ID={}", chain_id)))?;
+            profile.chain_id = Some(chain_id);
+        }
+        if let Ok(fee) = std::env::var("MRC20_FEE") {
+            profile.fee = parse_fee(&fee)?;
+        }
+        Ok(profile)
+    }
+}
+
+impl ProfileFile {
+    fn apply(self, mut profile: Profile) -> Result<Profile> {
+        if let Some(rpc_url) = self.rpc_url {
+            profile.rpc_url = rpc_url;
+        }
+        if let Some(chain_id) = self.chain_id {
+            profile.chain_id = Some(chain_id);
+        }
+        if let Some(fee) = self.fee {
+            profile.fee = parse_fee(&fee)?;
+        }
+        profile.tokens.extend(self.tokens);
+        Ok(profile)
+    }
+}
+
+fn parse_fee(fee: &str) -> Result<u64> {
+    units::parse_mas(fee).ok_or_else(|| Error::Config(format!("fee {} is not a MAS amount", fee)))
+}
+
+/// `mrc20/config.toml` in the user's configuration directory.
+fn user_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("mrc20").join("config.toml"))
+}
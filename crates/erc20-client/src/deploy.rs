@@ -0,0 +1,105 @@
+//! Contract deployment through the AssemblyScript deployer.
+//!
+//! The AS tooling deploys with an ExecuteSC operation that runs a small
+//! deployer bytecode. The contract, its constructor arguments and the coins
+//! to send with the constructor travel in the operation datastore:
+//!
+//! - `[0x00]` → number of contracts (u64 LE)
+//! - `u64 LE i` → bytecode of contract `i`, counting from 1
+//! - `Args(u64 i, bytes [0])` → constructor arguments of contract `i`
+//! - `Args(u64 i, bytes [1])` → coins for the constructor of contract `i` (u64 LE)
+//!
+//! The deployer creates each contract, calls its `constructor` and emits
+//! `Contract deployed at address: AS...`. Its bytecode is not part of this
+//! repository; it ships with `@massalabs/massa-sc-deployer`.
+
+use std::time::Duration;
+
+use massa_types::Args;
+
+use crate::error::{Error, Result};
+use crate::keypair::KeyPair;
+use crate::operation::{self, CallOptions};
+use crate::rpc::RpcClient;
+
+/// Prefix of the event the deployer emits for each contract.
+const DEPLOYED_EVENT: &str = "Contract deployed at address: ";
+
+/// A contract created by `deploy`.
+#[derive(Debug)]
+pub struct Deployment {
+    pub operation_id: String,
+    pub address: String,
+    /// The data of every event the deployment emitted, including the constructor's.
+    pub events: Vec<String>,
+}
+
+/// Operation datastore for deploying `bytecode` with `constructor_args` and `coins`.
+pub fn deployer_datastore(bytecode: &[u8], constructor_args: &[u8], coins: u64) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let entry_key = |field: u8| {
+        let mut key = Args::new();
+        key.add_u64(1).add_bytes(&[field]);
+        key.into_bytes()
+    };
+    let mut datastore = vec![
+        (vec![0u8], 1u64.to_le_bytes().to_vec()),
+        (1u64.to_le_bytes().to_vec(), bytecode.to_vec()),
+        (entry_key(0), constructor_args.to_vec()),
+    ];
+    if coins > 0 {
+        datastore.push((entry_key(1), coins.to_le_bytes().to_vec()));
+    }
+    datastore
+}
+
+/// Deploy `bytecode` and call its constructor with `constructor_args` and `coins` nanoMAS.
+///
+/// `options.coins` is the most the operation may spend, so it must cover
+/// `coins` and the storage cost of the new contract.
+#[allow(clippy::too_many_arguments)]
+pub fn deploy(
+    rpc: &RpcClient,
+    keypair: &KeyPair,
+    deployer_bytecode: &[u8],
+    bytecode: &[u8],
+    constructor_args: &[u8],
+    coins: u64,
+    options: &CallOptions,
+    timeout: Duration,
+) -> Result<Deployment> {
+    let status = rpc.get_status()?;
+    let content = operation::execute_sc_content(
+        deployer_bytecode,
+        &deployer_datastore(bytecode, constructor_args, coins),
+        options,
+        status.next_slot.period + options.validity_periods,
+    );
+    let signed = operation::sign(keypair, status.chain_id, content);
+    let operation_id = rpc
+        .send_operations(&[signed])?
+        .pop()
+        .ok_or_else(|| Error::Transport(String::from("send_operations returned no operation id")))?;
+
+    let events: Vec<String> = rpc
+        .wait_for_execution(&operation_id, timeout)
+        .map_err(|err| match err {
+            Error::Execution { message, .. } => Error::Execution {
+                function: String::from("constructor"),
+                message,
+            },
+            err => err,
+        })?
+        .into_iter()
+        .map(|event| event.data)
+        .collect();
+    let address = events
+        .iter()
+        .find_map(|event| event.strip_prefix(DEPLOYED_EVENT))
+        .map(String::from)
+        .ok_or_else(|| Error::Transport(format!("operation {} emitted no deployment event", operation_id)))?;
+    Ok(Deployment {
+        operation_id,
+        address,
+        events,
+    })
+}
@@ -0,0 +1,79 @@
+//! Client errors.
+
+use std::fmt;
+
+/// Everything that can go wrong talking to a node or decoding a contract's answer.
+#[derive(Debug)]
+pub enum Error {
+    /// The node could not be reached or sent something other than JSON-RPC.
+    Transport(String),
+    /// The node answered with a JSON-RPC error.
+    Rpc { code: i64, message: String },
+    /// The contract call panicked; `message` is its `ERR:{CODE}:{fields}:{message}` text.
+    Execution { function: String, message: String },
+    /// The contract returned bytes that do not match the expected encoding.
+    Decode { function: String, expected: &'static str },
+    /// A secret key that is not a valid `S...` key.
+    InvalidKey(String),
+    /// An address that is not a valid `AU...` or `AS...` address.
+    InvalidAddress(String),
+    /// `Args` bytes or values that do not match a type signature.
+    InvalidArgs(String),
+    /// A state-changing call was made on a client without a keypair.
+    MissingKeyPair,
+    /// The node is on another chain than the one configured.
+    WrongChain { expected: u64, actual: u64 },
+    /// A configuration file or profile that cannot be used.
+    Config(String),
+    /// The operation was not executed before the client stopped waiting.
+    Timeout { operation_id: String },
+}
+
+impl Error {
+    /// The `ERR:` code of a failed contract call, e.g. `INSUFFICIENT_BALANCE`.
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Error::Execution { message, .. } => {
+                let (_, rest) = message.split_once("ERR:")?;
+                rest.split(':').next()
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Transport(message) => write!(f, "transport error: {}", message),
+            Error::Rpc { code, message } => write!(f, "node error {}: {}", code, message),
+            Error::Execution { function, message } => write!(f, "{} failed: {}", function, message),
+            Error::Decode { function, expected } => write!(f, "{} did not return a {}", function, expected),
+            Error::InvalidKey(reason) => write!(f, "invalid secret key: {}", reason),
+            Error::InvalidAddress(address) => write!(f, "invalid address: {}", address),
+            Error::InvalidArgs(reason) => write!(f, "invalid args: {}", reason),
+            Error::MissingKeyPair => write!(f, "a keypair is required to send operations"),
+            Error::WrongChain { expected, actual } => {
+                write!(f, "the node is on chain {}, expected chain {}", actual, expected)
+            }
+            Error::Config(reason) => write!(f, "invalid configuration: {}", reason),
+            Error::Timeout { operation_id } => write!(f, "operation {} was not executed in time", operation_id),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ureq::Error> for Error {
+    fn from(err: ureq::Error) -> Self {
+        Error::Transport(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Transport(err.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,94 @@
+//! Holder table read straight from the token's datastore.
+//!
+//! The token keeps each non-zero balance under `BALANCE{address}`, so listing
+//! the final keys with that prefix and reading their values gives every
+//! holder without an indexer. Keys are listed once, in byte order, and the
+//! balances are read one page at a time.
+//!
+//! Pages are read at different times, so a transfer finalized in between can
+//! appear in some pages and not others. For an exact snapshot, compare the
+//! sum with `total_supply()` read before and after.
+
+use crate::keypair;
+use crate::{decode_error, Erc20Client, Result, U256};
+
+/// Datastore prefix of the token's balances.
+const BALANCE_KEY_PREFIX: &[u8] = b"BALANCE";
+/// Entries per `get_datastore_entries` request, the node's default `max_arguments`.
+pub const MAX_PAGE_SIZE: usize = 128;
+
+/// An address with a non-zero balance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Holder {
+    pub address: String,
+    pub balance: U256,
+}
+
+/// Holders in pages of at most `page_size`, ordered by address.
+pub struct HolderPages<'a> {
+    client: &'a Erc20Client,
+    addresses: std::vec::IntoIter<String>,
+    page_size: usize,
+}
+
+impl Iterator for HolderPages<'_> {
+    type Item = Result<Vec<Holder>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let addresses: Vec<String> = self.addresses.by_ref().take(self.page_size).collect();
+        if addresses.is_empty() {
+            return None;
+        }
+        Some(self.client.read_balances(addresses))
+    }
+}
+
+impl Erc20Client {
+    /// Every holder and their final balance, ordered by address.
+    pub fn holders(&self) -> Result<Vec<Holder>> {
+        let mut holders = Vec::new();
+        for page in self.holder_pages(MAX_PAGE_SIZE)? {
+            holders.extend(page?);
+        }
+        Ok(holders)
+    }
+
+    /// List the holders now and read their balances page by page as the iterator advances.
+    ///
+    /// `page_size` is capped at [`MAX_PAGE_SIZE`]. An address whose balance
+    /// was emptied after the listing is left out of its page.
+    pub fn holder_pages(&self, page_size: usize) -> Result<HolderPages<'_>> {
+        let keys = self.rpc.get_datastore_keys(&self.token, BALANCE_KEY_PREFIX, true)?;
+        let addresses: Vec<String> = keys
+            .into_iter()
+            .filter_map(|key| String::from_utf8(key.strip_prefix(BALANCE_KEY_PREFIX)?.to_vec()).ok())
+            // The prefix also matches any other key that starts with `BALANCE`
+            .filter(|address| keypair::address_bytes(address).is_ok())
+            .collect();
+        Ok(HolderPages {
+            client: self,
+            addresses: addresses.into_iter(),
+            page_size: page_size.clamp(1, MAX_PAGE_SIZE),
+        })
+    }
+
+    fn read_balances(&self, addresses: Vec<String>) -> Result<Vec<Holder>> {
+        let keys: Vec<Vec<u8>> = addresses
+            .iter()
+            .map(|address| [BALANCE_KEY_PREFIX, address.as_bytes()].concat())
+            .collect();
+        let entries = self.rpc.get_datastore_entries(&self.token, &keys)?;
+        let mut holders = Vec::with_capacity(addresses.len());
+        for (address, entry) in addresses.into_iter().zip(entries) {
+            let Some(value) = entry.final_value else {
+                continue;
+            };
+            let bytes: [u8; 32] = value.try_into().map_err(|_| decode_error("holders", "u256"))?;
+            let balance = U256::from_le_bytes(bytes);
+            if balance != U256::ZERO {
+                holders.push(Holder { address, balance });
+            }
+        }
+        Ok(holders)
+    }
+}
@@ -0,0 +1,89 @@
+//! Massa ed25519 keypairs and their string encodings.
+//!
+//! Secret keys, public keys and addresses are a version byte followed by the
+//! key or hash, base58check-encoded with an `S`, `P` or `AU` prefix.
+//! Signatures use the same encoding without a prefix.
+
+use ed25519_dalek::{Signer, SigningKey};
+
+use crate::error::{Error, Result};
+
+/// Version of the key, signature and address encodings.
+const VERSION: u8 = 0;
+
+/// A signing key loaded from a Massa `S...` secret key.
+pub struct KeyPair {
+    signing_key: SigningKey,
+}
+
+impl KeyPair {
+    /// Parse an `S...` secret key, as exported by the Massa wallet.
+    pub fn from_secret_key(secret_key: &str) -> Result<Self> {
+        let invalid = || Error::InvalidKey(String::from("expected an S... secret key"));
+        let encoded = secret_key.strip_prefix('S').ok_or_else(invalid)?;
+        let bytes = decode_versioned(encoded).ok_or_else(invalid)?;
+        let secret: [u8; 32] = bytes.try_into().map_err(|_| invalid())?;
+        Ok(KeyPair {
+            signing_key: SigningKey::from_bytes(&secret),
+        })
+    }
+
+    /// Version byte followed by the 32-byte public key, as hashed and signed by the node.
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        versioned(self.signing_key.verifying_key().as_bytes())
+    }
+
+    /// The `P...` public key.
+    pub fn public_key(&self) -> String {
+        format!("P{}", bs58::encode(self.public_key_bytes()).with_check().into_string())
+    }
+
+    /// The 32-byte hash identifying the `AU...` address of this key.
+    pub fn address_hash(&self) -> [u8; 32] {
+        blake3::hash(&self.public_key_bytes()).into()
+    }
+
+    /// The `AU...` address of this key.
+    pub fn address(&self) -> String {
+        format!("AU{}", bs58::encode(versioned(&self.address_hash())).with_check().into_string())
+    }
+
+    /// Sign `message` and return the base58check-encoded signature.
+    pub fn sign(&self, message: &[u8]) -> String {
+        let signature = self.signing_key.sign(message);
+        bs58::encode(versioned(&signature.to_bytes())).with_check().into_string()
+    }
+}
+
+fn versioned(bytes: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(bytes.len() + 1);
+    data.push(VERSION);
+    data.extend_from_slice(bytes);
+    data
+}
+
+/// Decode base58check data and strip its version byte.
+fn decode_versioned(encoded: &str) -> Option<Vec<u8>> {
+    let data = bs58::decode(encoded).with_check(None).into_vec().ok()?;
+    match data.split_first() {
+        Some((&VERSION, bytes)) => Some(bytes.to_vec()),
+        _ => None,
+    }
+}
+
+/// Decode an `AU...` or `AS...` address into its serialized form:
+/// the address kind (0 for user, 1 for contract), the version, then the hash.
+pub fn address_bytes(address: &str) -> Result<Vec<u8>> {
+    let invalid = || Error::InvalidAddress(String::from(address));
+    let (kind, encoded) = if let Some(encoded) = address.strip_prefix("AU") {
+        (0u8, encoded)
+    } else if let Some(encoded) = address.strip_prefix("AS") {
+        (1u8, encoded)
+    } else {
+        return Err(invalid());
+    };
+    let hash = decode_versioned(encoded).filter(|hash| hash.len() == 32).ok_or_else(invalid)?;
+    let mut bytes = vec![kind, VERSION];
+    bytes.extend_from_slice(&hash);
+    Ok(bytes)
+}
@@ -0,0 +1,327 @@
+//! Off-chain client for the MRC20 token.
+//!
+//! Wraps a node's read-only execution endpoint in typed calls, encoding the
+//! arguments with `Args` and decoding the raw return values the token uses
+//! (UTF-8 strings, a single `decimals` byte, 32-byte little-endian `U256`):
+//!
+//! ```no_run
+//! use erc20_client::Erc20Client;
+//!
+//! let token = Erc20Client::new("https://buildnet.massa.net/api/v2", "AS12...");
+//! let balance = token.balance_of("AU12...")?;
+//! # Ok::<(), erc20_client::Error>(())
+//! ```
+//!
+//! State-changing calls need a keypair. They are signed locally, sent as CallSC
+//! operations, and return once the node has executed them:
+//!
+//! ```no_run
+//! use erc20_client::{Erc20Client, KeyPair, U256};
+//!
+//! let keypair = KeyPair::from_secret_key("S12...")?;
+//! let token = Erc20Client::new("https://buildnet.massa.net/api/v2", "AS12...").with_keypair(keypair);
+//! let receipt = token.transfer("AU12...", U256::from(1_000u64))?;
+//! # Ok::<(), erc20_client::Error>(())
+//! ```
+//!
+//! Other contracts are reached through bindings generated by `tools/abigen`,
+//! which build on [`bindings::ContractCall`].
+
+pub mod bindings;
+pub mod codec;
+pub mod config;
+pub mod deploy;
+mod error;
+pub mod holders;
+pub mod keypair;
+pub mod network;
+pub mod operation;
+pub mod rpc;
+#[cfg(feature = "subscribe")]
+pub mod subscribe;
+pub mod units;
+
+pub use bindings::ContractCall;
+pub use error::{Error, Result};
+pub use holders::Holder;
+pub use keypair::KeyPair;
+pub use massa_types::{Args, U256};
+pub use operation::CallOptions;
+
+use std::time::Duration;
+
+use rpc::{ReadOnlyCall, ReadOnlyResult, RpcClient, READ_ONLY_MAX_GAS};
+
+/// Share of the simulated gas cost added to `max_gas` when estimating, in percent.
+const GAS_MARGIN_PERCENT: u64 = 20;
+
+/// Gas cost and events of a simulated call.
+#[derive(Debug)]
+pub struct GasEstimate {
+    pub gas_cost: u64,
+    /// The data of every event the call would emit, in order.
+    pub events: Vec<String>,
+}
+
+/// Typed access to one deployed MRC20 token.
+pub struct Erc20Client {
+    rpc: RpcClient,
+    token: String,
+    keypair: Option<KeyPair>,
+    options: CallOptions,
+    timeout: Duration,
+}
+
+/// An executed state-changing call.
+#[derive(Debug)]
+pub struct CallReceipt {
+    pub operation_id: String,
+    /// The data of every event the call emitted, in order.
+    pub events: Vec<String>,
+}
+
+impl Erc20Client {
+    /// Client for the token at `token_address`, through the node at `rpc_url`.
+    pub fn new(rpc_url: &str, token_address: &str) -> Self {
+        Erc20Client::with_rpc(RpcClient::new(rpc_url), token_address)
+    }
+
+    pub fn with_rpc(rpc: RpcClient, token_address: &str) -> Self {
+        Erc20Client {
+            rpc,
+            token: String::from(token_address),
+            keypair: None,
+            options: CallOptions::default(),
+            timeout: Duration::from_secs(120),
+        }
+    }
+
+    /// Sign state-changing calls with `keypair`.
+    pub fn with_keypair(mut self, keypair: KeyPair) -> Self {
+        self.keypair = Some(keypair);
+        self
+    }
+
+    /// Fee, gas and coins of state-changing calls.
+    pub fn with_options(mut self, options: CallOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// How long state-changing calls wait for their execution.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn token_address(&self) -> &str {
+        &self.token
+    }
+
+    /// Run `function` read-only and return its raw result.
+    pub fn read(&self, function: &str, args: Args) -> Result<Vec<u8>> {
+        let call = ReadOnlyCall {
+            max_gas: READ_ONLY_MAX_GAS,
+            target_address: self.token.clone(),
+            target_function: String::from(function),
+            parameter: args.into_bytes(),
+            caller_address: None,
+            coins: None,
+            fee: None,
+        };
+        self.execute_read_only(function, &call).map(|(ret, _)| ret)
+    }
+
+    /// Execute `call` and return its result and cost, or its failure as `Error::Execution`.
+    fn execute_read_only(&self, function: &str, call: &ReadOnlyCall) -> Result<(Vec<u8>, GasEstimate)> {
+        let response = self.rpc.execute_read_only_call(call)?;
+        match response.result {
+            ReadOnlyResult::Ok(ret) => Ok((
+                ret,
+                GasEstimate {
+                    gas_cost: response.gas_cost,
+                    events: response.output_events.into_iter().map(|event| event.data).collect(),
+                },
+            )),
+            ReadOnlyResult::Error(message) => Err(Error::Execution {
+                function: String::from(function),
+                message,
+            }),
+        }
+    }
+
+    fn read_string(&self, function: &str, args: Args) -> Result<String> {
+        String::from_utf8(self.read(function, args)?).map_err(|_| decode_error(function, "string"))
+    }
+
+    fn read_u256(&self, function: &str, args: Args) -> Result<U256> {
+        let ret = self.read(function, args)?;
+        let bytes: [u8; 32] = ret.try_into().map_err(|_| decode_error(function, "u256"))?;
+        Ok(U256::from_le_bytes(bytes))
+    }
+
+    pub fn name(&self) -> Result<String> {
+        self.read_string("name", Args::new())
+    }
+
+    pub fn symbol(&self) -> Result<String> {
+        self.read_string("symbol", Args::new())
+    }
+
+    pub fn decimals(&self) -> Result<u8> {
+        match self.read("decimals", Args::new())?.as_slice() {
+            [decimals] => Ok(*decimals),
+            _ => Err(decode_error("decimals", "u8")),
+        }
+    }
+
+    pub fn total_supply(&self) -> Result<U256> {
+        self.read_u256("totalSupply", Args::new())
+    }
+
+    pub fn balance_of(&self, address: &str) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_string(address);
+        self.read_u256("balanceOf", args)
+    }
+
+    pub fn allowance(&self, owner: &str, spender: &str) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_string(owner).add_string(spender);
+        self.read_u256("allowance", args)
+    }
+}
+
+impl Erc20Client {
+    /// The address of the keypair, which state-changing calls act as.
+    pub fn signer_address(&self) -> Result<String> {
+        self.keypair.as_ref().map(KeyPair::address).ok_or(Error::MissingKeyPair)
+    }
+
+    /// Simulate a call to `function` as the keypair's account, without signing or sending it.
+    ///
+    /// The simulation runs read-only with the call's coins and fee, so it fails
+    /// with `Error::Execution` where the call would.
+    pub fn estimate_gas(&self, function: &str, args: Args) -> Result<GasEstimate> {
+        self.simulate(function, args.into_bytes())
+    }
+
+    fn simulate(&self, function: &str, parameter: Vec<u8>) -> Result<GasEstimate> {
+        let call = ReadOnlyCall {
+            max_gas: READ_ONLY_MAX_GAS,
+            target_address: self.token.clone(),
+            target_function: String::from(function),
+            parameter,
+            caller_address: Some(self.signer_address()?),
+            coins: Some(units::format_mas(self.options.coins)),
+            fee: Some(units::format_mas(self.options.fee)),
+        };
+        self.execute_read_only(function, &call).map(|(_, estimate)| estimate)
+    }
+
+    /// Sign and submit a call to `function`, returning the operation id without waiting.
+    ///
+    /// With `CallOptions::estimate_gas`, the call is simulated first and sent
+    /// with its gas cost plus a margin, never above `max_gas`.
+    pub fn send(&self, function: &str, args: Args) -> Result<String> {
+        let keypair = self.keypair.as_ref().ok_or(Error::MissingKeyPair)?;
+        let parameter = args.into_bytes();
+        let mut options = self.options.clone();
+        if options.estimate_gas {
+            let gas_cost = self.simulate(function, parameter.clone())?.gas_cost;
+            let margin = gas_cost / 100 * GAS_MARGIN_PERCENT;
+            options.max_gas = gas_cost.saturating_add(margin).min(options.max_gas);
+        }
+        let status = self.rpc.get_status()?;
+        let content = operation::call_sc_content(
+            &self.token,
+            function,
+            &parameter,
+            &options,
+            status.next_slot.period + options.validity_periods,
+        )?;
+        let signed = operation::sign(keypair, status.chain_id, content);
+        self.rpc
+            .send_operations(&[signed])?
+            .pop()
+            .ok_or_else(|| Error::Transport(String::from("send_operations returned no operation id")))
+    }
+
+    /// Wait until the operation `operation_id` calling `function` is executed.
+    ///
+    /// Fails with `Error::Execution` carrying the contract's `ERR:` text if the
+    /// call panicked, and with `Error::Timeout` if it is not executed in time.
+    pub fn wait(&self, function: &str, operation_id: &str) -> Result<CallReceipt> {
+        let events = self.rpc.wait_for_execution(operation_id, self.timeout).map_err(|err| match err {
+            Error::Execution { message, .. } => Error::Execution {
+                function: String::from(function),
+                message,
+            },
+            err => err,
+        })?;
+        Ok(CallReceipt {
+            operation_id: String::from(operation_id),
+            events: events.into_iter().map(|event| event.data).collect(),
+        })
+    }
+
+    /// Sign and submit a call to `function`, then wait for its execution.
+    pub fn call(&self, function: &str, args: Args) -> Result<CallReceipt> {
+        let operation_id = self.send(function, args)?;
+        self.wait(function, &operation_id)
+    }
+
+    pub fn transfer(&self, to: &str, amount: U256) -> Result<CallReceipt> {
+        let mut args = Args::new();
+        args.add_string(to).add_u256(amount);
+        self.call("transfer", args)
+    }
+
+    pub fn increase_allowance(&self, spender: &str, amount: U256) -> Result<CallReceipt> {
+        let mut args = Args::new();
+        args.add_string(spender).add_u256(amount);
+        self.call("increaseAllowance", args)
+    }
+
+    pub fn decrease_allowance(&self, spender: &str, amount: U256) -> Result<CallReceipt> {
+        let mut args = Args::new();
+        args.add_string(spender).add_u256(amount);
+        self.call("decreaseAllowance", args)
+    }
+
+    /// Set the allowance of `spender` to `amount` through `approveIfCurrent`.
+    ///
+    /// The current allowance is read first. If the spender uses it before the
+    /// call executes, the allowance is left unchanged and the receipt's
+    /// `APPROVE_IF_CURRENT` event reports `success=false`.
+    pub fn approve(&self, spender: &str, amount: U256) -> Result<CallReceipt> {
+        let current = self.allowance(&self.signer_address()?, spender)?;
+        let mut args = Args::new();
+        args.add_string(spender).add_u256(current).add_u256(amount);
+        self.call("approveIfCurrent", args)
+    }
+
+    /// Mint `amount` to `to`; the keypair needs the `MINTER` role.
+    pub fn mint(&self, to: &str, amount: U256) -> Result<CallReceipt> {
+        let mut args = Args::new();
+        args.add_string(to).add_u256(amount);
+        self.call("mint", args)
+    }
+
+    pub fn burn(&self, amount: U256) -> Result<CallReceipt> {
+        let mut args = Args::new();
+        args.add_u256(amount);
+        self.call("burn", args)
+    }
+}
+
+fn decode_error(function: &str, expected: &'static str) -> Error {
+    Error::Decode {
+        function: String::from(function),
+        expected,
+    }
+}
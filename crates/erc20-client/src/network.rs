@@ -0,0 +1,49 @@
+//! Public Massa networks.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Network {
+    Buildnet,
+    Mainnet,
+}
+
+impl Network {
+    /// The public JSON-RPC endpoint of the network.
+    pub fn rpc_url(self) -> &'static str {
+        match self {
+            Network::Buildnet => "https://buildnet.massa.net/api/v2",
+            Network::Mainnet => "https://mainnet.massa.net/api/v2",
+        }
+    }
+
+    /// The chain id operations are signed for.
+    pub fn chain_id(self) -> u64 {
+        match self {
+            Network::Buildnet => 77_658_366,
+            Network::Mainnet => 77_658_377,
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "buildnet" => Ok(Network::Buildnet),
+            "mainnet" => Ok(Network::Mainnet),
+            _ => Err(format!("unknown network `{}`, expected buildnet or mainnet", name)),
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Network::Buildnet => write!(f, "buildnet"),
+            Network::Mainnet => write!(f, "mainnet"),
+        }
+    }
+}
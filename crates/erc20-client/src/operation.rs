@@ -0,0 +1,143 @@
+//! Serialization and signing of CallSC and ExecuteSC operations.
+//!
+//! The node expects the operation content in its binary format: integers as
+//! unsigned LEB128 varints, amounts as varints of nanoMAS, addresses as
+//! `keypair::address_bytes`, and byte strings after their varint length. The
+//! creator signs the blake3 hash of the chain id (u64 BE), its public key and
+//! the content, and that hash is also the operation id.
+
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::keypair::{self, KeyPair};
+
+/// Operation type id of ExecuteSC.
+const EXECUTE_SC: u64 = 3;
+/// Operation type id of CallSC.
+const CALL_SC: u64 = 4;
+/// Version of the operation id encoding.
+const ID_VERSION: u8 = 0;
+
+/// Fee, gas and lifetime settings of a state-changing call.
+#[derive(Clone, Debug)]
+pub struct CallOptions {
+    /// Fee paid to the block producer, in nanoMAS.
+    pub fee: u64,
+    /// Gas limit of the call.
+    pub max_gas: u64,
+    /// Coins sent to the contract with the call, in nanoMAS. For ExecuteSC,
+    /// the most coins the bytecode may spend.
+    pub coins: u64,
+    /// Number of periods after the current one during which the operation stays valid.
+    pub validity_periods: u64,
+    /// Simulate CallSC operations first and lower `max_gas` to their gas cost
+    /// plus a margin. The simulation also catches failing calls before they
+    /// are signed and their fee is paid.
+    pub estimate_gas: bool,
+}
+
+impl Default for CallOptions {
+    fn default() -> Self {
+        CallOptions {
+            fee: 10_000_000,
+            max_gas: 100_000_000,
+            coins: 0,
+            validity_periods: 10,
+            estimate_gas: false,
+        }
+    }
+}
+
+/// A signed operation in the form `send_operations` takes.
+#[derive(Serialize)]
+pub struct SignedOperation {
+    pub serialized_content: Vec<u8>,
+    pub creator_public_key: String,
+    pub signature: String,
+}
+
+fn push_varint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn push_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    push_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+/// Binary content of a CallSC operation.
+pub fn call_sc_content(
+    target: &str,
+    function: &str,
+    parameter: &[u8],
+    options: &CallOptions,
+    expire_period: u64,
+) -> Result<Vec<u8>> {
+    let mut content = Vec::new();
+    push_varint(&mut content, options.fee);
+    push_varint(&mut content, expire_period);
+    push_varint(&mut content, CALL_SC);
+    push_varint(&mut content, options.max_gas);
+    push_varint(&mut content, options.coins);
+    content.extend_from_slice(&keypair::address_bytes(target)?);
+    push_bytes(&mut content, function.as_bytes());
+    push_bytes(&mut content, parameter);
+    Ok(content)
+}
+
+/// Binary content of an ExecuteSC operation running `bytecode` with the operation datastore `datastore`.
+pub fn execute_sc_content(
+    bytecode: &[u8],
+    datastore: &[(Vec<u8>, Vec<u8>)],
+    options: &CallOptions,
+    expire_period: u64,
+) -> Vec<u8> {
+    let mut content = Vec::new();
+    push_varint(&mut content, options.fee);
+    push_varint(&mut content, expire_period);
+    push_varint(&mut content, EXECUTE_SC);
+    push_varint(&mut content, options.max_gas);
+    push_varint(&mut content, options.coins);
+    push_bytes(&mut content, bytecode);
+    push_varint(&mut content, datastore.len() as u64);
+    for (key, value) in datastore {
+        push_bytes(&mut content, key);
+        push_bytes(&mut content, value);
+    }
+    content
+}
+
+/// Hash of an operation, which its creator signs and which identifies it:
+/// blake3 of the chain id (u64 BE), the creator's public key and the content.
+fn operation_hash(keypair: &KeyPair, chain_id: u64, content: &[u8]) -> blake3::Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&chain_id.to_be_bytes());
+    hasher.update(&keypair.public_key_bytes());
+    hasher.update(content);
+    hasher.finalize()
+}
+
+/// The `O...` id the node gives the operation with `content` signed by `keypair` for the chain `chain_id`.
+pub fn operation_id(keypair: &KeyPair, chain_id: u64, content: &[u8]) -> String {
+    let mut id = vec![ID_VERSION];
+    id.extend_from_slice(operation_hash(keypair, chain_id, content).as_bytes());
+    format!("O{}", bs58::encode(id).with_check().into_string())
+}
+
+/// Sign `content` for the chain `chain_id`.
+pub fn sign(keypair: &KeyPair, chain_id: u64, content: Vec<u8>) -> SignedOperation {
+    let hash = operation_hash(keypair, chain_id, &content);
+    SignedOperation {
+        signature: keypair.sign(hash.as_bytes()),
+        creator_public_key: keypair.public_key(),
+        serialized_content: content,
+    }
+}
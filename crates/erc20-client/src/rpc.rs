@@ -0,0 +1,289 @@
+//! Minimal JSON-RPC client for a Massa node's public API.
+
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+use crate::operation::SignedOperation;
+
+/// Gas limit of read-only calls, the node's maximum for one call.
+pub const READ_ONLY_MAX_GAS: u64 = 4_294_167_295;
+
+/// How often `wait_for_execution` polls the node.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Connection to a node's public JSON-RPC endpoint, e.g. `https://buildnet.massa.net/api/v2`.
+#[derive(Clone)]
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+    chain_id: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+/// A call executed by the node without creating an operation.
+#[derive(Serialize)]
+pub struct ReadOnlyCall {
+    pub max_gas: u64,
+    pub target_address: String,
+    pub target_function: String,
+    pub parameter: Vec<u8>,
+    pub caller_address: Option<String>,
+    pub coins: Option<String>,
+    pub fee: Option<String>,
+}
+
+/// Outcome of a read-only call.
+#[derive(Deserialize)]
+pub struct ReadOnlyResponse {
+    pub result: ReadOnlyResult,
+    #[serde(default)]
+    pub output_events: Vec<OutputEvent>,
+    #[serde(default)]
+    pub gas_cost: u64,
+}
+
+/// The return bytes of the call, or the reason it failed.
+#[derive(Deserialize)]
+pub enum ReadOnlyResult {
+    Ok(Vec<u8>),
+    Error(String),
+}
+
+/// An event emitted by a contract.
+#[derive(Deserialize)]
+pub struct OutputEvent {
+    #[serde(default)]
+    pub context: EventContext,
+    pub data: String,
+}
+
+/// Where and how an event was emitted.
+#[derive(Default, Deserialize)]
+pub struct EventContext {
+    #[serde(default)]
+    pub slot: Slot,
+    /// Position of the event among the events of its slot.
+    #[serde(default)]
+    pub index_in_slot: u64,
+    #[serde(default)]
+    pub origin_operation_id: Option<String>,
+    #[serde(default)]
+    pub is_final: bool,
+    /// True for the event the node emits when the call panics.
+    #[serde(default)]
+    pub is_error: bool,
+}
+
+/// A block slot; slots are ordered by period, then thread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Slot {
+    pub period: u64,
+    pub thread: u8,
+}
+
+/// Selects events for `get_filtered_sc_output_event`; unset fields match everything.
+#[derive(Clone, Default, Serialize)]
+pub struct EventFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<Slot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<Slot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emitter_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_operation_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_final: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_error: Option<bool>,
+}
+
+/// The final and candidate values of one datastore entry, `None` where it is absent.
+#[derive(Deserialize)]
+pub struct DatastoreEntry {
+    pub final_value: Option<Vec<u8>>,
+    pub candidate_value: Option<Vec<u8>>,
+}
+
+#[derive(Deserialize)]
+struct DatastoreKeys {
+    keys: Vec<Vec<u8>>,
+}
+
+/// The parts of `get_status` the client needs.
+#[derive(Deserialize)]
+pub struct NodeStatus {
+    pub chain_id: u64,
+    pub next_slot: Slot,
+}
+
+/// Execution state of a submitted operation.
+#[derive(Deserialize)]
+pub struct OperationInfo {
+    pub id: String,
+    #[serde(default)]
+    pub in_pool: bool,
+    #[serde(default)]
+    pub is_operation_final: Option<bool>,
+    /// `None` until the operation is executed, then whether it succeeded.
+    #[serde(default)]
+    pub op_exec_status: Option<bool>,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        RpcClient {
+            url: String::from(url),
+            agent: ureq::Agent::new(),
+            chain_id: None,
+        }
+    }
+
+    /// Refuse to work with a node on any chain but `chain_id`, e.g. a mainnet
+    /// node behind a URL meant for buildnet. Checked by `get_status`, which
+    /// every signed operation goes through.
+    pub fn with_chain_id(mut self, chain_id: Option<u64>) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Send one JSON-RPC request and decode its result.
+    pub fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response: RpcResponse<T> = self.agent.post(&self.url).send_json(body)?.into_json()?;
+        if let Some(error) = response.error {
+            return Err(Error::Rpc {
+                code: error.code,
+                message: error.message,
+            });
+        }
+        response
+            .result
+            .ok_or_else(|| Error::Transport(format!("{} returned neither a result nor an error", method)))
+    }
+
+    pub fn get_status(&self) -> Result<NodeStatus> {
+        let status: NodeStatus = self.request("get_status", json!([]))?;
+        match self.chain_id {
+            Some(expected) if expected != status.chain_id => Err(Error::WrongChain {
+                expected,
+                actual: status.chain_id,
+            }),
+            _ => Ok(status),
+        }
+    }
+
+    /// Submit signed operations and return their ids.
+    pub fn send_operations(&self, operations: &[SignedOperation]) -> Result<Vec<String>> {
+        self.request("send_operations", json!([operations]))
+    }
+
+    pub fn get_operations(&self, ids: &[String]) -> Result<Vec<OperationInfo>> {
+        self.request("get_operations", json!([ids]))
+    }
+
+    /// Events matching `filter`, in execution order.
+    pub fn get_events(&self, filter: &EventFilter) -> Result<Vec<OutputEvent>> {
+        self.request("get_filtered_sc_output_event", json!([filter]))
+    }
+
+    /// Events emitted by the execution of the operation `operation_id`.
+    pub fn get_operation_events(&self, operation_id: &str) -> Result<Vec<OutputEvent>> {
+        self.get_events(&EventFilter {
+            original_operation_id: Some(String::from(operation_id)),
+            ..EventFilter::default()
+        })
+    }
+
+    /// Wait until the operation `operation_id` is executed and return its events.
+    ///
+    /// Fails with `Error::Execution` carrying the error event's text if the
+    /// operation failed, and with `Error::Timeout` once `timeout` has passed.
+    pub fn wait_for_execution(&self, operation_id: &str, timeout: Duration) -> Result<Vec<OutputEvent>> {
+        let deadline = Instant::now() + timeout;
+        let ids = [String::from(operation_id)];
+        loop {
+            let executed = self.get_operations(&ids)?.pop().and_then(|info| info.op_exec_status);
+            if let Some(success) = executed {
+                let events = self.get_operation_events(operation_id)?;
+                if !success {
+                    let message = events
+                        .iter()
+                        .rev()
+                        .find(|event| event.context.is_error)
+                        .map(|event| event.data.clone())
+                        .unwrap_or_default();
+                    return Err(Error::Execution {
+                        function: String::from(operation_id),
+                        message,
+                    });
+                }
+                return Ok(events);
+            }
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout {
+                    operation_id: String::from(operation_id),
+                });
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// The keys of `address`'s final or candidate datastore that start with `prefix`, in byte order.
+    pub fn get_datastore_keys(&self, address: &str, prefix: &[u8], is_final: bool) -> Result<Vec<Vec<u8>>> {
+        let request = json!({ "address": address, "prefix": prefix, "is_final": is_final });
+        let mut responses: Vec<DatastoreKeys> = self.request("get_addresses_datastore_keys", json!([[request]]))?;
+        let mut keys = responses
+            .pop()
+            .ok_or_else(|| Error::Transport(String::from("get_addresses_datastore_keys returned no response")))?
+            .keys;
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// The entries of `address`'s datastore under `keys`, in the same order.
+    pub fn get_datastore_entries(&self, address: &str, keys: &[Vec<u8>]) -> Result<Vec<DatastoreEntry>> {
+        let requests: Vec<Value> = keys.iter().map(|key| json!({ "address": address, "key": key })).collect();
+        let entries: Vec<DatastoreEntry> = self.request("get_datastore_entries", json!([requests]))?;
+        if entries.len() != keys.len() {
+            return Err(Error::Transport(format!(
+                "get_datastore_entries returned {} entries for {} keys",
+                entries.len(),
+                keys.len()
+            )));
+        }
+        Ok(entries)
+    }
+
+    /// Execute `call` on the node's current state.
+    pub fn execute_read_only_call(&self, call: &ReadOnlyCall) -> Result<ReadOnlyResponse> {
+        let mut responses: Vec<ReadOnlyResponse> = self.request("execute_read_only_call", json!([[call]]))?;
+        responses
+            .pop()
+            .ok_or_else(|| Error::Transport(String::from("execute_read_only_call returned no response")))
+    }
+}
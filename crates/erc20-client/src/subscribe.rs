@@ -0,0 +1,194 @@
+//! Real-time event subscription over the node's WebSocket API.
+//!
+//! The node streams new block headers, not contract events, so every header
+//! notification triggers a `get_filtered_sc_output_event` query for the events
+//! after the last one delivered. The same cursor is used after a reconnection,
+//! so events emitted while the connection was down are delivered, in order,
+//! once it is back.
+//!
+//! Needs the `subscribe` feature and a Tokio runtime.
+
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::rpc::{EventFilter, OutputEvent, RpcClient, Slot};
+use crate::{Error, Result};
+
+/// Header subscription of the node's JSON-RPC v2 API, used as a wake-up signal.
+const SUBSCRIBE_METHOD: &str = "subscribe_new_blocks_headers";
+/// First and longest wait before reconnecting.
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Events waiting for the consumer before the subscription stops querying.
+const BUFFER: usize = 1024;
+
+type WebSocket = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Position of the last delivered event.
+type Cursor = (Slot, u64);
+
+/// Events matching a filter as the node executes them.
+///
+/// Yields `Err` for connection and query failures, which are retried; the
+/// stream only ends when it is dropped.
+pub struct EventStream {
+    events: mpsc::Receiver<Result<OutputEvent>>,
+    task: JoinHandle<()>,
+}
+
+impl EventStream {
+    /// The next event, or the next failure while waiting for one.
+    pub async fn next(&mut self) -> Option<Result<OutputEvent>> {
+        self.events.recv().await
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl RpcClient {
+    /// WebSocket URL of the node's API, which serves both on the same endpoint.
+    pub fn websocket_url(&self) -> String {
+        let url = self.url();
+        if let Some(rest) = url.strip_prefix("https://") {
+            format!("wss://{}", rest)
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            format!("ws://{}", rest)
+        } else {
+            String::from(url)
+        }
+    }
+
+    /// Subscribe to the events matching `filter`, starting at `filter.start`.
+    ///
+    /// Connects once before returning, so an unreachable node fails here
+    /// rather than in the stream. Events that are not final may later be
+    /// reverted; set `is_final: Some(true)` to only receive final ones.
+    pub async fn subscribe_events(&self, filter: EventFilter) -> Result<EventStream> {
+        let url = self.websocket_url();
+        let socket = connect(&url).await?;
+        let (sender, events) = mpsc::channel(BUFFER);
+        let task = tokio::spawn(follow(self.clone(), url, filter, socket, sender));
+        Ok(EventStream { events, task })
+    }
+}
+
+async fn connect(url: &str) -> Result<WebSocket> {
+    let (mut socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .map_err(|err| Error::Transport(format!("connecting to {}: {}", url, err)))?;
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": SUBSCRIBE_METHOD, "params": [] });
+    socket.send(Message::text(request.to_string())).await.map_err(transport)?;
+    Ok(socket)
+}
+
+fn transport(err: tokio_tungstenite::tungstenite::Error) -> Error {
+    Error::Transport(err.to_string())
+}
+
+/// Deliver events until the stream is dropped, reconnecting as needed.
+async fn follow(
+    rpc: RpcClient,
+    url: String,
+    filter: EventFilter,
+    mut socket: WebSocket,
+    sender: mpsc::Sender<Result<OutputEvent>>,
+) {
+    let mut cursor = None;
+    loop {
+        match listen(&rpc, &filter, socket, &sender, &mut cursor).await {
+            Ok(()) => return,
+            Err(err) => {
+                if sender.send(Err(err)).await.is_err() {
+                    return;
+                }
+            }
+        }
+        let mut backoff = MIN_BACKOFF;
+        socket = loop {
+            tokio::time::sleep(backoff).await;
+            match connect(&url).await {
+                Ok(socket) => break socket,
+                Err(err) => {
+                    if sender.send(Err(err)).await.is_err() {
+                        return;
+                    }
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        };
+    }
+}
+
+/// Catch up, then query again on every notification.
+///
+/// Returns `Ok` once the stream is dropped and an error when the connection is lost.
+async fn listen(
+    rpc: &RpcClient,
+    filter: &EventFilter,
+    mut socket: WebSocket,
+    sender: &mpsc::Sender<Result<OutputEvent>>,
+    cursor: &mut Option<Cursor>,
+) -> Result<()> {
+    deliver(rpc, filter, sender, cursor).await?;
+    while !sender.is_closed() {
+        let text = match socket.next().await {
+            Some(message) => match message.map_err(transport)? {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            },
+            None => break,
+        };
+        let message: Value = serde_json::from_str(&text).map_err(|err| Error::Transport(err.to_string()))?;
+        if let Some(error) = message.get("error") {
+            return Err(Error::Transport(format!("{} failed: {}", SUBSCRIBE_METHOD, error)));
+        }
+        // Headers are notifications; the only other message is the subscription id
+        if message.get("method").is_some() {
+            deliver(rpc, filter, sender, cursor).await?;
+        }
+    }
+    if sender.is_closed() {
+        return Ok(());
+    }
+    Err(Error::Transport(String::from("the node closed the subscription")))
+}
+
+/// Send the events after `cursor` and move it to the last one sent.
+async fn deliver(
+    rpc: &RpcClient,
+    filter: &EventFilter,
+    sender: &mpsc::Sender<Result<OutputEvent>>,
+    cursor: &mut Option<Cursor>,
+) -> Result<()> {
+    let query = EventFilter {
+        start: cursor.map(|(slot, _)| slot).or(filter.start),
+        ..filter.clone()
+    };
+    let rpc = rpc.clone();
+    let mut events = tokio::task::spawn_blocking(move || rpc.get_events(&query))
+        .await
+        .map_err(|err| Error::Transport(err.to_string()))??;
+    events.sort_by_key(|event| (event.context.slot, event.context.index_in_slot));
+    for event in events {
+        let position = (event.context.slot, event.context.index_in_slot);
+        // The start slot is inclusive, so skip what was already delivered
+        if cursor.is_some_and(|cursor| position <= cursor) {
+            continue;
+        }
+        if sender.send(Ok(event)).await.is_err() {
+            return Ok(());
+        }
+        *cursor = Some(position);
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+//! Conversion between decimal amounts and base units.
+
+use mrc20_core::math;
+
+use crate::U256;
+
+/// Decimals of MAS amounts, which the node counts in nanoMAS.
+pub const MAS_DECIMALS: u8 = 9;
+
+/// Parse a decimal amount such as `"12.5"` into base units with `decimals` decimals.
+///
+/// Returns `None` for malformed input, more fractional digits than
+/// `decimals`, or a result above `U256::MAX`.
+pub fn parse_units(amount: &str, decimals: u8) -> Option<U256> {
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if (whole.is_empty() && fraction.is_empty()) || fraction.len() > decimals as usize {
+        return None;
+    }
+    let ten = U256::from(10u64);
+    let mut value = U256::ZERO;
+    let padding = decimals as usize - fraction.len();
+    for digit in whole.chars().chain(fraction.chars()).chain(std::iter::repeat_n('0', padding)) {
+        let digit = U256::from(digit.to_digit(10)? as u64);
+        value = math::checked_mul(value, ten)?.checked_add(digit)?;
+    }
+    Some(value)
+}
+
+/// Parse a MAS amount such as `"0.1"` into nanoMAS.
+pub fn parse_mas(amount: &str) -> Option<u64> {
+    math::to_u64(parse_units(amount, MAS_DECIMALS)?)
+}
+
+/// Format nanoMAS as a MAS amount, as the node's API takes them.
+pub fn format_mas(amount: u64) -> String {
+    format_units(U256::from(amount), MAS_DECIMALS)
+}
+
+/// Format base units with `decimals` decimals, e.g. `1500` with 3 decimals as `"1.5"`.
+///
+/// Trailing fractional zeros are dropped, so whole amounts have no decimal point.
+pub fn format_units(value: U256, decimals: u8) -> String {
+    let digits = value.to_string();
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", digits, width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        String::from(whole)
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
@@ -0,0 +1,15 @@
+//! M-of-N thresholds: bridge validator signatures and multisig confirmations.
+//!
+//! A threshold must stay between 1 and the number of members, so it can
+//! always be met and never by nobody. Removing a member is refused when it
+//! would leave fewer members than the threshold.
+
+/// Whether `threshold` is between 1 and `members`.
+pub fn is_valid_threshold(threshold: u32, members: u32) -> bool {
+    threshold > 0 && threshold <= members
+}
+
+/// Whether one of `members` can leave while `threshold` stays reachable.
+pub fn allows_removal(threshold: u32, members: u32) -> bool {
+    members > threshold
+}
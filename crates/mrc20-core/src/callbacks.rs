@@ -0,0 +1,28 @@
+//! Rules of the token operations that call back into another contract:
+//! `transferAndCall` and `flashLoan`.
+//!
+//! `transferAndCall` only sends to smart contract addresses (`AS...`), since a
+//! user address (`AU...`) has no `onTokenReceived` hook to run. A flash loan
+//! mints at most what the cap leaves room for and is repaid with its fee, so
+//! a completed loan burns the fee from the borrower.
+
+use crate::U256;
+
+/// Whether `address` is a smart contract address, which can receive `transferAndCall`.
+///
+/// Massa smart contract addresses start with `AS`, user addresses with `AU`.
+/// The SDK has no call-stack introspection, so the address prefix is the only
+/// way to tell a contract from a wallet.
+pub fn is_contract_address(address: &str) -> bool {
+    address.starts_with("AS")
+}
+
+/// Largest amount a flash loan can mint: what `cap` leaves above `total_supply`, or up to `U256::MAX` without a cap.
+pub fn max_flash_loan(cap: Option<U256>, total_supply: U256) -> U256 {
+    cap.unwrap_or(U256::MAX).saturating_sub(total_supply)
+}
+
+/// Amount burned from the borrower when a loan of `amount` is repaid, or None if it overflows.
+pub fn flash_repayment(amount: U256, fee: U256) -> Option<U256> {
+    amount.checked_add(fee)
+}
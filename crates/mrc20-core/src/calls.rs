@@ -0,0 +1,79 @@
+//! Cross-contract calls with checked return values.
+//!
+//! `abi::call` hands back the callee's raw return bytes. These wrappers decode
+//! the return conventions used across this repository (raw little-endian
+//! integers, single-byte booleans, raw UTF-8 strings) and fail with
+//! `ERR:CALLBACK_FAILED:target=...,function=...:...` when the bytes do not
+//! match, instead of slicing out of bounds or misreading a short value.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use massa_sc_sdk::{abi, Args};
+
+use crate::{errors, U256};
+
+/// Call `function` on `target` without coins and return its raw result.
+pub fn call(target: &str, function: &str, args: Args) -> Vec<u8> {
+    abi::call(target, function, &args.into_bytes(), 0)
+}
+
+/// Call `function` on `target` with `coins` attached and return its raw result.
+pub fn call_with_coins(target: &str, function: &str, args: Args, coins: u64) -> Vec<u8> {
+    abi::call(target, function, &args.into_bytes(), coins)
+}
+
+/// Run `function` from the bytecode at `target` in the calling contract's own
+/// context (its storage, balance and address) and return its raw result.
+pub fn local_call(target: &str, function: &str, args: Args) -> Vec<u8> {
+    abi::local_call(target, function, &args.into_bytes())
+}
+
+fn unexpected(target: &str, function: &str, expected: &str) -> ! {
+    fail!(
+        errors::CALLBACK_FAILED,
+        fields("target={},function={}", target, function),
+        "Expected a {} return value",
+        expected
+    )
+}
+
+/// Decode exactly `N` bytes.
+fn exact<const N: usize>(data: &[u8]) -> Option<[u8; N]> {
+    data.try_into().ok()
+}
+
+/// Call a function returning a raw u256 (32 bytes LE).
+pub fn call_u256(target: &str, function: &str, args: Args) -> U256 {
+    let ret = call(target, function, args);
+    exact(&ret).map(U256::from_le_bytes).unwrap_or_else(|| unexpected(target, function, "u256"))
+}
+
+/// Call a function returning a raw u64 (8 bytes LE).
+pub fn call_u64(target: &str, function: &str, args: Args) -> u64 {
+    let ret = call(target, function, args);
+    exact(&ret).map(u64::from_le_bytes).unwrap_or_else(|| unexpected(target, function, "u64"))
+}
+
+/// Call a function returning a raw u32 (4 bytes LE).
+pub fn call_u32(target: &str, function: &str, args: Args) -> u32 {
+    let ret = call(target, function, args);
+    exact(&ret).map(u32::from_le_bytes).unwrap_or_else(|| unexpected(target, function, "u32"))
+}
+
+/// Call a function returning a single byte, 1 for true and 0 for false.
+pub fn call_bool(target: &str, function: &str, args: Args) -> bool {
+    match call(target, function, args).as_slice() {
+        [1] => true,
+        [0] => false,
+        _ => unexpected(target, function, "bool"),
+    }
+}
+
+/// Call a function returning a raw UTF-8 string.
+pub fn call_string(target: &str, function: &str, args: Args) -> String {
+    let ret = call(target, function, args);
+    match core::str::from_utf8(&ret) {
+        Ok(value) => String::from(value),
+        Err(_) => unexpected(target, function, "string"),
+    }
+}
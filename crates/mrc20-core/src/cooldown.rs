@@ -0,0 +1,10 @@
+//! Per-account cooldowns counted in periods, as the faucet applies between drips.
+
+/// First period at which an account last served at `last` can be served again,
+/// 0 if it never was. Saturates rather than overflowing for huge cooldowns.
+pub fn next_period(last: Option<u64>, cooldown: u64) -> u64 {
+    match last {
+        Some(last) => last.saturating_add(cooldown),
+        None => 0,
+    }
+}
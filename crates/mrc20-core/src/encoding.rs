@@ -0,0 +1,52 @@
+//! Big-endian and hex encodings of U256.
+//!
+//! Massa stores and serializes `U256` little-endian, while EVM tooling and
+//! bridge payloads use big-endian words and `0x`-prefixed hex strings.
+
+use alloc::string::String;
+
+use crate::U256;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// 32-byte big-endian encoding, as in an EVM word.
+pub fn to_be_bytes(value: U256) -> [u8; 32] {
+    let mut bytes = value.to_le_bytes();
+    bytes.reverse();
+    bytes
+}
+
+/// Read a 32-byte big-endian word.
+pub fn from_be_bytes(mut bytes: [u8; 32]) -> U256 {
+    bytes.reverse();
+    U256::from_le_bytes(bytes)
+}
+
+/// Lowercase `0x`-prefixed hex of the big-endian word, always 64 digits.
+pub fn to_hex(value: U256) -> String {
+    let mut hex = String::with_capacity(66);
+    hex.push_str("0x");
+    for byte in to_be_bytes(value) {
+        hex.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        hex.push(HEX_DIGITS[(byte & 0x0f) as usize] as char);
+    }
+    hex
+}
+
+/// Parse big-endian hex, with or without `0x`, in either case and with 1 to 64 digits.
+///
+/// Returns `None` for empty, over-long or non-hex input.
+pub fn from_hex(hex: &str) -> Option<U256> {
+    let digits = hex.strip_prefix("0x").or_else(|| hex.strip_prefix("0X")).unwrap_or(hex);
+    if digits.is_empty() || digits.len() > 64 {
+        return None;
+    }
+
+    // Fill the little-endian bytes from the least significant digit
+    let mut bytes = [0u8; 32];
+    for (index, digit) in digits.bytes().rev().enumerate() {
+        let nibble = (digit as char).to_digit(16)? as u8;
+        bytes[index / 2] |= nibble << (4 * (index % 2));
+    }
+    Some(U256::from_le_bytes(bytes))
+}
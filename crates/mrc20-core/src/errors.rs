@@ -0,0 +1,121 @@
+//! Machine-readable error codes.
+//!
+//! Every failure panics with `ERR:{CODE}:{fields}:{message}`:
+//! - `CODE`: One of the constants below
+//! - `fields`: Comma-separated `key=value` pairs, possibly empty
+//! - `message`: Human-readable text, which may itself contain `:`
+//!
+//! Tooling can split the panic message on the first three `:` to get the parts,
+//! e.g. `ERR:INSUFFICIENT_BALANCE:needed=10,have=5:Transfer failed: insufficient funds`.
+
+use core::fmt::Arguments;
+
+/// A required argument is missing, malformed or out of range.
+pub const INVALID_ARGUMENT: &str = "INVALID_ARGUMENT";
+/// The caller lacks the ownership, role or other right the call requires.
+pub const UNAUTHORIZED: &str = "UNAUTHORIZED";
+/// The account holds fewer tokens than the operation moves.
+pub const INSUFFICIENT_BALANCE: &str = "INSUFFICIENT_BALANCE";
+/// The spender's allowance is lower than the amount spent.
+pub const INSUFFICIENT_ALLOWANCE: &str = "INSUFFICIENT_ALLOWANCE";
+/// The spender's recurring spending limit is exhausted for the current window.
+pub const SPENDING_LIMIT_EXCEEDED: &str = "SPENDING_LIMIT_EXCEEDED";
+/// The operation would touch vested or locked tokens.
+pub const TOKENS_LOCKED: &str = "TOKENS_LOCKED";
+/// The operation would push the total supply above the cap.
+pub const CAP_EXCEEDED: &str = "CAP_EXCEEDED";
+/// An amount computation overflowed or underflowed.
+pub const OVERFLOW: &str = "OVERFLOW";
+/// The token is paused.
+pub const PAUSED: &str = "PAUSED";
+/// The token is not paused.
+pub const NOT_PAUSED: &str = "NOT_PAUSED";
+/// The account is frozen.
+pub const FROZEN: &str = "FROZEN";
+/// The account is not frozen.
+pub const NOT_FROZEN: &str = "NOT_FROZEN";
+/// The account is not allowlisted while allowlist mode is on.
+pub const NOT_ALLOWLISTED: &str = "NOT_ALLOWLISTED";
+/// The contract is permanently disabled.
+pub const DECOMMISSIONED: &str = "DECOMMISSIONED";
+/// The transfer breaks the max transaction, max wallet or cooldown limit.
+pub const TRANSFER_LIMIT: &str = "TRANSFER_LIMIT";
+/// A deadline or validity window has passed.
+pub const EXPIRED: &str = "EXPIRED";
+/// A validity window has not started yet.
+pub const NOT_YET_VALID: &str = "NOT_YET_VALID";
+/// A one-time nonce or confirmation was already used.
+pub const ALREADY_USED: &str = "ALREADY_USED";
+/// A signature or the public key behind it does not match.
+pub const INVALID_SIGNATURE: &str = "INVALID_SIGNATURE";
+/// A merkle proof does not lead to the stored root.
+pub const INVALID_PROOF: &str = "INVALID_PROOF";
+/// The requested item does not exist.
+pub const NOT_FOUND: &str = "NOT_FOUND";
+/// The item to create already exists.
+pub const ALREADY_EXISTS: &str = "ALREADY_EXISTS";
+/// The contract is not in a state that allows the operation.
+pub const INVALID_STATE: &str = "INVALID_STATE";
+/// A callback into another contract did not return the expected value.
+pub const CALLBACK_FAILED: &str = "CALLBACK_FAILED";
+
+/// Panic with `ERR:{code}:{fields}:{message}`.
+pub fn fail(code: &str, fields: Arguments, message: Arguments) -> ! {
+    panic!("ERR:{}:{}:{}", code, fields, message)
+}
+
+/// Fail with an error code, optional `fields(...)` and a formatted message.
+#[macro_export]
+macro_rules! fail {
+    ($code:expr, fields($($field:tt)+), $($message:tt)+) => {
+        $crate::errors::fail($code, format_args!($($field)+), format_args!($($message)+))
+    };
+    ($code:expr, $($message:tt)+) => {
+        $crate::errors::fail($code, format_args!(""), format_args!($($message)+))
+    };
+}
+
+/// Fail like `fail!` unless the condition holds.
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr, $($rest:tt)+) => {
+        if !$cond {
+            $crate::fail!($($rest)+)
+        }
+    };
+}
+
+/// Unwrap helpers that fail with an error code instead of a free-text panic.
+pub trait OrFail<T> {
+    /// Unwrap, or fail with `ERR:{code}::{message}`.
+    fn or_fail(self, code: &str, message: &str) -> T;
+
+    /// Unwrap an argument read, or fail with
+    /// `ERR:INVALID_ARGUMENT:name={name}:{name} argument is missing or invalid`.
+    fn expect_arg(self, name: &str) -> T;
+}
+
+impl<T, E> OrFail<T> for Result<T, E> {
+    fn or_fail(self, code: &str, message: &str) -> T {
+        self.unwrap_or_else(|_| fail!(code, "{}", message))
+    }
+
+    fn expect_arg(self, name: &str) -> T {
+        self.unwrap_or_else(|_| invalid_argument(name))
+    }
+}
+
+impl<T> OrFail<T> for Option<T> {
+    fn or_fail(self, code: &str, message: &str) -> T {
+        self.unwrap_or_else(|| fail!(code, "{}", message))
+    }
+
+    fn expect_arg(self, name: &str) -> T {
+        self.unwrap_or_else(|| invalid_argument(name))
+    }
+}
+
+/// Fail with `ERR:INVALID_ARGUMENT:name={name}:{name} argument is missing or invalid`.
+pub fn invalid_argument(name: &str) -> ! {
+    fail!(INVALID_ARGUMENT, fields("name={}", name), "{} argument is missing or invalid", name)
+}
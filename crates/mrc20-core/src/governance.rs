@@ -0,0 +1,83 @@
+//! Vote counting and proposal states of the governor.
+//!
+//! A proposal is pending until its vote starts, active until its last voting
+//! period, then succeeded or defeated. It succeeds when its for votes exceed
+//! its against votes and its for and abstain votes reach the quorum, a share
+//! of the total supply at the proposal snapshot.
+
+use crate::U256;
+use crate::math::{self, Rounding};
+
+// Vote options
+pub const AGAINST: u8 = 0;
+pub const FOR: u8 = 1;
+pub const ABSTAIN: u8 = 2;
+
+// Proposal states returned by the governor's `proposalState`
+pub const STATE_ACTIVE: u8 = 0;
+pub const STATE_DEFEATED: u8 = 1;
+pub const STATE_SUCCEEDED: u8 = 2;
+pub const STATE_EXECUTED: u8 = 3;
+pub const STATE_PENDING: u8 = 4;
+
+/// Votes cast on a proposal, by option.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Tally {
+    pub for_votes: U256,
+    pub against_votes: U256,
+    pub abstain_votes: U256,
+}
+
+impl Tally {
+    pub const EMPTY: Tally = Tally {
+        for_votes: U256::ZERO,
+        against_votes: U256::ZERO,
+        abstain_votes: U256::ZERO,
+    };
+
+    /// Tally with `weight` added to the `support` option, or None for an
+    /// unknown option or if that option's count overflows.
+    pub fn with_vote(&self, support: u8, weight: U256) -> Option<Tally> {
+        let mut tally = *self;
+        let count = match support {
+            AGAINST => &mut tally.against_votes,
+            FOR => &mut tally.for_votes,
+            ABSTAIN => &mut tally.abstain_votes,
+            _ => return None,
+        };
+        *count = count.checked_add(weight)?;
+        Some(tally)
+    }
+
+    /// Whether the votes pass: more for than against, and for plus abstain reaching `quorum`.
+    pub fn passes(&self, quorum: U256) -> bool {
+        self.for_votes > self.against_votes && self.for_votes.saturating_add(self.abstain_votes) >= quorum
+    }
+}
+
+/// Quorum of a proposal: `quorum_bps` of `supply`, rounded down. None if it overflows.
+pub fn quorum(supply: U256, quorum_bps: u32) -> Option<U256> {
+    math::bps(supply, quorum_bps, Rounding::Floor)
+}
+
+/// State at `period` of a proposal voted from `start_period` to `end_period` inclusive.
+pub fn proposal_state(
+    period: u64,
+    start_period: u64,
+    end_period: u64,
+    executed: bool,
+    tally: &Tally,
+    quorum: U256,
+) -> u8 {
+    if executed {
+        STATE_EXECUTED
+    } else if period < start_period {
+        STATE_PENDING
+    } else if period <= end_period {
+        STATE_ACTIVE
+    } else if tally.passes(quorum) {
+        STATE_SUCCEEDED
+    } else {
+        STATE_DEFEATED
+    }
+}
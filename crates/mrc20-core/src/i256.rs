@@ -0,0 +1,235 @@
+//! Signed 256-bit integer for accounting deltas.
+//!
+//! `I256` is a two's-complement integer over the same 32-byte little-endian
+//! layout as `U256`, so it can be stored and passed in `Args` through
+//! `to_bits` / `from_bits` and `add_u256` / `next_u256`. All arithmetic is
+//! checked and returns `None` on overflow.
+
+use core::cmp::Ordering;
+use core::fmt;
+
+use crate::{math, U256};
+
+/// Number of 64-bit limbs in an I256.
+const LIMBS: usize = 4;
+
+/// Signed 256-bit integer, two's complement.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct I256 {
+    /// Little-endian limbs
+    limbs: [u64; LIMBS],
+}
+
+impl I256 {
+    pub const ZERO: I256 = I256 { limbs: [0; LIMBS] };
+    pub const MIN: I256 = I256 {
+        limbs: [0, 0, 0, 1 << 63],
+    };
+    pub const MAX: I256 = I256 {
+        limbs: [u64::MAX, u64::MAX, u64::MAX, u64::MAX >> 1],
+    };
+
+    /// Read 32 little-endian two's-complement bytes.
+    pub fn from_le_bytes(bytes: [u8; 32]) -> Self {
+        let mut limbs = [0u64; LIMBS];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            let mut limb_bytes = [0u8; 8];
+            limb_bytes.copy_from_slice(chunk);
+            *limb = u64::from_le_bytes(limb_bytes);
+        }
+        I256 { limbs }
+    }
+
+    /// Write 32 little-endian two's-complement bytes.
+    pub fn to_le_bytes(self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(self.limbs.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Reinterpret the bits of a U256, e.g. one read with `next_u256`.
+    pub fn from_bits(bits: U256) -> Self {
+        I256::from_le_bytes(bits.to_le_bytes())
+    }
+
+    /// The raw bits as a U256, e.g. to store them or pass them with `add_u256`.
+    pub fn to_bits(self) -> U256 {
+        U256::from_le_bytes(self.to_le_bytes())
+    }
+
+    /// Sign-extend an i128.
+    pub fn from_i128(value: i128) -> Self {
+        let fill = if value < 0 { u64::MAX } else { 0 };
+        I256 {
+            limbs: [value as u64, (value >> 64) as u64, fill, fill],
+        }
+    }
+
+    /// True if the value is below zero.
+    pub fn is_negative(self) -> bool {
+        self.limbs[LIMBS - 1] >> 63 == 1
+    }
+
+    /// -1, 0 or 1 depending on the sign.
+    pub fn signum(self) -> i8 {
+        if self.is_negative() {
+            -1
+        } else if self == I256::ZERO {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Magnitude as a U256. Unlike `checked_neg`, it does not overflow on `MIN`.
+    pub fn unsigned_abs(self) -> U256 {
+        if self.is_negative() {
+            self.wrapping_neg().to_bits()
+        } else {
+            self.to_bits()
+        }
+    }
+
+    fn wrapping_add(self, other: I256) -> I256 {
+        let mut limbs = [0u64; LIMBS];
+        let mut carry = false;
+        for (slot, (a, b)) in limbs.iter_mut().zip(self.limbs.iter().zip(other.limbs.iter())) {
+            let (sum, carry_a) = a.overflowing_add(*b);
+            let (sum, carry_b) = sum.overflowing_add(carry as u64);
+            *slot = sum;
+            carry = carry_a || carry_b;
+        }
+        I256 { limbs }
+    }
+
+    fn wrapping_neg(self) -> I256 {
+        let mut inverted = self;
+        for limb in inverted.limbs.iter_mut() {
+            *limb = !*limb;
+        }
+        inverted.wrapping_add(I256::from_i128(1))
+    }
+
+    /// `self + other`, or `None` on overflow.
+    pub fn checked_add(self, other: I256) -> Option<I256> {
+        let sum = self.wrapping_add(other);
+        // Overflow iff both operands have the same sign and the sum does not
+        if self.is_negative() == other.is_negative() && sum.is_negative() != self.is_negative() {
+            return None;
+        }
+        Some(sum)
+    }
+
+    /// `self - other`, or `None` on overflow.
+    pub fn checked_sub(self, other: I256) -> Option<I256> {
+        let difference = self.wrapping_add(other.wrapping_neg());
+        // Overflow iff the signs differ and the result's sign is not the minuend's
+        if self.is_negative() != other.is_negative() && difference.is_negative() != self.is_negative() {
+            return None;
+        }
+        Some(difference)
+    }
+
+    /// `-self`, or `None` for `MIN`.
+    pub fn checked_neg(self) -> Option<I256> {
+        if self == I256::MIN {
+            return None;
+        }
+        Some(self.wrapping_neg())
+    }
+
+    /// `self * other`, or `None` on overflow.
+    pub fn checked_mul(self, other: I256) -> Option<I256> {
+        let magnitude = math::checked_mul(self.unsigned_abs(), other.unsigned_abs())?;
+        if self.is_negative() != other.is_negative() {
+            I256::neg_from_magnitude(magnitude)
+        } else {
+            I256::try_from(magnitude).ok()
+        }
+    }
+
+    /// `-magnitude`, or `None` if it is below `MIN`.
+    fn neg_from_magnitude(magnitude: U256) -> Option<I256> {
+        if magnitude > I256::MIN.to_bits() {
+            return None;
+        }
+        Some(I256::from_bits(magnitude).wrapping_neg())
+    }
+
+    /// `value + self`, e.g. applying a delta to a balance.
+    ///
+    /// Returns `None` if the result is negative or does not fit in a U256.
+    pub fn checked_add_to(self, value: U256) -> Option<U256> {
+        if self.is_negative() {
+            value.checked_sub(self.unsigned_abs())
+        } else {
+            value.checked_add(self.to_bits())
+        }
+    }
+}
+
+impl From<i64> for I256 {
+    fn from(value: i64) -> Self {
+        I256::from_i128(value as i128)
+    }
+}
+
+/// Fails if the value is above `I256::MAX`.
+impl TryFrom<U256> for I256 {
+    type Error = ();
+
+    fn try_from(value: U256) -> Result<Self, Self::Error> {
+        let signed = I256::from_bits(value);
+        if signed.is_negative() {
+            return Err(());
+        }
+        Ok(signed)
+    }
+}
+
+/// Fails if the value is negative.
+impl TryFrom<I256> for U256 {
+    type Error = ();
+
+    fn try_from(value: I256) -> Result<Self, Self::Error> {
+        if value.is_negative() {
+            return Err(());
+        }
+        Ok(value.to_bits())
+    }
+}
+
+impl Ord for I256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            // Same sign: two's complement orders like the unsigned bits
+            _ => self.limbs.iter().rev().cmp(other.limbs.iter().rev()),
+        }
+    }
+}
+
+impl PartialOrd for I256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for I256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "-{}", self.unsigned_abs())
+        } else {
+            write!(f, "{}", self.unsigned_abs())
+        }
+    }
+}
+
+impl fmt::Debug for I256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
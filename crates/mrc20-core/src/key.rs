@@ -0,0 +1,87 @@
+//! Storage key building.
+//!
+//! Pure, so the key layouts can be checked host-side; `store` re-exports
+//! `Key` and `StorageKey` for the typed fields.
+
+use alloc::vec::Vec;
+
+/// A key part appended to a map prefix.
+pub trait StorageKey {
+    fn append_to(&self, key: &mut Vec<u8>);
+}
+
+/// Raw string bytes, e.g. an address.
+impl StorageKey for str {
+    fn append_to(&self, key: &mut Vec<u8>) {
+        key.extend_from_slice(self.as_bytes());
+    }
+}
+
+/// 8 bytes little-endian, e.g. an id.
+impl StorageKey for u64 {
+    fn append_to(&self, key: &mut Vec<u8>) {
+        key.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+/// A storage key built from a prefix and typed segments.
+///
+/// `Key::new` writes each segment after its length (u32 LE, as `Args` does for
+/// strings), so `("AB", "C")` and `("A", "BC")` give different keys, and a
+/// segment cannot extend the prefix into a sibling key (`VALIDATOR` + `_COUNT`
+/// vs `VALIDATOR_COUNT`). `Key::raw` concatenates segments as they are, which
+/// is the AS-compatible layout of the token's maps:
+///
+/// ```ignore
+/// let key = Key::new(b"ALLOWANCE").push(owner).push(spender).into_bytes();
+/// ```
+pub struct Key {
+    bytes: Vec<u8>,
+    length_prefixed: bool,
+}
+
+impl Key {
+    /// Key whose segments are length-prefixed and cannot collide.
+    pub fn new(prefix: &[u8]) -> Self {
+        Key {
+            bytes: prefix.to_vec(),
+            length_prefixed: true,
+        }
+    }
+
+    /// Key whose segments are concatenated as they are.
+    pub fn raw(prefix: &[u8]) -> Self {
+        Key {
+            bytes: prefix.to_vec(),
+            length_prefixed: false,
+        }
+    }
+
+    /// Append a segment.
+    pub fn push<K: StorageKey + ?Sized>(mut self, segment: &K) -> Self {
+        if self.length_prefixed {
+            let mut encoded = Vec::new();
+            segment.append_to(&mut encoded);
+            self.bytes.extend_from_slice(&(encoded.len() as u32).to_le_bytes());
+            self.bytes.extend_from_slice(&encoded);
+        } else {
+            segment.append_to(&mut self.bytes);
+        }
+        self
+    }
+
+    /// The segment of a one-segment `Key::new(prefix)` key, `None` if `key`
+    /// has another layout, e.g. a sibling key sharing the raw prefix.
+    pub fn single_segment<'a>(prefix: &[u8], key: &'a [u8]) -> Option<&'a [u8]> {
+        let (length, segment) = key.strip_prefix(prefix)?.split_first_chunk::<4>()?;
+        (u32::from_le_bytes(*length) as usize == segment.len()).then_some(segment)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
@@ -0,0 +1,46 @@
+//! Code shared by the contracts, the client and the host-side tests.
+//!
+//! The error codes, the U256 helpers (`math`, `i256`, `encoding`), storage
+//! key building (`key`) and the datastore cost rules (`storage_cost`) are
+//! pure and build for any target, as are the contracts' accounting rules:
+//! - `rewards`: Staking reward per token
+//! - `governance`: Governor vote tallies and proposal states
+//! - `vesting`: Vesting schedules of the wallet and the factory
+//! - `approvals`: M-of-N thresholds of the bridge and the multisig
+//! - `callbacks`: `transferAndCall` receivers and flash loan limits
+//! - `cooldown`: Per-account cooldowns of the faucet
+//!
+//! The `contract` feature adds the modules that go through the contract SDK:
+//! - `store`: Typed storage fields
+//! - `calls`: Cross-contract calls with checked return values
+//! - `token`: The MRC20 ledger (balances, allowances, supply and their events)
+//!
+//! Contracts import the `fail!` and `ensure!` macros with
+//! `#[macro_use] extern crate mrc20_core;`.
+
+#![no_std]
+
+extern crate alloc;
+
+pub use massa_types::U256;
+
+#[macro_use]
+pub mod errors;
+pub mod approvals;
+pub mod callbacks;
+pub mod cooldown;
+pub mod encoding;
+pub mod governance;
+pub mod i256;
+pub mod key;
+pub mod math;
+pub mod rewards;
+pub mod storage_cost;
+pub mod vesting;
+
+#[cfg(feature = "contract")]
+pub mod calls;
+#[cfg(feature = "contract")]
+pub mod store;
+#[cfg(feature = "contract")]
+pub mod token;
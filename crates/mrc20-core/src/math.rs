@@ -0,0 +1,333 @@
+//! U256 multiplication, division, wrapping arithmetic, basis points and integer conversions.
+//!
+//! The SDK's `U256` only provides checked and saturating addition and
+//! subtraction and comparisons, so products, quotients and wrapping variants
+//! are computed here on little-endian 64-bit limbs.
+//! `U256` is an SDK type, so these are free functions: the orphan rule keeps
+//! this repository from implementing `Mul`, `From<u128>` or `TryFrom` for it.
+//! `mul_div` keeps the full 512-bit product, so `a * b / denominator` is exact
+//! whenever the final result fits in 256 bits, even if `a * b` does not.
+//! `bps` and `percent_of` build on it with an explicit `Rounding` mode.
+//! `isqrt` and `checked_pow` serve AMM share math and bonding curves.
+
+use crate::U256;
+
+/// Number of 64-bit limbs in a U256.
+const LIMBS: usize = 4;
+
+/// Basis points in a whole (100%).
+pub const MAX_BPS: u32 = 10_000;
+
+/// How a division rounds its result.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rounding {
+    /// Toward zero, e.g. for amounts paid out
+    Floor,
+    /// Away from zero, e.g. for fees and amounts owed
+    Ceil,
+    /// To the nearest integer, halves away from zero
+    HalfUp,
+}
+
+fn to_limbs(value: U256) -> [u64; LIMBS] {
+    let bytes = value.to_le_bytes();
+    let mut limbs = [0u64; LIMBS];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        let mut limb_bytes = [0u8; 8];
+        limb_bytes.copy_from_slice(chunk);
+        *limb = u64::from_le_bytes(limb_bytes);
+    }
+    limbs
+}
+
+/// Build a U256 from the low `LIMBS` limbs.
+fn from_limbs(limbs: &[u64]) -> U256 {
+    let mut bytes = [0u8; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs.iter()) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    U256::from_le_bytes(bytes)
+}
+
+fn is_zero(limbs: &[u64]) -> bool {
+    limbs.iter().all(|limb| *limb == 0)
+}
+
+/// Full 512-bit product of two 256-bit values.
+fn full_mul(a: &[u64; LIMBS], b: &[u64; LIMBS]) -> [u64; 2 * LIMBS] {
+    let mut product = [0u64; 2 * LIMBS];
+    for (i, a_limb) in a.iter().enumerate() {
+        let mut carry: u128 = 0;
+        for (j, b_limb) in b.iter().enumerate() {
+            // At most (2^64 - 1)^2 + 2 * (2^64 - 1) = 2^128 - 1, so it never overflows
+            let current = *a_limb as u128 * *b_limb as u128 + product[i + j] as u128 + carry;
+            product[i + j] = current as u64;
+            carry = current >> 64;
+        }
+        product[i + LIMBS] = carry as u64;
+    }
+    product
+}
+
+/// Divide `dividend` by a non-zero `divisor`, returning (quotient, remainder).
+fn div_rem<const N: usize>(dividend: &[u64; N], divisor: &[u64; LIMBS]) -> ([u64; N], [u64; LIMBS]) {
+    let mut quotient = [0u64; N];
+
+    // Single-limb divisor: long division, most significant limb first
+    if is_zero(&divisor[1..]) {
+        let divisor = divisor[0] as u128;
+        let mut remainder: u128 = 0;
+        for (slot, limb) in quotient.iter_mut().zip(dividend.iter()).rev() {
+            let current = (remainder << 64) | *limb as u128;
+            *slot = (current / divisor) as u64;
+            remainder = current % divisor;
+        }
+        return (quotient, [remainder as u64, 0, 0, 0]);
+    }
+
+    // Shift-subtract, one bit at a time. The remainder stays below the divisor,
+    // so one extra limb is enough to hold it once shifted.
+    let mut remainder = [0u64; LIMBS + 1];
+    let top_bit = match dividend.iter().rposition(|limb| *limb != 0) {
+        Some(index) => index * 64 + 64 - dividend[index].leading_zeros() as usize,
+        None => return (quotient, [0u64; LIMBS]),
+    };
+    for bit in (0..top_bit).rev() {
+        let mut carry = (dividend[bit / 64] >> (bit % 64)) & 1;
+        for limb in remainder.iter_mut() {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+
+        if !is_below(&remainder, divisor) {
+            let mut borrow = false;
+            for (i, limb) in remainder.iter_mut().enumerate() {
+                let subtrahend = divisor.get(i).copied().unwrap_or(0);
+                let (diff, borrow_a) = limb.overflowing_sub(subtrahend);
+                let (diff, borrow_b) = diff.overflowing_sub(borrow as u64);
+                *limb = diff;
+                borrow = borrow_a || borrow_b;
+            }
+            quotient[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    let mut low = [0u64; LIMBS];
+    low.copy_from_slice(&remainder[..LIMBS]);
+    (quotient, low)
+}
+
+/// `value < divisor`, with `value` one limb wider than `divisor`.
+fn is_below(value: &[u64; LIMBS + 1], divisor: &[u64; LIMBS]) -> bool {
+    if value[LIMBS] != 0 {
+        return false;
+    }
+    for (limb, divisor_limb) in value[..LIMBS].iter().zip(divisor.iter()).rev() {
+        if limb != divisor_limb {
+            return limb < divisor_limb;
+        }
+    }
+    false
+}
+
+/// `a + b` modulo 2^256, and whether it overflowed.
+pub fn overflowing_add(a: U256, b: U256) -> (U256, bool) {
+    let (a, b) = (to_limbs(a), to_limbs(b));
+    let mut sum = [0u64; LIMBS];
+    let mut carry = false;
+    for (slot, (a_limb, b_limb)) in sum.iter_mut().zip(a.iter().zip(b.iter())) {
+        let (limb, carry_a) = a_limb.overflowing_add(*b_limb);
+        let (limb, carry_b) = limb.overflowing_add(carry as u64);
+        *slot = limb;
+        carry = carry_a || carry_b;
+    }
+    (from_limbs(&sum), carry)
+}
+
+/// `a - b` modulo 2^256, and whether it underflowed.
+pub fn overflowing_sub(a: U256, b: U256) -> (U256, bool) {
+    let (a, b) = (to_limbs(a), to_limbs(b));
+    let mut difference = [0u64; LIMBS];
+    let mut borrow = false;
+    for (slot, (a_limb, b_limb)) in difference.iter_mut().zip(a.iter().zip(b.iter())) {
+        let (limb, borrow_a) = a_limb.overflowing_sub(*b_limb);
+        let (limb, borrow_b) = limb.overflowing_sub(borrow as u64);
+        *slot = limb;
+        borrow = borrow_a || borrow_b;
+    }
+    (from_limbs(&difference), borrow)
+}
+
+/// `a * b` modulo 2^256, and whether it overflowed.
+pub fn overflowing_mul(a: U256, b: U256) -> (U256, bool) {
+    let product = full_mul(&to_limbs(a), &to_limbs(b));
+    (from_limbs(&product[..LIMBS]), !is_zero(&product[LIMBS..]))
+}
+
+/// `a + b` modulo 2^256.
+pub fn wrapping_add(a: U256, b: U256) -> U256 {
+    overflowing_add(a, b).0
+}
+
+/// `a - b` modulo 2^256.
+pub fn wrapping_sub(a: U256, b: U256) -> U256 {
+    overflowing_sub(a, b).0
+}
+
+/// `a * b` modulo 2^256.
+pub fn wrapping_mul(a: U256, b: U256) -> U256 {
+    overflowing_mul(a, b).0
+}
+
+/// `a * b`, or `None` on overflow.
+pub fn checked_mul(a: U256, b: U256) -> Option<U256> {
+    match overflowing_mul(a, b) {
+        (product, false) => Some(product),
+        (_, true) => None,
+    }
+}
+
+/// `a * b`, or the largest U256 on overflow.
+pub fn saturating_mul(a: U256, b: U256) -> U256 {
+    checked_mul(a, b).unwrap_or(U256::MAX)
+}
+
+/// `a / b` rounded down, or `None` if `b` is zero.
+pub fn checked_div(a: U256, b: U256) -> Option<U256> {
+    let divisor = to_limbs(b);
+    if is_zero(&divisor) {
+        return None;
+    }
+    let (quotient, _) = div_rem(&to_limbs(a), &divisor);
+    Some(from_limbs(&quotient))
+}
+
+/// `a % b`, or `None` if `b` is zero.
+pub fn checked_rem(a: U256, b: U256) -> Option<U256> {
+    let divisor = to_limbs(b);
+    if is_zero(&divisor) {
+        return None;
+    }
+    let (_, remainder) = div_rem(&to_limbs(a), &divisor);
+    Some(from_limbs(&remainder))
+}
+
+/// `a * b / denominator` rounded down, computed on the full 512-bit product.
+///
+/// Returns `None` if `denominator` is zero or the result does not fit in 256 bits.
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    mul_div_rounded(a, b, denominator, Rounding::Floor)
+}
+
+/// `a * b / denominator` with the given rounding, computed on the full 512-bit product.
+///
+/// Returns `None` if `denominator` is zero or the rounded result does not fit in 256 bits.
+pub fn mul_div_rounded(a: U256, b: U256, denominator: U256, rounding: Rounding) -> Option<U256> {
+    let divisor = to_limbs(denominator);
+    if is_zero(&divisor) {
+        return None;
+    }
+    let (quotient, remainder) = div_rem(&full_mul(&to_limbs(a), &to_limbs(b)), &divisor);
+    if !is_zero(&quotient[LIMBS..]) {
+        return None;
+    }
+    let quotient = from_limbs(&quotient[..LIMBS]);
+    let remainder = from_limbs(&remainder);
+
+    let round_up = match rounding {
+        Rounding::Floor => false,
+        Rounding::Ceil => remainder != U256::ZERO,
+        // remainder >= denominator / 2, without overflowing 2 * remainder
+        Rounding::HalfUp => remainder >= denominator - remainder,
+    };
+    if round_up {
+        quotient.checked_add(U256::from(1u64))
+    } else {
+        Some(quotient)
+    }
+}
+
+/// `basis_points / 10_000` of `amount`, e.g. a fee rate.
+///
+/// Returns `None` if the result does not fit in 256 bits, only possible above `MAX_BPS`.
+pub fn bps(amount: U256, basis_points: u32, rounding: Rounding) -> Option<U256> {
+    mul_div_rounded(amount, U256::from(basis_points), U256::from(MAX_BPS), rounding)
+}
+
+/// `percent / 100` of `amount`.
+///
+/// Returns `None` if the result does not fit in 256 bits, only possible above 100 percent.
+pub fn percent_of(amount: U256, percent: u32, rounding: Rounding) -> Option<U256> {
+    mul_div_rounded(amount, U256::from(percent), U256::from(100u64), rounding)
+}
+
+/// `base` raised to `exp`, or `None` on overflow. `0^0` is 1.
+pub fn checked_pow(base: U256, exp: u32) -> Option<U256> {
+    let mut result = U256::from(1u64);
+    let mut base = base;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = checked_mul(result, base)?;
+        }
+        exp >>= 1;
+        // Skip the last squaring, which could overflow without being used
+        if exp > 0 {
+            base = checked_mul(base, base)?;
+        }
+    }
+    Some(result)
+}
+
+/// Integer square root: the largest `r` with `r * r <= value`.
+pub fn isqrt(value: U256) -> U256 {
+    let limbs = to_limbs(value);
+    let bits = match limbs.iter().rposition(|limb| *limb != 0) {
+        Some(index) => index * 64 + 64 - limbs[index].leading_zeros() as usize,
+        None => return U256::ZERO,
+    };
+
+    // Newton's method from 2^ceil(bits / 2), which is at least the root, decreases to it
+    let shift = bits.div_ceil(2);
+    let mut start = [0u64; LIMBS];
+    start[shift / 64] = 1 << (shift % 64);
+    let mut root = from_limbs(&start);
+    loop {
+        // root >= sqrt(value), so value / root <= root <= 2^128 and the sum cannot wrap
+        let next = div_nonzero(wrapping_add(root, div_nonzero(value, root)), U256::from(2u64));
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+/// `a / b` for a `b` known to be non-zero.
+fn div_nonzero(a: U256, b: U256) -> U256 {
+    let (quotient, _) = div_rem(&to_limbs(a), &to_limbs(b));
+    from_limbs(&quotient)
+}
+
+/// Convert a u128 to a U256.
+pub fn from_u128(value: u128) -> U256 {
+    from_limbs(&[value as u64, (value >> 64) as u64])
+}
+
+/// `value` as a u64, or `None` if it does not fit.
+pub fn to_u64(value: U256) -> Option<u64> {
+    let limbs = to_limbs(value);
+    if !is_zero(&limbs[1..]) {
+        return None;
+    }
+    Some(limbs[0])
+}
+
+/// `value` as a u128, or `None` if it does not fit.
+pub fn to_u128(value: U256) -> Option<u128> {
+    let limbs = to_limbs(value);
+    if !is_zero(&limbs[2..]) {
+        return None;
+    }
+    Some((limbs[0] as u128) | ((limbs[1] as u128) << 64))
+}
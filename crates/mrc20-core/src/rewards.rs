@@ -0,0 +1,52 @@
+//! Reward-per-token accounting for staking rewards paid at a fixed rate.
+//!
+//! Each period distributes `rate` reward units among the stakers, pro rata to
+//! their stake, until the reserve funding them runs out. The pool keeps a
+//! running reward per staked unit, scaled by `PRECISION`; an account's
+//! rewards are its stake times the growth of that figure since it last
+//! settled. Periods with no stake distribute nothing and keep the reserve.
+//! The functions take plain values so the contract and host-side tests
+//! share the same arithmetic.
+
+use crate::U256;
+use crate::math;
+
+/// Scale of the reward per token (1e18).
+pub const PRECISION: u64 = 1_000_000_000_000_000_000;
+
+/// Reward state shared by all stakers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RewardPool {
+    /// Rewards earned per staked unit since the start, scaled by `PRECISION`
+    pub reward_per_token: U256,
+    /// Rewards still available for distribution
+    pub reserve: U256,
+    /// Rewards distributed per period
+    pub rate: U256,
+    /// Period up to which rewards have been distributed
+    pub last_update: u64,
+    /// Sum of all stakes
+    pub total_staked: U256,
+}
+
+impl RewardPool {
+    /// Reward per token and reserve after distributing the rewards due up to
+    /// `period`, or None if the reward per token overflows.
+    pub fn accrued(&self, period: u64) -> Option<(U256, U256)> {
+        if period <= self.last_update || self.total_staked == U256::ZERO {
+            return Some((self.reward_per_token, self.reserve));
+        }
+        let due = math::saturating_mul(self.rate, U256::from(period - self.last_update));
+        let distributed = if due < self.reserve { due } else { self.reserve };
+        let increment = math::mul_div(distributed, U256::from(PRECISION), self.total_staked)?;
+        Some((self.reward_per_token.saturating_add(increment), self.reserve - distributed))
+    }
+}
+
+/// Rewards of an account holding `stake` at `reward_per_token`, given the
+/// reward per token it last settled at (`paid`) and the rewards stored then.
+/// None if the pending rewards overflow.
+pub fn earned(stake: U256, reward_per_token: U256, paid: U256, stored: U256) -> Option<U256> {
+    let pending = math::mul_div(stake, reward_per_token.saturating_sub(paid), U256::from(PRECISION))?;
+    Some(stored.saturating_add(pending))
+}
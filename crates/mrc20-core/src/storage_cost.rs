@@ -0,0 +1,27 @@
+//! Coin cost of datastore entries.
+//!
+//! Massa locks `STORAGE_BYTE_COST` nanoMAS per byte of a datastore entry
+//! (key, value and a fixed per-entry overhead) from the balance of the
+//! address that owns it, and releases it when the entry is deleted. A
+//! contract that holds coins for its users, like wMAS, has to charge the
+//! cost of the entries it creates for them, or its balance falls below what
+//! it owes.
+
+/// nanoMAS locked per datastore byte (0.0001 MAS).
+pub const STORAGE_BYTE_COST: u64 = 100_000;
+
+/// Bytes a datastore entry costs on top of its key and value.
+pub const ENTRY_BASE_SIZE: u64 = 4;
+
+/// Size of a U256 value in the datastore.
+pub const U256_VALUE_SIZE: usize = 32;
+
+/// Cost in nanoMAS of a new entry with a key of `key_len` bytes and a value of `value_len` bytes.
+pub const fn entry_cost(key_len: usize, value_len: usize) -> u64 {
+    (ENTRY_BASE_SIZE + key_len as u64 + value_len as u64) * STORAGE_BYTE_COST
+}
+
+/// Cost of a new U256 entry under `prefix` followed by `key_part`, e.g. a balance.
+pub fn u256_entry_cost(prefix: &[u8], key_part: &str) -> u64 {
+    entry_cost(prefix.len() + key_part.len(), U256_VALUE_SIZE)
+}
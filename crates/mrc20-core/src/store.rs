@@ -0,0 +1,317 @@
+//! Typed storage fields.
+//!
+//! `StorageCell`, `StorageMap` and `StorageDoubleMap` wrap `storage` with the
+//! key prefixing and value encoding the contracts otherwise hand-roll, so a
+//! storage field is declared once as a constant next to its key:
+//!
+//! ```ignore
+//! const NONCES: StorageMap<str, u64> = StorageMap::new(b"NONCE");
+//! let nonce = NONCES.get_or(owner, 0);
+//! ```
+//!
+//! Map keys are built with `Key::new`: the prefix followed by each key part
+//! (strings as raw bytes, integers little-endian) after its length.
+//! `StorageMap::raw` and `StorageDoubleMap::raw` concatenate the parts as
+//! they are instead, which is the AS-compatible layout of `BALANCE` and
+//! `ALLOWANCE`. Reads return `None` for missing or malformed values.
+//!
+//! The SDK's `storage` module has no such wrappers yet; this module is written
+//! so it can move there unchanged.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use massa_sc_sdk::storage;
+
+pub use crate::key::{Key, StorageKey};
+use crate::U256;
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+/// A value that can be stored under a key.
+pub trait StorageValue: Sized {
+    fn to_storage(&self) -> Vec<u8>;
+
+    /// Decode stored bytes, `None` if they are malformed.
+    fn from_storage(data: &[u8]) -> Option<Self>;
+}
+
+/// Read the first `N` bytes, ignoring any trailing data like the contracts always did.
+fn fixed<const N: usize>(data: &[u8]) -> Option<[u8; N]> {
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(data.get(..N)?);
+    Some(bytes)
+}
+
+impl StorageValue for U256 {
+    fn to_storage(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        fixed(data).map(U256::from_le_bytes)
+    }
+}
+
+impl StorageValue for u64 {
+    fn to_storage(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        fixed(data).map(u64::from_le_bytes)
+    }
+}
+
+impl StorageValue for u32 {
+    fn to_storage(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        fixed(data).map(u32::from_le_bytes)
+    }
+}
+
+impl StorageValue for u8 {
+    fn to_storage(&self) -> Vec<u8> {
+        alloc::vec![*self]
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        data.first().copied()
+    }
+}
+
+/// Stored as a single byte, 1 for true.
+impl StorageValue for bool {
+    fn to_storage(&self) -> Vec<u8> {
+        alloc::vec![*self as u8]
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        data.first().map(|byte| *byte == 1)
+    }
+}
+
+/// Stored as raw UTF-8 bytes.
+impl StorageValue for String {
+    fn to_storage(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_storage(data: &[u8]) -> Option<Self> {
+        core::str::from_utf8(data).ok().map(String::from)
+    }
+}
+
+/// The raw value under `key`, `None` if missing.
+///
+/// This costs a `has` and a `get`: the SDK has no single-call optional read
+/// and `get` aborts on a missing key. Contracts read through here so that is
+/// the only place to change once the SDK gains one.
+pub fn get_opt(key: &[u8]) -> Option<Vec<u8>> {
+    if !storage::has(key) {
+        return None;
+    }
+    Some(storage::get(key))
+}
+
+/// The value under `key`, `None` if missing or malformed.
+pub fn read<T: StorageValue>(key: &[u8]) -> Option<T> {
+    get_opt(key).and_then(|data| T::from_storage(&data))
+}
+
+fn key_builder(prefix: &[u8], raw: bool) -> Key {
+    if raw {
+        Key::raw(prefix)
+    } else {
+        Key::new(prefix)
+    }
+}
+
+fn remove(key: &[u8]) {
+    if storage::has(key) {
+        storage::delete(key);
+    }
+}
+
+/// A page of the segments of the one-segment `Key::new(prefix)` keys, in the
+/// order the datastore lists them.
+///
+/// Skips the first `offset` segments and returns at most `limit`. Keys under
+/// the same raw prefix with another layout, like `VALIDATOR_COUNT` next to
+/// `Key::new(b"VALIDATOR")` keys, are left out before paging.
+pub fn segments_with_prefix(prefix: &[u8], offset: usize, limit: usize) -> Vec<Vec<u8>> {
+    storage::get_keys(prefix)
+        .iter()
+        .filter_map(|key| Key::single_segment(prefix, key))
+        .skip(offset)
+        .take(limit)
+        .map(<[u8]>::to_vec)
+        .collect()
+}
+
+// ============================================================================
+// Fields
+// ============================================================================
+
+/// A single value stored under a fixed key.
+pub struct StorageCell<T> {
+    key: &'static [u8],
+    value: PhantomData<fn() -> T>,
+}
+
+impl<T> StorageCell<T> {
+    pub const fn new(key: &'static [u8]) -> Self {
+        StorageCell {
+            key,
+            value: PhantomData,
+        }
+    }
+
+    pub fn key(&self) -> &'static [u8] {
+        self.key
+    }
+
+    pub fn exists(&self) -> bool {
+        storage::has(self.key)
+    }
+
+    /// Delete the key, if present.
+    pub fn remove(&self) {
+        remove(self.key);
+    }
+}
+
+impl<T: StorageValue> StorageCell<T> {
+    /// The stored value, `None` if missing or malformed.
+    pub fn get(&self) -> Option<T> {
+        read(self.key)
+    }
+
+    /// The stored value, `default` if missing or malformed.
+    pub fn get_or(&self, default: T) -> T {
+        self.get().unwrap_or(default)
+    }
+
+    pub fn set(&self, value: &T) {
+        storage::set(self.key, &value.to_storage());
+    }
+}
+
+/// Values stored under a prefix followed by one key part.
+pub struct StorageMap<K: ?Sized, V> {
+    prefix: &'static [u8],
+    raw: bool,
+    entries: PhantomData<fn(&K) -> V>,
+}
+
+impl<K: ?Sized, V> StorageMap<K, V> {
+    /// Map whose key part is length-prefixed, as `Key::new` builds it.
+    pub const fn new(prefix: &'static [u8]) -> Self {
+        StorageMap {
+            prefix,
+            raw: false,
+            entries: PhantomData,
+        }
+    }
+
+    /// Map whose key part is appended as it is, as `Key::raw` builds it.
+    pub const fn raw(prefix: &'static [u8]) -> Self {
+        StorageMap {
+            prefix,
+            raw: true,
+            entries: PhantomData,
+        }
+    }
+}
+
+impl<K: StorageKey + ?Sized, V: StorageValue> StorageMap<K, V> {
+    /// Build the full key: prefix + key.
+    pub fn key(&self, key: &K) -> Vec<u8> {
+        key_builder(self.prefix, self.raw).push(key).into_bytes()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        storage::has(&self.key(key))
+    }
+
+    /// The stored value, `None` if missing or malformed.
+    pub fn get(&self, key: &K) -> Option<V> {
+        read(&self.key(key))
+    }
+
+    /// The stored value, `default` if missing or malformed.
+    pub fn get_or(&self, key: &K, default: V) -> V {
+        self.get(key).unwrap_or(default)
+    }
+
+    pub fn set(&self, key: &K, value: &V) {
+        storage::set(&self.key(key), &value.to_storage());
+    }
+
+    /// Delete the entry, if present.
+    pub fn remove(&self, key: &K) {
+        remove(&self.key(key));
+    }
+}
+
+/// Values stored under a prefix followed by two key parts, e.g. owner and spender.
+pub struct StorageDoubleMap<K1: ?Sized, K2: ?Sized, V> {
+    prefix: &'static [u8],
+    raw: bool,
+    entries: PhantomData<fn(&K1, &K2) -> V>,
+}
+
+impl<K1: ?Sized, K2: ?Sized, V> StorageDoubleMap<K1, K2, V> {
+    /// Map whose key parts are length-prefixed, as `Key::new` builds them.
+    pub const fn new(prefix: &'static [u8]) -> Self {
+        StorageDoubleMap {
+            prefix,
+            raw: false,
+            entries: PhantomData,
+        }
+    }
+
+    /// Map whose key parts are concatenated as they are, as `Key::raw` builds them.
+    pub const fn raw(prefix: &'static [u8]) -> Self {
+        StorageDoubleMap {
+            prefix,
+            raw: true,
+            entries: PhantomData,
+        }
+    }
+}
+
+impl<K1: StorageKey + ?Sized, K2: StorageKey + ?Sized, V: StorageValue> StorageDoubleMap<K1, K2, V> {
+    /// Build the full key: prefix + first key + second key.
+    pub fn key(&self, first: &K1, second: &K2) -> Vec<u8> {
+        key_builder(self.prefix, self.raw).push(first).push(second).into_bytes()
+    }
+
+    pub fn contains(&self, first: &K1, second: &K2) -> bool {
+        storage::has(&self.key(first, second))
+    }
+
+    /// The stored value, `None` if missing or malformed.
+    pub fn get(&self, first: &K1, second: &K2) -> Option<V> {
+        read(&self.key(first, second))
+    }
+
+    /// The stored value, `default` if missing or malformed.
+    pub fn get_or(&self, first: &K1, second: &K2, default: V) -> V {
+        self.get(first, second).unwrap_or(default)
+    }
+
+    pub fn set(&self, first: &K1, second: &K2, value: &V) {
+        storage::set(&self.key(first, second), &value.to_storage());
+    }
+
+    /// Delete the entry, if present.
+    pub fn remove(&self, first: &K1, second: &K2) {
+        remove(&self.key(first, second));
+    }
+}
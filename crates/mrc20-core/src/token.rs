@@ -0,0 +1,225 @@
+//! MRC20 ledger shared by the token contracts.
+//!
+//! Balances, allowances and the total supply under the AssemblyScript token's
+//! keys, with the transfer, mint and burn rules and events every MRC20 in this
+//! repository follows. A contract layers its own rules (pause, freeze, caps,
+//! locks) and bookkeeping (checkpoints, holder lists) on top through `Hooks`;
+//! `NoHooks` gives the plain token.
+//!
+//! Zero balances and allowances are stored as missing keys, so emptied
+//! accounts free their storage.
+
+use alloc::string::String;
+
+use crate::errors::{self, OrFail};
+use crate::storage_cost;
+use crate::store::{StorageCell, StorageDoubleMap, StorageMap};
+use crate::U256;
+use massa_sc_sdk::abi;
+
+// ============================================================================
+// Storage Keys
+// ============================================================================
+
+pub const NAME_KEY: &[u8] = b"NAME";
+pub const SYMBOL_KEY: &[u8] = b"SYMBOL";
+pub const DECIMALS_KEY: &[u8] = b"DECIMALS";
+pub const TOTAL_SUPPLY_KEY: &[u8] = b"TOTAL_SUPPLY";
+pub const BALANCE_KEY_PREFIX: &[u8] = b"BALANCE";
+pub const ALLOWANCE_KEY_PREFIX: &[u8] = b"ALLOWANCE";
+
+pub const TOTAL_SUPPLY: StorageCell<U256> = StorageCell::new(TOTAL_SUPPLY_KEY);
+// AS-compatible raw keys: `BALANCE{address}` and `ALLOWANCE{owner}{spender}`
+pub const BALANCES: StorageMap<str, U256> = StorageMap::raw(BALANCE_KEY_PREFIX);
+pub const ALLOWANCES: StorageDoubleMap<str, str, U256> = StorageDoubleMap::raw(ALLOWANCE_KEY_PREFIX);
+
+// ============================================================================
+// Events
+// ============================================================================
+//
+// Payloads keep the AS event name as prefix and append `key=value` fields, so
+// prefix matching keeps working while indexers can read the details.
+
+pub const TRANSFER_EVENT: &str = "TRANSFER SUCCESS";
+pub const APPROVAL_EVENT: &str = "APPROVAL SUCCESS";
+pub const MINT_EVENT: &str = "MINT SUCCESS";
+pub const BURN_EVENT: &str = "BURN_SUCCESS";
+
+/// "TRANSFER SUCCESS:from={from},to={to},amount={amount}"
+pub fn transfer_event(from: &str, to: &str, amount: U256) -> String {
+    alloc::format!("{}:from={},to={},amount={}", TRANSFER_EVENT, from, to, amount)
+}
+
+/// "APPROVAL SUCCESS:owner={owner},spender={spender},amount={allowance}"
+pub fn approval_event(owner: &str, spender: &str, allowance: U256) -> String {
+    alloc::format!("{}:owner={},spender={},amount={}", APPROVAL_EVENT, owner, spender, allowance)
+}
+
+/// "MINT SUCCESS:to={to},amount={amount}"
+pub fn mint_event(to: &str, amount: U256) -> String {
+    alloc::format!("{}:to={},amount={}", MINT_EVENT, to, amount)
+}
+
+/// "BURN_SUCCESS:from={from},amount={amount}"
+pub fn burn_event(from: &str, amount: U256) -> String {
+    alloc::format!("{}:from={},amount={}", BURN_EVENT, from, amount)
+}
+
+// ============================================================================
+// Hooks
+// ============================================================================
+
+/// Contract-specific checks and bookkeeping run by the ledger operations.
+///
+/// Checks fail the call to reject the operation. Every method defaults to doing nothing.
+pub trait Hooks {
+    /// Checks on both ends of a transfer, before the balance is checked.
+    fn before_transfer(&self, _from: &str, _to: &str) {}
+
+    /// Checks on a transfer once the new balances are known, e.g. locks or wallet limits.
+    fn check_transfer(&self, _from: &str, _to: &str, _amount: U256, _new_from_balance: U256, _new_to_balance: U256) {}
+
+    /// Checks on a mint once the new supply is known, e.g. a cap.
+    fn check_mint(&self, _to: &str, _new_supply: U256) {}
+
+    /// Checks on a burn once the new balance is known, e.g. locks.
+    fn check_burn(&self, _from: &str, _new_balance: U256) {}
+
+    /// Called before a balance is written.
+    fn balance_changed(&self, _address: &str, _previous: U256, _new: U256) {}
+
+    /// Called before the total supply is written.
+    fn supply_changed(&self, _previous: U256, _new: U256) {}
+
+    /// Called before an allowance is written.
+    fn allowance_changed(&self, _owner: &str, _spender: &str, _previous: U256, _new: U256) {}
+}
+
+/// The plain ledger, without extra rules or bookkeeping.
+pub struct NoHooks;
+
+impl Hooks for NoHooks {}
+
+// ============================================================================
+// Ledger
+// ============================================================================
+
+pub fn balance_of(address: &str) -> U256 {
+    BALANCES.get_or(address, U256::ZERO)
+}
+
+/// Storage cost in nanoMAS of crediting `address`: the price of a new
+/// balance entry if it holds nothing yet, zero otherwise.
+pub fn balance_entry_cost(address: &str) -> u64 {
+    if balance_of(address) == U256::ZERO {
+        storage_cost::u256_entry_cost(BALANCE_KEY_PREFIX, address)
+    } else {
+        0
+    }
+}
+
+pub fn set_balance(hooks: &impl Hooks, address: &str, amount: U256) {
+    hooks.balance_changed(address, balance_of(address), amount);
+    if amount == U256::ZERO {
+        BALANCES.remove(address);
+    } else {
+        BALANCES.set(address, &amount);
+    }
+}
+
+pub fn total_supply() -> U256 {
+    TOTAL_SUPPLY.get_or(U256::ZERO)
+}
+
+pub fn set_total_supply(hooks: &impl Hooks, amount: U256) {
+    hooks.supply_changed(total_supply(), amount);
+    TOTAL_SUPPLY.set(&amount);
+}
+
+pub fn allowance(owner: &str, spender: &str) -> U256 {
+    ALLOWANCES.get_or(owner, spender, U256::ZERO)
+}
+
+pub fn set_allowance(hooks: &impl Hooks, owner: &str, spender: &str, amount: U256) {
+    hooks.allowance_changed(owner, spender, allowance(owner, spender), amount);
+    if amount == U256::ZERO {
+        ALLOWANCES.remove(owner, spender);
+    } else {
+        ALLOWANCES.set(owner, spender, &amount);
+    }
+}
+
+/// Move `amount` from `from` to `to`.
+///
+/// As in ERC20, a self-transfer succeeds without changing any balance once
+/// the sender is shown to hold `amount`. Emits no event, since callers word
+/// theirs differently (transfers, escrows, payments).
+pub fn move_balance(hooks: &impl Hooks, from: &str, to: &str, amount: U256) {
+    hooks.before_transfer(from, to);
+
+    let from_balance = balance_of(from);
+    ensure!(
+        from_balance >= amount,
+        errors::INSUFFICIENT_BALANCE,
+        fields("needed={},have={}", amount, from_balance),
+        "Transfer failed: insufficient funds"
+    );
+    if from == to {
+        return;
+    }
+
+    let new_to_balance = balance_of(to).checked_add(amount).or_fail(errors::OVERFLOW, "Transfer failed: overflow");
+    let new_from_balance = from_balance - amount;
+    hooks.check_transfer(from, to, amount, new_from_balance, new_to_balance);
+
+    set_balance(hooks, from, new_from_balance);
+    set_balance(hooks, to, new_to_balance);
+}
+
+/// Consume `amount` of the allowance `owner` gave `spender`. `operation` names the call in the error.
+pub fn spend_allowance(hooks: &impl Hooks, owner: &str, spender: &str, amount: U256, operation: &str) {
+    let spender_allowance = allowance(owner, spender);
+    ensure!(
+        spender_allowance >= amount,
+        errors::INSUFFICIENT_ALLOWANCE,
+        fields("needed={},have={}", amount, spender_allowance),
+        "{} failed: insufficient allowance",
+        operation
+    );
+    set_allowance(hooks, owner, spender, spender_allowance - amount);
+}
+
+/// Create `amount` tokens for `to` and emit `MINT SUCCESS`.
+pub fn mint(hooks: &impl Hooks, to: &str, amount: U256) {
+    let new_supply =
+        total_supply().checked_add(amount).or_fail(errors::OVERFLOW, "Requested mint amount causes an overflow");
+    hooks.check_mint(to, new_supply);
+    let new_balance =
+        balance_of(to).checked_add(amount).or_fail(errors::OVERFLOW, "Requested mint amount causes an overflow");
+
+    set_total_supply(hooks, new_supply);
+    set_balance(hooks, to, new_balance);
+
+    abi::generate_event(&mint_event(to, amount));
+}
+
+/// Destroy `amount` tokens of `from` and emit `BURN_SUCCESS`.
+pub fn burn(hooks: &impl Hooks, from: &str, amount: U256) {
+    let balance = balance_of(from);
+    ensure!(
+        balance >= amount,
+        errors::INSUFFICIENT_BALANCE,
+        fields("needed={},have={}", amount, balance),
+        "Burn failed: insufficient funds"
+    );
+    let new_balance = balance - amount;
+    hooks.check_burn(from, new_balance);
+    let new_supply = total_supply()
+        .checked_sub(amount)
+        .or_fail(errors::OVERFLOW, "Requested burn amount causes an underflow of the total supply");
+
+    set_total_supply(hooks, new_supply);
+    set_balance(hooks, from, new_balance);
+
+    abi::generate_event(&burn_event(from, amount));
+}
@@ -0,0 +1,55 @@
+//! Vesting schedules shared by the vesting wallet and the vesting factory.
+//!
+//! Nothing vests before `start + cliff`; from there the vested amount grows
+//! linearly from what has accrued since `start` until `start + duration`,
+//! when the whole grant is vested. The factory validates a schedule with the
+//! same rules the wallet constructor enforces, so a creation cannot fail
+//! halfway through.
+
+use crate::U256;
+use crate::math;
+
+/// Timing of a grant, in periods.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Schedule {
+    /// Period at which vesting starts
+    pub start: u64,
+    /// Periods after `start` before anything vests
+    pub cliff: u64,
+    /// Periods after `start` until fully vested
+    pub duration: u64,
+}
+
+/// Why a schedule is rejected.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScheduleError {
+    /// The duration is 0
+    ZeroDuration,
+    /// The cliff ends after the duration
+    CliffAfterDuration,
+}
+
+impl Schedule {
+    /// Check the duration is non-zero and at least the cliff.
+    pub fn validate(&self) -> Result<(), ScheduleError> {
+        if self.duration == 0 {
+            Err(ScheduleError::ZeroDuration)
+        } else if self.cliff > self.duration {
+            Err(ScheduleError::CliffAfterDuration)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Part of `amount` vested at `period`.
+    pub fn vested(&self, amount: U256, period: u64) -> U256 {
+        if period < self.start.saturating_add(self.cliff) {
+            U256::ZERO
+        } else if period >= self.start.saturating_add(self.duration) {
+            amount
+        } else {
+            // The elapsed periods are below the duration, so the share is below `amount`
+            math::mul_div(amount, U256::from(period - self.start), U256::from(self.duration)).unwrap_or(amount)
+        }
+    }
+}
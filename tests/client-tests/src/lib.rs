@@ -0,0 +1,470 @@
+//! Host-side tests of the off-chain client
+//!
+//! Known-answer tests pin the bytes the node expects: the key, address and
+//! signature encodings, and the serialized content, signature and id of
+//! CallSC and ExecuteSC operations. Keys come from the RFC 8032 ed25519 test
+//! vectors, so the signatures can be checked against the RFC.
+//!
+//! The amount conversions (`units`) are checked for round trips, edge
+//! decimals, rejected inputs and overflow, and the `Args` codec for the
+//! layout and round trip of every field type and its errors on truncated or
+//! malformed input.
+
+#![cfg(test)]
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use erc20_client::network::Network;
+use erc20_client::codec::{self, ArgType, ArgsReader, Value};
+use erc20_client::units::{self, MAS_DECIMALS};
+use erc20_client::{keypair, operation, CallOptions, Error, KeyPair, U256};
+
+/// RFC 8032 section 7.1, test 1: secret key, public key and signature of the empty message
+const RFC8032_SECRET: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+const RFC8032_PUBLIC: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+const RFC8032_EMPTY_SIGNATURE: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+
+/// The RFC 8032 keys in the Massa encodings
+const SECRET_KEY: &str = "S12CK6vgUFHBTWVA9bMakzaB94Fhjuhwi4K6zQWU7tU8nKPfuUHe";
+const PUBLIC_KEY: &str = "P12dqvheyJXzEYpywfm8g7TshzLbaXWTwHKQPkh4rYX3Db1zSMsn";
+const ADDRESS: &str = "AU1rXFdJdkueAgBr6w4Qei1SgCGfvnKBW4EZmJFQrE7qMYXADt78";
+
+/// Signature and id of the CallSC operation of `test_signed_operation` on buildnet
+const SIGNATURE: &str =
+    "15kpi2n7hb7Xe3XfrhaE9EJgkUgoqsxLR44MT1BnTALKRQ8x61GU6Qf94HDEuSLjraZJYToLkkbmj46FyZPgMxWaYSqquT";
+const OPERATION_ID: &str = "O12tzBVNmQTsyEbsjiwPEzao8ZJGdGkxf6xTRubabF97BEPuSaxr";
+
+/// Addresses whose hashes are 32 bytes of 0x11 and 0x22
+const CONTRACT: &str = "AS18WwpJCixn9cKe3jAyXvxNeo5JrBFKj43ULkUeTfeLMqP8kqE7";
+const USER: &str = "AU1G2tdbQSvZJDeH6TLx4rukJb9chMVeT75wgVxHvLHfifg2JQVn";
+
+fn keypair() -> KeyPair {
+    KeyPair::from_secret_key(SECRET_KEY).unwrap()
+}
+
+/// base58check encoding of a version byte followed by `bytes`
+fn versioned_check(version: u8, bytes: &[u8]) -> String {
+    let mut data = vec![version];
+    data.extend_from_slice(bytes);
+    bs58::encode(data).with_check().into_string()
+}
+
+#[test]
+fn test_keypair_encodings() {
+    assert_eq!(SECRET_KEY, format!("S{}", versioned_check(0, &hex::decode(RFC8032_SECRET).unwrap())));
+
+    let keypair = keypair();
+    assert_eq!(keypair.public_key_bytes(), hex::decode(format!("00{}", RFC8032_PUBLIC)).unwrap());
+    assert_eq!(keypair.public_key(), PUBLIC_KEY);
+    let hash: [u8; 32] = blake3::hash(&keypair.public_key_bytes()).into();
+    assert_eq!(keypair.address_hash(), hash);
+    assert_eq!(keypair.address(), format!("AU{}", versioned_check(0, &hash)));
+    assert_eq!(keypair.address(), ADDRESS);
+}
+
+#[test]
+fn test_keypair_signature() {
+    let keypair = keypair();
+    let expected = versioned_check(0, &hex::decode(RFC8032_EMPTY_SIGNATURE).unwrap());
+    assert_eq!(keypair.sign(b""), expected);
+    // ed25519 signatures are deterministic
+    assert_eq!(keypair.sign(b""), expected);
+    assert_ne!(keypair.sign(b"message"), expected);
+}
+
+#[test]
+fn test_keypair_rejects_invalid_keys() {
+    let secret = hex::decode(RFC8032_SECRET).unwrap();
+    let invalid = [
+        // No S prefix
+        SECRET_KEY[1..].to_string(),
+        // A public key
+        PUBLIC_KEY.to_string(),
+        // Bad checksum
+        format!("{}1", &SECRET_KEY[..SECRET_KEY.len() - 1]),
+        // Unknown version
+        format!("S{}", versioned_check(1, &secret)),
+        // Not 32 bytes
+        format!("S{}", versioned_check(0, &secret[..31])),
+        format!("S{}", versioned_check(0, &[secret.as_slice(), &[0]].concat())),
+        String::new(),
+    ];
+    for key in invalid {
+        assert!(matches!(KeyPair::from_secret_key(&key), Err(Error::InvalidKey(_))), "{} was accepted", key);
+    }
+}
+
+#[test]
+fn test_address_bytes() {
+    let mut expected = vec![1, 0];
+    expected.extend_from_slice(&[0x11; 32]);
+    assert_eq!(keypair::address_bytes(CONTRACT).unwrap(), expected);
+
+    let mut expected = vec![0, 0];
+    expected.extend_from_slice(&[0x22; 32]);
+    assert_eq!(keypair::address_bytes(USER).unwrap(), expected);
+
+    let invalid = [
+        // Unknown prefix
+        format!("AX{}", &CONTRACT[2..]),
+        CONTRACT[2..].to_string(),
+        // Bad checksum
+        format!("{}1", &CONTRACT[..CONTRACT.len() - 1]),
+        // Unknown version
+        format!("AS{}", versioned_check(1, &[0x11; 32])),
+        // Hash of the wrong length
+        format!("AS{}", versioned_check(0, &[0x11; 31])),
+        String::from("AS"),
+    ];
+    for address in invalid {
+        assert!(
+            matches!(keypair::address_bytes(&address), Err(Error::InvalidAddress(_))),
+            "{} was accepted",
+            address
+        );
+    }
+}
+
+#[test]
+fn test_call_sc_content() {
+    let options = CallOptions {
+        fee: 1_000,
+        max_gas: 100_000_000,
+        coins: 300,
+        ..CallOptions::default()
+    };
+    let content = operation::call_sc_content(CONTRACT, "transfer", &[1, 2, 3], &options, 128).unwrap();
+    let expected = [
+        // fee, expire period, CallSC type id, max gas, coins as varints
+        "e807",
+        "8001",
+        "04",
+        "80c2d72f",
+        "ac02",
+        // Contract address: kind, version, hash
+        "0100",
+        &"11".repeat(32),
+        // Function and parameter after their varint length
+        "08",
+        "7472616e73666572",
+        "03",
+        "010203",
+    ]
+    .concat();
+    assert_eq!(hex::encode(&content), expected);
+
+    // The target must be a valid address
+    let err = operation::call_sc_content("AS1invalid", "transfer", &[], &options, 128).unwrap_err();
+    assert!(matches!(err, Error::InvalidAddress(_)), "{:?}", err);
+}
+
+#[test]
+fn test_execute_sc_content() {
+    let options = CallOptions {
+        fee: 10_000_000,
+        max_gas: 127,
+        coins: 0,
+        ..CallOptions::default()
+    };
+    let datastore = [(b"k".to_vec(), Vec::new()), (Vec::new(), vec![0xff; 128])];
+    let content = operation::execute_sc_content(b"\0asm", &datastore, &options, 0);
+    let expected = [
+        // fee, expire period, ExecuteSC type id, max gas, coins as varints
+        "80ade204",
+        "00",
+        "03",
+        "7f",
+        "00",
+        // Bytecode after its varint length
+        "04",
+        "0061736d",
+        // Datastore entry count, then each key and value after their varint length
+        "02",
+        "016b",
+        "00",
+        "00",
+        "8001",
+        &"ff".repeat(128),
+    ]
+    .concat();
+    assert_eq!(hex::encode(&content), expected);
+}
+
+#[test]
+fn test_signed_operation() {
+    let keypair = keypair();
+    let chain_id = Network::Buildnet.chain_id();
+    let content = operation::call_sc_content(CONTRACT, "transfer", &[1, 2, 3], &CallOptions::default(), 128).unwrap();
+
+    // The creator signs blake3(chain id BE || version || public key || content)
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&77_658_366u64.to_be_bytes());
+    hasher.update(&[0]);
+    hasher.update(&hex::decode(RFC8032_PUBLIC).unwrap());
+    hasher.update(&content);
+    let hash = hasher.finalize();
+
+    let signed = operation::sign(&keypair, chain_id, content.clone());
+    assert_eq!(signed.serialized_content, content);
+    assert_eq!(signed.creator_public_key, PUBLIC_KEY);
+    assert_eq!(signed.signature, SIGNATURE);
+
+    let decoded = bs58::decode(&signed.signature).with_check(None).into_vec().unwrap();
+    assert_eq!(decoded[0], 0);
+    let signature = Signature::from_slice(&decoded[1..]).unwrap();
+    let public_key: [u8; 32] = hex::decode(RFC8032_PUBLIC).unwrap().try_into().unwrap();
+    let verifying_key = VerifyingKey::from_bytes(&public_key).unwrap();
+    assert!(verifying_key.verify(hash.as_bytes(), &signature).is_ok());
+
+    // The operation id is the signed hash
+    let id = operation::operation_id(&keypair, chain_id, &content);
+    assert_eq!(id, format!("O{}", versioned_check(0, hash.as_bytes())));
+    assert_eq!(id, OPERATION_ID);
+
+    // Another chain gives another signature and id
+    let mainnet = operation::sign(&keypair, Network::Mainnet.chain_id(), content.clone());
+    assert_ne!(mainnet.signature, signed.signature);
+    assert_ne!(operation::operation_id(&keypair, Network::Mainnet.chain_id(), &content), id);
+}
+
+/// U256::MAX in decimal
+const U256_MAX: &str = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+#[test]
+fn test_parse_units() {
+    assert_eq!(units::parse_units("12.5", 3), Some(U256::from(12_500u64)));
+    assert_eq!(units::parse_units("12", 3), Some(U256::from(12_000u64)));
+    assert_eq!(units::parse_units("0.001", 3), Some(U256::from(1u64)));
+    assert_eq!(units::parse_units("0", 18), Some(U256::ZERO));
+    assert_eq!(units::parse_units("007.50", 2), Some(U256::from(750u64)));
+    assert_eq!(units::parse_units("1", 18), Some(U256::from(1_000_000_000_000_000_000u64)));
+
+    // No decimals: only whole amounts
+    assert_eq!(units::parse_units("42", 0), Some(U256::from(42u64)));
+    assert_eq!(units::parse_units("42.0", 0), None);
+    assert_eq!(units::parse_units("0.1", 0), None);
+
+    // More fractional digits than decimals are rejected, not rounded, even if zero
+    assert_eq!(units::parse_units("1.2345", 3), None);
+    assert_eq!(units::parse_units("1.2340", 3), None);
+    assert_eq!(units::parse_units("0.0000000001", MAS_DECIMALS), None);
+
+    // A leading or trailing dot stands for a zero whole or fractional part
+    assert_eq!(units::parse_units(".5", 3), Some(U256::from(500u64)));
+    assert_eq!(units::parse_units("5.", 3), Some(U256::from(5_000u64)));
+    assert_eq!(units::parse_units("5.", 0), Some(U256::from(5u64)));
+    assert_eq!(units::parse_units(".5", 0), None);
+
+    for malformed in ["", ".", "..5", "1.2.3", "1,5", "-1", "+1", " 1", "1 ", "1e3", "0x10", "½"] {
+        assert_eq!(units::parse_units(malformed, 3), None, "{:?} was accepted", malformed);
+    }
+}
+
+#[test]
+fn test_parse_units_overflow() {
+    let max = units::parse_units(U256_MAX, 0);
+    assert_eq!(max, Some(U256::MAX));
+    assert_eq!(units::parse_units(&format!("{}.", U256_MAX), 0), Some(U256::MAX));
+
+    // One more, or the same digits scaled by a decimal, no longer fit
+    assert_eq!(units::parse_units(&U256_MAX.replace("935", "936"), 0), None);
+    assert_eq!(units::parse_units(U256_MAX, 1), None);
+    assert_eq!(units::parse_units(&format!("{}0", U256_MAX), 0), None);
+
+    // The largest amount with 18 decimals
+    let whole = &U256_MAX[..U256_MAX.len() - 18];
+    let fraction = &U256_MAX[U256_MAX.len() - 18..];
+    assert_eq!(units::parse_units(&format!("{}.{}", whole, fraction), 18), Some(U256::MAX));
+    assert_eq!(units::parse_units(&format!("{}.{}", whole, fraction), 255), None);
+
+    // MAS amounts must also fit in a u64 of nanoMAS
+    assert_eq!(units::parse_mas("18446744073.709551615"), Some(u64::MAX));
+    assert_eq!(units::parse_mas("18446744073.709551616"), None);
+}
+
+#[test]
+fn test_format_units() {
+    assert_eq!(units::format_units(U256::from(1_500u64), 3), "1.5");
+    assert_eq!(units::format_units(U256::from(1_000u64), 3), "1");
+    assert_eq!(units::format_units(U256::from(1u64), 3), "0.001");
+    assert_eq!(units::format_units(U256::from(10u64), 3), "0.01");
+    assert_eq!(units::format_units(U256::ZERO, 3), "0");
+    assert_eq!(units::format_units(U256::ZERO, 0), "0");
+    assert_eq!(units::format_units(U256::from(120u64), 0), "120");
+    assert_eq!(units::format_units(U256::MAX, 0), U256_MAX);
+    assert_eq!(
+        units::format_units(U256::MAX, 18),
+        format!("{}.{}", &U256_MAX[..U256_MAX.len() - 18], &U256_MAX[U256_MAX.len() - 18..])
+    );
+    // More decimals than digits
+    assert_eq!(units::format_units(U256::from(5u64), 255), format!("0.{}5", "0".repeat(254)));
+
+    assert_eq!(units::format_mas(100_000_000), "0.1");
+    assert_eq!(units::format_mas(0), "0");
+    assert_eq!(units::format_mas(u64::MAX), "18446744073.709551615");
+}
+
+#[test]
+fn test_units_round_trip() {
+    let values = [
+        U256::ZERO,
+        U256::from(1u64),
+        U256::from(10u64),
+        U256::from(1_500u64),
+        U256::from(u64::MAX),
+        units::parse_units("340282366920938463463374607431768211456", 0).unwrap(),
+        U256::MAX,
+    ];
+    for decimals in [0u8, 1, 6, MAS_DECIMALS, 18, 77, 78, 255] {
+        for value in values {
+            let text = units::format_units(value, decimals);
+            assert_eq!(units::parse_units(&text, decimals), Some(value), "{} with {} decimals", text, decimals);
+        }
+    }
+    for text in ["0", "1", "0.001", "12.5", "999999.999999"] {
+        let value = units::parse_units(text, 6).unwrap();
+        assert_eq!(units::format_units(value, 6), text);
+    }
+    // Formatting drops the padding a parsed amount may have had
+    assert_eq!(units::format_units(units::parse_units("01.500", 6).unwrap(), 6), "1.5");
+}
+
+/// One value of every type, with its `Args` encoding
+fn codec_samples() -> Vec<(Value, &'static str)> {
+    vec![
+        (Value::String(String::from("Hé")), "03000000"),
+        (Value::String(String::new()), "00000000"),
+        (Value::Bool(false), "00"),
+        (Value::Bool(true), "01"),
+        (Value::U8(0xab), "ab"),
+        (Value::U32(0x0102_0304), "04030201"),
+        (Value::U64(u64::MAX - 1), "feffffffffffffff"),
+        (Value::U256(U256::from(0x0102u64)), "0201"),
+        (Value::U256(U256::MAX), ""),
+        (Value::Bytes(vec![0xde, 0xad]), "02000000dead"),
+        (Value::Bytes(Vec::new()), "00000000"),
+    ]
+}
+
+/// Expected `Args` bytes of a sample: the listed prefix, completed for strings and U256
+fn sample_bytes(value: &Value, prefix: &str) -> Vec<u8> {
+    let mut bytes = hex::decode(prefix).unwrap();
+    match value {
+        Value::String(text) => bytes.extend_from_slice(text.as_bytes()),
+        Value::U256(value) if *value == U256::MAX => bytes = vec![0xff; 32],
+        Value::U256(_) => bytes.resize(32, 0),
+        _ => {}
+    }
+    bytes
+}
+
+#[test]
+fn test_codec_round_trip() {
+    let samples = codec_samples();
+    for (value, prefix) in &samples {
+        let bytes = codec::encode(std::slice::from_ref(value));
+        assert_eq!(bytes, sample_bytes(value, prefix), "{:?}", value);
+        assert_eq!(codec::decode(&bytes, &[value.arg_type()]).unwrap(), std::slice::from_ref(value));
+    }
+
+    // All of them in one call, fields back to back
+    let values: Vec<Value> = samples.iter().map(|(value, _)| value.clone()).collect();
+    let types: Vec<ArgType> = values.iter().map(Value::arg_type).collect();
+    let bytes = codec::encode(&values);
+    let expected: Vec<u8> = samples.iter().flat_map(|(value, prefix)| sample_bytes(value, prefix)).collect();
+    assert_eq!(bytes, expected);
+    assert_eq!(codec::decode(&bytes, &types).unwrap(), values);
+
+    // The reader reports where each field starts
+    let mut reader = ArgsReader::new(&bytes);
+    for (value, prefix) in &samples {
+        let start = reader.offset();
+        assert_eq!(reader.read(value.arg_type()).unwrap(), *value);
+        assert_eq!(reader.offset() - start, sample_bytes(value, prefix).len());
+    }
+    assert!(reader.remaining().is_empty());
+
+    assert_eq!(codec::encode(&[]), Vec::<u8>::new());
+    assert_eq!(codec::decode(&[], &[]).unwrap(), []);
+}
+
+#[test]
+fn test_codec_truncated_input() {
+    for (value, _) in codec_samples() {
+        let bytes = codec::encode(std::slice::from_ref(&value));
+        for len in 0..bytes.len() {
+            let err = codec::decode(&bytes[..len], &[value.arg_type()]).unwrap_err();
+            let Error::InvalidArgs(message) = err else { panic!("{:?}", err) };
+            assert!(message.starts_with(&format!("{} at byte ", value.arg_type())), "{}", message);
+            assert!(message.contains(" bytes, ") && message.ends_with(" left"), "{}", message);
+        }
+    }
+
+    // The offset and sizes point at the truncated field
+    let bytes = codec::encode(&[Value::U8(1), Value::U64(2)]);
+    match codec::decode(&bytes[..5], &[ArgType::U8, ArgType::U64]) {
+        Err(Error::InvalidArgs(message)) => assert_eq!(message, "u64 at byte 1 needs 8 bytes, 4 left"),
+        other => panic!("{:?}", other),
+    }
+
+    // A string whose length runs past the end
+    let err = codec::decode(&hex::decode("0a000000616263").unwrap(), &[ArgType::String]).unwrap_err();
+    assert!(err.to_string().contains("string at byte 4 needs 10 bytes, 3 left"), "{}", err);
+
+    // More fields expected than encoded
+    let err = codec::decode(&codec::encode(&[Value::Bool(true)]), &[ArgType::Bool, ArgType::U32]).unwrap_err();
+    assert!(err.to_string().contains("u32 at byte 1 needs 4 bytes, 0 left"), "{}", err);
+}
+
+#[test]
+fn test_codec_malformed_input() {
+    let err = codec::decode(&[2], &[ArgType::Bool]).unwrap_err();
+    assert!(err.to_string().contains("bool at byte 0 is 2, not 0 or 1"), "{}", err);
+
+    let err = codec::decode(&hex::decode("02000000c328").unwrap(), &[ArgType::String]).unwrap_err();
+    assert!(err.to_string().contains("string at byte 0 of length 2 is not UTF-8"), "{}", err);
+    // The same bytes are fine as bytes
+    let decoded = codec::decode(&hex::decode("02000000c328").unwrap(), &[ArgType::Bytes]).unwrap();
+    assert_eq!(decoded, [Value::Bytes(vec![0xc3, 0x28])]);
+
+    let err = codec::decode(&[1, 0], &[ArgType::U8]).unwrap_err();
+    assert!(err.to_string().contains("1 trailing bytes after the last field"), "{}", err);
+}
+
+#[test]
+fn test_codec_signature_and_text() {
+    assert_eq!(
+        codec::parse_signature("string, string,u8 ,u256").unwrap(),
+        [ArgType::String, ArgType::String, ArgType::U8, ArgType::U256]
+    );
+    assert_eq!(codec::parse_signature(" ").unwrap(), []);
+    for signature in ["u16", "string,,u8", "U8", "string,"] {
+        let parsed = codec::parse_signature(signature);
+        assert!(matches!(parsed, Err(Error::InvalidArgs(_))), "{:?} was accepted", signature);
+    }
+
+    // Every value prints as text its type parses back, except strings, which print quoted
+    for (value, _) in codec_samples() {
+        let text = value.to_string();
+        let text = match &value {
+            Value::String(_) => text.trim_matches('"').to_string(),
+            _ => text,
+        };
+        assert_eq!(Value::parse(value.arg_type(), &text).unwrap(), value, "{}", text);
+        assert_eq!(value.arg_type().name().parse::<ArgType>().unwrap(), value.arg_type());
+    }
+    assert_eq!(Value::parse(ArgType::Bytes, "dead").unwrap(), Value::Bytes(vec![0xde, 0xad]));
+    assert_eq!(Value::parse(ArgType::U256, U256_MAX).unwrap(), Value::U256(U256::MAX));
+
+    let invalid = [
+        (ArgType::Bool, "1"),
+        (ArgType::U8, "256"),
+        (ArgType::U32, "-1"),
+        (ArgType::U64, "18446744073709551616"),
+        (ArgType::U256, "1.5"),
+        (ArgType::Bytes, "0xabc"),
+    ];
+    for (ty, text) in invalid {
+        let err = Value::parse(ty, text).unwrap_err();
+        assert!(err.to_string().contains(&format!("{:?} is not a valid {}", text, ty)), "{}", err);
+    }
+}
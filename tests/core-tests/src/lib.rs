@@ -0,0 +1,638 @@
+//! Host-side tests of the pure `mrc20-core` logic
+//!
+//! Covers the parts of the shared crate that contracts rely on for their
+//! accounting but that need no runtime: storage key layouts, the datastore
+//! cost rules wMAS uses to keep its supply covered by its coins, the
+//! reward-per-token accrual behind staking, the faucet cooldown, governor
+//! tallies and proposal states, vesting schedules, the bridge and multisig
+//! thresholds, and the flash loan and `transferAndCall` rules. The contract
+//! tests cannot reach these paths: the testkit has no clock to move and no
+//! second contract to call.
+
+#![cfg(test)]
+
+use std::collections::BTreeMap;
+
+use mrc20_core::U256;
+use mrc20_core::approvals;
+use mrc20_core::callbacks;
+use mrc20_core::cooldown;
+use mrc20_core::governance::{self, ABSTAIN, AGAINST, FOR, Tally};
+use mrc20_core::governance::{STATE_ACTIVE, STATE_DEFEATED, STATE_EXECUTED, STATE_PENDING, STATE_SUCCEEDED};
+use mrc20_core::key::Key;
+use mrc20_core::math;
+use mrc20_core::rewards::{self, PRECISION, RewardPool};
+use mrc20_core::storage_cost::{self, ENTRY_BASE_SIZE, STORAGE_BYTE_COST, U256_VALUE_SIZE};
+use mrc20_core::vesting::{Schedule, ScheduleError};
+
+const ALICE: &str = "AU12cMW9zRKFDS43Z2W88VCmdQFxmHjAo54XvuVV34UzJeXRLXW9M";
+const BOB: &str = "AU12UBnqTHDQALpocVBnkPNy7y5CndUJQTLutaVDDFgMJcq5kQiKq";
+const CAROL: &str = "AU1qTbRnSiZsCH6Dz3SV6ya7BFBbGMeCdvJpq5ja2dJc8KPxmRKNp";
+
+/// wMAS balance key prefix, as in the token's datastore layout
+const BALANCE_KEY_PREFIX: &[u8] = b"BALANCE";
+
+/// Model of the wMAS contract on a datastore that charges entry storage
+/// from the contract's coins, as the node does.
+#[derive(Default)]
+struct WrappedMas {
+    coins: u64,
+    supply: u64,
+    balances: BTreeMap<String, u64>,
+}
+
+impl WrappedMas {
+    /// Mirror of `deposit`: returns the minted amount, or None when the coins
+    /// do not cover the storage cost.
+    fn deposit(&mut self, caller: &str, sent: u64) -> Option<u64> {
+        let is_new = !self.balances.contains_key(caller);
+        let cost = if is_new { storage_cost::u256_entry_cost(BALANCE_KEY_PREFIX, caller) } else { 0 };
+        if sent == 0 || sent <= cost {
+            return None;
+        }
+        let minted = sent - cost;
+
+        self.coins += sent;
+        if is_new {
+            // The node charges the bytes actually written
+            let key_len = BALANCE_KEY_PREFIX.len() + caller.len();
+            let charged = (ENTRY_BASE_SIZE + (key_len + U256_VALUE_SIZE) as u64) * STORAGE_BYTE_COST;
+            self.coins -= charged;
+        }
+        self.supply += minted;
+        *self.balances.entry(caller.to_string()).or_default() += minted;
+        Some(minted)
+    }
+
+    /// Mirror of `withdraw`: an emptied balance entry is deleted and its storage released.
+    fn withdraw(&mut self, caller: &str, amount: u64) {
+        let balance = self.balances[caller];
+        assert!(balance >= amount);
+        self.coins -= amount;
+        self.supply -= amount;
+        if balance == amount {
+            self.balances.remove(caller);
+            let key_len = BALANCE_KEY_PREFIX.len() + caller.len();
+            self.coins += storage_cost::entry_cost(key_len, U256_VALUE_SIZE);
+        } else {
+            self.balances.insert(caller.to_string(), balance - amount);
+        }
+    }
+}
+
+/// Model of the staking contract: the pool plus each account's stake,
+/// settled reward per token and stored rewards, updated like `update_rewards`.
+struct Staking {
+    pool: RewardPool,
+    accounts: BTreeMap<&'static str, (U256, U256, U256)>,
+}
+
+impl Staking {
+    fn new(rate: u64, reserve: u64) -> Self {
+        let pool = RewardPool {
+            reward_per_token: U256::ZERO,
+            reserve: U256::from(reserve),
+            rate: U256::from(rate),
+            last_update: 0,
+            total_staked: U256::ZERO,
+        };
+        Staking { pool, accounts: BTreeMap::new() }
+    }
+
+    /// Mirror of `update_rewards`.
+    fn update(&mut self, account: &'static str, period: u64) {
+        let (reward_per_token, reserve) = self.pool.accrued(period).unwrap();
+        self.pool.reward_per_token = reward_per_token;
+        self.pool.reserve = reserve;
+        self.pool.last_update = period;
+        let (stake, paid, stored) = self.accounts.get(account).copied().unwrap_or((U256::ZERO, U256::ZERO, U256::ZERO));
+        let earned = rewards::earned(stake, reward_per_token, paid, stored).unwrap();
+        self.accounts.insert(account, (stake, reward_per_token, earned));
+    }
+
+    fn stake(&mut self, account: &'static str, amount: u64, period: u64) {
+        self.update(account, period);
+        let stake = &mut self.accounts.get_mut(account).unwrap().0;
+        *stake = stake.checked_add(U256::from(amount)).unwrap();
+        self.pool.total_staked = self.pool.total_staked.checked_add(U256::from(amount)).unwrap();
+    }
+
+    fn unstake(&mut self, account: &'static str, amount: u64, period: u64) {
+        self.update(account, period);
+        let stake = &mut self.accounts.get_mut(account).unwrap().0;
+        *stake = stake.checked_sub(U256::from(amount)).unwrap();
+        self.pool.total_staked = self.pool.total_staked.checked_sub(U256::from(amount)).unwrap();
+    }
+
+    fn set_rate(&mut self, rate: u64, period: u64) {
+        let (reward_per_token, reserve) = self.pool.accrued(period).unwrap();
+        self.pool.reward_per_token = reward_per_token;
+        self.pool.reserve = reserve;
+        self.pool.last_update = period;
+        self.pool.rate = U256::from(rate);
+    }
+
+    /// Mirror of `earned`: rewards as of `period` without writing anything.
+    fn earned(&self, account: &str, period: u64) -> U256 {
+        let (reward_per_token, _) = self.pool.accrued(period).unwrap();
+        let (stake, paid, stored) = self.accounts.get(account).copied().unwrap_or((U256::ZERO, U256::ZERO, U256::ZERO));
+        rewards::earned(stake, reward_per_token, paid, stored).unwrap()
+    }
+}
+
+/// Model of the bridge validator set, updated like `removeValidator` and `setThreshold`.
+struct Validators {
+    count: u32,
+    threshold: u32,
+}
+
+impl Validators {
+    fn set_threshold(&mut self, threshold: u32) -> bool {
+        if !approvals::is_valid_threshold(threshold, self.count) {
+            return false;
+        }
+        self.threshold = threshold;
+        true
+    }
+
+    fn remove(&mut self) -> bool {
+        if !approvals::allows_removal(self.threshold, self.count) {
+            return false;
+        }
+        self.count -= 1;
+        true
+    }
+}
+
+/// Model of the token's flash mint: total supply and the borrower's balance, updated like `flashLoan`.
+struct FlashMint {
+    cap: Option<U256>,
+    supply: U256,
+    balance: U256,
+}
+
+impl FlashMint {
+    /// Mirror of `flashLoan` with a borrower that approves `approved` for the repayment.
+    /// Returns false, leaving everything untouched, when the loan reverts.
+    fn loan(&mut self, amount: U256, fee: U256, approved: U256) -> bool {
+        if amount > callbacks::max_flash_loan(self.cap, self.supply) {
+            return false;
+        }
+        let Some(repayment) = callbacks::flash_repayment(amount, fee) else {
+            return false;
+        };
+        let supply = self.supply.checked_add(amount).unwrap();
+        let balance = self.balance.checked_add(amount).unwrap();
+        if approved < repayment {
+            return false;
+        }
+        let (Some(supply), Some(balance)) = (supply.checked_sub(repayment), balance.checked_sub(repayment)) else {
+            return false;
+        };
+        self.supply = supply;
+        self.balance = balance;
+        true
+    }
+}
+
+#[test]
+fn test_entry_cost() {
+    assert_eq!(storage_cost::entry_cost(0, 0), 400_000);
+    assert_eq!(storage_cost::entry_cost(10, 32), 4_600_000);
+    // BALANCE + a 53-character address + 32 value bytes + 4 overhead bytes
+    assert_eq!(ALICE.len(), 53);
+    assert_eq!(storage_cost::u256_entry_cost(BALANCE_KEY_PREFIX, ALICE), 9_600_000);
+}
+
+#[test]
+fn test_wmas_first_deposits_keep_supply_covered() {
+    let mut wmas = WrappedMas::default();
+
+    // First-time deposits pay for their balance entry
+    for (caller, sent) in [(ALICE, 1_000_000_000), (BOB, 9_600_001), (CAROL, 50_000_000)] {
+        let minted = wmas.deposit(caller, sent).expect("deposit covers the storage cost");
+        assert_eq!(minted, sent - 9_600_000);
+        assert!(wmas.supply <= wmas.coins, "supply {} above coins {}", wmas.supply, wmas.coins);
+    }
+    assert_eq!(wmas.supply, wmas.coins);
+
+    // Later deposits mint the full amount
+    assert_eq!(wmas.deposit(ALICE, 5), Some(5));
+    assert_eq!(wmas.supply, wmas.coins);
+
+    // Deposits that do not cover a new entry are rejected
+    let mut empty = WrappedMas::default();
+    assert_eq!(empty.deposit(ALICE, 9_600_000), None);
+    assert_eq!(empty.deposit(ALICE, 0), None);
+    assert_eq!(empty.coins, 0);
+
+    // Withdrawing everything releases the entry, which only adds coins
+    let alice_balance = wmas.balances[ALICE];
+    wmas.withdraw(ALICE, alice_balance);
+    wmas.withdraw(CAROL, 1);
+    assert!(wmas.supply <= wmas.coins);
+    assert_eq!(wmas.coins - wmas.supply, 9_600_000);
+
+    // Re-depositing pays for the entry again
+    assert_eq!(wmas.deposit(ALICE, 10_000_000), Some(400_000));
+    assert_eq!(wmas.supply, wmas.coins - 9_600_000);
+}
+
+#[test]
+fn test_key_layouts() {
+    // Raw keys are the AS layout: the prefix followed by the segments as they are
+    let raw = Key::raw(b"ALLOWANCE").push("AB").push("C").into_bytes();
+    assert_eq!(raw, b"ALLOWANCEABC".to_vec());
+    let raw_id = Key::raw(b"PROPOSAL").push(&7u64).into_bytes();
+    assert_eq!(raw_id, [b"PROPOSAL".as_slice(), &7u64.to_le_bytes()].concat());
+
+    // Length-prefixed keys write each segment after its u32 LE length
+    let key = Key::new(b"VALIDATOR").push("P1").into_bytes();
+    assert_eq!(key, [b"VALIDATOR".as_slice(), &2u32.to_le_bytes(), b"P1"].concat());
+    let id_key = Key::new(b"VOTE").push(&7u64).into_bytes();
+    assert_eq!(id_key, [b"VOTE".as_slice(), &8u32.to_le_bytes(), &7u64.to_le_bytes()].concat());
+}
+
+#[test]
+fn test_key_collisions() {
+    // Raw two-part keys are ambiguous
+    let ab_c = Key::raw(b"ALLOWANCE").push("AB").push("C").into_bytes();
+    let a_bc = Key::raw(b"ALLOWANCE").push("A").push("BC").into_bytes();
+    assert_eq!(ab_c, a_bc);
+    let ab_c = Key::new(b"ALLOWANCE").push("AB").push("C").into_bytes();
+    let a_bc = Key::new(b"ALLOWANCE").push("A").push("BC").into_bytes();
+    assert_ne!(ab_c, a_bc);
+
+    // A raw segment can extend a prefix into a sibling key
+    assert_eq!(Key::raw(b"VALIDATOR").push("_COUNT").into_bytes(), b"VALIDATOR_COUNT".to_vec());
+    let validator = Key::new(b"VALIDATOR").push("_COUNT").into_bytes();
+    assert_ne!(validator, b"VALIDATOR_COUNT".to_vec());
+    assert!(!validator.starts_with(b"VALIDATOR_"));
+
+    // Segments of different lengths never share an encoding
+    let keys: Vec<Vec<u8>> = ["", "A", "AA", "A\0", "\0"]
+        .iter()
+        .map(|segment| Key::new(b"P").push(*segment).as_bytes().to_vec())
+        .collect();
+    for (i, first) in keys.iter().enumerate() {
+        for second in &keys[i + 1..] {
+            assert_ne!(first, second);
+        }
+    }
+}
+
+#[test]
+fn test_key_single_segment() {
+    let key = Key::new(b"VALIDATOR").push("P1validatorOne").into_bytes();
+    assert_eq!(Key::single_segment(b"VALIDATOR", &key), Some(b"P1validatorOne".as_slice()));
+    let empty = Key::new(b"VALIDATOR").push("").into_bytes();
+    assert_eq!(Key::single_segment(b"VALIDATOR", &empty), Some(b"".as_slice()));
+
+    // Sibling keys, other prefixes, truncated and multi-segment keys are not segments
+    assert_eq!(Key::single_segment(b"VALIDATOR", b"VALIDATOR_COUNT"), None);
+    assert_eq!(Key::single_segment(b"VALIDATOR", b"VALIDATOR"), None);
+    assert_eq!(Key::single_segment(b"OWNER", &key), None);
+    assert_eq!(Key::single_segment(b"VALIDATOR", &key[..key.len() - 1]), None);
+    let two = Key::new(b"VALIDATOR").push("P1").push("P2").into_bytes();
+    assert_eq!(Key::single_segment(b"VALIDATOR", &two), None);
+}
+
+#[test]
+fn test_rewards_accrual() {
+    let pool = RewardPool {
+        reward_per_token: U256::ZERO,
+        reserve: U256::from(1_000u64),
+        rate: U256::from(10u64),
+        last_update: 5,
+        total_staked: U256::from(4u64),
+    };
+    // Nothing accrues at or before the last update
+    assert_eq!(pool.accrued(5), Some((U256::ZERO, U256::from(1_000u64))));
+    assert_eq!(pool.accrued(3), Some((U256::ZERO, U256::from(1_000u64))));
+    // 3 periods at 10 per period over 4 staked units
+    let (reward_per_token, reserve) = pool.accrued(8).unwrap();
+    assert_eq!(reward_per_token, U256::from(PRECISION / 4 * 30));
+    assert_eq!(reserve, U256::from(970u64));
+    let earned = |stake: u64, paid: U256, stored: u64| {
+        rewards::earned(U256::from(stake), reward_per_token, paid, U256::from(stored)).unwrap()
+    };
+    assert_eq!(earned(3, U256::ZERO, 0), U256::from(22u64));
+    assert_eq!(earned(1, U256::ZERO, 5), U256::from(12u64));
+    // Settled at the current reward per token: only the stored rewards remain
+    assert_eq!(earned(3, reward_per_token, 7), U256::from(7u64));
+}
+
+#[test]
+fn test_rewards_zero_total_stake() {
+    let pool = RewardPool {
+        reward_per_token: U256::from(42u64),
+        reserve: U256::from(1_000u64),
+        rate: U256::from(10u64),
+        last_update: 0,
+        total_staked: U256::ZERO,
+    };
+    // Periods without stake distribute nothing and keep the reserve
+    assert_eq!(pool.accrued(100), Some((U256::from(42u64), U256::from(1_000u64))));
+
+    let mut staking = Staking::new(10, 1_000);
+    staking.stake(ALICE, 100, 50);
+    assert_eq!(staking.pool.reserve, U256::from(1_000u64));
+    assert_eq!(staking.earned(ALICE, 50), U256::ZERO);
+    assert_eq!(staking.earned(ALICE, 60), U256::from(100u64));
+
+    // Unstaking everything stops the accrual until someone stakes again
+    staking.unstake(ALICE, 100, 60);
+    assert_eq!(staking.earned(ALICE, 90), U256::from(100u64));
+    staking.stake(BOB, 10, 90);
+    assert_eq!(staking.pool.reserve, U256::from(900u64));
+    assert_eq!(staking.earned(BOB, 91), U256::from(10u64));
+}
+
+#[test]
+fn test_rewards_multiple_stakers() {
+    let mut staking = Staking::new(100, 1_000_000);
+    staking.stake(ALICE, 100, 0);
+    // Alice alone for 10 periods
+    staking.stake(BOB, 300, 10);
+    assert_eq!(staking.earned(ALICE, 10), U256::from(1_000u64));
+    assert_eq!(staking.earned(BOB, 10), U256::ZERO);
+    // Then 1:3 for 10 periods
+    staking.stake(CAROL, 600, 20);
+    assert_eq!(staking.earned(ALICE, 20), U256::from(1_250u64));
+    assert_eq!(staking.earned(BOB, 20), U256::from(750u64));
+    // Then 1:3:6 for 10 periods
+    assert_eq!(staking.earned(ALICE, 30), U256::from(1_350u64));
+    assert_eq!(staking.earned(BOB, 30), U256::from(1_050u64));
+    assert_eq!(staking.earned(CAROL, 30), U256::from(600u64));
+
+    // Everything distributed so far is owed to someone
+    let owed = [ALICE, BOB, CAROL]
+        .iter()
+        .fold(U256::ZERO, |sum, account| sum.checked_add(staking.earned(account, 30)).unwrap());
+    let (_, reserve) = staking.pool.accrued(30).unwrap();
+    assert_eq!(owed, U256::from(3_000u64));
+    assert_eq!(reserve, U256::from(1_000_000u64 - 3_000));
+}
+
+#[test]
+fn test_rewards_rate_change() {
+    let mut staking = Staking::new(100, 1_000_000);
+    staking.stake(ALICE, 50, 0);
+    staking.stake(BOB, 50, 0);
+    // 10 periods at 100, then 10 at 20, then 10 with rewards paused
+    staking.set_rate(20, 10);
+    staking.set_rate(0, 20);
+    assert_eq!(staking.earned(ALICE, 30), U256::from(600u64));
+    assert_eq!(staking.earned(BOB, 30), U256::from(600u64));
+    assert_eq!(staking.pool.reserve, U256::from(1_000_000u64 - 1_200));
+
+    // A rate change without settling first would pay the new rate retroactively
+    let mut unsettled = Staking::new(100, 1_000_000);
+    unsettled.stake(ALICE, 100, 0);
+    unsettled.pool.rate = U256::from(20u64);
+    assert_eq!(unsettled.earned(ALICE, 10), U256::from(200u64));
+}
+
+#[test]
+fn test_rewards_reserve_exhausted() {
+    let mut staking = Staking::new(100, 250);
+    staking.stake(ALICE, 1, 0);
+    staking.stake(BOB, 4, 0);
+    // The reserve only covers 2.5 periods
+    assert_eq!(staking.earned(ALICE, 2), U256::from(40u64));
+    assert_eq!(staking.earned(ALICE, 10), U256::from(50u64));
+    assert_eq!(staking.earned(BOB, 10), U256::from(200u64));
+    staking.update(ALICE, 10);
+    assert_eq!(staking.pool.reserve, U256::ZERO);
+    assert_eq!(staking.earned(ALICE, 1_000), U256::from(50u64));
+}
+
+#[test]
+fn test_rewards_precision_and_overflow() {
+    // Rewards smaller than the total stake still accrue thanks to the scale
+    let pool = RewardPool {
+        reward_per_token: U256::ZERO,
+        reserve: U256::MAX,
+        rate: U256::from(1u64),
+        last_update: 0,
+        total_staked: U256::from(3u64),
+    };
+    let (reward_per_token, _) = pool.accrued(1).unwrap();
+    assert_eq!(reward_per_token, U256::from(PRECISION / 3));
+    let three = U256::from(3u64);
+    assert_eq!(rewards::earned(three, reward_per_token, U256::ZERO, U256::ZERO), Some(U256::ZERO));
+    assert_eq!(rewards::earned(three, U256::from(PRECISION), U256::ZERO, U256::ZERO), Some(three));
+
+    // An increment that does not fit in 256 bits is reported, not wrapped
+    let overflowing = RewardPool { rate: U256::MAX, total_staked: U256::from(1u64), ..pool };
+    assert_eq!(overflowing.accrued(1), None);
+    assert_eq!(rewards::earned(U256::MAX, U256::MAX, U256::ZERO, U256::ZERO), None);
+}
+
+#[test]
+fn test_faucet_cooldown() {
+    // Accounts that never dripped can drip at once
+    assert_eq!(cooldown::next_period(None, 100), 0);
+    assert_eq!(cooldown::next_period(Some(40), 100), 140);
+    // A zero cooldown allows another drip in the same period
+    assert_eq!(cooldown::next_period(Some(40), 0), 40);
+    // A cooldown past the last period never elapses
+    assert_eq!(cooldown::next_period(Some(40), u64::MAX), u64::MAX);
+}
+
+#[test]
+fn test_governance_tally() {
+    let weight = U256::from(10u64);
+    let tally = Tally::EMPTY
+        .with_vote(FOR, weight)
+        .and_then(|tally| tally.with_vote(AGAINST, U256::from(4u64)))
+        .and_then(|tally| tally.with_vote(ABSTAIN, U256::from(2u64)))
+        .and_then(|tally| tally.with_vote(FOR, weight))
+        .unwrap();
+    assert_eq!(
+        tally,
+        Tally {
+            for_votes: U256::from(20u64),
+            against_votes: U256::from(4u64),
+            abstain_votes: U256::from(2u64),
+        }
+    );
+
+    // Unknown options and overflowing counts are rejected
+    assert_eq!(tally.with_vote(3, weight), None);
+    assert_eq!(tally.with_vote(FOR, U256::MAX), None);
+    assert!(Tally::EMPTY.with_vote(AGAINST, U256::MAX).is_some());
+}
+
+#[test]
+fn test_governance_quorum() {
+    let supply = U256::from(1_000_001u64);
+    assert_eq!(governance::quorum(supply, 400), Some(U256::from(40_000u64)));
+    assert_eq!(governance::quorum(supply, 0), Some(U256::ZERO));
+    assert_eq!(governance::quorum(supply, math::MAX_BPS), Some(supply));
+    assert_eq!(governance::quorum(U256::MAX, math::MAX_BPS), Some(U256::MAX));
+}
+
+#[test]
+fn test_governance_proposal_states() {
+    let quorum = U256::from(100u64);
+    let votes = |for_votes: u64, against_votes: u64, abstain_votes: u64| Tally {
+        for_votes: U256::from(for_votes),
+        against_votes: U256::from(against_votes),
+        abstain_votes: U256::from(abstain_votes),
+    };
+    let state = |period: u64, tally: &Tally| governance::proposal_state(period, 10, 20, false, tally, quorum);
+
+    // Voting runs from the start period to the end period inclusive
+    let passing = votes(80, 10, 20);
+    assert_eq!(state(9, &passing), STATE_PENDING);
+    assert_eq!(state(10, &passing), STATE_ACTIVE);
+    assert_eq!(state(20, &passing), STATE_ACTIVE);
+    assert_eq!(state(21, &passing), STATE_SUCCEEDED);
+
+    // Abstentions count toward the quorum but not toward the majority
+    assert_eq!(state(21, &votes(80, 10, 19)), STATE_DEFEATED);
+    assert_eq!(state(21, &votes(10, 10, 500)), STATE_DEFEATED);
+    assert_eq!(state(21, &votes(11, 10, 89)), STATE_SUCCEEDED);
+    assert_eq!(state(21, &Tally::EMPTY), STATE_DEFEATED);
+
+    // Executed wins over every other state
+    assert_eq!(governance::proposal_state(9, 10, 20, true, &passing, quorum), STATE_EXECUTED);
+    assert_eq!(governance::proposal_state(21, 10, 20, true, &Tally::EMPTY, quorum), STATE_EXECUTED);
+
+    // A zero quorum only needs a majority
+    assert_eq!(governance::proposal_state(21, 10, 20, false, &votes(1, 0, 0), U256::ZERO), STATE_SUCCEEDED);
+}
+
+#[test]
+fn test_vesting_schedule_validation() {
+    let schedule = |cliff: u64, duration: u64| Schedule {
+        start: 100,
+        cliff,
+        duration,
+    };
+    assert_eq!(schedule(0, 1).validate(), Ok(()));
+    assert_eq!(schedule(50, 50).validate(), Ok(()));
+    assert_eq!(schedule(0, 0).validate(), Err(ScheduleError::ZeroDuration));
+    assert_eq!(schedule(51, 50).validate(), Err(ScheduleError::CliffAfterDuration));
+}
+
+#[test]
+fn test_vesting_amounts() {
+    let amount = U256::from(1_000u64);
+    let schedule = Schedule {
+        start: 100,
+        cliff: 25,
+        duration: 100,
+    };
+    // Nothing before the cliff, then everything accrued since the start
+    assert_eq!(schedule.vested(amount, 0), U256::ZERO);
+    assert_eq!(schedule.vested(amount, 124), U256::ZERO);
+    assert_eq!(schedule.vested(amount, 125), U256::from(250u64));
+    assert_eq!(schedule.vested(amount, 133), U256::from(330u64));
+    assert_eq!(schedule.vested(amount, 199), U256::from(990u64));
+    assert_eq!(schedule.vested(amount, 200), amount);
+    assert_eq!(schedule.vested(amount, u64::MAX), amount);
+
+    // Partial amounts round down, so the full grant only vests at the end
+    let odd = Schedule {
+        start: 0,
+        cliff: 0,
+        duration: 3,
+    };
+    assert_eq!(odd.vested(U256::from(100u64), 1), U256::from(33u64));
+    assert_eq!(odd.vested(U256::from(100u64), 2), U256::from(66u64));
+    assert_eq!(odd.vested(U256::MAX, 2), math::mul_div(U256::MAX, U256::from(2u64), U256::from(3u64)).unwrap());
+
+    // A schedule ending past the last period does not overflow: it ends at the last period
+    let late = Schedule {
+        start: u64::MAX - 10,
+        cliff: 0,
+        duration: 100,
+    };
+    assert_eq!(late.vested(amount, u64::MAX - 1), U256::from(90u64));
+    assert_eq!(late.vested(amount, u64::MAX), amount);
+
+    // Without a duration everything vests at once at the end of the cliff
+    let immediate = Schedule {
+        start: 100,
+        cliff: 10,
+        duration: 0,
+    };
+    assert_eq!(immediate.vested(amount, 109), U256::ZERO);
+    assert_eq!(immediate.vested(amount, 110), amount);
+}
+
+#[test]
+fn test_approval_thresholds() {
+    assert!(approvals::is_valid_threshold(1, 1));
+    assert!(approvals::is_valid_threshold(3, 5));
+    assert!(!approvals::is_valid_threshold(0, 5));
+    assert!(!approvals::is_valid_threshold(6, 5));
+    assert!(!approvals::is_valid_threshold(1, 0));
+
+    let mut validators = Validators { count: 3, threshold: 2 };
+    assert!(validators.remove());
+    // Two validators left for a threshold of 2: neither can leave
+    assert!(!validators.remove());
+    assert!(!validators.set_threshold(3));
+    assert!(!validators.set_threshold(0));
+    assert!(validators.set_threshold(1));
+    assert!(validators.remove());
+    // The last validator never leaves
+    assert!(!validators.remove());
+    assert_eq!((validators.count, validators.threshold), (1, 1));
+}
+
+#[test]
+fn test_transfer_and_call_receivers() {
+    assert!(callbacks::is_contract_address("AS12LpYyAjYRJfYhyu7fkrS224gMdvFHVEeVWoeHZzMdhis7UZ3Eb"));
+    for address in [ALICE, BOB, "", "as12", "A", "S12"] {
+        assert!(!callbacks::is_contract_address(address), "{address} is not a contract");
+    }
+}
+
+#[test]
+fn test_flash_loan_limits() {
+    let supply = U256::from(900u64);
+    assert_eq!(callbacks::max_flash_loan(Some(U256::from(1_000u64)), supply), U256::from(100u64));
+    assert_eq!(callbacks::max_flash_loan(None, supply), U256::MAX.checked_sub(supply).unwrap());
+    // A cap lowered below the supply leaves nothing to lend
+    assert_eq!(callbacks::max_flash_loan(Some(supply), supply), U256::ZERO);
+    assert_eq!(callbacks::max_flash_loan(Some(U256::from(800u64)), supply), U256::ZERO);
+
+    assert_eq!(callbacks::flash_repayment(U256::from(100u64), U256::from(3u64)), Some(U256::from(103u64)));
+    assert_eq!(callbacks::flash_repayment(U256::MAX, U256::from(1u64)), None);
+}
+
+#[test]
+fn test_flash_loan_settlement() {
+    let mut token = FlashMint {
+        cap: Some(U256::from(1_000u64)),
+        supply: U256::from(900u64),
+        balance: U256::from(10u64),
+    };
+    let fee = U256::from(3u64);
+
+    // A repaid loan burns the fee from the borrower
+    assert!(token.loan(U256::from(100u64), fee, U256::from(103u64)));
+    assert_eq!(token.supply, U256::from(897u64));
+    assert_eq!(token.balance, U256::from(7u64));
+
+    // Loans above the cap, short approvals and unaffordable fees revert
+    assert!(!token.loan(U256::from(104u64), fee, U256::MAX));
+    assert!(!token.loan(U256::from(50u64), fee, U256::from(52u64)));
+    assert!(!token.loan(U256::from(50u64), U256::from(8u64), U256::MAX));
+    assert_eq!((token.supply, token.balance), (U256::from(897u64), U256::from(7u64)));
+
+    // Without a cap the whole remaining range can be borrowed for free
+    let mut uncapped = FlashMint {
+        cap: None,
+        supply: U256::from(1u64),
+        balance: U256::ZERO,
+    };
+    let max = callbacks::max_flash_loan(None, uncapped.supply);
+    assert!(uncapped.loan(max, U256::ZERO, max));
+    assert!(!uncapped.loan(max, U256::from(1u64), U256::MAX));
+    assert_eq!((uncapped.supply, uncapped.balance), (U256::from(1u64), U256::ZERO));
+}
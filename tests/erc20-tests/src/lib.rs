@@ -13,10 +13,202 @@ const ALICE: &str = "AU1aliceAddress1234567890123456789012345678901234";
 const BOB: &str = "AU1bobAddress12345678901234567890123456789012345";
 const CHARLIE: &str = "AU1charlieAddress12345678901234567890123456789012";
 
+/// Path to the release WASM of a contract package, building it first.
+///
+/// The package is rebuilt with `cargo build --release --target wasm32v1-none`
+/// when its artifact is missing or older than any file under `contracts/` or
+/// `crates/mrc20-core/`, since the contracts share source files. Builds are serialized so parallel
+/// tests do not start the same build twice.
+fn contract_wasm(package: &str) -> std::path::PathBuf {
+    static BUILD: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let _guard = BUILD.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    let artifact = root
+        .join("target/wasm32v1-none/release")
+        .join(format!("{}.wasm", package.replace('-', "_")));
+    let built_at = std::fs::metadata(&artifact).and_then(|metadata| metadata.modified()).ok();
+    let sources = newest_modification(&root.join("contracts"));
+    let sources = sources.max(newest_modification(&root.join("crates/mrc20-core")));
+    if built_at.is_some_and(|built_at| built_at >= sources) {
+        return artifact;
+    }
+
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let status = std::process::Command::new(cargo)
+        .current_dir(&root)
+        .args(["build", "--release", "--target", "wasm32v1-none", "-p", package])
+        .status()
+        .unwrap_or_else(|err| panic!("Failed to run cargo to build {}: {}", package, err));
+    assert!(status.success(), "Building {} for wasm32v1-none failed", package);
+    artifact
+}
+
+/// Latest modification time of any file under `dir`
+fn newest_modification(dir: &std::path::Path) -> std::time::SystemTime {
+    let mut newest = std::time::SystemTime::UNIX_EPOCH;
+    for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let modified = if path.is_dir() {
+            newest_modification(&path)
+        } else {
+            entry.metadata().and_then(|metadata| metadata.modified()).unwrap_or(newest)
+        };
+        newest = newest.max(modified);
+    }
+    newest
+}
+
 /// Helper to build WASM path
 fn wasm_path() -> std::path::PathBuf {
-    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("../../target/wasm32v1-none/release/erc20_token.wasm")
+    contract_wasm("erc20-token")
+}
+
+/// Helper to build the mock receiver WASM path
+fn receiver_wasm_path() -> std::path::PathBuf {
+    contract_wasm("token-receiver-mock")
+}
+
+/// Helper to build the datastore writer mock WASM path
+fn datastore_writer_wasm_path() -> std::path::PathBuf {
+    contract_wasm("datastore-writer-mock")
+}
+
+/// Helper to build the wMAS WASM path
+fn wmas_wasm_path() -> std::path::PathBuf {
+    contract_wasm("wmas")
+}
+
+/// Helper to build the merkle airdrop WASM path
+fn merkle_airdrop_wasm_path() -> std::path::PathBuf {
+    contract_wasm("merkle-airdrop")
+}
+
+/// Helper to build the staking WASM path
+fn staking_wasm_path() -> std::path::PathBuf {
+    contract_wasm("staking")
+}
+
+/// Helper to build the governor WASM path
+fn governor_wasm_path() -> std::path::PathBuf {
+    contract_wasm("governor")
+}
+
+/// Helper to build the timelock WASM path
+fn timelock_wasm_path() -> std::path::PathBuf {
+    contract_wasm("timelock")
+}
+
+/// Helper to build the multisig wallet WASM path
+fn multisig_wasm_path() -> std::path::PathBuf {
+    contract_wasm("multisig")
+}
+
+/// Helper to build the token factory WASM path
+fn token_factory_wasm_path() -> std::path::PathBuf {
+    contract_wasm("token-factory")
+}
+
+/// Helper to build the bridge WASM path
+fn bridge_wasm_path() -> std::path::PathBuf {
+    contract_wasm("bridge")
+}
+
+/// Helper to build the faucet WASM path
+fn faucet_wasm_path() -> std::path::PathBuf {
+    contract_wasm("faucet")
+}
+
+/// Helper to build the vesting wallet WASM path
+fn vesting_wallet_wasm_path() -> std::path::PathBuf {
+    contract_wasm("vesting-wallet")
+}
+
+/// Helper to build the vesting factory WASM path
+fn vesting_factory_wasm_path() -> std::path::PathBuf {
+    contract_wasm("vesting-factory")
+}
+
+/// Keccak256 of `data`, matching the contract's hashing
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    use sha3::{Digest, Keccak256};
+    Keccak256::digest(data).into()
+}
+
+/// Airdrop leaf: keccak256(index || amount || address)
+fn airdrop_leaf(index: u64, account: &str, amount: U256) -> [u8; 32] {
+    let mut data = index.to_le_bytes().to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(account.as_bytes());
+    keccak256(&data)
+}
+
+/// Build a merkle tree over `leaves` with sorted pair hashing, returning the
+/// root and the concatenated proof of every leaf. An odd node is carried up as is.
+fn airdrop_tree(leaves: &[[u8; 32]]) -> ([u8; 32], Vec<Vec<u8>>) {
+    let mut proofs = vec![Vec::new(); leaves.len()];
+    let mut positions: Vec<usize> = (0..leaves.len()).collect();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        for (proof, position) in proofs.iter_mut().zip(positions.iter_mut()) {
+            let sibling = *position ^ 1;
+            if sibling < level.len() {
+                proof.extend_from_slice(&level[sibling]);
+            }
+            *position /= 2;
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => {
+                    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+                    let mut data = first.to_vec();
+                    data.extend_from_slice(second);
+                    keccak256(&data)
+                }
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    (level[0], proofs)
+}
+
+/// Run a call that must fail and return its error, e.g. `ERR:{CODE}:{fields}:{message}`
+fn execute_expect_failure(runtime: &TestRuntime, wasm: &[u8], function: &str, args: &[u8]) -> String {
+    match runtime.execute(wasm, function, args) {
+        Ok(response) => panic!("{} must fail, returned {:?}", function, response.ret),
+        Err(err) => format!("{:?}", err),
+    }
+}
+
+/// Number of events emitted so far, to scope later checks to the calls that follow
+fn event_mark(runtime: &TestRuntime) -> usize {
+    runtime.interface.events().len()
+}
+
+/// Events emitted since `mark`
+fn events_since(runtime: &TestRuntime, mark: usize) -> Vec<String> {
+    runtime.interface.events().iter().skip(mark).cloned().collect()
+}
+
+/// Events of `events` named `name`, the part before the first `:`
+fn find_events<'a>(events: &'a [String], name: &str) -> Vec<&'a str> {
+    events
+        .iter()
+        .map(String::as_str)
+        .filter(|event| event.split_once(':').map_or(*event, |(event_name, _)| event_name) == name)
+        .collect()
+}
+
+/// Fields of a `NAME:key=value,...` event by key; parts without `=` are skipped
+fn event_fields(event: &str) -> std::collections::HashMap<&str, &str> {
+    event
+        .split_once(':')
+        .map_or("", |(_, fields)| fields)
+        .split(',')
+        .filter_map(|field| field.split_once('='))
+        .collect()
 }
 
 /// Helper to create constructor args with U256
@@ -29,31 +221,813 @@ fn constructor_args(name: &str, symbol: &str, decimals: u8, initial_supply: U256
     args.into_bytes()
 }
 
+/// Helper to create constructor args with a supply cap
+fn constructor_args_with_cap(
+    name: &str,
+    symbol: &str,
+    decimals: u8,
+    initial_supply: U256,
+    cap: U256,
+) -> Vec<u8> {
+    let mut args = Args::new();
+    args.add_string(name)
+        .add_string(symbol)
+        .add_u8(decimals)
+        .add_u256(initial_supply)
+        .add_u256(cap);
+    args.into_bytes()
+}
+
+/// Typed decoding of a raw return value, e.g. `response.ret.as_u256()?`
+///
+/// Integers must be exactly 32, 8 or 4 bytes and are read with `massa_types::Args`,
+/// so they follow the same rules as `next_u256` / `next_u64` / `next_u32`. Strings and
+/// booleans are raw, as the contract returns them.
+trait ReturnValue {
+    fn as_u256(&self) -> Result<U256>;
+    fn as_u64(&self) -> Result<u64>;
+    fn as_u32(&self) -> Result<u32>;
+    fn as_string(&self) -> Result<String>;
+    fn as_bool(&self) -> Result<bool>;
+    fn as_args(&self) -> Args;
+}
+
+impl ReturnValue for [u8] {
+    fn as_u256(&self) -> Result<U256> {
+        anyhow::ensure!(self.len() == 32, "Expected a u256 return value, got {:?}", self);
+        Ok(self.as_args().next_u256()?)
+    }
+
+    fn as_u64(&self) -> Result<u64> {
+        anyhow::ensure!(self.len() == 8, "Expected a u64 return value, got {:?}", self);
+        Ok(self.as_args().next_u64()?)
+    }
+
+    fn as_u32(&self) -> Result<u32> {
+        anyhow::ensure!(self.len() == 4, "Expected a u32 return value, got {:?}", self);
+        Ok(self.as_args().next_u32()?)
+    }
+
+    fn as_string(&self) -> Result<String> {
+        Ok(String::from_utf8(self.to_vec())?)
+    }
+
+    /// A single byte, 1 for true and 0 for false
+    fn as_bool(&self) -> Result<bool> {
+        match self {
+            [1] => Ok(true),
+            [0] => Ok(false),
+            _ => anyhow::bail!("Expected a bool return value, got {:?}", self),
+        }
+    }
+
+    fn as_args(&self) -> Args {
+        Args::from_bytes(self.to_vec())
+    }
+}
+
+/// Write a raw datastore entry at the top of the call stack through the writer mock
+fn set_raw_entry(runtime: &TestRuntime, writer_wasm: &[u8], key: &[u8], value: &[u8]) -> Result<()> {
+    let mut entry_args = Args::new();
+    entry_args.add_bytes(key).add_bytes(value);
+    runtime.execute(writer_wasm, "setEntry", &entry_args.into_bytes())?;
+    Ok(())
+}
+
+/// Whether a raw datastore entry exists at the top of the call stack
+fn has_raw_entry(runtime: &TestRuntime, writer_wasm: &[u8], key: &[u8]) -> Result<bool> {
+    let mut key_args = Args::new();
+    key_args.add_bytes(key);
+    runtime.execute(writer_wasm, "hasEntry", &key_args.into_bytes())?.ret.as_bool()
+}
+
+/// Value of a raw datastore entry at the top of the call stack, exactly as stored
+fn get_raw_entry(runtime: &TestRuntime, writer_wasm: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    let mut key_args = Args::new();
+    key_args.add_bytes(key);
+    Ok(runtime.execute(writer_wasm, "getEntry", &key_args.into_bytes())?.ret)
+}
+
+/// Raw datastore keys starting with `prefix` at the top of the call stack, sorted
+fn list_raw_keys(runtime: &TestRuntime, writer_wasm: &[u8], prefix: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut prefix_args = Args::new();
+    prefix_args.add_bytes(prefix);
+    let mut ret = runtime.execute(writer_wasm, "listKeys", &prefix_args.into_bytes())?.ret.as_args();
+    let count = ret.next_u32()?;
+    let mut keys = (0..count).map(|_| ret.next_bytes()).collect::<Result<Vec<_>, _>>()?;
+    keys.sort();
+    Ok(keys)
+}
+
+/// Balance key as stored: "BALANCE" + address, as in the AssemblyScript token
+fn balance_key(address: &str) -> Vec<u8> {
+    [b"BALANCE".as_slice(), address.as_bytes()].concat()
+}
+
+/// Allowance key as stored: "ALLOWANCE" + owner + spender, as in the AssemblyScript token
+fn allowance_key(owner: &str, spender: &str) -> Vec<u8> {
+    [b"ALLOWANCE".as_slice(), owner.as_bytes(), spender.as_bytes()].concat()
+}
+
+/// The MRC20 token deployed in its own runtime, with typed wrappers around its entrypoints.
+///
+/// State-changing calls take the calling account; read-only calls run without one.
+struct TokenHarness {
+    wasm: Vec<u8>,
+    /// datastore-writer-mock, run at the token's address to inspect its raw storage
+    writer_wasm: Vec<u8>,
+    runtime: TestRuntime,
+}
+
+impl TokenHarness {
+    /// Load the token in a fresh runtime without running its constructor
+    fn new() -> Result<Self> {
+        Ok(TokenHarness {
+            wasm: std::fs::read(wasm_path())?,
+            writer_wasm: std::fs::read(datastore_writer_wasm_path())?,
+            runtime: TestRuntime::new(),
+        })
+    }
+
+    /// Deploy the token as DEPLOYER, who receives the whole supply
+    fn deploy(name: &str, symbol: &str, decimals: u8, supply: U256) -> Result<Self> {
+        Self::deploy_with(&constructor_args(name, symbol, decimals, supply))
+    }
+
+    /// Deploy the token as DEPLOYER with a supply cap
+    fn deploy_with_cap(name: &str, symbol: &str, decimals: u8, supply: U256, cap: U256) -> Result<Self> {
+        Self::deploy_with(&constructor_args_with_cap(name, symbol, decimals, supply, cap))
+    }
+
+    /// Deploy the token as DEPLOYER with raw constructor arguments
+    fn deploy_with(args: &[u8]) -> Result<Self> {
+        let harness = Self::new()?;
+        harness.call(DEPLOYER, "constructor", args)?;
+        Ok(harness)
+    }
+
+    /// Call `function` as `caller` and return its raw result
+    fn call(&self, caller: &str, function: &str, args: &[u8]) -> Result<Vec<u8>> {
+        self.runtime
+            .interface
+            .set_call_stack(vec![caller.to_string(), "AS_CONTRACT".to_string()]);
+        Ok(self.runtime.execute(&self.wasm, function, args)?.ret)
+    }
+
+    /// Call a read-only `function` and return its raw result
+    fn query(&self, function: &str, args: &[u8]) -> Result<Vec<u8>> {
+        self.runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
+        Ok(self.runtime.execute(&self.wasm, function, args)?.ret)
+    }
+
+    /// Call `function` as `caller`, which must fail, and return its error
+    fn call_expect_failure(&self, caller: &str, function: &str, args: &[u8]) -> String {
+        self.runtime
+            .interface
+            .set_call_stack(vec![caller.to_string(), "AS_CONTRACT".to_string()]);
+        execute_expect_failure(&self.runtime, &self.wasm, function, args)
+    }
+
+    fn name(&self) -> Result<String> {
+        self.query("name", &[])?.as_string()
+    }
+
+    fn symbol(&self) -> Result<String> {
+        self.query("symbol", &[])?.as_string()
+    }
+
+    fn decimals(&self) -> Result<u8> {
+        match self.query("decimals", &[])?.as_slice() {
+            [decimals] => Ok(*decimals),
+            ret => anyhow::bail!("Expected a u8 return value, got {:?}", ret),
+        }
+    }
+
+    fn total_supply(&self) -> Result<U256> {
+        self.query("totalSupply", &[])?.as_u256()
+    }
+
+    fn balance_of(&self, address: &str) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_string(address);
+        self.query("balanceOf", &args.into_bytes())?.as_u256()
+    }
+
+    fn allowance(&self, owner: &str, spender: &str) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_string(owner).add_string(spender);
+        self.query("allowance", &args.into_bytes())?.as_u256()
+    }
+
+    fn transfer(&self, from: &str, to: &str, amount: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(to).add_u256(amount);
+        self.call(from, "transfer", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn transfer_from(&self, spender: &str, owner: &str, to: &str, amount: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(owner).add_string(to).add_u256(amount);
+        self.call(spender, "transferFrom", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn increase_allowance(&self, owner: &str, spender: &str, amount: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(spender).add_u256(amount);
+        self.call(owner, "increaseAllowance", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn decrease_allowance(&self, owner: &str, spender: &str, amount: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(spender).add_u256(amount);
+        self.call(owner, "decreaseAllowance", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn grant_role(&self, caller: &str, role: &str, account: &str) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(role).add_string(account);
+        self.call(caller, "grantRole", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn approve_with_deadline(&self, owner: &str, spender: &str, amount: U256, expiry_period: u64) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(spender).add_u256(amount).add_u64(expiry_period);
+        self.call(owner, "approveWithDeadline", &args.into_bytes())?;
+        Ok(())
+    }
+
+    /// Swap the allowance to `amount` if it is still `expected`; returns whether it did and the
+    /// allowance after the call
+    fn approve_if_current(&self, owner: &str, spender: &str, expected: U256, amount: U256) -> Result<(bool, U256)> {
+        let mut args = Args::new();
+        args.add_string(spender).add_u256(expected).add_u256(amount);
+        let mut ret = self.call(owner, "approveIfCurrent", &args.into_bytes())?.as_args();
+        Ok((ret.next_bool()?, ret.next_u256()?))
+    }
+
+    fn allowance_expiry(&self, owner: &str, spender: &str) -> Result<u64> {
+        let mut args = Args::new();
+        args.add_string(owner).add_string(spender);
+        self.query("allowanceExpiry", &args.into_bytes())?.as_u64()
+    }
+
+    fn mint(&self, minter: &str, to: &str, amount: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(to).add_u256(amount);
+        self.call(minter, "mint", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn bridge_mint(&self, bridge: &str, to: &str, amount: U256, source_tx_id: &str) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(to).add_u256(amount).add_string(source_tx_id);
+        self.call(bridge, "bridgeMint", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn burn(&self, from: &str, amount: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u256(amount);
+        self.call(from, "burn", &args.into_bytes())?;
+        Ok(())
+    }
+
+    /// Decommission as `caller`, deleting up to `limit` keys, and return how many are left
+    fn decommission(&self, caller: &str, limit: u32) -> Result<u64> {
+        let mut args = Args::new();
+        args.add_u32(limit);
+        self.call(caller, "decommission", &args.into_bytes())?.as_u64()
+    }
+
+    fn decommissioned(&self) -> Result<bool> {
+        self.query("decommissioned", &[])?.as_bool()
+    }
+
+    fn pause(&self, caller: &str) -> Result<()> {
+        self.call(caller, "pause", &[])?;
+        Ok(())
+    }
+
+    fn unpause(&self, caller: &str) -> Result<()> {
+        self.call(caller, "unpause", &[])?;
+        Ok(())
+    }
+
+    fn paused(&self) -> Result<bool> {
+        self.query("paused", &[])?.as_bool()
+    }
+
+    fn freeze_account(&self, caller: &str, account: &str) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(account);
+        self.call(caller, "freezeAccount", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn unfreeze_account(&self, caller: &str, account: &str) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(account);
+        self.call(caller, "unfreezeAccount", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn is_frozen(&self, account: &str) -> Result<bool> {
+        let mut args = Args::new();
+        args.add_string(account);
+        self.query("isFrozen", &args.into_bytes())?.as_bool()
+    }
+
+    /// Supply cap, `None` when the token is uncapped
+    fn cap(&self) -> Result<Option<U256>> {
+        let ret = self.query("cap", &[])?;
+        if ret.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(ret.as_u256()?))
+    }
+
+    fn set_cap(&self, caller: &str, cap: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u256(cap);
+        self.call(caller, "setCap", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn propose_owner(&self, caller: &str, new_owner: &str) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(new_owner);
+        self.call(caller, "proposeOwner", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn accept_ownership(&self, caller: &str) -> Result<()> {
+        self.call(caller, "acceptOwnership", &[])?;
+        Ok(())
+    }
+
+    fn owner_address(&self) -> Result<String> {
+        self.query("ownerAddress", &[])?.as_string()
+    }
+
+    /// Proposed owner, `None` when no transfer is pending
+    fn pending_owner(&self) -> Result<Option<String>> {
+        let ret = self.query("pendingOwner", &[])?;
+        if ret.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(ret.as_string()?))
+    }
+
+    fn has_role(&self, role: &str, account: &str) -> Result<bool> {
+        let mut args = Args::new();
+        args.add_string(role).add_string(account);
+        self.query("hasRole", &args.into_bytes())?.as_bool()
+    }
+
+    fn revoke_role(&self, caller: &str, role: &str, account: &str) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(role).add_string(account);
+        self.call(caller, "revokeRole", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn nonces(&self, owner: &str) -> Result<u64> {
+        let mut args = Args::new();
+        args.add_string(owner);
+        self.query("nonces", &args.into_bytes())?.as_u64()
+    }
+
+    fn authorization_state(&self, authorizer: &str, nonce: &str) -> Result<bool> {
+        let mut args = Args::new();
+        args.add_string(authorizer).add_string(nonce);
+        self.query("authorizationState", &args.into_bytes())?.as_bool()
+    }
+
+    /// Take a snapshot as `caller` and return its id
+    fn snapshot(&self, caller: &str) -> Result<u64> {
+        self.call(caller, "snapshot", &[])?.as_u64()
+    }
+
+    fn balance_of_at(&self, address: &str, snapshot_id: u64) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_string(address).add_u64(snapshot_id);
+        self.query("balanceOfAt", &args.into_bytes())?.as_u256()
+    }
+
+    fn total_supply_at(&self, snapshot_id: u64) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_u64(snapshot_id);
+        self.query("totalSupplyAt", &args.into_bytes())?.as_u256()
+    }
+
+    fn max_flash_loan(&self) -> Result<U256> {
+        self.query("maxFlashLoan", &[])?.as_u256()
+    }
+
+    fn flash_fee(&self, amount: U256) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_u256(amount);
+        self.query("flashFee", &args.into_bytes())?.as_u256()
+    }
+
+    fn set_flash_fee(&self, caller: &str, fee: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u256(fee);
+        self.call(caller, "setFlashFee", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn create_vesting_schedule(
+        &self,
+        caller: &str,
+        beneficiary: &str,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+        amount: U256,
+    ) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(beneficiary)
+            .add_u64(start)
+            .add_u64(cliff)
+            .add_u64(duration)
+            .add_u256(amount);
+        self.call(caller, "createVestingSchedule", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn releasable_amount(&self, beneficiary: &str) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_string(beneficiary);
+        self.query("releasableAmount", &args.into_bytes())?.as_u256()
+    }
+
+    /// Release the vested part of `beneficiary`'s schedule
+    fn release(&self, beneficiary: &str) -> Result<()> {
+        self.call(beneficiary, "release", &[])?;
+        Ok(())
+    }
+
+    fn lock_tokens(&self, caller: &str, address: &str, amount: U256, until_period: u64) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(address).add_u256(amount).add_u64(until_period);
+        self.call(caller, "lockTokens", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn unlock_tokens(&self, caller: &str, address: &str, amount: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(address).add_u256(amount);
+        self.call(caller, "unlockTokens", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn locked_balance_of(&self, address: &str) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_string(address);
+        self.query("lockedBalanceOf", &args.into_bytes())?.as_u256()
+    }
+
+    fn transferable_balance_of(&self, address: &str) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_string(address);
+        self.query("transferableBalanceOf", &args.into_bytes())?.as_u256()
+    }
+
+    fn rescue_token(&self, caller: &str, token_address: &str, to: &str, amount: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(token_address).add_string(to).add_u256(amount);
+        self.call(caller, "rescueToken", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn token_uri(&self) -> Result<String> {
+        self.query("tokenURI", &[])?.as_string()
+    }
+
+    fn description(&self) -> Result<String> {
+        self.query("description", &[])?.as_string()
+    }
+
+    fn set_token_uri(&self, caller: &str, uri: &str) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(uri);
+        self.call(caller, "setTokenURI", &args.into_bytes())?;
+        Ok(())
+    }
+
+    /// Mint to every `(recipient, amount)` of `batch` in one call
+    fn mint_batch(&self, minter: &str, batch: &[(&str, U256)]) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u32(batch.len() as u32);
+        for (recipient, amount) in batch {
+            args.add_string(recipient).add_u256(*amount);
+        }
+        self.call(minter, "mintBatch", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn set_spending_limit(
+        &self,
+        owner: &str,
+        spender: &str,
+        amount_per_period: U256,
+        period_length: u64,
+    ) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(spender).add_u256(amount_per_period).add_u64(period_length);
+        self.call(owner, "setSpendingLimit", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn remaining_spending_limit(&self, owner: &str, spender: &str) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_string(owner).add_string(spender);
+        self.query("remainingSpendingLimit", &args.into_bytes())?.as_u256()
+    }
+
+    fn allowlist_mode(&self) -> Result<bool> {
+        self.query("allowlistMode", &[])?.as_bool()
+    }
+
+    fn add_to_allowlist(&self, caller: &str, address: &str) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(address);
+        self.call(caller, "addToAllowlist", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn remove_from_allowlist(&self, caller: &str, address: &str) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(address);
+        self.call(caller, "removeFromAllowlist", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn is_allowlisted(&self, address: &str) -> Result<bool> {
+        let mut args = Args::new();
+        args.add_string(address);
+        self.query("isAllowlisted", &args.into_bytes())?.as_bool()
+    }
+
+    fn set_max_tx_amount(&self, caller: &str, max_tx_amount: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u256(max_tx_amount);
+        self.call(caller, "setMaxTxAmount", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn set_max_wallet_balance(&self, caller: &str, max_wallet_balance: U256) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u256(max_wallet_balance);
+        self.call(caller, "setMaxWalletBalance", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn set_transfer_cooldown(&self, caller: &str, cooldown_periods: u64) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u64(cooldown_periods);
+        self.call(caller, "setTransferCooldown", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn transfer_cooldown_periods(&self) -> Result<u64> {
+        self.query("transferCooldownPeriods", &[])?.as_u64()
+    }
+
+    fn set_limit_exempt(&self, caller: &str, address: &str, exempt: bool) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(address).add_bool(exempt);
+        self.call(caller, "setLimitExempt", &args.into_bytes())?;
+        Ok(())
+    }
+
+    /// Storage layout version the token's datastore is at
+    fn storage_version(&self) -> Result<u32> {
+        self.query("storageVersion", &[])?.as_u32()
+    }
+
+    fn migrate(&self, caller: &str) -> Result<()> {
+        self.call(caller, "migrate", &[])?;
+        Ok(())
+    }
+
+    fn holder_count(&self) -> Result<u64> {
+        self.query("holderCount", &[])?.as_u64()
+    }
+
+    /// Page of up to `limit` holders starting at `offset`
+    fn holders(&self, offset: u64, limit: u32) -> Result<Vec<String>> {
+        let mut args = Args::new();
+        args.add_u64(offset).add_u32(limit);
+        let mut page = self.query("holders", &args.into_bytes())?.as_args();
+        let count = page.next_u32()?;
+        Ok((0..count).map(|_| page.next_string()).collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Page of up to `limit` `(spender, allowance)` pairs of `owner` starting at `offset`
+    fn allowances_of(&self, owner: &str, offset: u64, limit: u32) -> Result<Vec<(String, U256)>> {
+        let mut args = Args::new();
+        args.add_string(owner).add_u64(offset).add_u32(limit);
+        let mut page = self.query("allowancesOf", &args.into_bytes())?.as_args();
+        let count = page.next_u32()?;
+        let mut allowances = Vec::new();
+        for _ in 0..count {
+            allowances.push((page.next_string()?, page.next_u256()?));
+        }
+        Ok(allowances)
+    }
+
+    fn features(&self) -> Result<Vec<String>> {
+        let mut features = self.query("features", &[])?.as_args();
+        let count = features.next_u32()?;
+        Ok((0..count).map(|_| features.next_string()).collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn supports_interface(&self, interface_id: &str) -> Result<bool> {
+        let mut args = Args::new();
+        args.add_string(interface_id);
+        self.query("supportsInterface", &args.into_bytes())?.as_bool()
+    }
+
+    fn multisig_threshold(&self) -> Result<u32> {
+        self.query("multisigThreshold", &[])?.as_u32()
+    }
+
+    fn multisig_owner_count(&self) -> Result<u32> {
+        self.query("multisigOwnerCount", &[])?.as_u32()
+    }
+
+    fn add_owner(&self, caller: &str, address: &str) -> Result<()> {
+        let mut args = Args::new();
+        args.add_string(address);
+        self.call(caller, "addOwner", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn set_threshold(&self, caller: &str, threshold: u32) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u32(threshold);
+        self.call(caller, "setThreshold", &args.into_bytes())?;
+        Ok(())
+    }
+
+    /// Propose calling `operation` with `operation_args` as a multisig owner and return the proposal id
+    fn propose_operation(&self, caller: &str, operation: &str, operation_args: &[u8]) -> Result<u64> {
+        let mut args = Args::new();
+        args.add_string(operation).add_bytes(operation_args);
+        self.call(caller, "proposeOperation", &args.into_bytes())?.as_u64()
+    }
+
+    fn confirm_operation(&self, caller: &str, id: u64) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u64(id);
+        self.call(caller, "confirmOperation", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn is_confirmed(&self, id: u64, owner: &str) -> Result<bool> {
+        let mut args = Args::new();
+        args.add_u64(id).add_string(owner);
+        self.query("isConfirmed", &args.into_bytes())?.as_bool()
+    }
+
+    /// Encoded proposal: operation, args, confirmations and whether it was executed
+    fn proposal(&self, id: u64) -> Result<Args> {
+        let mut args = Args::new();
+        args.add_u64(id);
+        Ok(self.query("proposal", &args.into_bytes())?.as_args())
+    }
+
+    /// Subscribe `payer` to pay `payee` every `interval_periods` and return the subscription id
+    fn create_subscription(
+        &self,
+        payer: &str,
+        payee: &str,
+        amount_per_interval: U256,
+        interval_periods: u64,
+    ) -> Result<u64> {
+        let mut args = Args::new();
+        args.add_string(payee).add_u256(amount_per_interval).add_u64(interval_periods);
+        self.call(payer, "createSubscription", &args.into_bytes())?.as_u64()
+    }
+
+    /// Run a subscription payment as the contract's own deferred call
+    fn process_subscription(&self, id: u64) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u64(id);
+        self.call("AS_CONTRACT", "processSubscription", &args.into_bytes())?;
+        Ok(())
+    }
+
+    fn cancel_subscription(&self, caller: &str, id: u64) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u64(id);
+        self.call(caller, "cancelSubscription", &args.into_bytes())?;
+        Ok(())
+    }
+
+    /// Whether a subscription is still active, from `subscriptionStatus`
+    fn subscription_active(&self, id: u64) -> Result<bool> {
+        let mut args = Args::new();
+        args.add_u64(id);
+        let mut status = self.query("subscriptionStatus", &args.into_bytes())?.as_args();
+        status.next_string()?;
+        status.next_string()?;
+        status.next_u256()?;
+        for _ in 0..4 {
+            status.next_u64()?;
+        }
+        Ok(status.next_bool()?)
+    }
+
+    /// Stream `total_amount` from `sender` to `to` between two periods and return the stream id
+    fn create_stream(
+        &self,
+        sender: &str,
+        to: &str,
+        total_amount: U256,
+        start_period: u64,
+        end_period: u64,
+    ) -> Result<u64> {
+        let mut args = Args::new();
+        args.add_string(to).add_u256(total_amount).add_u64(start_period).add_u64(end_period);
+        self.call(sender, "createStream", &args.into_bytes())?.as_u64()
+    }
+
+    fn stream_withdrawable(&self, id: u64) -> Result<U256> {
+        let mut args = Args::new();
+        args.add_u64(id);
+        self.query("streamWithdrawable", &args.into_bytes())?.as_u256()
+    }
+
+    fn cancel_stream(&self, caller: &str, id: u64) -> Result<()> {
+        let mut args = Args::new();
+        args.add_u64(id);
+        self.call(caller, "cancelStream", &args.into_bytes())?;
+        Ok(())
+    }
+
+    /// Encoded stream: sender, recipient, total amount, start and end periods, withdrawn amount
+    /// and whether it was cancelled
+    fn stream(&self, id: u64) -> Result<Args> {
+        let mut args = Args::new();
+        args.add_u64(id);
+        Ok(self.query("stream", &args.into_bytes())?.as_args())
+    }
+
+    fn events(&self) -> Vec<String> {
+        self.runtime.interface.events()
+    }
+
+    /// Value of a raw entry of the token's datastore
+    fn raw_entry(&self, key: &[u8]) -> Result<Vec<u8>> {
+        self.runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
+        get_raw_entry(&self.runtime, &self.writer_wasm, key)
+    }
+
+    /// Write a raw entry to the token's datastore
+    fn set_raw_entry(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
+        set_raw_entry(&self.runtime, &self.writer_wasm, key, value)
+    }
+
+    /// Whether the token's datastore has a raw entry at `key`
+    fn has_raw_entry(&self, key: &[u8]) -> Result<bool> {
+        self.runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
+        has_raw_entry(&self.runtime, &self.writer_wasm, key)
+    }
+
+    /// Keys of the token's datastore starting with `prefix`, sorted
+    fn raw_keys(&self, prefix: &[u8]) -> Result<Vec<Vec<u8>>> {
+        self.runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
+        list_raw_keys(&self.runtime, &self.writer_wasm, prefix)
+    }
+}
+
 #[test]
 fn test_constructor() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
-    let runtime = TestRuntime::new();
-
-    // Set up call stack for deployment context
-    runtime
-        .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
+    let token = TokenHarness::new()?;
 
     let initial_supply = U256::from(1_000_000u64);
     let args = constructor_args("TestToken", "TTK", 18, initial_supply);
-    let response = runtime.execute(&wasm, "constructor", &args)?;
+    let response = token.call(DEPLOYER, "constructor", &args)?;
 
     // Check events
-    let events = runtime.interface.events();
-    assert!(events.len() >= 1, "Expected at least 1 event");
-    assert!(
-        events[0].contains("CHANGE_OWNER"),
-        "Expected CHANGE_OWNER event"
-    );
-    assert!(
-        events[0].contains(DEPLOYER),
-        "Expected deployer address in event"
-    );
+    let events = token.events();
+    assert_eq!(find_events(&events, "CHANGE_OWNER"), [format!("CHANGE_OWNER:{}", DEPLOYER)]);
+
+    // The initial supply is reported as a mint so indexers see the deployer's balance
+    let mints = find_events(&events, "MINT SUCCESS");
+    assert_eq!(mints.len(), 1, "Expected one MINT SUCCESS event: {:?}", events);
+    let fields = event_fields(mints[0]);
+    assert_eq!(fields["to"], DEPLOYER);
+    assert_eq!(fields["amount"], initial_supply.to_string());
 
     println!("Constructor events: {:?}", events);
     println!("Response: {:?}", response);
@@ -63,21 +1037,9 @@ fn test_constructor() -> Result<()> {
 
 #[test]
 fn test_name() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
-    let runtime = TestRuntime::new();
-
-    // Set up deployment
-    runtime
-        .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let args = constructor_args("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64));
-    runtime.execute(&wasm, "constructor", &args)?;
-
-    // Call name()
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
-    let response = runtime.execute(&wasm, "name", &[])?;
-    let name = String::from_utf8(response.ret.clone())?;
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
 
+    let name = token.name()?;
     assert_eq!(name, "MassaCoin");
     println!("Token name: {}", name);
 
@@ -86,21 +1048,9 @@ fn test_name() -> Result<()> {
 
 #[test]
 fn test_symbol() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
-    let runtime = TestRuntime::new();
-
-    // Set up deployment
-    runtime
-        .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let args = constructor_args("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64));
-    runtime.execute(&wasm, "constructor", &args)?;
-
-    // Call symbol()
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
-    let response = runtime.execute(&wasm, "symbol", &[])?;
-    let symbol = String::from_utf8(response.ret.clone())?;
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
 
+    let symbol = token.symbol()?;
     assert_eq!(symbol, "MCOIN");
     println!("Token symbol: {}", symbol);
 
@@ -109,21 +1059,9 @@ fn test_symbol() -> Result<()> {
 
 #[test]
 fn test_decimals() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
-    let runtime = TestRuntime::new();
-
-    // Set up deployment
-    runtime
-        .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let args = constructor_args("MassaCoin", "MCOIN", 9, U256::from(1_000_000u64));
-    runtime.execute(&wasm, "constructor", &args)?;
-
-    // Call decimals()
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
-    let response = runtime.execute(&wasm, "decimals", &[])?;
-    let decimals = response.ret[0];
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 9, U256::from(1_000_000u64))?;
 
+    let decimals = token.decimals()?;
     assert_eq!(decimals, 9);
     println!("Token decimals: {}", decimals);
 
@@ -132,25 +1070,10 @@ fn test_decimals() -> Result<()> {
 
 #[test]
 fn test_total_supply() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
-    let runtime = TestRuntime::new();
-
-    // Set up deployment
-    runtime
-        .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
     let initial_supply = U256::from(5_000_000u64);
-    let args = constructor_args("MassaCoin", "MCOIN", 18, initial_supply);
-    runtime.execute(&wasm, "constructor", &args)?;
-
-    // Call totalSupply()
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
-    let response = runtime.execute(&wasm, "totalSupply", &[])?;
-    
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let total_supply = U256::from_le_bytes(bytes);
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, initial_supply)?;
 
+    let total_supply = token.total_supply()?;
     assert_eq!(total_supply, initial_supply);
     println!("Total supply: {}", total_supply);
 
@@ -159,484 +1082,3192 @@ fn test_total_supply() -> Result<()> {
 
 #[test]
 fn test_balance_of() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
+    let initial_supply = U256::from(1_000_000u64);
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, initial_supply)?;
+
+    // The deployer holds the whole supply
+    let balance = token.balance_of(DEPLOYER)?;
+    assert_eq!(balance, initial_supply);
+    println!("Deployer balance: {}", balance);
+
+    // Check Alice balance (should be 0)
+    let alice_balance = token.balance_of(ALICE)?;
+    assert_eq!(alice_balance, U256::ZERO);
+    println!("Alice balance: {}", alice_balance);
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer() -> Result<()> {
+    let initial_supply = U256::from(1_000_000u64);
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, initial_supply)?;
+
+    // Transfer from deployer to Alice
+    let transfer_amount = U256::from(100_000u64);
+    let mark = event_mark(&token.runtime);
+    token.transfer(DEPLOYER, ALICE, transfer_amount)?;
+
+    // Check that the transfer emitted exactly its own event
+    let events = events_since(&token.runtime, mark);
+    let expected_event = format!(
+        "TRANSFER SUCCESS:from={},to={},amount={}",
+        DEPLOYER, ALICE, transfer_amount
+    );
+    assert_eq!(events, [expected_event]);
+    println!("Transfer event: {:?}", events);
+
+    // Check balances
+    let deployer_balance = token.balance_of(DEPLOYER)?;
+    let expected_deployer = initial_supply.checked_sub(transfer_amount).unwrap();
+    assert_eq!(deployer_balance, expected_deployer, "Deployer balance should decrease");
+
+    let alice_balance = token.balance_of(ALICE)?;
+    assert_eq!(alice_balance, transfer_amount, "Alice balance should increase");
+
+    println!("Deployer balance: {}, Alice balance: {}", deployer_balance, alice_balance);
+
+    Ok(())
+}
+
+#[test]
+fn test_increase_decrease_allowance() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Deployer increases allowance for Alice
+    let approve_amount = U256::from(50_000u64);
+    token.increase_allowance(DEPLOYER, ALICE, approve_amount)?;
+
+    let expected_event = format!(
+        "APPROVAL SUCCESS:owner={},spender={},amount={}",
+        DEPLOYER, ALICE, approve_amount
+    );
+    assert!(token.events().contains(&expected_event), "Expected approval event");
+
+    // Check allowance
+    let allowance = token.allowance(DEPLOYER, ALICE)?;
+    assert_eq!(allowance, approve_amount);
+    println!("Allowance from {} to {}: {}", DEPLOYER, ALICE, allowance);
+
+    // Decrease allowance
+    let decrease_amount = U256::from(20_000u64);
+    token.decrease_allowance(DEPLOYER, ALICE, decrease_amount)?;
+
+    let expected = approve_amount.checked_sub(decrease_amount).unwrap();
+    let expected_event = format!(
+        "APPROVAL SUCCESS:owner={},spender={},amount={}",
+        DEPLOYER, ALICE, expected
+    );
+    assert!(token.events().contains(&expected_event), "Expected approval event");
+
+    // Check new allowance
+    let new_allowance = token.allowance(DEPLOYER, ALICE)?;
+    assert_eq!(new_allowance, expected);
+    println!("New allowance: {}", new_allowance);
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_from() -> Result<()> {
+    let initial_supply = U256::from(1_000_000u64);
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, initial_supply)?;
+
+    // Deployer increases allowance for Alice
+    let approve_amount = U256::from(100_000u64);
+    token.increase_allowance(DEPLOYER, ALICE, approve_amount)?;
+
+    // Alice transfers from Deployer to Bob
+    let transfer_amount = U256::from(50_000u64);
+    token.transfer_from(ALICE, DEPLOYER, BOB, transfer_amount)?;
+
+    let expected_event = format!(
+        "TRANSFER SUCCESS:from={},to={},amount={}",
+        DEPLOYER, BOB, transfer_amount
+    );
+    assert!(token.events().contains(&expected_event), "Expected transfer event");
+
+    // Check balances
+    let deployer_balance = token.balance_of(DEPLOYER)?;
+    let expected_deployer = initial_supply.checked_sub(transfer_amount).unwrap();
+    assert_eq!(deployer_balance, expected_deployer);
+
+    let bob_balance = token.balance_of(BOB)?;
+    assert_eq!(bob_balance, transfer_amount);
+
+    // Check remaining allowance
+    let remaining_allowance = token.allowance(DEPLOYER, ALICE)?;
+    let expected_allowance = approve_amount.checked_sub(transfer_amount).unwrap();
+    assert_eq!(remaining_allowance, expected_allowance);
+
+    println!(
+        "Deployer: {}, Bob: {}, Remaining allowance: {}",
+        deployer_balance, bob_balance, remaining_allowance
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_mint() -> Result<()> {
+    let initial_supply = U256::from(1_000_000u64);
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, initial_supply)?;
+
+    // Mint tokens to Alice (owner only)
+    let mint_amount = U256::from(500_000u64);
+    token.mint(DEPLOYER, ALICE, mint_amount)?;
+
+    let expected_event = format!("MINT SUCCESS:to={},amount={}", ALICE, mint_amount);
+    assert!(token.events().contains(&expected_event), "Expected mint event");
+
+    // Check new total supply
+    let new_supply = token.total_supply()?;
+    let expected_supply = initial_supply.checked_add(mint_amount).unwrap();
+    assert_eq!(new_supply, expected_supply);
+
+    // Check Alice balance
+    let alice_balance = token.balance_of(ALICE)?;
+    assert_eq!(alice_balance, mint_amount);
+
+    println!("New total supply: {}, Alice balance: {}", new_supply, alice_balance);
+
+    Ok(())
+}
+
+#[test]
+fn test_burn() -> Result<()> {
+    let initial_supply = U256::from(1_000_000u64);
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, initial_supply)?;
+
+    // Deployer burns some tokens
+    let burn_amount = U256::from(200_000u64);
+    token.burn(DEPLOYER, burn_amount)?;
+
+    let expected_event = format!("BURN_SUCCESS:from={},amount={}", DEPLOYER, burn_amount);
+    assert!(token.events().contains(&expected_event), "Expected burn event");
+
+    // Check new total supply
+    let new_supply = token.total_supply()?;
+    let expected_supply = initial_supply.checked_sub(burn_amount).unwrap();
+    assert_eq!(new_supply, expected_supply);
+
+    // Check deployer balance
+    let deployer_balance = token.balance_of(DEPLOYER)?;
+    assert_eq!(deployer_balance, expected_supply);
+
+    println!("New total supply: {}, Deployer balance: {}", new_supply, deployer_balance);
+
+    Ok(())
+}
+
+#[test]
+fn test_full_transfer_flow() -> Result<()> {
+    println!("=== Full MRC20 Transfer Flow Test ===\n");
+
+    // Step 1: Deploy contract
+    println!("Step 1: Deploying MRC20 token...");
+    let initial_supply = U256::from(10_000_000u64);
+    let token = TokenHarness::deploy("MassaToken", "MASS", 18, initial_supply)?;
+    println!("  Deployed MassaToken (MASS) with initial supply: {}", initial_supply);
+
+    // Step 2: Check initial balances
+    println!("\nStep 2: Checking initial balances...");
+    let deployer_balance = token.balance_of(DEPLOYER)?;
+    println!("  Deployer balance: {}", deployer_balance);
+
+    // Step 3: Transfer to Alice
+    println!("\nStep 3: Deployer transfers 1,000,000 to Alice...");
+    token.transfer(DEPLOYER, ALICE, U256::from(1_000_000u64))?;
+
+    // Step 4: Alice transfers to Bob
+    println!("Step 4: Alice transfers 500,000 to Bob...");
+    token.transfer(ALICE, BOB, U256::from(500_000u64))?;
+
+    // Step 5: Bob approves Charlie
+    println!("Step 5: Bob approves Charlie to spend 200,000...");
+    token.increase_allowance(BOB, CHARLIE, U256::from(200_000u64))?;
+
+    // Step 6: Charlie transfers from Bob to Alice
+    println!("Step 6: Charlie transfers 100,000 from Bob to Alice...");
+    token.transfer_from(CHARLIE, BOB, ALICE, U256::from(100_000u64))?;
+
+    // Step 7: Final balances
+    println!("\nStep 7: Final balances:");
+    let expected = [
+        ("Deployer", DEPLOYER, 9_000_000u64),
+        ("Alice", ALICE, 600_000),
+        ("Bob", BOB, 400_000),
+        ("Charlie", CHARLIE, 0),
+    ];
+    for (label, address, amount) in expected {
+        let balance = token.balance_of(address)?;
+        println!("  {}: {}", label, balance);
+        assert_eq!(balance, U256::from(amount));
+    }
+
+    // Check remaining allowance
+    let allowance = token.allowance(BOB, CHARLIE)?;
+    println!("\n  Bob->Charlie allowance remaining: {}", allowance);
+    assert_eq!(allowance, U256::from(100_000u64));
+    assert_eq!(token.total_supply()?, initial_supply);
+
+    println!("\n=== Test completed successfully! ===");
+
+    Ok(())
+}
+
+/// Deterministic xorshift generator, so failures are reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Random value in `0..=max`
+    fn up_to(&mut self, max: u64) -> u64 {
+        self.next_u64() % (max + 1)
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[(self.next_u64() % items.len() as u64) as usize]
+    }
+}
+
+#[test]
+fn test_random_operations_keep_invariants() -> Result<()> {
+    const ACCOUNTS: [&str; 4] = [DEPLOYER, ALICE, BOB, CHARLIE];
+    const STEPS: usize = 100;
+
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Expected state, updated only by the calls the contract accepts
+    let mut balances: std::collections::HashMap<&str, u64> = ACCOUNTS.iter().map(|account| (*account, 0)).collect();
+    balances.insert(DEPLOYER, 1_000_000);
+    let mut allowances = std::collections::HashMap::new();
+    let mut supply = 1_000_000u64;
+
+    let mut rng = Rng(0x5eed_1234_abcd_0001);
+    for step in 0..STEPS {
+        let caller = rng.pick(&ACCOUNTS);
+        let other = rng.pick(&ACCOUNTS);
+        let target = rng.pick(&ACCOUNTS);
+        // Amounts up to 1.5x the caller's balance, so about a third of the calls must fail
+        let amount = rng.up_to(balances[caller] + balances[caller] / 2 + 1);
+
+        let (operation, amount, result, expected_ok) = match rng.next_u64() % 5 {
+            0 => {
+                let ok = amount <= balances[caller];
+                if ok {
+                    *balances.get_mut(caller).unwrap() -= amount;
+                    *balances.get_mut(target).unwrap() += amount;
+                }
+                ("transfer", amount, token.transfer(caller, target, U256::from(amount)), ok)
+            }
+            1 => {
+                *allowances.entry((caller, other)).or_insert(0u64) += amount;
+                let result = token.increase_allowance(caller, other, U256::from(amount));
+                ("increaseAllowance", amount, result, true)
+            }
+            2 => {
+                // `caller` spends `other`'s tokens
+                let allowance = allowances.get(&(other, caller)).copied().unwrap_or(0);
+                let amount = rng.up_to(allowance.max(balances[other]) + 1);
+                let ok = amount <= allowance && amount <= balances[other];
+                if ok {
+                    *allowances.get_mut(&(other, caller)).unwrap() -= amount;
+                    *balances.get_mut(other).unwrap() -= amount;
+                    *balances.get_mut(target).unwrap() += amount;
+                }
+                let result = token.transfer_from(caller, other, target, U256::from(amount));
+                ("transferFrom", amount, result, ok)
+            }
+            3 => {
+                // Only the deployer holds the MINTER role
+                let ok = caller == DEPLOYER;
+                if ok {
+                    supply += amount;
+                    *balances.get_mut(target).unwrap() += amount;
+                }
+                ("mint", amount, token.mint(caller, target, U256::from(amount)), ok)
+            }
+            _ => {
+                let ok = amount <= balances[caller];
+                if ok {
+                    supply -= amount;
+                    *balances.get_mut(caller).unwrap() -= amount;
+                }
+                ("burn", amount, token.burn(caller, U256::from(amount)), ok)
+            }
+        };
+        assert_eq!(
+            result.is_ok(),
+            expected_ok,
+            "Step {}: {} by {} of {}: {:?}",
+            step,
+            operation,
+            caller,
+            amount,
+            result
+        );
+
+        // Balances add up to the supply and match the model, failed calls change nothing
+        let mut total = U256::ZERO;
+        for account in ACCOUNTS {
+            let balance = token.balance_of(account)?;
+            assert_eq!(balance, U256::from(balances[account]), "Step {}: balance of {}", step, account);
+            total = total.checked_add(balance).unwrap();
+        }
+        assert_eq!(total, token.total_supply()?, "Step {}: balances must add up to the supply", step);
+        assert_eq!(total, U256::from(supply));
+
+        // Allowances only move through increaseAllowance and transferFrom
+        for ((owner, spender), allowance) in &allowances {
+            assert_eq!(
+                token.allowance(owner, spender)?,
+                U256::from(*allowance),
+                "Step {}: allowance of {} for {}",
+                step,
+                spender,
+                owner
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "needs the AssemblyScript reference wasm, see MRC20_AS_WASM"]
+fn test_differential_against_assemblyscript() -> Result<()> {
+    const ACCOUNTS: [&str; 4] = [DEPLOYER, ALICE, BOB, CHARLIE];
+
+    // The official massa-standards MRC20, built with the mintable and burnable extensions
+    let as_wasm = std::fs::read(std::env::var("MRC20_AS_WASM")?)?;
+    let rust_wasm = std::fs::read(wasm_path())?;
+    let contracts = [("AS", as_wasm, TestRuntime::new()), ("Rust", rust_wasm, TestRuntime::new())];
+
+    let transfer = |to: &str, amount: u64| {
+        let mut args = Args::new();
+        args.add_string(to).add_u256(U256::from(amount));
+        args.into_bytes()
+    };
+    let transfer_from = |owner: &str, to: &str, amount: u64| {
+        let mut args = Args::new();
+        args.add_string(owner).add_string(to).add_u256(U256::from(amount));
+        args.into_bytes()
+    };
+    let burn = |amount: u64| {
+        let mut args = Args::new();
+        args.add_u256(U256::from(amount));
+        args.into_bytes()
+    };
+
+    // (caller, function, args), including calls both contracts must reject
+    let script = [
+        (DEPLOYER, "constructor", constructor_args("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))),
+        (DEPLOYER, "transfer", transfer(ALICE, 100_000)),
+        (ALICE, "transfer", transfer(BOB, 200_000)),
+        (ALICE, "increaseAllowance", transfer(BOB, 50_000)),
+        (BOB, "transferFrom", transfer_from(ALICE, CHARLIE, 30_000)),
+        (BOB, "transferFrom", transfer_from(ALICE, CHARLIE, 30_000)),
+        (ALICE, "decreaseAllowance", transfer(BOB, 100_000)),
+        (DEPLOYER, "mint", transfer(CHARLIE, 5_000)),
+        (ALICE, "mint", transfer(ALICE, 5_000)),
+        (CHARLIE, "burn", burn(1_000)),
+        (CHARLIE, "burn", burn(1_000_000)),
+        (DEPLOYER, "transfer", transfer(DEPLOYER, 10)),
+    ];
+
+    // Read-only calls whose results must match byte for byte
+    let mut queries = vec![
+        ("name", Vec::new()),
+        ("symbol", Vec::new()),
+        ("decimals", Vec::new()),
+        ("totalSupply", Vec::new()),
+    ];
+    for owner in ACCOUNTS {
+        let mut args = Args::new();
+        args.add_string(owner);
+        queries.push(("balanceOf", args.into_bytes()));
+        for spender in ACCOUNTS {
+            let mut args = Args::new();
+            args.add_string(owner).add_string(spender);
+            queries.push(("allowance", args.into_bytes()));
+        }
+    }
+
+    for (step, (caller, function, args)) in script.iter().enumerate() {
+        // Success and the names of the emitted events must match; the Rust
+        // events append fields and its errors are structured, so neither is compared in full
+        let outcomes: Vec<(bool, Vec<String>)> = contracts
+            .iter()
+            .map(|(_, wasm, runtime)| {
+                runtime
+                    .interface
+                    .set_call_stack(vec![caller.to_string(), "AS_CONTRACT".to_string()]);
+                let mark = event_mark(runtime);
+                let ok = runtime.execute(wasm, function, args).is_ok();
+                let names = events_since(runtime, mark)
+                    .iter()
+                    .map(|event| event.split_once(':').map_or(event.as_str(), |(name, _)| name).to_string())
+                    .collect();
+                (ok, names)
+            })
+            .collect();
+        assert_eq!(outcomes[0], outcomes[1], "Step {}: {} by {} diverges", step, function, caller);
+
+        for (query, query_args) in &queries {
+            let results: Vec<Vec<u8>> = contracts
+                .iter()
+                .map(|(_, wasm, runtime)| {
+                    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
+                    Ok(runtime.execute(wasm, query, query_args)?.ret)
+                })
+                .collect::<Result<_>>()?;
+            assert_eq!(
+                results[0], results[1],
+                "Step {}: {} differs between {} and {}",
+                step, query, contracts[0].0, contracts[1].0
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_raw_balance_entries() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+    token.transfer(DEPLOYER, ALICE, U256::from(250_000u64))?;
+
+    // BALANCE{address} holds exactly the 32 little-endian bytes of the balance
+    let stored = token.raw_entry(&balance_key(ALICE))?;
+    assert_eq!(stored.len(), 32);
+    assert_eq!(stored, U256::from(250_000u64).to_le_bytes().to_vec());
+    assert_eq!(token.raw_entry(&balance_key(DEPLOYER))?, U256::from(750_000u64).to_le_bytes().to_vec());
+
+    // Only holders have a balance entry
+    assert_eq!(token.raw_keys(b"BALANCE")?, vec![balance_key(ALICE), balance_key(DEPLOYER)]);
+    token.transfer(ALICE, BOB, U256::from(250_000u64))?;
+    assert_eq!(token.raw_keys(b"BALANCE")?, vec![balance_key(BOB), balance_key(DEPLOYER)]);
+    assert!(!token.has_raw_entry(&balance_key(ALICE))?);
+
+    Ok(())
+}
+
+#[test]
+fn test_extension_keys_are_length_prefixed() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+    token.grant_role(DEPLOYER, "MINTER", ALICE)?;
+
+    // "ROLE" + len("MINTER") + "MINTER" + len(ALICE) + ALICE, lengths as u32 LE
+    let mut role_key = b"ROLE".to_vec();
+    for segment in ["MINTER", ALICE] {
+        role_key.extend_from_slice(&(segment.len() as u32).to_le_bytes());
+        role_key.extend_from_slice(segment.as_bytes());
+    }
+    assert_eq!(token.raw_entry(&role_key)?, vec![1u8]);
+    let concatenated = [b"ROLE".as_slice(), b"MINTER", ALICE.as_bytes()].concat();
+    assert!(!token.has_raw_entry(&concatenated)?, "Only BALANCE and ALLOWANCE keep raw keys");
+
+    Ok(())
+}
+
+#[test]
+fn test_storage_layout_matches_fixture() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+    token.transfer(DEPLOYER, ALICE, U256::from(250_000u64))?;
+    token.increase_allowance(ALICE, BOB, U256::from(1_000u64))?;
+
+    // Keys built the way the AssemblyScript token builds them
+    let keys = [
+        ("NAME", b"NAME".to_vec()),
+        ("SYMBOL", b"SYMBOL".to_vec()),
+        ("DECIMALS", b"DECIMALS".to_vec()),
+        ("TOTAL_SUPPLY", b"TOTAL_SUPPLY".to_vec()),
+        ("BALANCE{DEPLOYER}", balance_key(DEPLOYER)),
+        ("BALANCE{ALICE}", balance_key(ALICE)),
+        ("ALLOWANCE{ALICE}{BOB}", allowance_key(ALICE, BOB)),
+        ("OWNER", b"OWNER".to_vec()),
+    ];
+
+    let fixture = include_str!("../fixtures/storage_layout.txt");
+    let expected: Vec<Vec<&str>> = fixture
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.split(' ').collect())
+        .collect();
+    assert!(
+        expected.iter().all(|fields| fields.len() == 3),
+        "Fixture lines are `LABEL keyhex valuehex`"
+    );
+    assert_eq!(
+        expected.iter().map(|fields| fields[0]).collect::<Vec<_>>(),
+        keys.iter().map(|(label, _)| *label).collect::<Vec<_>>(),
+        "Fixture and test must list the same keys"
+    );
+
+    for (fields, (label, key)) in expected.iter().zip(keys.iter()) {
+        assert_eq!(hex::encode(key), fields[1], "Key of {} changed", label);
+        let stored = token.raw_entry(key)?;
+        assert_eq!(hex::encode(&stored), fields[2], "Value of {} changed", label);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_u256_large_values() -> Result<()> {
+    // Use a large U256 value (10^24 = 1 million tokens with 18 decimals)
+    let large_supply = U256::from(10u64).pow(24);
+
+    println!("Testing with large supply: {}", large_supply);
+    let token = TokenHarness::deploy("LargeToken", "LTK", 18, large_supply)?;
+
+    // Check total supply
+    let total_supply = token.total_supply()?;
+    assert_eq!(total_supply, large_supply);
+    println!("Large supply verified: {}", total_supply);
+
+    // Transfer a large amount
+    let transfer_amount = U256::from(10u64).pow(23); // 100,000 tokens
+    token.transfer(DEPLOYER, ALICE, transfer_amount)?;
+
+    // Check Alice balance
+    let alice_balance = token.balance_of(ALICE)?;
+    assert_eq!(alice_balance, transfer_amount);
+    println!("Alice received: {}", alice_balance);
+
+    Ok(())
+}
+
+#[test]
+fn test_pause_blocks_operations() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Owner pauses the token
+    token.pause(DEPLOYER)?;
+    assert!(token.events().contains(&"PAUSED".to_string()), "Expected PAUSED event");
+    assert!(token.paused()?);
+
+    // Every balance-moving operation must be rejected
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(ALICE).add_u256(U256::from(1_000u64));
+    let mut transfer_from_args = Args::new();
+    transfer_from_args
+        .add_string(ALICE)
+        .add_string(BOB)
+        .add_u256(U256::from(1_000u64));
+    let mut mint_args = Args::new();
+    mint_args.add_string(ALICE).add_u256(U256::from(1_000u64));
+    let mut burn_args = Args::new();
+    burn_args.add_u256(U256::from(1_000u64));
+    let mut burn_from_args = Args::new();
+    burn_from_args.add_string(ALICE).add_u256(U256::from(1_000u64));
+
+    let calls = [
+        ("transfer", transfer_args.into_bytes()),
+        ("transferFrom", transfer_from_args.into_bytes()),
+        ("mint", mint_args.into_bytes()),
+        ("burn", burn_args.into_bytes()),
+        ("burnFrom", burn_from_args.into_bytes()),
+    ];
+    for (function, call_args) in calls.iter() {
+        let err = token.call_expect_failure(DEPLOYER, function, call_args);
+        assert!(
+            err.contains("ERR:PAUSED::Token is paused"),
+            "Unexpected error for {}: {}",
+            function,
+            err
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_unpause_restores_transfers() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Only the owner can pause
+    assert!(token.pause(ALICE).is_err(), "Non-owner must not pause");
+
+    // Pause then unpause
+    token.pause(DEPLOYER)?;
+    token.unpause(DEPLOYER)?;
+    assert!(token.events().contains(&"UNPAUSED".to_string()), "Expected UNPAUSED event");
+    assert!(!token.paused()?);
+
+    // Transfers work again
+    let transfer_amount = U256::from(1_000u64);
+    token.transfer(DEPLOYER, ALICE, transfer_amount)?;
+    assert_eq!(token.balance_of(ALICE)?, transfer_amount);
+
+    Ok(())
+}
+
+#[test]
+fn test_freeze_account() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Give Alice some tokens, then freeze her
+    token.transfer(DEPLOYER, ALICE, U256::from(10_000u64))?;
+    token.freeze_account(DEPLOYER, ALICE)?;
+
+    let expected_event = format!("FREEZE_ACCOUNT:{}", ALICE);
+    assert!(token.events().contains(&expected_event), "Expected freeze event");
+    assert!(token.is_frozen(ALICE)?);
+
+    // Alice cannot send
+    let result = token.transfer(ALICE, BOB, U256::from(1_000u64));
+    assert!(result.is_err(), "Frozen account must not send tokens");
+
+    // Nobody can send to Alice
+    let result = token.transfer(DEPLOYER, ALICE, U256::from(1_000u64));
+    assert!(result.is_err(), "Frozen account must not receive tokens");
+
+    // A spender cannot move Alice's tokens either
+    token.increase_allowance(ALICE, BOB, U256::from(5_000u64))?;
+    let result = token.transfer_from(BOB, ALICE, BOB, U256::from(1_000u64));
+    assert!(result.is_err(), "transferFrom from frozen account must fail");
+
+    Ok(())
+}
+
+#[test]
+fn test_unfreeze_account() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Only the owner can freeze
+    let result = token.freeze_account(ALICE, BOB);
+    assert!(result.is_err(), "Non-owner must not freeze accounts");
+
+    // Freeze then unfreeze Alice
+    token.freeze_account(DEPLOYER, ALICE)?;
+    token.unfreeze_account(DEPLOYER, ALICE)?;
+
+    let expected_event = format!("UNFREEZE_ACCOUNT:{}", ALICE);
+    assert!(token.events().contains(&expected_event), "Expected unfreeze event");
+
+    // Alice can receive tokens again
+    let transfer_amount = U256::from(1_000u64);
+    token.transfer(DEPLOYER, ALICE, transfer_amount)?;
+    assert!(!token.is_frozen(ALICE)?);
+    assert_eq!(token.balance_of(ALICE)?, transfer_amount);
+
+    Ok(())
+}
+
+#[test]
+fn test_mint_up_to_cap() -> Result<()> {
+    // Set up deployment with a cap
+    let initial_supply = U256::from(1_000_000u64);
+    let cap = U256::from(1_500_000u64);
+    let token = TokenHarness::deploy_with_cap("MassaCoin", "MCOIN", 18, initial_supply, cap)?;
+    assert_eq!(token.cap()?, Some(cap));
+
+    // Minting exactly up to the cap succeeds
+    token.mint(DEPLOYER, ALICE, U256::from(500_000u64))?;
+    assert_eq!(token.total_supply()?, cap);
+
+    // Any further mint is rejected
+    let result = token.mint(DEPLOYER, ALICE, U256::from(1u64));
+    assert!(result.is_err(), "Mint above cap must fail");
+
+    Ok(())
+}
+
+#[test]
+fn test_set_cap() -> Result<()> {
+    // Set up deployment without a cap
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+    assert_eq!(token.cap()?, None, "Token should be uncapped");
+
+    // Cap below the current supply is rejected
+    let result = token.set_cap(DEPLOYER, U256::from(999_999u64));
+    assert!(result.is_err(), "Cap below total supply must fail");
+
+    // Set the cap once
+    token.set_cap(DEPLOYER, U256::from(1_200_000u64))?;
+
+    // Second call is rejected
+    let result = token.set_cap(DEPLOYER, U256::from(2_000_000u64));
+    assert!(result.is_err(), "Cap can only be set once");
+
+    // Over-cap mint is rejected, the supply is untouched
+    let result = token.mint(DEPLOYER, ALICE, U256::from(200_001u64));
+    assert!(result.is_err(), "Mint above cap must fail");
+    assert_eq!(token.total_supply()?, U256::from(1_000_000u64));
+
+    Ok(())
+}
+
+#[test]
+fn test_two_step_ownership_transfer() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Only the owner can propose
+    let result = token.propose_owner(ALICE, ALICE);
+    assert!(result.is_err(), "Non-owner must not propose");
+
+    // Deployer proposes Alice
+    token.propose_owner(DEPLOYER, ALICE)?;
+    let expected_event = format!("PROPOSE_OWNER:{}", ALICE);
+    assert!(token.events().contains(&expected_event), "Expected propose event");
+
+    // Ownership has not moved yet
+    assert_eq!(token.owner_address()?, DEPLOYER);
+    assert_eq!(token.pending_owner()?.as_deref(), Some(ALICE));
+
+    // Bob cannot accept on Alice's behalf
+    let result = token.accept_ownership(BOB);
+    assert!(result.is_err(), "Only the pending owner can accept");
+
+    // Alice accepts
+    token.accept_ownership(ALICE)?;
+    let expected_event = format!("ACCEPT_OWNER:{}", ALICE);
+    assert!(token.events().contains(&expected_event), "Expected accept event");
+
+    assert_eq!(token.owner_address()?, ALICE);
+    assert_eq!(token.pending_owner()?, None, "Pending owner should be cleared");
+
+    // The proposal cannot be replayed
+    let result = token.accept_ownership(ALICE);
+    assert!(result.is_err(), "Accepted proposal must be consumed");
+
+    Ok(())
+}
+
+#[test]
+fn test_minter_role() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Deployer holds every role
+    for role in ["MINTER", "BURNER", "PAUSER", "COMPLIANCE", "BRIDGE_MINTER", "SNAPSHOT"] {
+        assert!(token.has_role(role, DEPLOYER)?, "Deployer should hold {}", role);
+    }
+
+    // Alice (a bridge, say) cannot mint yet
+    let result = token.mint(ALICE, BOB, U256::from(1_000u64));
+    assert!(result.is_err(), "Mint without MINTER role must fail");
+
+    // Only the owner can grant roles
+    let result = token.grant_role(ALICE, "MINTER", ALICE);
+    assert!(result.is_err(), "Non-owner must not grant roles");
+
+    // Unknown roles are rejected
+    let result = token.grant_role(DEPLOYER, "ADMIN", ALICE);
+    assert!(result.is_err(), "Unknown role must be rejected");
+
+    // Deployer grants MINTER to Alice
+    token.grant_role(DEPLOYER, "MINTER", ALICE)?;
+    let expected_event = format!("GRANT_ROLE:MINTER:{}", ALICE);
+    assert!(token.events().contains(&expected_event), "Expected grant event");
+
+    // Alice mints without being the owner
+    let mint_amount = U256::from(1_000u64);
+    token.mint(ALICE, BOB, mint_amount)?;
+    assert_eq!(token.balance_of(BOB)?, mint_amount);
+
+    // Revoke and check Alice can no longer mint
+    token.revoke_role(DEPLOYER, "MINTER", ALICE)?;
+    let expected_event = format!("REVOKE_ROLE:MINTER:{}", ALICE);
+    assert!(token.events().contains(&expected_event), "Expected revoke event");
+
+    let result = token.mint(ALICE, BOB, mint_amount);
+    assert!(result.is_err(), "Mint after revocation must fail");
+
+    Ok(())
+}
+
+#[test]
+fn test_permit_rejects_invalid_signature() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Nonce starts at zero
+    assert_eq!(token.nonces(DEPLOYER)?, 0);
+
+    // A relayer submits a permit with a bogus signature
+    let mut permit_args = Args::new();
+    permit_args
+        .add_string(DEPLOYER)
+        .add_string(ALICE)
+        .add_u256(U256::from(50_000u64))
+        .add_u64(u64::MAX)
+        .add_string("P1invalidPublicKey")
+        .add_string("1invalidSignature");
+    let result = token.call(BOB, "permit", &permit_args.into_bytes());
+    assert!(result.is_err(), "Permit with invalid signature must fail");
+
+    // Allowance and nonce are untouched
+    assert_eq!(token.allowance(DEPLOYER, ALICE)?, U256::ZERO);
+    assert_eq!(token.nonces(DEPLOYER)?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_permit_rejects_expired_deadline() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // A deadline in the past is rejected before the signature is checked
+    let mut permit_args = Args::new();
+    permit_args
+        .add_string(DEPLOYER)
+        .add_string(ALICE)
+        .add_u256(U256::from(50_000u64))
+        .add_u64(0)
+        .add_string("P1invalidPublicKey")
+        .add_string("1invalidSignature");
+    let err = token.call_expect_failure(BOB, "permit", &permit_args.into_bytes());
+    assert!(err.contains("ERR:EXPIRED::Permit expired"), "Unexpected error: {}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_with_authorization_rejects_invalid_signature() -> Result<()> {
+    let initial_supply = U256::from(1_000_000u64);
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, initial_supply)?;
+
+    // A relayer submits an authorization with a bogus signature
+    let mut auth_args = Args::new();
+    auth_args
+        .add_string(DEPLOYER)
+        .add_string(ALICE)
+        .add_u256(U256::from(10_000u64))
+        .add_u64(0)
+        .add_u64(u64::MAX)
+        .add_string("nonce-1")
+        .add_string("P1invalidPublicKey")
+        .add_string("1invalidSignature");
+    let result = token.call(BOB, "transferWithAuthorization", &auth_args.into_bytes());
+    assert!(result.is_err(), "Authorization with invalid signature must fail");
+
+    // Nonce is still unused and no tokens moved
+    assert!(!token.authorization_state(DEPLOYER, "nonce-1")?);
+    assert_eq!(token.balance_of(DEPLOYER)?, initial_supply);
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_with_authorization_rejects_expired() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // validBefore of zero is always in the past
+    let mut auth_args = Args::new();
+    auth_args
+        .add_string(DEPLOYER)
+        .add_string(ALICE)
+        .add_u256(U256::from(10_000u64))
+        .add_u64(0)
+        .add_u64(0)
+        .add_string("nonce-1")
+        .add_string("P1invalidPublicKey")
+        .add_string("1invalidSignature");
+    let result = token.call(BOB, "transferWithAuthorization", &auth_args.into_bytes());
+    assert!(result.is_err(), "Expired authorization must fail");
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_balances() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Only the owner and SNAPSHOT role holders can snapshot
+    assert!(token.snapshot(ALICE).is_err(), "Non-owner must not snapshot");
+
+    // Snapshot 1: deployer holds everything
+    assert_eq!(token.snapshot(DEPLOYER)?, 1);
+    assert!(token.events().contains(&"SNAPSHOT:1".to_string()), "Expected SNAPSHOT event");
+
+    // Move tokens, mint, then take snapshot 2
+    token.transfer(DEPLOYER, ALICE, U256::from(300_000u64))?;
+    token.mint(DEPLOYER, ALICE, U256::from(100_000u64))?;
+    token.snapshot(DEPLOYER)?;
+
+    // Move more tokens after snapshot 2
+    token.transfer(DEPLOYER, ALICE, U256::from(50_000u64))?;
+
+    let expected = [
+        (DEPLOYER, 1u64, 1_000_000u64),
+        (ALICE, 1, 0),
+        (DEPLOYER, 2, 700_000),
+        (ALICE, 2, 400_000),
+    ];
+    for (address, snapshot_id, expected_balance) in expected {
+        assert_eq!(
+            token.balance_of_at(address, snapshot_id)?,
+            U256::from(expected_balance),
+            "Balance of {} at snapshot {}",
+            address,
+            snapshot_id
+        );
+    }
+
+    for (snapshot_id, expected_supply) in [(1u64, 1_000_000u64), (2, 1_100_000)] {
+        assert_eq!(token.total_supply_at(snapshot_id)?, U256::from(expected_supply));
+    }
+
+    // Future snapshots cannot be queried
+    assert!(token.total_supply_at(3).is_err(), "Nonexistent snapshot must fail");
+
+    // A SNAPSHOT role holder, like a governor, snapshots without owning the token
+    token.grant_role(DEPLOYER, "SNAPSHOT", BOB)?;
+    assert_eq!(token.snapshot(BOB)?, 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_receiver_mock_hook() -> Result<()> {
+    let receiver_wasm = std::fs::read(receiver_wasm_path())?;
+    let runtime = TestRuntime::new();
+
+    // The token calls the hook with (from, amount, data)
+    runtime
+        .interface
+        .set_call_stack(vec!["AS_TOKEN".to_string(), "AS_RECEIVER".to_string()]);
+    let mut hook_args = Args::new();
+    hook_args
+        .add_string(ALICE)
+        .add_u256(U256::from(1_000u64))
+        .add_bytes(b"hello");
+    runtime.execute(&receiver_wasm, "onTokenReceived", &hook_args.into_bytes())?;
+
+    let events = runtime.interface.events();
+    let expected_event = format!("TOKEN_RECEIVED:{}", ALICE);
+    assert!(events.iter().any(|e| *e == expected_event), "Expected TOKEN_RECEIVED event");
+
+    // The receiver can refuse tokens by panicking
+    let mut hook_args = Args::new();
+    hook_args
+        .add_string(ALICE)
+        .add_u256(U256::from(1_000u64))
+        .add_bytes(b"reject");
+    let result = runtime.execute(&receiver_wasm, "onTokenReceived", &hook_args.into_bytes());
+    assert!(result.is_err(), "Receiver should reject tokens");
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_and_call_requires_receiver_hook() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Alice's address is a user address, so it is rejected before any transfer
+    let mut call_args = Args::new();
+    call_args
+        .add_string(ALICE)
+        .add_u256(U256::from(1_000u64))
+        .add_bytes(b"hello");
+    let err = token.call_expect_failure(DEPLOYER, "transferAndCall", &call_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=receiverAddress:"), "Unexpected error: {}", err);
+
+    // A contract address passes the check and reaches the hook call
+    let mut call_args = Args::new();
+    call_args
+        .add_string("AS_RECEIVER")
+        .add_u256(U256::from(1_000u64))
+        .add_bytes(b"hello");
+    let err = token.call_expect_failure(DEPLOYER, "transferAndCall", &call_args.into_bytes());
+    assert!(!err.contains("ERR:INVALID_ARGUMENT:"), "Unexpected error: {}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_flash_loan_queries() -> Result<()> {
+    // Set up deployment with a cap
+    let initial_supply = U256::from(1_000_000u64);
+    let cap = U256::from(1_500_000u64);
+    let token = TokenHarness::deploy_with_cap("MassaCoin", "MCOIN", 18, initial_supply, cap)?;
+
+    // maxFlashLoan is the room left under the cap
+    assert_eq!(token.max_flash_loan()?, U256::from(500_000u64));
+
+    // Fee is zero by default
+    assert_eq!(token.flash_fee(U256::from(1_000u64))?, U256::ZERO);
+
+    // Only the owner sets the fee
+    let result = token.set_flash_fee(ALICE, U256::from(10u64));
+    assert!(result.is_err(), "Non-owner must not set the flash fee");
+
+    token.set_flash_fee(DEPLOYER, U256::from(10u64))?;
+    assert_eq!(token.flash_fee(U256::from(1_000u64))?, U256::from(10u64));
+
+    // Loans above maxFlashLoan are rejected
+    let result = token.flash_fee(U256::from(500_001u64));
+    assert!(result.is_err(), "flashFee above maxFlashLoan must fail");
+
+    Ok(())
+}
+
+#[test]
+fn test_flash_loan_requires_borrower_hook() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Alice is not a borrower contract, so the loan reverts
+    let mut loan_args = Args::new();
+    loan_args
+        .add_string(ALICE)
+        .add_u256(U256::from(1_000u64))
+        .add_bytes(b"");
+    let result = token.call(ALICE, "flashLoan", &loan_args.into_bytes());
+    assert!(result.is_err(), "flashLoan to a non-borrower must fail");
+
+    Ok(())
+}
+
+#[test]
+fn test_vesting_locks_granted_tokens() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Grant Alice 100,000 tokens vesting far in the future
+    let grant = U256::from(100_000u64);
+    let start = u64::MAX / 2;
+    token.create_vesting_schedule(DEPLOYER, ALICE, start, 10, 100, grant)?;
+
+    let expected_event = format!("VESTING_CREATED:{}", ALICE);
+    assert!(token.events().contains(&expected_event), "Expected vesting event");
+
+    // A second schedule for the same beneficiary is rejected
+    let result = token.create_vesting_schedule(DEPLOYER, ALICE, start, 10, 100, grant);
+    assert!(result.is_err(), "Duplicate schedule must fail");
+
+    // Tokens are in Alice's balance but nothing is releasable yet
+    assert_eq!(token.balance_of(ALICE)?, grant);
+    assert_eq!(token.releasable_amount(ALICE)?, U256::ZERO);
+
+    // Alice can neither move, burn nor release the locked grant
+    let result = token.transfer(ALICE, BOB, U256::from(1u64));
+    assert!(result.is_err(), "Locked tokens must not be transferable");
+
+    let result = token.burn(ALICE, U256::from(1u64));
+    assert!(result.is_err(), "Locked tokens must not be burnable");
+
+    let result = token.release(ALICE);
+    assert!(result.is_err(), "Nothing should be releasable before the cliff");
+
+    // Tokens received on top of the grant remain transferable
+    token.transfer(DEPLOYER, ALICE, U256::from(5_000u64))?;
+    token.transfer(ALICE, BOB, U256::from(5_000u64))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_vesting_requires_owner() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    let result = token.create_vesting_schedule(ALICE, BOB, 0, 0, 100, U256::from(1_000u64));
+    assert!(result.is_err(), "Non-owner must not create vesting schedules");
+
+    // Invalid schedules are rejected
+    let result = token.create_vesting_schedule(DEPLOYER, BOB, 0, 200, 100, U256::from(1_000u64));
+    assert!(result.is_err(), "Cliff longer than duration must fail");
+
+    Ok(())
+}
+
+#[test]
+fn test_lock_tokens() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+    token.transfer(DEPLOYER, ALICE, U256::from(10_000u64))?;
+
+    // Bob cannot lock Alice's tokens
+    let result = token.lock_tokens(BOB, ALICE, U256::from(6_000u64), u64::MAX);
+    assert!(result.is_err(), "Third parties must not lock tokens");
+
+    // Alice locks 6,000 of her own tokens
+    token.lock_tokens(ALICE, ALICE, U256::from(6_000u64), u64::MAX)?;
+
+    let expected_event = format!("LOCK_TOKENS:{}", ALICE);
+    assert!(token.events().contains(&expected_event), "Expected lock event");
+
+    assert_eq!(token.locked_balance_of(ALICE)?, U256::from(6_000u64));
+    assert_eq!(token.transferable_balance_of(ALICE)?, U256::from(4_000u64));
+
+    // Only the unlocked part can move
+    let result = token.transfer(ALICE, BOB, U256::from(4_001u64));
+    assert!(result.is_err(), "Transfer into the locked part must fail");
+
+    token.transfer(ALICE, BOB, U256::from(4_000u64))?;
+
+    // Owner releases 1,000 early
+    token.unlock_tokens(DEPLOYER, ALICE, U256::from(1_000u64))?;
+    token.transfer(ALICE, BOB, U256::from(1_000u64))?;
+
+    // Alice cannot unlock herself
+    let result = token.unlock_tokens(ALICE, ALICE, U256::from(1_000u64));
+    assert!(result.is_err(), "Only the owner can unlock early");
+
+    Ok(())
+}
+
+#[test]
+fn test_rescue_own_tokens() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Alice mistakenly sends tokens to the token contract itself
+    token.transfer(DEPLOYER, ALICE, U256::from(10_000u64))?;
+    token.transfer(ALICE, "AS_CONTRACT", U256::from(10_000u64))?;
+
+    // Only the owner can rescue
+    let result = token.rescue_token(ALICE, "AS_CONTRACT", ALICE, U256::from(10_000u64));
+    assert!(result.is_err(), "Non-owner must not rescue tokens");
+
+    // Owner sends them back to Alice
+    token.rescue_token(DEPLOYER, "AS_CONTRACT", ALICE, U256::from(10_000u64))?;
+
+    let expected_event = format!("RESCUE_TOKEN:AS_CONTRACT:{}", ALICE);
+    assert!(token.events().contains(&expected_event), "Expected rescue event");
+
+    assert_eq!(token.balance_of(ALICE)?, U256::from(10_000u64));
+
+    Ok(())
+}
+
+#[test]
+fn test_rescue_coins_requires_owner() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    let mut rescue_args = Args::new();
+    rescue_args.add_string(ALICE).add_u64(1);
+    let result = token.call(ALICE, "rescueCoins", &rescue_args.into_bytes());
+    assert!(result.is_err(), "Non-owner must not rescue coins");
+
+    Ok(())
+}
+
+#[test]
+fn test_return_values() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // transfer returns true
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(ALICE).add_u256(U256::from(1_000u64));
+    let mut ret = token.call(DEPLOYER, "transfer", &transfer_args.into_bytes())?.as_args();
+    assert!(ret.next_bool()?, "transfer should return true");
+
+    // increaseAllowance returns true and the new allowance
+    let mut approve_args = Args::new();
+    approve_args.add_string(BOB).add_u256(U256::from(5_000u64));
+    let mut ret = token.call(DEPLOYER, "increaseAllowance", &approve_args.into_bytes())?.as_args();
+    assert!(ret.next_bool()?, "increaseAllowance should return true");
+    assert_eq!(ret.next_u256()?, U256::from(5_000u64));
+
+    // decreaseAllowance returns true and the new allowance
+    let mut decrease_args = Args::new();
+    decrease_args.add_string(BOB).add_u256(U256::from(2_000u64));
+    let mut ret = token.call(DEPLOYER, "decreaseAllowance", &decrease_args.into_bytes())?.as_args();
+    assert!(ret.next_bool()?, "decreaseAllowance should return true");
+    assert_eq!(ret.next_u256()?, U256::from(3_000u64));
+
+    // transferFrom returns true
+    let mut transfer_from_args = Args::new();
+    transfer_from_args
+        .add_string(DEPLOYER)
+        .add_string(CHARLIE)
+        .add_u256(U256::from(3_000u64));
+    let mut ret = token.call(BOB, "transferFrom", &transfer_from_args.into_bytes())?.as_args();
+    assert!(ret.next_bool()?, "transferFrom should return true");
+
+    Ok(())
+}
+
+#[test]
+fn test_token_metadata() -> Result<()> {
+    // Deploy uncapped (zero cap) with a URI and a description
+    let mut args = Args::new();
+    args.add_string("MassaCoin")
+        .add_string("MCOIN")
+        .add_u8(18)
+        .add_u256(U256::from(1_000_000u64))
+        .add_u256(U256::ZERO)
+        .add_string("ipfs://logo")
+        .add_string("A coin for tests");
+    let token = TokenHarness::deploy_with(&args.into_bytes())?;
+
+    assert_eq!(token.token_uri()?, "ipfs://logo");
+    assert_eq!(token.description()?, "A coin for tests");
+    assert_eq!(token.cap()?, None, "Zero cap means uncapped");
+
+    // Only the owner updates the URI
+    let result = token.set_token_uri(ALICE, "ipfs://evil");
+    assert!(result.is_err(), "Non-owner must not set the token URI");
+
+    token.set_token_uri(DEPLOYER, "ipfs://logo-v2")?;
+    assert!(token.events().contains(&"SET_TOKEN_URI:ipfs://logo-v2".to_string()), "Expected URI event");
+
+    assert_eq!(token.token_uri()?, "ipfs://logo-v2");
+
+    Ok(())
+}
+
+#[test]
+fn test_mint_batch() -> Result<()> {
+    // Set up deployment with a cap
+    let initial_supply = U256::from(1_000_000u64);
+    let cap = U256::from(1_600_000u64);
+    let token = TokenHarness::deploy_with_cap("MassaCoin", "MCOIN", 18, initial_supply, cap)?;
+
+    // Mint to three recipients at once
+    let recipients = [(ALICE, 100_000u64), (BOB, 200_000), (CHARLIE, 300_000)];
+    let batch = recipients.map(|(recipient, amount)| (recipient, U256::from(amount)));
+    let mark = event_mark(&token.runtime);
+    token.mint_batch(DEPLOYER, &batch)?;
+
+    // One mint event per recipient, in order
+    let events = events_since(&token.runtime, mark);
+    let mints = find_events(&events, "MINT SUCCESS");
+    assert_eq!(mints.len(), recipients.len(), "Unexpected mint events: {:?}", events);
+    for (event, (recipient, amount)) in mints.iter().zip(recipients) {
+        let fields = event_fields(event);
+        assert_eq!(fields["to"], recipient);
+        assert_eq!(fields["amount"], amount.to_string());
+    }
+
+    for (recipient, amount) in recipients {
+        assert_eq!(token.balance_of(recipient)?, U256::from(amount));
+    }
+
+    assert_eq!(token.total_supply()?, U256::from(1_600_000u64));
+
+    // A batch whose total exceeds the cap is rejected as a whole
+    let result = token.mint_batch(DEPLOYER, &[(ALICE, U256::from(1u64))]);
+    assert!(result.is_err(), "Batch above cap must fail");
+
+    // Non-minters cannot batch mint
+    let result = token.mint_batch(ALICE, &[(ALICE, U256::ZERO)]);
+    assert!(result.is_err(), "Batch mint without MINTER role must fail");
+
+    Ok(())
+}
+
+#[test]
+fn test_approve_with_deadline() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // An expiry of 0 approves without a deadline
+    token.approve_with_deadline(DEPLOYER, ALICE, U256::from(10_000u64), 0)?;
+
+    // Approve Alice until a far future period
+    let expiry = u64::MAX;
+    token.approve_with_deadline(DEPLOYER, ALICE, U256::from(10_000u64), expiry)?;
+
+    assert_eq!(token.allowance_expiry(DEPLOYER, ALICE)?, expiry);
+    assert_eq!(token.allowance(DEPLOYER, ALICE)?, U256::from(10_000u64));
+
+    // Alice spends within the deadline
+    token.transfer_from(ALICE, DEPLOYER, BOB, U256::from(4_000u64))?;
+
+    // Plain allowances never expire
+    assert_eq!(token.allowance_expiry(DEPLOYER, BOB)?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_approval_without_deadline_clears_expiry() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+    let amount = U256::from(10_000u64);
+
+    // A plain approval after a deadline approval never expires
+    token.approve_with_deadline(DEPLOYER, ALICE, amount, u64::MAX)?;
+    assert_eq!(token.allowance_expiry(DEPLOYER, ALICE)?, u64::MAX);
+    token.increase_allowance(DEPLOYER, ALICE, U256::from(1u64))?;
+    assert_eq!(token.allowance_expiry(DEPLOYER, ALICE)?, 0);
+    assert_eq!(token.allowance(DEPLOYER, ALICE)?, U256::from(10_001u64));
+
+    token.approve_with_deadline(DEPLOYER, ALICE, amount, u64::MAX)?;
+    assert_eq!(token.approve_if_current(DEPLOYER, ALICE, amount, U256::from(500u64))?, (true, U256::from(500u64)));
+    assert_eq!(token.allowance_expiry(DEPLOYER, ALICE)?, 0);
+
+    token.approve_with_deadline(DEPLOYER, ALICE, amount, u64::MAX)?;
+    token.approve_with_deadline(DEPLOYER, ALICE, amount, 0)?;
+    assert_eq!(token.allowance_expiry(DEPLOYER, ALICE)?, 0);
+
+    // Spending keeps the expiry until the allowance reaches zero
+    token.approve_with_deadline(DEPLOYER, ALICE, amount, u64::MAX)?;
+    token.transfer_from(ALICE, DEPLOYER, BOB, U256::from(4_000u64))?;
+    assert_eq!(token.allowance_expiry(DEPLOYER, ALICE)?, u64::MAX);
+    token.decrease_allowance(DEPLOYER, ALICE, U256::from(6_000u64))?;
+    assert_eq!(token.allowance_expiry(DEPLOYER, ALICE)?, 0);
+
+    // A later approval does not inherit the cleared deadline
+    token.increase_allowance(DEPLOYER, ALICE, amount)?;
+    assert_eq!(token.allowance_expiry(DEPLOYER, ALICE)?, 0);
+    assert_eq!(token.allowance(DEPLOYER, ALICE)?, amount);
+
+    Ok(())
+}
+
+#[test]
+fn test_spending_limit() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Zero-length windows are rejected
+    let result = token.set_spending_limit(DEPLOYER, ALICE, U256::from(1_000u64), 0);
+    assert!(result.is_err(), "Zero period length must fail");
+
+    // Alice may spend 1,000 per window of 1,000,000 periods
+    token.set_spending_limit(DEPLOYER, ALICE, U256::from(1_000u64), 1_000_000)?;
+
+    let expected_event = format!(
+        "SPENDING_LIMIT:owner={},spender={},amount=1000,periodLength=1000000",
+        DEPLOYER, ALICE
+    );
+    assert!(token.events().contains(&expected_event), "Expected spending limit event");
+
+    // Alice spends 600, then 400, then nothing more in this window
+    for amount in [600u64, 400] {
+        token.transfer_from(ALICE, DEPLOYER, BOB, U256::from(amount))?;
+    }
+    let result = token.transfer_from(ALICE, DEPLOYER, BOB, U256::from(1u64));
+    assert!(result.is_err(), "Spend above the window limit must fail");
+
+    assert_eq!(token.remaining_spending_limit(DEPLOYER, ALICE)?, U256::ZERO);
+    assert_eq!(token.balance_of(BOB)?, U256::from(1_000u64));
+
+    // Removing the limit falls back to the (empty) one-shot allowance
+    token.set_spending_limit(DEPLOYER, ALICE, U256::ZERO, 1)?;
+
+    let result = token.transfer_from(ALICE, DEPLOYER, BOB, U256::from(1u64));
+    assert!(result.is_err(), "Spend without limit or allowance must fail");
+
+    Ok(())
+}
+
+#[test]
+fn test_allowlist_mode() -> Result<()> {
+    // Deploy in allowlist mode
+    let mut args = Args::new();
+    args.add_string("SecurityToken")
+        .add_string("SEC")
+        .add_u8(0)
+        .add_u256(U256::from(1_000_000u64))
+        .add_u256(U256::ZERO)
+        .add_string("")
+        .add_string("")
+        .add_bool(true);
+    let token = TokenHarness::deploy_with(&args.into_bytes())?;
+
+    assert!(token.allowlist_mode()?);
+
+    // Transfers to non-allowlisted addresses fail
+    let result = token.transfer(DEPLOYER, ALICE, U256::from(1_000u64));
+    assert!(result.is_err(), "Transfer to non-allowlisted address must fail");
+
+    // A compliance officer manages the allowlist
+    token.grant_role(DEPLOYER, "COMPLIANCE", CHARLIE)?;
+
+    let result = token.add_to_allowlist(BOB, BOB);
+    assert!(result.is_err(), "Only owner or COMPLIANCE can allowlist");
+
+    token.add_to_allowlist(CHARLIE, ALICE)?;
+
+    let expected_event = format!("ALLOWLIST_ADD:{}", ALICE);
+    assert!(token.events().contains(&expected_event), "Expected allowlist event");
+
+    // Deployer -> Alice now works, Alice -> Bob does not
+    token.transfer(DEPLOYER, ALICE, U256::from(1_000u64))?;
+
+    let result = token.transfer(ALICE, BOB, U256::from(500u64));
+    assert!(result.is_err(), "Transfer to non-allowlisted address must fail");
+
+    // Removing Alice blocks her outgoing transfers too
+    token.remove_from_allowlist(DEPLOYER, ALICE)?;
+    assert!(!token.is_allowlisted(ALICE)?);
+
+    let result = token.transfer(ALICE, DEPLOYER, U256::from(500u64));
+    assert!(result.is_err(), "Removed address must not send");
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_limits() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Configure launch limits
+    token.set_max_tx_amount(DEPLOYER, U256::from(1_000u64))?;
+    token.set_max_wallet_balance(DEPLOYER, U256::from(5_000u64))?;
+    token.set_transfer_cooldown(DEPLOYER, 1_000_000)?;
+
+    assert_eq!(token.transfer_cooldown_periods()?, 1_000_000);
+
+    // The exempt deployer can fund Alice above maxTxAmount, but not above maxWalletBalance
+    let result = token.transfer(DEPLOYER, ALICE, U256::from(5_001u64));
+    assert!(result.is_err(), "Recipient above maxWalletBalance must fail");
+
+    token.transfer(DEPLOYER, ALICE, U256::from(5_000u64))?;
+
+    // Alice is limited by maxTxAmount
+    let result = token.transfer(ALICE, BOB, U256::from(1_001u64));
+    assert!(result.is_err(), "Transfer above maxTxAmount must fail");
+
+    // One transfer goes through, the next one hits the cooldown
+    token.transfer(ALICE, BOB, U256::from(1_000u64))?;
+
+    let result = token.transfer(ALICE, BOB, U256::from(1u64));
+    assert!(result.is_err(), "Transfer during cooldown must fail");
+
+    // Exempting Alice lifts the limits
+    token.set_limit_exempt(DEPLOYER, ALICE, true)?;
+
+    token.transfer(ALICE, CHARLIE, U256::from(2_000u64))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_zero_balance_and_allowance_round_trip() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+    token.transfer(DEPLOYER, ALICE, U256::from(1_000u64))?;
+    assert!(token.has_raw_entry(&balance_key(ALICE))?);
+
+    // Alice empties her balance, which deletes her balance key
+    token.transfer(ALICE, BOB, U256::from(1_000u64))?;
+    assert!(!token.has_raw_entry(&balance_key(ALICE))?, "Emptied balance must not be stored");
+    assert_eq!(token.balance_of(ALICE)?, U256::ZERO, "Emptied balance should read as zero");
+
+    // A deleted balance can be funded again
+    token.transfer(BOB, ALICE, U256::from(400u64))?;
+    assert_eq!(token.raw_entry(&balance_key(ALICE))?, U256::from(400u64).to_le_bytes().to_vec());
+    assert_eq!(token.balance_of(ALICE)?, U256::from(400u64));
+
+    // Decreasing an allowance to zero deletes it
+    token.increase_allowance(DEPLOYER, ALICE, U256::from(500u64))?;
+    assert!(token.has_raw_entry(&allowance_key(DEPLOYER, ALICE))?);
+    token.decrease_allowance(DEPLOYER, ALICE, U256::from(500u64))?;
+    assert!(
+        !token.has_raw_entry(&allowance_key(DEPLOYER, ALICE))?,
+        "Cleared allowance must not be stored"
+    );
+    assert_eq!(token.allowance(DEPLOYER, ALICE)?, U256::ZERO, "Cleared allowance should read as zero");
+
+    // Spending from a cleared allowance fails
+    let result = token.transfer_from(ALICE, DEPLOYER, BOB, U256::from(1u64));
+    assert!(result.is_err(), "transferFrom without allowance must fail");
+
+    // A cleared allowance can be granted again
+    token.increase_allowance(DEPLOYER, ALICE, U256::from(200u64))?;
+    assert_eq!(
+        token.raw_entry(&allowance_key(DEPLOYER, ALICE))?,
+        U256::from(200u64).to_le_bytes().to_vec()
+    );
+    assert_eq!(token.allowance(DEPLOYER, ALICE)?, U256::from(200u64));
+
+    Ok(())
+}
+
+#[test]
+fn test_storage_version_and_migrate() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Fresh deployments are written at the current storage version
+    assert_eq!(token.storage_version()?, 1);
+
+    // Only the owner can migrate
+    let result = token.migrate(ALICE);
+    assert!(result.is_err(), "Non-owner migrate must fail");
+
+    // Migrating up-to-date storage is a no-op
+    let mark = event_mark(&token.runtime);
+    token.migrate(DEPLOYER)?;
+
+    let events = events_since(&token.runtime, mark);
+    assert!(find_events(&events, "MIGRATE").is_empty(), "No migration step should run");
+
+    // Balances are untouched
+    assert_eq!(token.balance_of(DEPLOYER)?, U256::from(1_000_000u64));
+
+    Ok(())
+}
+
+#[test]
+fn test_migrate_from_version_0() -> Result<()> {
+    let token = TokenHarness::new()?;
+
+    // Seed a version 0 deployment: AS reference layout, no STORAGE_VERSION key,
+    // emptied balances and allowances kept as zero values
+    let zero = U256::ZERO.to_le_bytes();
+    let alice_balance = balance_key(ALICE);
+    let bob_balance = balance_key(BOB);
+    let bob_allowance = allowance_key(ALICE, BOB);
+    let charlie_allowance = allowance_key(ALICE, CHARLIE);
+    let entries: Vec<(&[u8], Vec<u8>)> = vec![
+        (b"NAME".as_slice(), b"MassaCoin".to_vec()),
+        (b"SYMBOL".as_slice(), b"MCOIN".to_vec()),
+        (b"DECIMALS".as_slice(), vec![18]),
+        (b"TOTAL_SUPPLY".as_slice(), U256::from(1_000u64).to_le_bytes().to_vec()),
+        (b"OWNER".as_slice(), DEPLOYER.as_bytes().to_vec()),
+        (alice_balance.as_slice(), U256::from(1_000u64).to_le_bytes().to_vec()),
+        (bob_balance.as_slice(), zero.to_vec()),
+        (bob_allowance.as_slice(), zero.to_vec()),
+        (charlie_allowance.as_slice(), U256::from(5u64).to_le_bytes().to_vec()),
+    ];
+    for (key, value) in &entries {
+        token.set_raw_entry(key, value)?;
+    }
+
+    assert_eq!(token.storage_version()?, 0);
+
+    // The owner migrates to version 1
+    let mark = event_mark(&token.runtime);
+    token.migrate(DEPLOYER)?;
+
+    let events = events_since(&token.runtime, mark);
+    assert_eq!(find_events(&events, "MIGRATE"), vec!["MIGRATE:from=0,to=1"]);
+
+    assert_eq!(token.storage_version()?, 1);
+
+    // Zero entries are gone, the others are kept
+    assert!(!token.has_raw_entry(&bob_balance)?);
+    assert!(!token.has_raw_entry(&bob_allowance)?);
+    assert!(token.has_raw_entry(&alice_balance)?);
+    assert!(token.has_raw_entry(&charlie_allowance)?);
+
+    assert_eq!(token.balance_of(ALICE)?, U256::from(1_000u64));
+    assert_eq!(token.allowance(ALICE, CHARLIE)?, U256::from(5u64));
+
+    // The holder and spender lists are rebuilt from the remaining entries
+    assert_eq!(token.holder_count()?, 1);
+    assert_eq!(token.allowances_of(ALICE, 0, 10)?, vec![(CHARLIE.to_string(), U256::from(5u64))]);
+
+    // A migrated holder can empty their balance and a migrated allowance can reach zero
+    token.transfer(ALICE, BOB, U256::from(1_000u64))?;
+    token.decrease_allowance(ALICE, CHARLIE, U256::from(5u64))?;
+
+    assert!(!token.has_raw_entry(&alice_balance)?);
+    assert!(!token.has_raw_entry(&charlie_allowance)?);
+    assert_eq!(token.holder_count()?, 1);
+    assert_eq!(token.holders(0, 10)?, vec![BOB]);
+    assert!(token.allowances_of(ALICE, 0, 10)?.is_empty());
+
+    // A second migrate has nothing left to do
+    let mark = event_mark(&token.runtime);
+    token.migrate(DEPLOYER)?;
+    assert!(find_events(&events_since(&token.runtime, mark), "MIGRATE").is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_decommission() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+    token.transfer(DEPLOYER, ALICE, U256::from(1_000u64))?;
+
+    // Only the owner can decommission
+    let result = token.decommission(ALICE, u32::MAX);
+    assert!(result.is_err(), "Non-owner decommission must fail");
+
+    let remaining = token.decommission(DEPLOYER, u32::MAX)?;
+    assert_eq!(remaining, 0, "A single page large enough deletes every key");
+
+    let expected_event = format!("DECOMMISSIONED:{}", DEPLOYER);
+    assert!(token.events().contains(&expected_event), "Expected decommission event");
+
+    assert!(token.decommissioned()?);
+
+    // Balances are wiped
+    assert_eq!(token.balance_of(ALICE)?, U256::ZERO, "Balances should be wiped");
+
+    // The contract is permanently disabled
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(BOB).add_u256(U256::ZERO);
+    let err = token.call_expect_failure(ALICE, "transfer", &transfer_args.into_bytes());
+    assert!(err.contains("ERR:DECOMMISSIONED::Contract is decommissioned"), "Unexpected error: {}", err);
+
+    let mut limit_args = Args::new();
+    limit_args.add_string(BOB).add_u256(U256::from(10u64)).add_u64(5);
+    let err = token.call_expect_failure(ALICE, "setSpendingLimit", &limit_args.into_bytes());
+    assert!(err.contains("ERR:DECOMMISSIONED::Contract is decommissioned"), "Unexpected error: {}", err);
+
+    let mut lock_args = Args::new();
+    lock_args.add_string(ALICE).add_u256(U256::ZERO).add_u64(1_000);
+    let err = token.call_expect_failure(ALICE, "lockTokens", &lock_args.into_bytes());
+    assert!(err.contains("ERR:DECOMMISSIONED::Contract is decommissioned"), "Unexpected error: {}", err);
+
+    let mut set_owner_args = Args::new();
+    set_owner_args.add_string(ALICE);
+    let result = token.call(ALICE, "setOwner", &set_owner_args.into_bytes());
+    assert!(result.is_err(), "Ownership cannot be reclaimed after decommission");
+
+    let mut decommission_args = Args::new();
+    decommission_args.add_u32(u32::MAX);
+    let err = token.call_expect_failure(ALICE, "decommission", &decommission_args.into_bytes());
+    assert!(err.contains("ERR:DECOMMISSIONED::Contract is decommissioned"), "Unexpected error: {}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_decommission_in_pages() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+    token.transfer(DEPLOYER, ALICE, U256::from(1_000u64))?;
+    token.increase_allowance(ALICE, BOB, U256::from(10u64))?;
+    let keys = token.raw_keys(&[])?.len() as u64;
+
+    // The owner's first call retires the contract and deletes the first page
+    assert!(token.decommission(ALICE, 2).is_err(), "Non-owner decommission must fail");
+    let remaining = token.decommission(DEPLOYER, 2)?;
+    assert_eq!(remaining, keys - 2);
+    assert_eq!(token.raw_keys(&[])?.len() as u64, remaining + 1, "Only the flag is added");
+    assert!(token.decommissioned()?);
+    assert!(token.transfer(ALICE, BOB, U256::from(1u64)).is_err(), "Transfers are blocked from the first page on");
+
+    // Anyone can delete the next pages
+    assert_eq!(token.decommission(ALICE, 1)?, remaining - 1);
+    assert_eq!(token.decommission(BOB, u32::MAX)?, 0);
+    assert_eq!(token.raw_keys(&[])?, vec![b"DECOMMISSIONED".to_vec()]);
+
+    // Nothing is left to delete
+    let mut decommission_args = Args::new();
+    decommission_args.add_u32(1);
+    let err = token.call_expect_failure(DEPLOYER, "decommission", &decommission_args.into_bytes());
+    assert!(err.contains("ERR:DECOMMISSIONED::Contract is decommissioned"), "Unexpected error: {}", err);
+    assert!(token.decommission(DEPLOYER, 0).is_err(), "A zero limit must fail");
+
+    Ok(())
+}
+
+#[test]
+fn test_multisig_ownership() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Single-owner mode is the default
+    assert_eq!(token.multisig_threshold()?, 0);
+
+    // The owner sets up a 2-of-3 multisig
+    for owner in [DEPLOYER, ALICE, BOB] {
+        token.add_owner(DEPLOYER, owner)?;
+    }
+
+    let result = token.set_threshold(DEPLOYER, 4);
+    assert!(result.is_err(), "Threshold above the owner count must fail");
+
+    token.set_threshold(DEPLOYER, 2)?;
+
+    assert_eq!(token.multisig_owner_count()?, 3);
+
+    // Owner functions can no longer be called directly, even by the owner
+    let mut max_tx_args = Args::new();
+    max_tx_args.add_u256(U256::from(10u64));
+    let err = token.call_expect_failure(DEPLOYER, "setMaxTxAmount", &max_tx_args.into_bytes());
+    assert!(
+        err.contains("ERR:UNAUTHORIZED::Admin functions require a confirmed multisig proposal"),
+        "Unexpected error: {}",
+        err
+    );
+
+    // Role holders keep acting directly
+    token.mint(DEPLOYER, ALICE, U256::from(1u64))?;
+    token.bridge_mint(DEPLOYER, ALICE, U256::from(2u64), "0xsource")?;
+    token.pause(DEPLOYER)?;
+    token.unpause(DEPLOYER)?;
+
+    assert_eq!(token.balance_of(ALICE)?, U256::from(3u64));
+
+    // Multisig owners without the role do not get it
+    let mut mint_args = Args::new();
+    mint_args.add_string(BOB).add_u256(U256::from(1u64));
+    let err = token.call_expect_failure(BOB, "mint", &mint_args.into_bytes());
+    assert!(
+        err.contains(&format!("ERR:UNAUTHORIZED:caller={},role=MINTER:", BOB)),
+        "Unexpected error: {}",
+        err
+    );
+
+    // Only multisig owners can propose
+    let result = token.propose_operation(CHARLIE, "pause", &[]);
+    assert!(result.is_err(), "Non-owner proposal must fail");
+
+    // Alice proposes a pause, which counts as her confirmation
+    assert_eq!(token.propose_operation(ALICE, "pause", &[])?, 0);
+
+    let expected_event = format!("MULTISIG_CONFIRM:id=0,owner={}", ALICE);
+    assert!(token.events().contains(&expected_event), "Expected confirm event");
+
+    // One confirmation is not enough
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = token.call_expect_failure(ALICE, "executeOperation", &id_args.into_bytes());
+    assert!(
+        err.contains("ERR:INVALID_STATE:id=0,confirmations=1,threshold=2:Proposal does not have enough confirmations"),
+        "Unexpected error: {}",
+        err
+    );
+
+    // Confirming twice fails
+    let result = token.confirm_operation(ALICE, 0);
+    assert!(result.is_err(), "Double confirmation must fail");
+
+    // Bob confirms
+    token.confirm_operation(BOB, 0)?;
+
+    assert!(token.is_confirmed(0, BOB)?);
+
+    let mut proposal = token.proposal(0)?;
+    assert_eq!(proposal.next_string().unwrap(), "pause");
+    assert!(proposal.next_bytes().unwrap().is_empty());
+    assert_eq!(proposal.next_u32().unwrap(), 2);
+    assert!(!proposal.next_bool().unwrap());
+
+    Ok(())
+}
+
+#[test]
+fn test_holder_enumeration() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    for holder in [ALICE, BOB] {
+        token.transfer(DEPLOYER, holder, U256::from(1_000u64))?;
+    }
+
+    assert_eq!(token.holder_count()?, 3);
+    assert_eq!(token.holders(0, 10)?, vec![DEPLOYER, ALICE, BOB]);
+
+    // Alice empties her balance: Bob takes her slot
+    token.transfer(ALICE, CHARLIE, U256::from(1_000u64))?;
+
+    assert_eq!(token.holder_count()?, 3);
+    assert_eq!(token.holders(1, 10)?, vec![BOB, CHARLIE]);
+
+    // Offsets past the end return an empty page
+    assert!(token.holders(10, 10)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_supports_interface() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    let ids = token.features()?;
+    assert!(ids.iter().any(|id| id == "MRC20"));
+    assert!(ids.iter().any(|id| id == "permit"));
+
+    for (interface_id, expected) in [("mintable", true), ("pausable", true), ("votes", false)] {
+        assert_eq!(token.supports_interface(interface_id)?, expected, "supportsInterface({})", interface_id);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_allowances_of() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    for (spender, amount) in [(ALICE, 100u64), (BOB, 200u64), (CHARLIE, 300u64)] {
+        token.increase_allowance(DEPLOYER, spender, U256::from(amount))?;
+    }
+
+    // Revoking Alice's allowance removes her from the list
+    token.decrease_allowance(DEPLOYER, ALICE, U256::from(100u64))?;
+
+    let expected = vec![(CHARLIE.to_string(), U256::from(300u64)), (BOB.to_string(), U256::from(200u64))];
+    assert_eq!(token.allowances_of(DEPLOYER, 0, 10)?, expected);
+
+    // Pagination
+    assert_eq!(token.allowances_of(DEPLOYER, 1, 1)?, vec![(BOB.to_string(), U256::from(200u64))]);
+
+    // Owners without approvals have an empty list
+    assert!(token.allowances_of(ALICE, 0, 10)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_constructor_rejects_invalid_args() -> Result<()> {
+    // Missing arguments fail instead of falling back to defaults
+    let err = TokenHarness::new()?.call_expect_failure(DEPLOYER, "constructor", &[]);
+    assert!(
+        err.contains("ERR:INVALID_ARGUMENT:name=name:name argument is missing or invalid"),
+        "Unexpected error: {}",
+        err
+    );
+
+    let mut args = Args::new();
+    args.add_string("MassaCoin").add_string("MCOIN").add_u8(18);
+    let err = TokenHarness::new()?.call_expect_failure(DEPLOYER, "constructor", &args.into_bytes());
+    assert!(
+        err.contains("ERR:INVALID_ARGUMENT:name=totalSupply:totalSupply argument is missing or invalid"),
+        "Unexpected error: {}",
+        err
+    );
+
+    // Empty symbol
+    let args = constructor_args("MassaCoin", "", 18, U256::from(1_000_000u64));
+    let err = TokenHarness::new()?.call_expect_failure(DEPLOYER, "constructor", &args);
+    assert!(
+        err.contains("ERR:INVALID_ARGUMENT:name=symbol:symbol argument must not be empty"),
+        "Unexpected error: {}",
+        err
+    );
+
+    // Initial supply above the cap
+    let args = constructor_args_with_cap("MassaCoin", "MCOIN", 18, U256::from(2_000u64), U256::from(1_000u64));
+    let err = TokenHarness::new()?.call_expect_failure(DEPLOYER, "constructor", &args);
+    assert!(
+        err.contains("ERR:CAP_EXCEEDED:needed=2000,max=1000:totalSupply argument exceeds the supply cap"),
+        "Unexpected error: {}",
+        err
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_constructor_rejects_malformed_optional_args() -> Result<()> {
+    let supply = U256::from(1_000u64);
+
+    // Bytes after the required arguments must decode as the next optional one
+    let mut truncated_cap = constructor_args("MassaCoin", "MCOIN", 18, supply);
+    truncated_cap.extend([0x01u8; 5]);
+    let mut truncated_uri = constructor_args_with_cap("MassaCoin", "MCOIN", 18, supply, U256::ZERO);
+    truncated_uri.extend(10u32.to_le_bytes());
+    truncated_uri.extend(b"ipfs");
+    let mut truncated_description = Args::new();
+    truncated_description
+        .add_string("MassaCoin")
+        .add_string("MCOIN")
+        .add_u8(18)
+        .add_u256(supply)
+        .add_u256(U256::ZERO)
+        .add_string("ipfs://token");
+    let mut truncated_description = truncated_description.into_bytes();
+    truncated_description.extend([0x02u8, 0x00]);
+    let mut missing_mode = Args::new();
+    missing_mode
+        .add_string("MassaCoin")
+        .add_string("MCOIN")
+        .add_u8(18)
+        .add_u256(supply)
+        .add_u256(U256::ZERO)
+        .add_string("ipfs://token")
+        .add_string("");
+    let missing_mode = missing_mode.into_bytes();
+
+    for (entrypoint, args, name) in [
+        ("constructor", &truncated_cap, "cap"),
+        ("constructorWithDefaults", &truncated_cap, "cap"),
+        ("constructor", &truncated_uri, "tokenURI"),
+        ("constructor", &truncated_description, "description"),
+    ] {
+        let err = TokenHarness::new()?.call_expect_failure(DEPLOYER, entrypoint, args);
+        assert!(
+            err.contains(&format!("ERR:INVALID_ARGUMENT:name={}:", name)),
+            "Unexpected error for {} with a malformed {}: {}",
+            entrypoint,
+            name,
+            err
+        );
+    }
+
+    // Arguments that end exactly after a field leave the rest at their defaults
+    let token = TokenHarness::deploy_with(&missing_mode)?;
+    assert_eq!(token.token_uri()?, "ipfs://token");
+    assert!(!token.allowlist_mode()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_truncated_u256_arguments_are_rejected() -> Result<()> {
+    let initial_supply = U256::from(1_000_000u64);
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, initial_supply)?;
+
+    // U256 arguments are exactly 32 bytes: shorter ones fail instead of being zero-padded
+    for length in [0usize, 1, 16, 31] {
+        let mut args = Args::new();
+        args.add_string(ALICE);
+        let mut bytes = args.into_bytes();
+        bytes.extend(std::iter::repeat_n(0x01u8, length));
+
+        for entrypoint in ["transfer", "increaseAllowance"] {
+            let err = token.call_expect_failure(DEPLOYER, entrypoint, &bytes);
+            assert!(
+                err.contains("ERR:INVALID_ARGUMENT:name=amount:"),
+                "Unexpected error for {} with {} bytes: {}",
+                entrypoint,
+                length,
+                err
+            );
+        }
+    }
+
+    // Nothing moved
+    assert_eq!(token.balance_of(DEPLOYER)?, initial_supply);
+
+    Ok(())
+}
+
+#[test]
+fn test_constructor_with_defaults() -> Result<()> {
+    let token = TokenHarness::new()?;
+    token.call(DEPLOYER, "constructorWithDefaults", &[])?;
+
+    assert_eq!(token.name()?, "MassaToken");
+    assert_eq!(token.symbol()?, "MT");
+    assert_eq!(token.decimals()?, 18);
+
+    assert_eq!(token.total_supply()?, U256::from(1_000_000_000_000_000_000u64));
+
+    Ok(())
+}
+
+#[test]
+fn test_self_transfer_is_noop() -> Result<()> {
+    let initial_supply = U256::from(1_000_000u64);
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, initial_supply)?;
+
+    // Self-transfer succeeds and emits the transfer event
+    let amount = U256::from(1_000u64);
+    token.transfer(DEPLOYER, DEPLOYER, amount)?;
+
+    let expected_event = format!(
+        "TRANSFER SUCCESS:from={},to={},amount={}",
+        DEPLOYER, DEPLOYER, amount
+    );
+    assert!(token.events().contains(&expected_event), "Expected transfer event");
+
+    // Self-transfer above the balance still fails
+    let result = token.transfer(DEPLOYER, DEPLOYER, U256::from(1_000_001u64));
+    assert!(result.is_err(), "Self-transfer above balance must fail");
+
+    // transferFrom with owner == recipient spends the allowance only
+    token.increase_allowance(DEPLOYER, ALICE, amount)?;
+    token.transfer_from(ALICE, DEPLOYER, DEPLOYER, amount)?;
+
+    assert_eq!(token.balance_of(DEPLOYER)?, initial_supply, "Balance should be unchanged");
+    assert_eq!(token.allowance(DEPLOYER, ALICE)?, U256::ZERO, "Allowance should be spent");
+
+    Ok(())
+}
+
+#[test]
+fn test_error_codes() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+    token.transfer(DEPLOYER, ALICE, U256::from(100u64))?;
+
+    // Insufficient balance reports the needed and available amounts
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(BOB).add_u256(U256::from(150u64));
+    let err = token.call_expect_failure(ALICE, "transfer", &transfer_args.into_bytes());
+    assert!(
+        err.contains("ERR:INSUFFICIENT_BALANCE:needed=150,have=100:Transfer failed: insufficient funds"),
+        "Unexpected error: {}",
+        err
+    );
+
+    // Insufficient allowance
+    let mut transfer_from_args = Args::new();
+    transfer_from_args.add_string(DEPLOYER).add_string(BOB).add_u256(U256::from(10u64));
+    let err = token.call_expect_failure(ALICE, "transferFrom", &transfer_from_args.into_bytes());
+    assert!(err.contains("ERR:INSUFFICIENT_ALLOWANCE:needed=10,have=0:"), "Unexpected error: {}", err);
+
+    // Missing role
+    let err = token.call_expect_failure(ALICE, "pause", &[]);
+    assert!(err.contains(&format!("ERR:UNAUTHORIZED:caller={},role=PAUSER:", ALICE)), "Unexpected error: {}", err);
+
+    // Missing argument
+    let err = token.call_expect_failure(ALICE, "balanceOf", &[]);
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=Address:"), "Unexpected error: {}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_approve_if_current() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Setting an allowance from zero
+    let outcome = token.approve_if_current(DEPLOYER, ALICE, U256::ZERO, U256::from(100u64))?;
+    assert_eq!(outcome, (true, U256::from(100u64)));
+
+    // Alice front-runs the change by spending part of the allowance
+    token.transfer_from(ALICE, DEPLOYER, ALICE, U256::from(60u64))?;
+
+    // The owner's swap from 100 to 50 no longer matches and leaves the allowance untouched
+    let outcome = token.approve_if_current(DEPLOYER, ALICE, U256::from(100u64), U256::from(50u64))?;
+    assert_eq!(outcome, (false, U256::from(40u64)));
+
+    let expected_event = format!(
+        "APPROVE_IF_CURRENT:owner={},spender={},expected=100,current=40,success=false",
+        DEPLOYER, ALICE
+    );
+    assert!(token.events().contains(&expected_event), "Expected failed CAS event");
+
+    assert_eq!(token.allowance(DEPLOYER, ALICE)?, U256::from(40u64));
+
+    Ok(())
+}
+
+#[test]
+fn test_relayed_transfer_rejects_invalid_orders() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Expired order
+    let mut relay_args = Args::new();
+    relay_args
+        .add_string(DEPLOYER)
+        .add_string(ALICE)
+        .add_u256(U256::from(1_000u64))
+        .add_u256(U256::from(10u64))
+        .add_u64(0)
+        .add_u64(0)
+        .add_string("P1invalidPublicKey")
+        .add_string("1invalidSignature");
+    let err = token.call_expect_failure(BOB, "relayedTransfer", &relay_args.into_bytes());
+    assert!(err.contains("ERR:EXPIRED::Relayed transfer expired"), "Unexpected error: {}", err);
+
+    // Wrong nonce
+    let mut relay_args = Args::new();
+    relay_args
+        .add_string(DEPLOYER)
+        .add_string(ALICE)
+        .add_u256(U256::from(1_000u64))
+        .add_u256(U256::from(10u64))
+        .add_u64(5)
+        .add_u64(u64::MAX)
+        .add_string("P1invalidPublicKey")
+        .add_string("1invalidSignature");
+    let err = token.call_expect_failure(BOB, "relayedTransfer", &relay_args.into_bytes());
+    assert!(err.contains("ERR:ALREADY_USED:nonce=5,expected=0:"), "Unexpected error: {}", err);
+
+    // Invalid signature
+    let mut relay_args = Args::new();
+    relay_args
+        .add_string(DEPLOYER)
+        .add_string(ALICE)
+        .add_u256(U256::from(1_000u64))
+        .add_u256(U256::from(10u64))
+        .add_u64(0)
+        .add_u64(u64::MAX)
+        .add_string("P1invalidPublicKey")
+        .add_string("1invalidSignature");
+    let result = token.call(BOB, "relayedTransfer", &relay_args.into_bytes());
+    assert!(result.is_err(), "Relayed transfer with an invalid signature must fail");
+
+    // Balances and nonce are untouched
+    assert_eq!(token.balance_of(BOB)?, U256::ZERO);
+    assert_eq!(token.nonces(DEPLOYER)?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_wmas() -> Result<()> {
+    let wasm = std::fs::read(wmas_wasm_path())?;
+    let runtime = TestRuntime::new();
+
+    // Deploy with no arguments and no initial supply
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
+    runtime.execute(&wasm, "constructor", &[])?;
+
+    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
+    let response = runtime.execute(&wasm, "name", &[])?;
+    assert_eq!(response.ret, b"Wrapped MAS".to_vec());
+    let response = runtime.execute(&wasm, "symbol", &[])?;
+    assert_eq!(response.ret, b"WMAS".to_vec());
+    let response = runtime.execute(&wasm, "decimals", &[])?;
+    assert_eq!(response.ret, vec![9u8]);
+    let response = runtime.execute(&wasm, "totalSupply", &[])?;
+    assert_eq!(response.ret, U256::ZERO.to_le_bytes().to_vec());
+
+    // Deposit without coins fails
+    runtime
+        .interface
+        .set_call_stack(vec![ALICE.to_string(), "AS_CONTRACT".to_string()]);
+    let err = execute_expect_failure(&runtime, &wasm, "deposit", &[]);
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=coins:"), "Unexpected error: {}", err);
+
+    // Withdraw beyond the balance fails
+    let mut withdraw_args = Args::new();
+    withdraw_args.add_u256(U256::from(1u64));
+    let err = execute_expect_failure(&runtime, &wasm, "withdraw", &withdraw_args.into_bytes());
+    assert!(err.contains("ERR:INSUFFICIENT_BALANCE:needed=1,have=0:"), "Unexpected error: {}", err);
+
+    // Withdraw beyond the coin range fails
+    let mut withdraw_args = Args::new();
+    withdraw_args.add_u256(U256::from(u64::MAX).checked_add(U256::from(1u64)).unwrap());
+    let err = execute_expect_failure(&runtime, &wasm, "withdraw", &withdraw_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=amount:"), "Unexpected error: {}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_subscription_validation() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Zero amount and zero interval are rejected
+    for (amount, interval, name) in [(0u64, 10u64, "amountPerInterval"), (100, 0, "intervalPeriods")] {
+        let mut subscription_args = Args::new();
+        subscription_args.add_string(BOB).add_u256(U256::from(amount)).add_u64(interval);
+        let err = token.call_expect_failure(ALICE, "createSubscription", &subscription_args.into_bytes());
+        assert!(err.contains(&format!("ERR:INVALID_ARGUMENT:name={}:", name)), "Unexpected error: {}", err);
+    }
+
+    // The first payment is due immediately, so an empty account cannot subscribe
+    let mut subscription_args = Args::new();
+    subscription_args.add_string(BOB).add_u256(U256::from(100u64)).add_u64(10);
+    let err = token.call_expect_failure(ALICE, "createSubscription", &subscription_args.into_bytes());
+    assert!(err.contains("ERR:INSUFFICIENT_BALANCE:needed=100,have=0:"), "Unexpected error: {}", err);
+
+    // Payments can only be triggered by the contract's deferred calls
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = token.call_expect_failure(ALICE, "processSubscription", &id_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
+
+    // Unknown subscriptions
+    for function in ["subscriptionStatus", "cancelSubscription"] {
+        let mut id_args = Args::new();
+        id_args.add_u64(0);
+        let err = token.call_expect_failure(ALICE, function, &id_args.into_bytes());
+        assert!(err.contains("ERR:NOT_FOUND::Subscription does not exist"), "Unexpected error: {}", err);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_subscription_frozen_payer() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+    token.transfer(DEPLOYER, ALICE, U256::from(1_000u64))?;
+
+    // A frozen payer cannot subscribe
+    token.freeze_account(DEPLOYER, ALICE)?;
+    let mut subscription_args = Args::new();
+    subscription_args.add_string(BOB).add_u256(U256::from(100u64)).add_u64(10);
+    let err = token.call_expect_failure(ALICE, "createSubscription", &subscription_args.into_bytes());
+    assert!(err.contains(&format!("ERR:FROZEN:account={}:", ALICE)), "Unexpected error: {}", err);
+
+    token.unfreeze_account(DEPLOYER, ALICE)?;
+    assert_eq!(token.create_subscription(ALICE, BOB, U256::from(100u64), 10)?, 0);
+
+    // Freezing the payer afterwards does not make the subscription's runs fail
+    token.freeze_account(DEPLOYER, ALICE)?;
+    token.process_subscription(0)?;
+    assert!(token.subscription_active(0)?);
+
+    // The frozen payer can still cancel and get the fee budget back
+    token.cancel_subscription(ALICE, 0)?;
+    let events = token.events();
+    let expected = format!("SUBSCRIPTION_CANCELLED:id=0,by={}", ALICE);
+    assert!(events.contains(&expected), "Missing cancel event: {:?}", events);
+    assert!(!token.subscription_active(0)?);
+
+    Ok(())
+}
+
+#[test]
+fn test_subscription_cancel_after_run() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    assert_eq!(token.create_subscription(DEPLOYER, BOB, U256::from(100u64), 10)?, 0);
+
+    // Runs for unknown subscriptions, or before the payment is due, do nothing
+    let mark = event_mark(&token.runtime);
+    for id in [0u64, 7] {
+        token.process_subscription(id)?;
+    }
+    assert!(events_since(&token.runtime, mark).is_empty());
+
+    // The payee cancels after those runs
+    token.cancel_subscription(BOB, 0)?;
+    assert!(!token.subscription_active(0)?);
+
+    // Cancelling twice fails, later runs still do nothing
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = token.call_expect_failure(BOB, "cancelSubscription", &id_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_STATE:id=0:Subscription is not active"), "Unexpected error: {}", err);
+
+    token.process_subscription(0)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_stream_create_and_cancel() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
+
+    // Invalid period range
+    let mut stream_args = Args::new();
+    stream_args.add_string(ALICE).add_u256(U256::from(1_000u64)).add_u64(100).add_u64(100);
+    let err = token.call_expect_failure(DEPLOYER, "createStream", &stream_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=endPeriod:"), "Unexpected error: {}", err);
+
+    // Stream starting far in the future escrows the full amount
+    let start_period = 1_000_000_000u64;
+    let id = token.create_stream(DEPLOYER, ALICE, U256::from(1_000u64), start_period, start_period + 100)?;
+    assert_eq!(id, 0);
+
+    let events = token.events();
+    let expected = format!(
+        "STREAM_CREATED:id=0,sender={},recipient={},amount=1000,start={},end={}",
+        DEPLOYER,
+        ALICE,
+        start_period,
+        start_period + 100
+    );
+    assert!(events.contains(&expected), "Missing stream event: {:?}", events);
+
+    assert_eq!(token.balance_of(DEPLOYER)?, U256::from(999_000u64));
+    assert_eq!(token.balance_of("AS_CONTRACT")?, U256::from(1_000u64));
+
+    // Nothing is withdrawable before the start period
+    assert_eq!(token.stream_withdrawable(0)?, U256::ZERO);
+
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = token.call_expect_failure(ALICE, "withdrawFromStream", &id_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_STATE:id=0:Nothing to withdraw"), "Unexpected error: {}", err);
+
+    // Strangers cannot cancel
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = token.call_expect_failure(BOB, "cancelStream", &id_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
+
+    // The escrow cannot be rescued
+    let mut rescue_args = Args::new();
+    rescue_args.add_string("AS_CONTRACT").add_string(BOB).add_u256(U256::from(1u64));
+    let err = token.call_expect_failure(DEPLOYER, "rescueToken", &rescue_args.into_bytes());
+    assert!(err.contains("ERR:INSUFFICIENT_BALANCE:needed=1,have=0:"), "Unexpected error: {}", err);
+
+    // The sender cancels and gets everything back
+    token.cancel_stream(DEPLOYER, 0)?;
+    assert_eq!(token.balance_of(DEPLOYER)?, U256::from(1_000_000u64));
+    assert_eq!(token.balance_of("AS_CONTRACT")?, U256::ZERO);
+
+    let mut stream = token.stream(0)?;
+    assert_eq!(stream.next_string().unwrap(), DEPLOYER);
+    assert_eq!(stream.next_string().unwrap(), ALICE);
+    assert_eq!(stream.next_u256().unwrap(), U256::from(1_000u64));
+    assert_eq!(stream.next_u64().unwrap(), start_period);
+    assert_eq!(stream.next_u64().unwrap(), start_period + 100);
+    assert_eq!(stream.next_u256().unwrap(), U256::ZERO);
+    assert!(stream.next_bool().unwrap());
+
+    Ok(())
+}
+
+#[test]
+fn test_merkle_airdrop_proofs() -> Result<()> {
+    let wasm = std::fs::read(merkle_airdrop_wasm_path())?;
+    let runtime = TestRuntime::new();
+
+    let entries = [(ALICE, 100u64), (BOB, 250u64), (CHARLIE, 75u64)];
+    let leaves: Vec<[u8; 32]> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, (account, amount))| airdrop_leaf(index as u64, account, U256::from(*amount)))
+        .collect();
+    let (root, proofs) = airdrop_tree(&leaves);
+
+    // Deploy
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_AIRDROP".to_string()]);
+    let mut args = Args::new();
+    args.add_string("AS_TOKEN").add_bytes(&root);
+    runtime.execute(&wasm, "constructor", &args.into_bytes())?;
+
+    runtime.interface.set_call_stack(vec!["AS_AIRDROP".to_string()]);
+    let response = runtime.execute(&wasm, "merkleRoot", &[])?;
+    assert_eq!(response.ret, root.to_vec());
+
+    // Every entry verifies with its proof, and only with its own data
+    for (index, (account, amount)) in entries.iter().enumerate() {
+        let mut verify_args = Args::new();
+        verify_args
+            .add_u64(index as u64)
+            .add_string(account)
+            .add_u256(U256::from(*amount))
+            .add_bytes(&proofs[index]);
+        let response = runtime.execute(&wasm, "verifyClaim", &verify_args.into_bytes())?;
+        assert!(response.ret.as_bool()?, "Entry {} should verify", index);
+
+        let mut verify_args = Args::new();
+        verify_args
+            .add_u64(index as u64)
+            .add_string(account)
+            .add_u256(U256::from(*amount + 1))
+            .add_bytes(&proofs[index]);
+        let response = runtime.execute(&wasm, "verifyClaim", &verify_args.into_bytes())?;
+        assert!(!response.ret.as_bool()?, "Entry {} with a wrong amount should not verify", index);
+    }
+
+    // Claiming someone else's entry fails
+    runtime
+        .interface
+        .set_call_stack(vec![CHARLIE.to_string(), "AS_AIRDROP".to_string()]);
+    let mut claim_args = Args::new();
+    claim_args.add_u64(0).add_u256(U256::from(100u64)).add_bytes(&proofs[0]);
+    let err = execute_expect_failure(&runtime, &wasm, "claim", &claim_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_PROOF:index=0:"), "Unexpected error: {}", err);
+
+    // Malformed proof
+    let mut claim_args = Args::new();
+    claim_args.add_u64(2).add_u256(U256::from(75u64)).add_bytes(&[0u8; 31]);
+    let err = execute_expect_failure(&runtime, &wasm, "claim", &claim_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=proof:"), "Unexpected error: {}", err);
+
+    let mut index_args = Args::new();
+    index_args.add_u64(0);
+    let response = runtime.execute(&wasm, "isClaimed", &index_args.into_bytes())?;
+    assert!(!response.ret.as_bool()?);
+
+    // Only the owner can recover tokens
+    let mut recover_args = Args::new();
+    recover_args.add_string(CHARLIE).add_u256(U256::from(1u64));
+    let err = execute_expect_failure(&runtime, &wasm, "recover", &recover_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_staking_admin_and_queries() -> Result<()> {
+    let wasm = std::fs::read(staking_wasm_path())?;
+    let runtime = TestRuntime::new();
+
+    // Deploy
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_STAKING".to_string()]);
+    let mut args = Args::new();
+    args.add_string("AS_TOKEN").add_u256(U256::from(10u64));
+    runtime.execute(&wasm, "constructor", &args.into_bytes())?;
+
+    runtime.interface.set_call_stack(vec!["AS_STAKING".to_string()]);
+    let response = runtime.execute(&wasm, "rewardRate", &[])?;
+    assert_eq!(response.ret, U256::from(10u64).to_le_bytes().to_vec());
+    for function in ["totalStaked", "rewardReserve"] {
+        let response = runtime.execute(&wasm, function, &[])?;
+        assert_eq!(response.ret, U256::ZERO.to_le_bytes().to_vec(), "{}", function);
+    }
+    let mut address_args = Args::new();
+    address_args.add_string(ALICE);
+    let response = runtime.execute(&wasm, "earned", &address_args.into_bytes())?;
+    assert_eq!(response.ret, U256::ZERO.to_le_bytes().to_vec());
+
+    // Nothing staked, nothing earned
+    runtime
+        .interface
+        .set_call_stack(vec![ALICE.to_string(), "AS_STAKING".to_string()]);
+    let mut amount_args = Args::new();
+    amount_args.add_u256(U256::from(1u64));
+    let err = execute_expect_failure(&runtime, &wasm, "unstake", &amount_args.into_bytes());
+    assert!(err.contains("ERR:INSUFFICIENT_BALANCE:needed=1,have=0:"), "Unexpected error: {}", err);
+    let err = execute_expect_failure(&runtime, &wasm, "claimRewards", &[]);
+    assert!(err.contains("ERR:INVALID_STATE::No rewards to claim"), "Unexpected error: {}", err);
+
+    let mut amount_args = Args::new();
+    amount_args.add_u256(U256::ZERO);
+    let err = execute_expect_failure(&runtime, &wasm, "stake", &amount_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=amount:"), "Unexpected error: {}", err);
+
+    // Only the owner sets the reward rate
+    let mut rate_args = Args::new();
+    rate_args.add_u256(U256::from(20u64));
+    let err = execute_expect_failure(&runtime, &wasm, "setRewardRate", &rate_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
+
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_STAKING".to_string()]);
+    let mut rate_args = Args::new();
+    rate_args.add_u256(U256::from(20u64));
+    runtime.execute(&wasm, "setRewardRate", &rate_args.into_bytes())?;
+    let events = runtime.interface.events();
+    assert!(events.iter().any(|e| e == "REWARD_RATE:20"), "Missing rate event: {:?}", events);
+
+    Ok(())
+}
+
+#[test]
+fn test_governor_settings_and_validation() -> Result<()> {
+    let wasm = std::fs::read(governor_wasm_path())?;
+
+    // Quorum above 100% is rejected
+    let runtime = TestRuntime::new();
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_GOVERNOR".to_string()]);
+    let mut args = Args::new();
+    args.add_string("AS_TOKEN").add_u64(100).add_u32(10_001).add_u256(U256::ZERO).add_u64(5);
+    let err = execute_expect_failure(&runtime, &wasm, "constructor", &args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=quorumBps:"), "Unexpected error: {}", err);
+
+    // Votes cannot open in the proposal's own period
+    let runtime = TestRuntime::new();
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_GOVERNOR".to_string()]);
+    let mut args = Args::new();
+    args.add_string("AS_TOKEN").add_u64(100).add_u32(400).add_u256(U256::ZERO).add_u64(0);
+    let err = execute_expect_failure(&runtime, &wasm, "constructor", &args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=votingDelay:"), "Unexpected error: {}", err);
+
     let runtime = TestRuntime::new();
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_GOVERNOR".to_string()]);
+    let mut args = Args::new();
+    args.add_string("AS_TOKEN").add_u64(100).add_u32(400).add_u256(U256::from(1_000u64)).add_u64(5);
+    runtime.execute(&wasm, "constructor", &args.into_bytes())?;
+
+    runtime.interface.set_call_stack(vec!["AS_GOVERNOR".to_string()]);
+    let response = runtime.execute(&wasm, "settings", &[])?;
+    let mut settings = response.ret.as_args();
+    assert_eq!(settings.next_u64().unwrap(), 100);
+    assert_eq!(settings.next_u32().unwrap(), 400);
+    assert_eq!(settings.next_u256().unwrap(), U256::from(1_000u64));
+    assert_eq!(settings.next_u64().unwrap(), 5);
+    let response = runtime.execute(&wasm, "proposalCount", &[])?;
+    assert_eq!(response.ret.as_u64()?, 0);
 
-    // Set up deployment
+    // Settings only change through proposals
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let initial_supply = U256::from(1_000_000u64);
-    let args = constructor_args("MassaCoin", "MCOIN", 18, initial_supply);
-    runtime.execute(&wasm, "constructor", &args)?;
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_GOVERNOR".to_string()]);
+    let mut settings_args = Args::new();
+    settings_args.add_u64(10).add_u32(100).add_u256(U256::ZERO).add_u64(1);
+    let err = execute_expect_failure(&runtime, &wasm, "updateSettings", &settings_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
 
-    // Check deployer balance
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
-    let mut balance_args = Args::new();
-    balance_args.add_string(DEPLOYER);
-    let response = runtime.execute(&wasm, "balanceOf", &balance_args.into_bytes())?;
-    
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let balance = U256::from_le_bytes(bytes);
+    // Proposals need a checkpoint from an earlier period, so the proposal
+    // itself cannot take the snapshot its voting power is read at
+    let mut propose_args = Args::new();
+    propose_args.add_string("AS_TOKEN").add_string("mint").add_bytes(&[]).add_string("Mint");
+    let err = execute_expect_failure(&runtime, &wasm, "propose", &propose_args.into_bytes());
+    assert!(
+        err.contains("ERR:NOT_YET_VALID:checkpointPeriod=0:Proposals need a checkpoint from an earlier period"),
+        "Unexpected error: {}",
+        err
+    );
 
-    assert_eq!(balance, initial_supply);
-    println!("Deployer balance: {}", balance);
+    // Votes need a valid option and an existing proposal
+    let mut vote_args = Args::new();
+    vote_args.add_u64(0).add_u8(3);
+    let err = execute_expect_failure(&runtime, &wasm, "castVote", &vote_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=support:"), "Unexpected error: {}", err);
 
-    // Check Alice balance (should be 0)
-    let mut alice_args = Args::new();
-    alice_args.add_string(ALICE);
-    let response = runtime.execute(&wasm, "balanceOf", &alice_args.into_bytes())?;
-    
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let alice_balance = U256::from_le_bytes(bytes);
+    let mut vote_args = Args::new();
+    vote_args.add_u64(0).add_u8(1);
+    let err = execute_expect_failure(&runtime, &wasm, "castVote", &vote_args.into_bytes());
+    assert!(err.contains("ERR:NOT_FOUND:id=0:"), "Unexpected error: {}", err);
 
-    assert_eq!(alice_balance, U256::ZERO);
-    println!("Alice balance: {}", alice_balance);
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = execute_expect_failure(&runtime, &wasm, "execute", &id_args.into_bytes());
+    assert!(err.contains("ERR:NOT_FOUND:id=0:"), "Unexpected error: {}", err);
 
     Ok(())
 }
 
 #[test]
-fn test_transfer() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
+fn test_timelock_schedule_and_cancel() -> Result<()> {
+    let wasm = std::fs::read(timelock_wasm_path())?;
     let runtime = TestRuntime::new();
 
-    // Set up deployment
+    // Deploy with a 10-period minimum delay
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let initial_supply = U256::from(1_000_000u64);
-    let args = constructor_args("MassaCoin", "MCOIN", 18, initial_supply);
-    runtime.execute(&wasm, "constructor", &args)?;
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_TIMELOCK".to_string()]);
+    let mut args = Args::new();
+    args.add_u64(10);
+    runtime.execute(&wasm, "constructor", &args.into_bytes())?;
 
-    // Transfer from deployer to Alice
+    let mut mint_args = Args::new();
+    mint_args.add_string(ALICE).add_u256(U256::from(1_000u64));
+    let mint_args = mint_args.into_bytes();
+
+    // Delays below the minimum are rejected
+    let mut schedule_args = Args::new();
+    schedule_args.add_string("AS_TOKEN").add_string("mint").add_bytes(&mint_args).add_u64(5);
+    let err = execute_expect_failure(&runtime, &wasm, "schedule", &schedule_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=delay,needed=10,have=5:"), "Unexpected error: {}", err);
+
+    // Only the owner schedules
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let transfer_amount = U256::from(100_000u64);
-    let mut transfer_args = Args::new();
-    transfer_args.add_string(ALICE).add_u256(transfer_amount);
-    runtime.execute(&wasm, "transfer", &transfer_args.into_bytes())?;
+        .set_call_stack(vec![ALICE.to_string(), "AS_TIMELOCK".to_string()]);
+    let mut schedule_args = Args::new();
+    schedule_args.add_string("AS_TOKEN").add_string("mint").add_bytes(&mint_args).add_u64(10);
+    let err = execute_expect_failure(&runtime, &wasm, "schedule", &schedule_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
 
-    // Check events
-    let events = runtime.interface.events();
-    let transfer_event = events.iter().find(|e| e.contains("TRANSFER SUCCESS"));
-    assert!(transfer_event.is_some(), "Expected transfer event");
-    println!("Transfer event: {:?}", transfer_event);
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_TIMELOCK".to_string()]);
+    let mut schedule_args = Args::new();
+    schedule_args.add_string("AS_TOKEN").add_string("mint").add_bytes(&mint_args).add_u64(10);
+    let response = runtime.execute(&wasm, "schedule", &schedule_args.into_bytes())?;
+    assert_eq!(response.ret.as_u64()?, 0);
 
-    // Check balances
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let response = runtime.execute(&wasm, "operation", &id_args.into_bytes())?;
+    let mut operation = response.ret.as_args();
+    assert_eq!(operation.next_string().unwrap(), "AS_TOKEN");
+    assert_eq!(operation.next_string().unwrap(), "mint");
+    assert_eq!(operation.next_bytes().unwrap(), mint_args);
+    let ready_period = operation.next_u64().unwrap();
+    assert_eq!(operation.next_u8().unwrap(), 0);
 
-    let mut deployer_args = Args::new();
-    deployer_args.add_string(DEPLOYER);
-    let response = runtime.execute(&wasm, "balanceOf", &deployer_args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let deployer_balance = U256::from_le_bytes(bytes);
-    
-    let expected_deployer = initial_supply.checked_sub(transfer_amount).unwrap();
-    assert_eq!(deployer_balance, expected_deployer, "Deployer balance should decrease");
+    // Too early to execute
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = execute_expect_failure(&runtime, &wasm, "execute", &id_args.into_bytes());
+    assert!(err.contains(&format!("ERR:NOT_YET_VALID:id=0,ready={}:", ready_period)), "Unexpected error: {}", err);
 
-    let mut alice_args = Args::new();
-    alice_args.add_string(ALICE);
-    let response = runtime.execute(&wasm, "balanceOf", &alice_args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let alice_balance = U256::from_le_bytes(bytes);
-    
-    assert_eq!(alice_balance, transfer_amount, "Alice balance should increase");
+    // Cancelled operations can never run
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    runtime.execute(&wasm, "cancel", &id_args.into_bytes())?;
+    let events = runtime.interface.events();
+    assert!(events.iter().any(|e| e == "TIMELOCK_CANCELLED:id=0"), "Missing cancel event: {:?}", events);
 
-    println!("Deployer balance: {}, Alice balance: {}", deployer_balance, alice_balance);
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = execute_expect_failure(&runtime, &wasm, "execute", &id_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_STATE:id=0:Operation is cancelled"), "Unexpected error: {}", err);
+
+    // Settings only change through the timelock
+    let mut delay_args = Args::new();
+    delay_args.add_u64(0);
+    let err = execute_expect_failure(&runtime, &wasm, "updateDelay", &delay_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
 
     Ok(())
 }
 
 #[test]
-fn test_increase_decrease_allowance() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
+fn test_multisig_wallet_confirmations() -> Result<()> {
+    let wasm = std::fs::read(multisig_wasm_path())?;
     let runtime = TestRuntime::new();
 
-    // Set up deployment
+    // Deploy a 2-of-3 wallet
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let args = constructor_args("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64));
-    runtime.execute(&wasm, "constructor", &args)?;
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_WALLET".to_string()]);
+    let mut owners = Args::new();
+    owners.add_u32(3).add_string(ALICE).add_string(BOB).add_string(CHARLIE);
+    let mut args = Args::new();
+    args.add_bytes(&owners.into_bytes()).add_u32(2);
+    runtime.execute(&wasm, "constructor", &args.into_bytes())?;
 
-    // Deployer increases allowance for Alice
+    runtime.interface.set_call_stack(vec!["AS_WALLET".to_string()]);
+    let response = runtime.execute(&wasm, "ownerCount", &[])?;
+    assert_eq!(response.ret, 3u32.to_le_bytes().to_vec());
+    let response = runtime.execute(&wasm, "threshold", &[])?;
+    assert_eq!(response.ret, 2u32.to_le_bytes().to_vec());
+
+    let mut transfer_args = Args::new();
+    transfer_args.add_string(DEPLOYER).add_u256(U256::from(500u64));
+    let transfer_args = transfer_args.into_bytes();
+
+    // Non-owners cannot submit
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let approve_amount = U256::from(50_000u64);
-    let mut approve_args = Args::new();
-    approve_args.add_string(ALICE).add_u256(approve_amount);
-    runtime.execute(&wasm, "increaseAllowance", &approve_args.into_bytes())?;
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_WALLET".to_string()]);
+    let mut submit_args = Args::new();
+    submit_args.add_string("AS_TOKEN").add_string("transfer").add_bytes(&transfer_args).add_u64(0);
+    let err = execute_expect_failure(&runtime, &wasm, "submitTransaction", &submit_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
 
-    // Check allowance
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
-    let mut allowance_args = Args::new();
-    allowance_args.add_string(DEPLOYER).add_string(ALICE);
-    let response = runtime.execute(&wasm, "allowance", &allowance_args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let allowance = U256::from_le_bytes(bytes);
+    // Alice submits and confirms
+    runtime
+        .interface
+        .set_call_stack(vec![ALICE.to_string(), "AS_WALLET".to_string()]);
+    let mut submit_args = Args::new();
+    submit_args.add_string("AS_TOKEN").add_string("transfer").add_bytes(&transfer_args).add_u64(0);
+    let response = runtime.execute(&wasm, "submitTransaction", &submit_args.into_bytes())?;
+    assert_eq!(response.ret.as_u64()?, 0);
 
-    assert_eq!(allowance, approve_amount);
-    println!("Allowance from {} to {}: {}", DEPLOYER, ALICE, allowance);
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = execute_expect_failure(&runtime, &wasm, "confirmTransaction", &id_args.into_bytes());
+    assert!(err.contains("ERR:ALREADY_USED:id=0:"), "Unexpected error: {}", err);
 
-    // Decrease allowance
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = execute_expect_failure(&runtime, &wasm, "executeTransaction", &id_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_STATE:id=0,confirmations=1,threshold=2:"), "Unexpected error: {}", err);
+
+    // Bob confirms, then revokes
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let decrease_amount = U256::from(20_000u64);
-    let mut decrease_args = Args::new();
-    decrease_args.add_string(ALICE).add_u256(decrease_amount);
-    runtime.execute(&wasm, "decreaseAllowance", &decrease_args.into_bytes())?;
+        .set_call_stack(vec![BOB.to_string(), "AS_WALLET".to_string()]);
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    runtime.execute(&wasm, "confirmTransaction", &id_args.into_bytes())?;
 
-    // Check new allowance
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
-    let mut allowance_args = Args::new();
-    allowance_args.add_string(DEPLOYER).add_string(ALICE);
-    let response = runtime.execute(&wasm, "allowance", &allowance_args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let new_allowance = U256::from_le_bytes(bytes);
+    let mut confirmed_args = Args::new();
+    confirmed_args.add_u64(0).add_string(BOB);
+    let response = runtime.execute(&wasm, "isConfirmed", &confirmed_args.into_bytes())?;
+    assert!(response.ret.as_bool()?);
 
-    let expected = approve_amount.checked_sub(decrease_amount).unwrap();
-    assert_eq!(new_allowance, expected);
-    println!("New allowance: {}", new_allowance);
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    runtime.execute(&wasm, "revokeConfirmation", &id_args.into_bytes())?;
+
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let response = runtime.execute(&wasm, "transaction", &id_args.into_bytes())?;
+    let mut transaction = response.ret.as_args();
+    assert_eq!(transaction.next_string().unwrap(), "AS_TOKEN");
+    assert_eq!(transaction.next_string().unwrap(), "transfer");
+    assert_eq!(transaction.next_bytes().unwrap(), transfer_args);
+    assert_eq!(transaction.next_u64().unwrap(), 0);
+    assert_eq!(transaction.next_u32().unwrap(), 1);
+    assert!(!transaction.next_bool().unwrap());
+
+    // Owner changes only go through the wallet
+    let mut owner_args = Args::new();
+    owner_args.add_string(DEPLOYER);
+    let err = execute_expect_failure(&runtime, &wasm, "addOwner", &owner_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
 
     Ok(())
 }
 
 #[test]
-fn test_transfer_from() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
+fn test_token_factory_registry() -> Result<()> {
+    let wasm = std::fs::read(token_factory_wasm_path())?;
+    let token_wasm = std::fs::read(wasm_path())?;
     let runtime = TestRuntime::new();
 
-    // Set up deployment
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let initial_supply = U256::from(1_000_000u64);
-    let args = constructor_args("MassaCoin", "MCOIN", 18, initial_supply);
-    runtime.execute(&wasm, "constructor", &args)?;
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_FACTORY".to_string()]);
 
-    // Deployer increases allowance for Alice
-    runtime
-        .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let approve_amount = U256::from(100_000u64);
-    let mut approve_args = Args::new();
-    approve_args.add_string(ALICE).add_u256(approve_amount);
-    runtime.execute(&wasm, "increaseAllowance", &approve_args.into_bytes())?;
+    // The token bytecode is required
+    let mut args = Args::new();
+    args.add_bytes(&[]);
+    let err = execute_expect_failure(&runtime, &wasm, "constructor", &args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=bytecode:"), "Unexpected error: {}", err);
 
-    // Alice transfers from Deployer to Bob
+    let mut args = Args::new();
+    args.add_bytes(&token_wasm);
+    runtime.execute(&wasm, "constructor", &args.into_bytes())?;
+
+    let response = runtime.execute(&wasm, "bytecodeHash", &[])?;
+    assert_eq!(response.ret, keccak256(&token_wasm).to_vec());
+    let response = runtime.execute(&wasm, "tokenCount", &[])?;
+    assert_eq!(response.ret.as_u64()?, 0);
+
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = execute_expect_failure(&runtime, &wasm, "tokenAt", &id_args.into_bytes());
+    assert!(err.contains("ERR:NOT_FOUND:id=0:"), "Unexpected error: {}", err);
+
+    let mut address_args = Args::new();
+    address_args.add_string("AS_TOKEN");
+    let response = runtime.execute(&wasm, "isFactoryToken", &address_args.into_bytes())?;
+    assert!(!response.ret.as_bool()?);
+
+    // Invalid metadata is rejected before deploying anything
     runtime
         .interface
-        .set_call_stack(vec![ALICE.to_string(), "AS_CONTRACT".to_string()]);
-    let transfer_amount = U256::from(50_000u64);
-    let mut transfer_args = Args::new();
-    transfer_args
-        .add_string(DEPLOYER)
-        .add_string(BOB)
-        .add_u256(transfer_amount);
-    runtime.execute(&wasm, "transferFrom", &transfer_args.into_bytes())?;
+        .set_call_stack(vec![ALICE.to_string(), "AS_FACTORY".to_string()]);
+    let mut create_args = Args::new();
+    create_args.add_string("").add_string("TKN").add_u8(18).add_u256(U256::from(1_000u64));
+    let err = execute_expect_failure(&runtime, &wasm, "createToken", &create_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=name:"), "Unexpected error: {}", err);
 
-    // Check balances
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
+    let mut create_args = Args::new();
+    create_args.add_string("Token").add_string("").add_u8(18).add_u256(U256::from(1_000u64));
+    let err = execute_expect_failure(&runtime, &wasm, "createToken", &create_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=symbol:"), "Unexpected error: {}", err);
 
-    let mut deployer_args = Args::new();
-    deployer_args.add_string(DEPLOYER);
-    let response = runtime.execute(&wasm, "balanceOf", &deployer_args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let deployer_balance = U256::from_le_bytes(bytes);
-    
-    let expected_deployer = initial_supply.checked_sub(transfer_amount).unwrap();
-    assert_eq!(deployer_balance, expected_deployer);
+    Ok(())
+}
 
-    let mut bob_args = Args::new();
-    bob_args.add_string(BOB);
-    let response = runtime.execute(&wasm, "balanceOf", &bob_args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let bob_balance = U256::from_le_bytes(bytes);
-    assert_eq!(bob_balance, transfer_amount);
+#[test]
+fn test_bridge_mint_role() -> Result<()> {
+    let token = TokenHarness::deploy("MassaCoin", "MCOIN", 18, U256::from(1_000_000u64))?;
 
-    // Check remaining allowance
-    let mut allowance_args = Args::new();
-    allowance_args.add_string(DEPLOYER).add_string(ALICE);
-    let response = runtime.execute(&wasm, "allowance", &allowance_args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let remaining_allowance = U256::from_le_bytes(bytes);
-    
-    let expected_allowance = approve_amount.checked_sub(transfer_amount).unwrap();
-    assert_eq!(remaining_allowance, expected_allowance);
+    // MINTER does not allow bridge mints
+    token.grant_role(DEPLOYER, "MINTER", ALICE)?;
 
-    println!(
-        "Deployer: {}, Bob: {}, Remaining allowance: {}",
-        deployer_balance, bob_balance, remaining_allowance
+    let mut mint_args = Args::new();
+    mint_args.add_string(BOB).add_u256(U256::from(500u64)).add_string("0xsource");
+    let err = token.call_expect_failure(ALICE, "bridgeMint", &mint_args.into_bytes());
+    assert!(
+        err.contains(&format!("ERR:UNAUTHORIZED:caller={},role=BRIDGE_MINTER:", ALICE)),
+        "Unexpected error: {}",
+        err
     );
 
+    // The bridge mints once it holds BRIDGE_MINTER
+    token.grant_role(DEPLOYER, "BRIDGE_MINTER", "AS_BRIDGE")?;
+    token.bridge_mint("AS_BRIDGE", BOB, U256::from(500u64), "0xsource")?;
+
+    let events = token.events();
+    assert!(events.contains(&format!("MINT SUCCESS:to={},amount=500", BOB)));
+    assert!(events.contains(&format!("BRIDGE_MINT:to={},amount=500,sourceTxId=0xsource", BOB)));
+
+    assert_eq!(token.balance_of(BOB)?, U256::from(500u64));
+
     Ok(())
 }
 
+/// Public keys returned by the bridge's `validators` view, sorted.
+fn bridge_validators(runtime: &TestRuntime, wasm: &[u8], offset: u64, limit: u32) -> Result<Vec<String>> {
+    let mut page_args = Args::new();
+    page_args.add_u64(offset).add_u32(limit);
+    let response = runtime.execute(wasm, "validators", &page_args.into_bytes())?;
+    let mut page = response.ret.as_args();
+    let count = page.next_u32().unwrap();
+    let mut public_keys: Vec<String> = (0..count).map(|_| page.next_string().unwrap()).collect();
+    public_keys.sort();
+    Ok(public_keys)
+}
+
 #[test]
-fn test_mint() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
+fn test_bridge_validators_and_rejections() -> Result<()> {
+    let wasm = std::fs::read(bridge_wasm_path())?;
     let runtime = TestRuntime::new();
 
-    // Set up deployment
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let initial_supply = U256::from(1_000_000u64);
-    let args = constructor_args("MassaCoin", "MCOIN", 18, initial_supply);
-    runtime.execute(&wasm, "constructor", &args)?;
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_BRIDGE".to_string()]);
+    let mut validators = Args::new();
+    validators.add_u32(2).add_string("P1validatorOne").add_string("P1validatorTwo");
+    let validators = validators.into_bytes();
 
-    // Mint tokens to Alice (owner only)
+    // The threshold must be reachable
+    let mut args = Args::new();
+    args.add_string("AS_TOKEN").add_bytes(&validators).add_u32(3);
+    let err = execute_expect_failure(&runtime, &wasm, "constructor", &args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=threshold,threshold=3,validators=2:"), "Unexpected error: {}", err);
+
+    let mut args = Args::new();
+    args.add_string("AS_TOKEN").add_bytes(&validators).add_u32(2);
+    runtime.execute(&wasm, "constructor", &args.into_bytes())?;
+
+    let mut key_args = Args::new();
+    key_args.add_string("P1validatorOne");
+    let response = runtime.execute(&wasm, "isValidator", &key_args.into_bytes())?;
+    assert!(response.ret.as_bool()?);
+    let response = runtime.execute(&wasm, "validatorCount", &[])?;
+    assert_eq!(response.ret, 2u32.to_le_bytes().to_vec());
+    assert_eq!(bridge_validators(&runtime, &wasm, 0, 10)?, ["P1validatorOne", "P1validatorTwo"]);
+
+    // Locks need a non-zero amount
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let mint_amount = U256::from(500_000u64);
+        .set_call_stack(vec![ALICE.to_string(), "AS_BRIDGE".to_string()]);
+    let mut lock_args = Args::new();
+    lock_args.add_u256(U256::ZERO).add_string("ethereum").add_string("0xabc");
+    let err = execute_expect_failure(&runtime, &wasm, "lock", &lock_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=amount:"), "Unexpected error: {}", err);
+
+    // Mints need signatures from validators, up to the threshold
+    let mut signatures = Args::new();
+    signatures.add_u32(1).add_string("P1stranger").add_string("signature");
     let mut mint_args = Args::new();
-    mint_args.add_string(ALICE).add_u256(mint_amount);
-    runtime.execute(&wasm, "mint", &mint_args.into_bytes())?;
+    mint_args
+        .add_string(ALICE)
+        .add_u256(U256::from(500u64))
+        .add_string("0xsource")
+        .add_bytes(&signatures.into_bytes());
+    let err = execute_expect_failure(&runtime, &wasm, "mintFromBridge", &mint_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_SIGNATURE:publicKey=P1stranger:"), "Unexpected error: {}", err);
 
-    // Check new total supply
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
-    let response = runtime.execute(&wasm, "totalSupply", &[])?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let new_supply = U256::from_le_bytes(bytes);
-    
-    let expected_supply = initial_supply.checked_add(mint_amount).unwrap();
-    assert_eq!(new_supply, expected_supply);
+    let mut signatures = Args::new();
+    signatures.add_u32(0);
+    let mut mint_args = Args::new();
+    mint_args
+        .add_string(ALICE)
+        .add_u256(U256::from(500u64))
+        .add_string("0xsource")
+        .add_bytes(&signatures.into_bytes());
+    let err = execute_expect_failure(&runtime, &wasm, "mintFromBridge", &mint_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_SIGNATURE:needed=2,have=0:"), "Unexpected error: {}", err);
 
-    // Check Alice balance
-    let mut alice_args = Args::new();
-    alice_args.add_string(ALICE);
-    let response = runtime.execute(&wasm, "balanceOf", &alice_args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let alice_balance = U256::from_le_bytes(bytes);
-    assert_eq!(alice_balance, mint_amount);
+    let mut processed_args = Args::new();
+    processed_args.add_string("0xsource");
+    let response = runtime.execute(&wasm, "isProcessed", &processed_args.into_bytes())?;
+    assert!(!response.ret.as_bool()?);
 
-    println!("New total supply: {}, Alice balance: {}", new_supply, alice_balance);
+    // Only the owner manages validators
+    let mut key_args = Args::new();
+    key_args.add_string("P1validatorThree");
+    let err = execute_expect_failure(&runtime, &wasm, "addValidator", &key_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
+
+    // A public key that extends the validator prefix into VALIDATOR_COUNT gets its own key
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_BRIDGE".to_string()]);
+    let mut key_args = Args::new();
+    key_args.add_string("_COUNT");
+    let key_args = key_args.into_bytes();
+    let response = runtime.execute(&wasm, "isValidator", &key_args)?;
+    assert!(!response.ret.as_bool()?);
+    runtime.execute(&wasm, "addValidator", &key_args)?;
+    let response = runtime.execute(&wasm, "isValidator", &key_args)?;
+    assert!(response.ret.as_bool()?);
+    let response = runtime.execute(&wasm, "validatorCount", &[])?;
+    assert_eq!(response.ret, 3u32.to_le_bytes().to_vec());
+
+    // The listing skips VALIDATOR_COUNT, which shares the scanned prefix, and pages
+    assert_eq!(bridge_validators(&runtime, &wasm, 0, 10)?, ["P1validatorOne", "P1validatorTwo", "_COUNT"]);
+    let mut pages = bridge_validators(&runtime, &wasm, 0, 2)?;
+    assert_eq!(pages.len(), 2);
+    pages.extend(bridge_validators(&runtime, &wasm, 2, 2)?);
+    pages.sort();
+    assert_eq!(pages, ["P1validatorOne", "P1validatorTwo", "_COUNT"]);
+    assert!(bridge_validators(&runtime, &wasm, 3, 10)?.is_empty());
+
+    runtime.execute(&wasm, "removeValidator", &key_args)?;
+    let response = runtime.execute(&wasm, "validatorCount", &[])?;
+    assert_eq!(response.ret, 2u32.to_le_bytes().to_vec());
+    assert_eq!(bridge_validators(&runtime, &wasm, 0, 10)?, ["P1validatorOne", "P1validatorTwo"]);
+    let mut key_args = Args::new();
+    key_args.add_string("P1validatorTwo");
+    let response = runtime.execute(&wasm, "isValidator", &key_args.into_bytes())?;
+    assert!(response.ret.as_bool()?);
 
     Ok(())
 }
 
 #[test]
-fn test_burn() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
+fn test_failing_token_calls_abort() -> Result<()> {
+    let mut staking_args = Args::new();
+    staking_args.add_string("AS_TOKEN").add_u256(U256::from(10u64));
+    let mut faucet_args = Args::new();
+    faucet_args.add_string("AS_TOKEN").add_u256(U256::from(1_000u64)).add_u64(100);
+    let mut validators = Args::new();
+    validators.add_u32(1).add_string("P1validatorOne");
+    let mut bridge_args = Args::new();
+    bridge_args.add_string("AS_TOKEN").add_bytes(&validators.into_bytes()).add_u32(1);
+
+    let mut amount_args = Args::new();
+    amount_args.add_u256(U256::from(500u64));
+    let amount_args = amount_args.into_bytes();
+    let mut lock_args = Args::new();
+    lock_args.add_u256(U256::from(500u64)).add_string("ethereum").add_string("0xabc");
+
+    // Each contract's token is not deployed, so its token call fails
+    let cases = [
+        (staking_wasm_path(), "AS_STAKING", staking_args, "stake", amount_args.clone(), "STAKE"),
+        (faucet_wasm_path(), "AS_FAUCET", faucet_args, "refill", amount_args, "FAUCET_REFILL"),
+        (bridge_wasm_path(), "AS_BRIDGE", bridge_args, "lock", lock_args.into_bytes(), "BRIDGE_LOCK"),
+    ];
+    for (path, address, constructor_args, function, call_args, event) in cases {
+        let wasm = std::fs::read(path)?;
+        let runtime = TestRuntime::new();
+        runtime
+            .interface
+            .set_call_stack(vec![DEPLOYER.to_string(), address.to_string()]);
+        runtime.execute(&wasm, "constructor", &constructor_args.into_bytes())?;
+
+        let mark = event_mark(&runtime);
+        let err = execute_expect_failure(&runtime, &wasm, function, &call_args);
+
+        // The contract's own checks passed and the call aborted at the token call
+        assert!(!err.contains("ERR:INVALID_ARGUMENT:"), "{}: unexpected error: {}", function, err);
+        assert!(!err.contains("ERR:UNAUTHORIZED:"), "{}: unexpected error: {}", function, err);
+        let events = events_since(&runtime, mark);
+        assert!(find_events(&events, event).is_empty(), "{}: {:?}", function, events);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_faucet_rate_limit_and_admin() -> Result<()> {
+    let wasm = std::fs::read(faucet_wasm_path())?;
     let runtime = TestRuntime::new();
 
-    // Set up deployment
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let initial_supply = U256::from(1_000_000u64);
-    let args = constructor_args("MassaCoin", "MCOIN", 18, initial_supply);
-    runtime.execute(&wasm, "constructor", &args)?;
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_FAUCET".to_string()]);
 
-    // Deployer burns some tokens
+    // The drip amount must be non-zero
+    let mut args = Args::new();
+    args.add_string("AS_TOKEN").add_u256(U256::ZERO).add_u64(100);
+    let err = execute_expect_failure(&runtime, &wasm, "constructor", &args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=dripAmount:"), "Unexpected error: {}", err);
+
+    let mut args = Args::new();
+    args.add_string("AS_TOKEN").add_u256(U256::from(1_000u64)).add_u64(100);
+    runtime.execute(&wasm, "constructor", &args.into_bytes())?;
+
+    let response = runtime.execute(&wasm, "cooldown", &[])?;
+    assert_eq!(response.ret.as_u64()?, 100);
+    let response = runtime.execute(&wasm, "reserve", &[])?;
+    assert_eq!(response.ret, U256::ZERO.to_le_bytes().to_vec());
+
+    // A new address can drip right away, but the faucet is not funded yet
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let burn_amount = U256::from(200_000u64);
-    let mut burn_args = Args::new();
-    burn_args.add_u256(burn_amount);
-    runtime.execute(&wasm, "burn", &burn_args.into_bytes())?;
+        .set_call_stack(vec![ALICE.to_string(), "AS_FAUCET".to_string()]);
+    let mut address_args = Args::new();
+    address_args.add_string(ALICE);
+    let response = runtime.execute(&wasm, "nextDripPeriod", &address_args.into_bytes())?;
+    assert_eq!(response.ret.as_u64()?, 0);
 
-    // Check new total supply
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
-    let response = runtime.execute(&wasm, "totalSupply", &[])?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let new_supply = U256::from_le_bytes(bytes);
-    
-    let expected_supply = initial_supply.checked_sub(burn_amount).unwrap();
-    assert_eq!(new_supply, expected_supply);
+    let err = execute_expect_failure(&runtime, &wasm, "drip", &[]);
+    assert!(err.contains("ERR:INSUFFICIENT_BALANCE:needed=1000,have=0:"), "Unexpected error: {}", err);
 
-    // Check deployer balance
-    let mut deployer_args = Args::new();
-    deployer_args.add_string(DEPLOYER);
-    let response = runtime.execute(&wasm, "balanceOf", &deployer_args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let deployer_balance = U256::from_le_bytes(bytes);
-    assert_eq!(deployer_balance, expected_supply);
+    // Only the owner funds and configures the faucet
+    let mut refill_args = Args::new();
+    refill_args.add_u256(U256::from(10_000u64));
+    let err = execute_expect_failure(&runtime, &wasm, "refill", &refill_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
 
-    println!("New total supply: {}, Deployer balance: {}", new_supply, deployer_balance);
+    let mut settings_args = Args::new();
+    settings_args.add_u256(U256::from(5u64)).add_u64(1);
+    let err = execute_expect_failure(&runtime, &wasm, "setDripSettings", &settings_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
+
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_FAUCET".to_string()]);
+    let mut settings_args = Args::new();
+    settings_args.add_u256(U256::from(5u64)).add_u64(1);
+    runtime.execute(&wasm, "setDripSettings", &settings_args.into_bytes())?;
+    let response = runtime.execute(&wasm, "dripAmount", &[])?;
+    assert_eq!(response.ret, U256::from(5u64).to_le_bytes().to_vec());
+
+    let mut withdraw_args = Args::new();
+    withdraw_args.add_u256(U256::from(1u64));
+    let err = execute_expect_failure(&runtime, &wasm, "withdraw", &withdraw_args.into_bytes());
+    assert!(err.contains("ERR:INSUFFICIENT_BALANCE:needed=1,have=0:"), "Unexpected error: {}", err);
 
     Ok(())
 }
 
+/// Current period of the runtime, read through the datastore writer mock
+fn current_period(runtime: &TestRuntime, writer_wasm: &[u8]) -> Result<u64> {
+    runtime.execute(writer_wasm, "currentPeriod", &[])?.ret.as_u64()
+}
+
 #[test]
-fn test_full_transfer_flow() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
+fn test_faucet_drip_cooldown() -> Result<()> {
+    let wasm = std::fs::read(faucet_wasm_path())?;
+    let writer_wasm = std::fs::read(datastore_writer_wasm_path())?;
     let runtime = TestRuntime::new();
 
-    println!("=== Full MRC20 Transfer Flow Test ===\n");
-
-    // Step 1: Deploy contract
-    println!("Step 1: Deploying MRC20 token...");
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let initial_supply = U256::from(10_000_000u64);
-    let args = constructor_args("MassaToken", "MASS", 18, initial_supply);
-    runtime.execute(&wasm, "constructor", &args)?;
-    println!("  Deployed MassaToken (MASS) with initial supply: {}", initial_supply);
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_FAUCET".to_string()]);
+    let mut args = Args::new();
+    args.add_string("AS_TOKEN").add_u256(U256::from(1_000u64)).add_u64(1);
+    runtime.execute(&wasm, "constructor", &args.into_bytes())?;
 
-    // Step 2: Check initial balances
-    println!("\nStep 2: Checking initial balances...");
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
+    // The runtime cannot advance periods and the token is not deployed, so
+    // seed a funded reserve and Alice's last drip around the current period
+    let period = current_period(&runtime, &writer_wasm)?;
+    assert!(period >= 1, "The cooldown window needs an earlier period");
+    let last_drip_key = [b"LAST_DRIP".as_slice(), ALICE.as_bytes()].concat();
+    set_raw_entry(&runtime, &writer_wasm, b"RESERVE", &U256::from(10_000u64).to_le_bytes())?;
+    set_raw_entry(&runtime, &writer_wasm, &last_drip_key, &period.to_le_bytes())?;
 
-    let mut deployer_args = Args::new();
-    deployer_args.add_string(DEPLOYER);
-    let response = runtime.execute(&wasm, "balanceOf", &deployer_args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let deployer_balance = U256::from_le_bytes(bytes);
-    println!("  Deployer balance: {}", deployer_balance);
+    let mut address_args = Args::new();
+    address_args.add_string(ALICE);
+    let address_args = address_args.into_bytes();
+    let response = runtime.execute(&wasm, "nextDripPeriod", &address_args)?;
+    assert_eq!(response.ret.as_u64()?, period + 1);
 
-    // Step 3: Transfer to Alice
-    println!("\nStep 3: Deployer transfers 1,000,000 to Alice...");
+    // A second drip within the cooldown fails
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let mut transfer_args = Args::new();
-    transfer_args.add_string(ALICE).add_u256(U256::from(1_000_000u64));
-    runtime.execute(&wasm, "transfer", &transfer_args.into_bytes())?;
+        .set_call_stack(vec![ALICE.to_string(), "AS_FAUCET".to_string()]);
+    let mark = event_mark(&runtime);
+    let err = execute_expect_failure(&runtime, &wasm, "drip", &[]);
+    let expected = format!("ERR:NOT_YET_VALID:account={},next={}:", ALICE, period + 1);
+    assert!(err.contains(&expected), "Unexpected error: {}", err);
+    assert!(err.contains("Cooldown has not passed since the last drip"), "Unexpected error: {}", err);
 
-    // Step 4: Alice transfers to Bob
-    println!("Step 4: Alice transfers 500,000 to Bob...");
-    runtime
-        .interface
-        .set_call_stack(vec![ALICE.to_string(), "AS_CONTRACT".to_string()]);
-    let mut transfer_args = Args::new();
-    transfer_args.add_string(BOB).add_u256(U256::from(500_000u64));
-    runtime.execute(&wasm, "transfer", &transfer_args.into_bytes())?;
+    // Once a period has passed since the last drip, the cooldown no longer
+    // blocks it: the drip gets past its checks and aborts at the token transfer
+    set_raw_entry(&runtime, &writer_wasm, &last_drip_key, &(period - 1).to_le_bytes())?;
+    let response = runtime.execute(&wasm, "nextDripPeriod", &address_args)?;
+    assert_eq!(response.ret.as_u64()?, period);
+    let err = execute_expect_failure(&runtime, &wasm, "drip", &[]);
+    assert!(!err.contains("ERR:NOT_YET_VALID:"), "Unexpected error: {}", err);
+    assert!(!err.contains("ERR:INSUFFICIENT_BALANCE:"), "Unexpected error: {}", err);
+    assert!(find_events(&events_since(&runtime, mark), "FAUCET_DRIP").is_empty());
 
-    // Step 5: Bob approves Charlie
-    println!("Step 5: Bob approves Charlie to spend 200,000...");
+    // A longer cooldown also applies to past drips
     runtime
         .interface
-        .set_call_stack(vec![BOB.to_string(), "AS_CONTRACT".to_string()]);
-    let mut approve_args = Args::new();
-    approve_args.add_string(CHARLIE).add_u256(U256::from(200_000u64));
-    runtime.execute(&wasm, "increaseAllowance", &approve_args.into_bytes())?;
-
-    // Step 6: Charlie transfers from Bob to Alice
-    println!("Step 6: Charlie transfers 100,000 from Bob to Alice...");
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_FAUCET".to_string()]);
+    set_raw_entry(&runtime, &writer_wasm, &last_drip_key, &(period - 1).to_le_bytes())?;
+    let mut settings_args = Args::new();
+    settings_args.add_u256(U256::from(1_000u64)).add_u64(2);
+    runtime.execute(&wasm, "setDripSettings", &settings_args.into_bytes())?;
     runtime
         .interface
-        .set_call_stack(vec![CHARLIE.to_string(), "AS_CONTRACT".to_string()]);
-    let mut transfer_from_args = Args::new();
-    transfer_from_args
-        .add_string(BOB)
-        .add_string(ALICE)
-        .add_u256(U256::from(100_000u64));
-    runtime.execute(&wasm, "transferFrom", &transfer_from_args.into_bytes())?;
+        .set_call_stack(vec![ALICE.to_string(), "AS_FAUCET".to_string()]);
+    let err = execute_expect_failure(&runtime, &wasm, "drip", &[]);
+    assert!(err.contains(&expected), "Unexpected error: {}", err);
 
-    // Step 7: Final balances
-    println!("\nStep 7: Final balances:");
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
+    Ok(())
+}
 
-    let mut args = Args::new();
-    args.add_string(DEPLOYER);
-    let response = runtime.execute(&wasm, "balanceOf", &args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    println!("  Deployer: {}", U256::from_le_bytes(bytes));
+#[test]
+fn test_vesting_wallet_schedule() -> Result<()> {
+    let wasm = std::fs::read(vesting_wallet_wasm_path())?;
+    let runtime = TestRuntime::new();
 
-    let mut args = Args::new();
-    args.add_string(ALICE);
-    let response = runtime.execute(&wasm, "balanceOf", &args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    println!("  Alice: {}", U256::from_le_bytes(bytes));
+    runtime
+        .interface
+        .set_call_stack(vec!["AS_FACTORY".to_string(), "AS_WALLET".to_string()]);
 
+    // The cliff cannot outlast the duration
     let mut args = Args::new();
-    args.add_string(BOB);
-    let response = runtime.execute(&wasm, "balanceOf", &args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    println!("  Bob: {}", U256::from_le_bytes(bytes));
+    args.add_string("AS_TOKEN")
+        .add_string(ALICE)
+        .add_u64(1_000_000)
+        .add_u64(200)
+        .add_u64(100)
+        .add_u256(U256::from(10_000u64));
+    let err = execute_expect_failure(&runtime, &wasm, "constructor", &args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=cliff:"), "Unexpected error: {}", err);
 
+    // Vesting starts far in the future
     let mut args = Args::new();
-    args.add_string(CHARLIE);
-    let response = runtime.execute(&wasm, "balanceOf", &args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    println!("  Charlie: {}", U256::from_le_bytes(bytes));
+    args.add_string("AS_TOKEN")
+        .add_string(ALICE)
+        .add_u64(1_000_000)
+        .add_u64(100)
+        .add_u64(1_000)
+        .add_u256(U256::from(10_000u64));
+    runtime.execute(&wasm, "constructor", &args.into_bytes())?;
 
-    // Check remaining allowance
-    let mut args = Args::new();
-    args.add_string(BOB).add_string(CHARLIE);
-    let response = runtime.execute(&wasm, "allowance", &args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    println!("\n  Bob->Charlie allowance remaining: {}", U256::from_le_bytes(bytes));
+    let response = runtime.execute(&wasm, "schedule", &[])?;
+    let mut schedule = response.ret.as_args();
+    assert_eq!(schedule.next_string().unwrap(), "AS_TOKEN");
+    assert_eq!(schedule.next_string().unwrap(), ALICE);
+    assert_eq!(schedule.next_u64().unwrap(), 1_000_000);
+    assert_eq!(schedule.next_u64().unwrap(), 100);
+    assert_eq!(schedule.next_u64().unwrap(), 1_000);
+    assert_eq!(schedule.next_u256().unwrap(), U256::from(10_000u64));
+    assert_eq!(schedule.next_u256().unwrap(), U256::ZERO);
 
-    println!("\n=== Test completed successfully! ===");
+    let response = runtime.execute(&wasm, "vestedAmount", &[])?;
+    assert_eq!(response.ret, U256::ZERO.to_le_bytes().to_vec());
+    let response = runtime.execute(&wasm, "releasableAmount", &[])?;
+    assert_eq!(response.ret, U256::ZERO.to_le_bytes().to_vec());
+
+    // Nothing to release before the cliff
+    runtime
+        .interface
+        .set_call_stack(vec![ALICE.to_string(), "AS_WALLET".to_string()]);
+    let err = execute_expect_failure(&runtime, &wasm, "release", &[]);
+    assert!(err.contains("ERR:INVALID_STATE:"), "Unexpected error: {}", err);
 
     Ok(())
 }
 
 #[test]
-fn test_u256_large_values() -> Result<()> {
-    let wasm = std::fs::read(wasm_path())?;
+fn test_vesting_factory_registry() -> Result<()> {
+    let wasm = std::fs::read(vesting_factory_wasm_path())?;
+    let wallet_wasm = std::fs::read(vesting_wallet_wasm_path())?;
     let runtime = TestRuntime::new();
 
-    // Use a large U256 value (10^24 = 1 million tokens with 18 decimals)
-    let large_supply = U256::from(10u64).pow(24);
-    
-    println!("Testing with large supply: {}", large_supply);
-
-    // Set up deployment
     runtime
         .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let args = constructor_args("LargeToken", "LTK", 18, large_supply);
-    runtime.execute(&wasm, "constructor", &args)?;
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_FACTORY".to_string()]);
+    let mut args = Args::new();
+    args.add_bytes(&wallet_wasm);
+    runtime.execute(&wasm, "constructor", &args.into_bytes())?;
 
-    // Check total supply
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
-    let response = runtime.execute(&wasm, "totalSupply", &[])?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let total_supply = U256::from_le_bytes(bytes);
+    let response = runtime.execute(&wasm, "bytecodeHash", &[])?;
+    assert_eq!(response.ret, keccak256(&wallet_wasm).to_vec());
+    let response = runtime.execute(&wasm, "vestingCount", &[])?;
+    assert_eq!(response.ret.as_u64()?, 0);
 
-    assert_eq!(total_supply, large_supply);
-    println!("Large supply verified: {}", total_supply);
+    let mut beneficiary_args = Args::new();
+    beneficiary_args.add_string(ALICE);
+    let response = runtime.execute(&wasm, "vestingsOf", &beneficiary_args.into_bytes())?;
+    let mut vestings = response.ret.as_args();
+    assert_eq!(vestings.next_u32().unwrap(), 0);
 
-    // Transfer a large amount
-    runtime
-        .interface
-        .set_call_stack(vec![DEPLOYER.to_string(), "AS_CONTRACT".to_string()]);
-    let transfer_amount = U256::from(10u64).pow(23); // 100,000 tokens
-    let mut transfer_args = Args::new();
-    transfer_args.add_string(ALICE).add_u256(transfer_amount);
-    runtime.execute(&wasm, "transfer", &transfer_args.into_bytes())?;
+    let mut id_args = Args::new();
+    id_args.add_u64(0);
+    let err = execute_expect_failure(&runtime, &wasm, "vestingAt", &id_args.into_bytes());
+    assert!(err.contains("ERR:NOT_FOUND:id=0:"), "Unexpected error: {}", err);
 
-    // Check Alice balance
-    runtime.interface.set_call_stack(vec!["AS_CONTRACT".to_string()]);
-    let mut alice_args = Args::new();
-    alice_args.add_string(ALICE);
-    let response = runtime.execute(&wasm, "balanceOf", &alice_args.into_bytes())?;
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&response.ret[..32]);
-    let alice_balance = U256::from_le_bytes(bytes);
+    // Invalid grants are rejected before deploying anything
+    let mut create_args = Args::new();
+    create_args
+        .add_string("AS_TOKEN")
+        .add_string(ALICE)
+        .add_u256(U256::ZERO)
+        .add_u64(0)
+        .add_u64(0)
+        .add_u64(100);
+    let err = execute_expect_failure(&runtime, &wasm, "createVesting", &create_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=amount:"), "Unexpected error: {}", err);
 
-    assert_eq!(alice_balance, transfer_amount);
-    println!("Alice received: {}", alice_balance);
+    let mut create_args = Args::new();
+    create_args
+        .add_string("AS_TOKEN")
+        .add_string(ALICE)
+        .add_u256(U256::from(1_000u64))
+        .add_u64(0)
+        .add_u64(0)
+        .add_u64(0);
+    let err = execute_expect_failure(&runtime, &wasm, "createVesting", &create_args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=duration:"), "Unexpected error: {}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_keccak256_matches_reference_vectors() -> Result<()> {
+    let wasm = std::fs::read(token_factory_wasm_path())?;
+
+    // Published Keccak-256 vectors, then an input longer than one 136-byte block
+    let multi_block: Vec<u8> = (0..=255u8).collect();
+    let vectors: [(&[u8], Option<&str>); 3] = [
+        (b"abc", Some("4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45")),
+        (
+            b"The quick brown fox jumps over the lazy dog",
+            Some("4d741b6f1eb29cb2a9b9911c82f56fa8d73b04959d3d9d222895df6c0b28aa15"),
+        ),
+        (&multi_block, None),
+    ];
+
+    // The factory hashes whatever bytecode it stores with abi::keccak256
+    for (data, expected) in vectors {
+        let runtime = TestRuntime::new();
+        runtime
+            .interface
+            .set_call_stack(vec![DEPLOYER.to_string(), "AS_FACTORY".to_string()]);
+
+        let mut args = Args::new();
+        args.add_bytes(data);
+        runtime.execute(&wasm, "constructor", &args.into_bytes())?;
+
+        let response = runtime.execute(&wasm, "bytecodeHash", &[])?;
+        assert_eq!(response.ret, keccak256(data).to_vec(), "Contract and off-chain hashes differ");
+        if let Some(expected) = expected {
+            assert_eq!(hex::encode(&response.ret), expected);
+        }
+    }
 
     Ok(())
 }
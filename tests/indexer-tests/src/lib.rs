@@ -0,0 +1,406 @@
+//! Tests of the event indexer
+//!
+//! Checks the parsing of the token's event payloads, how the database
+//! applies them (balance and allowance upserts, history, atomic batches),
+//! how already-applied events are skipped when the node sends them again, the
+//! ledger export formats and the Prometheus metrics, all without a node.
+
+#![cfg(test)]
+
+use erc20_client::U256;
+use erc20_client::rpc::{EventContext, OutputEvent, Slot};
+use indexer::db::{Database, IndexedEvent, Position};
+use indexer::events::{self, TokenEvent};
+use indexer::export::{self, Format, SlotBound};
+use indexer::metrics::Metrics;
+
+const TOKEN: &str = "AS12token";
+const ALICE: &str = "AU12alice";
+const BOB: &str = "AU12bob";
+const CAROL: &str = "AU12carol";
+
+/// U256::MAX in decimal
+const U256_MAX: &str = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+fn position(period: u64, thread: u8, index_in_slot: u64) -> Position {
+    Position {
+        slot: Slot { period, thread },
+        index_in_slot,
+    }
+}
+
+/// An event as the node returns it
+fn output_event(position: Position, data: &str) -> OutputEvent {
+    OutputEvent {
+        context: EventContext {
+            slot: position.slot,
+            index_in_slot: position.index_in_slot,
+            origin_operation_id: Some(format!("O1op{}x{}", position.slot.period, position.index_in_slot)),
+            is_final: true,
+            ..EventContext::default()
+        },
+        data: String::from(data),
+    }
+}
+
+/// A parsed event at `position`
+fn indexed(position: Position, data: &str) -> IndexedEvent {
+    IndexedEvent {
+        position,
+        operation_id: Some(format!("O1op{}x{}", position.slot.period, position.index_in_slot)),
+        event: TokenEvent::parse(data).unwrap(),
+    }
+}
+
+fn amount(value: u64) -> U256 {
+    U256::from(value)
+}
+
+/// A fresh in-memory database
+fn database() -> Database {
+    Database::open(":memory:").unwrap()
+}
+
+/// Mint 1000 to Alice, move 300 to Bob, approve Carol, burn 100 of Bob's
+fn seeded_database() -> Database {
+    let mut db = database();
+    let events = [
+        indexed(position(10, 0, 0), &format!("MINT SUCCESS:to={},amount=1000", ALICE)),
+        indexed(position(10, 0, 1), &format!("TRANSFER SUCCESS:from={},to={},amount=300", ALICE, BOB)),
+        indexed(position(10, 5, 0), &format!("APPROVAL SUCCESS:owner={},spender={},amount=50", ALICE, CAROL)),
+        indexed(position(11, 31, 0), &format!("BURN_SUCCESS:from={},amount=100", BOB)),
+    ];
+    db.apply(&events, position(12, 0, 3)).unwrap();
+    db
+}
+
+#[test]
+fn test_event_parsing() {
+    let transfer = TokenEvent::parse(&format!("TRANSFER SUCCESS:from={},to={},amount=300", ALICE, BOB));
+    assert_eq!(
+        transfer,
+        Some(TokenEvent::Transfer {
+            from: String::from(ALICE),
+            to: String::from(BOB),
+            amount: amount(300),
+        })
+    );
+    let approval = TokenEvent::parse(&format!("APPROVAL SUCCESS:owner={},spender={},amount=0", ALICE, BOB));
+    assert_eq!(
+        approval,
+        Some(TokenEvent::Approval {
+            owner: String::from(ALICE),
+            spender: String::from(BOB),
+            amount: U256::ZERO,
+        })
+    );
+    let mint = TokenEvent::parse(&format!("MINT SUCCESS:to={},amount={}", ALICE, U256_MAX));
+    assert_eq!(
+        mint,
+        Some(TokenEvent::Mint {
+            to: String::from(ALICE),
+            amount: U256::MAX,
+        })
+    );
+    // Burns are named with an underscore, unlike the other events
+    let burn = TokenEvent::parse(&format!("BURN_SUCCESS:from={},amount=7", BOB));
+    assert_eq!(
+        burn,
+        Some(TokenEvent::Burn {
+            from: String::from(BOB),
+            amount: amount(7),
+        })
+    );
+    assert_eq!(TokenEvent::parse(&format!("BURN SUCCESS:from={},amount=7", BOB)), None);
+
+    let kinds: Vec<&str> = [transfer, approval, mint, burn]
+        .iter()
+        .map(|event| event.as_ref().unwrap().kind())
+        .collect();
+    assert_eq!(kinds, ["transfer", "approval", "mint", "burn"]);
+
+    // Fields are found by name, in any order and next to unknown ones
+    assert_eq!(
+        TokenEvent::parse(&format!("TRANSFER SUCCESS:amount=5,memo=x,to={},from={}", BOB, ALICE)),
+        Some(TokenEvent::Transfer {
+            from: String::from(ALICE),
+            to: String::from(BOB),
+            amount: amount(5),
+        })
+    );
+}
+
+#[test]
+fn test_event_parsing_rejects_other_payloads() {
+    let ignored = [
+        String::new(),
+        String::from("TRANSFER SUCCESS"),
+        format!("TRANSFER SUCCESS:from={},amount=300", ALICE),
+        format!("TRANSFER SUCCESS:from={},to={},amount=", ALICE, BOB),
+        format!("TRANSFER SUCCESS:from={},to={},amount=-1", ALICE, BOB),
+        format!("TRANSFER SUCCESS:from={},to={},amount=1.5", ALICE, BOB),
+        format!("TRANSFER SUCCESS:from={},to={},amount={}0", ALICE, BOB, U256_MAX),
+        format!("MINT SUCCESS:from={},amount=1", ALICE),
+        format!("transfer success:from={},to={},amount=1", ALICE, BOB),
+        format!("FAUCET_DRIP:to={},amount=1000", ALICE),
+        format!("STAKE:account={},amount=10", ALICE),
+        String::from("ERR:PAUSED::Contract is paused"),
+    ];
+    for data in ignored {
+        assert_eq!(TokenEvent::parse(&data), None, "{:?} was parsed", data);
+    }
+}
+
+#[test]
+fn test_db_applies_events() {
+    let db = seeded_database();
+    assert_eq!(db.cursor().unwrap(), Some(position(12, 0, 3)));
+    assert_eq!(db.balance(ALICE).unwrap(), amount(700));
+    assert_eq!(db.balance(BOB).unwrap(), amount(200));
+    assert_eq!(db.balance(CAROL).unwrap(), U256::ZERO);
+    assert_eq!(db.total_supply().unwrap(), amount(900));
+    assert_eq!(db.holder_count().unwrap(), 2);
+    assert_eq!(db.allowance(ALICE, CAROL).unwrap(), amount(50));
+    assert_eq!(db.allowance(CAROL, ALICE).unwrap(), U256::ZERO);
+
+    let history: Vec<(Position, String)> = db
+        .balance_history(BOB)
+        .unwrap()
+        .into_iter()
+        .map(|change| (change.position, change.balance))
+        .collect();
+    assert_eq!(
+        history,
+        [(position(10, 0, 1), String::from("300")), (position(11, 31, 0), String::from("200"))]
+    );
+    assert!(db.balance_history(CAROL).unwrap().is_empty());
+}
+
+#[test]
+fn test_db_upserts() {
+    let mut db = seeded_database();
+    let events = [
+        // A new approval replaces the allowance, down to zero
+        indexed(position(13, 0, 0), &format!("APPROVAL SUCCESS:owner={},spender={},amount=80", ALICE, CAROL)),
+        indexed(position(13, 0, 1), &format!("APPROVAL SUCCESS:owner={},spender={},amount=0", ALICE, CAROL)),
+        // An emptied balance is kept as zero but no longer counts as a holder
+        indexed(position(13, 1, 0), &format!("TRANSFER SUCCESS:from={},to={},amount=200", BOB, ALICE)),
+        indexed(position(13, 1, 1), &format!("TRANSFER SUCCESS:from={},to={},amount=0", CAROL, BOB)),
+    ];
+    db.apply(&events, position(13, 1, 1)).unwrap();
+
+    assert_eq!(db.allowance(ALICE, CAROL).unwrap(), U256::ZERO);
+    assert_eq!(db.balance(ALICE).unwrap(), amount(900));
+    assert_eq!(db.balance(BOB).unwrap(), U256::ZERO);
+    assert_eq!(db.holder_count().unwrap(), 1);
+    assert_eq!(db.total_supply().unwrap(), amount(900));
+    assert_eq!(db.cursor().unwrap(), Some(position(13, 1, 1)));
+
+    // One history row per change, the last one for each position
+    let bob: Vec<String> = db.balance_history(BOB).unwrap().into_iter().map(|change| change.balance).collect();
+    assert_eq!(bob, ["300", "200", "0", "0"]);
+
+    // The cursor can move without events, e.g. past other events of the token
+    db.apply(&[], position(20, 3, 0)).unwrap();
+    assert_eq!(db.cursor().unwrap(), Some(position(20, 3, 0)));
+    assert_eq!(db.balance(ALICE).unwrap(), amount(900));
+}
+
+#[test]
+fn test_db_apply_is_atomic() {
+    let mut db = seeded_database();
+
+    // Replaying an applied event fails instead of counting it twice, and
+    // nothing of the batch is kept
+    let events = [
+        indexed(position(13, 0, 0), &format!("MINT SUCCESS:to={},amount=5", CAROL)),
+        indexed(position(10, 0, 1), &format!("TRANSFER SUCCESS:from={},to={},amount=300", ALICE, BOB)),
+    ];
+    assert!(db.apply(&events, position(13, 0, 0)).is_err());
+    assert_eq!(db.balance(ALICE).unwrap(), amount(700));
+    assert_eq!(db.balance(CAROL).unwrap(), U256::ZERO);
+    assert_eq!(db.cursor().unwrap(), Some(position(12, 0, 3)));
+
+    // Spending more than the indexed balance means events are missing
+    let events = [
+        indexed(position(13, 0, 0), &format!("MINT SUCCESS:to={},amount=5", CAROL)),
+        indexed(position(13, 0, 1), &format!("BURN_SUCCESS:from={},amount=201", BOB)),
+    ];
+    let err = db.apply(&events, position(13, 0, 1)).unwrap_err();
+    assert!(err.to_string().contains("events before the start slot are missing"), "{}", err);
+    assert_eq!(db.balance(BOB).unwrap(), amount(200));
+    assert_eq!(db.balance(CAROL).unwrap(), U256::ZERO);
+    assert_eq!(db.cursor().unwrap(), Some(position(12, 0, 3)));
+}
+
+#[test]
+fn test_event_filter() {
+    let start = Slot { period: 5, thread: 0 };
+    let filter = events::event_filter(TOKEN, start, None);
+    assert_eq!(filter.start, Some(start));
+    assert_eq!(filter.end, None);
+    assert_eq!(filter.emitter_address.as_deref(), Some(TOKEN));
+    // Only final events, which can no longer be reverted
+    assert_eq!(filter.is_final, Some(true));
+
+    // With a cursor, from its slot, which the node includes
+    let filter = events::event_filter(TOKEN, start, Some(position(40, 7, 2)));
+    assert_eq!(filter.start, Some(Slot { period: 40, thread: 7 }));
+    assert_eq!(filter.is_final, Some(true));
+}
+
+#[test]
+fn test_new_events_skip_applied() {
+    let cursor = Some(position(40, 7, 2));
+    let events = vec![
+        // Already applied: the cursor's slot is sent again
+        output_event(position(40, 7, 1), &format!("MINT SUCCESS:to={},amount=1", ALICE)),
+        output_event(position(40, 7, 2), &format!("MINT SUCCESS:to={},amount=2", ALICE)),
+        // New, out of order, with an event of another kind in between
+        output_event(position(41, 0, 0), &format!("BURN_SUCCESS:from={},amount=4", ALICE)),
+        output_event(position(40, 7, 3), &format!("MINT SUCCESS:to={},amount=3", ALICE)),
+        output_event(position(41, 2, 0), "PAUSED:by=AU12owner"),
+    ];
+    let (latest, indexed) = events::new_events(events, cursor);
+    // The cursor moves past the last event, even if it is not a token event
+    assert_eq!(latest, Some(position(41, 2, 0)));
+    let positions: Vec<Position> = indexed.iter().map(|event| event.position).collect();
+    assert_eq!(positions, [position(40, 7, 3), position(41, 0, 0)]);
+    assert_eq!(indexed[0].operation_id.as_deref(), Some("O1op40x3"));
+    assert_eq!(indexed[1].event.kind(), "burn");
+
+    // Only applied events: nothing to do and the cursor stays
+    let events = vec![output_event(position(40, 7, 2), &format!("MINT SUCCESS:to={},amount=2", ALICE))];
+    let (latest, indexed) = events::new_events(events, cursor);
+    assert_eq!(latest, cursor);
+    assert!(indexed.is_empty());
+
+    // Without a cursor everything is new
+    let events = vec![output_event(position(0, 0, 0), &format!("MINT SUCCESS:to={},amount=2", ALICE))];
+    let (latest, indexed) = events::new_events(events, None);
+    assert_eq!(latest, Some(position(0, 0, 0)));
+    assert_eq!(indexed.len(), 1);
+    assert_eq!(events::new_events(Vec::new(), None).0, None);
+}
+
+/// The export of `db` as text
+fn exported(db: &Database, format: Format, from: Option<&str>, to: Option<&str>) -> String {
+    let mut out = Vec::new();
+    let from = from.map(|bound| SlotBound::parse(bound).unwrap());
+    let to = to.map(|bound| SlotBound::parse(bound).unwrap());
+    export::export(db, format, from, to, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_export_csv() {
+    let db = seeded_database();
+    let expected = format!(
+        "period,thread,index_in_slot,kind,from,to,amount,operation_id\n\
+         10,0,0,mint,,{alice},1000,O1op10x0\n\
+         10,0,1,transfer,{alice},{bob},300,O1op10x1\n\
+         11,31,0,burn,{bob},,100,O1op11x0\n",
+        alice = ALICE,
+        bob = BOB
+    );
+    assert_eq!(exported(&db, Format::Csv, None, None), expected);
+
+    // Bounds are inclusive, and a bare period covers all its threads
+    let lines = |from: Option<&str>, to: Option<&str>| exported(&db, Format::Csv, from, to).lines().count() - 1;
+    assert_eq!(lines(Some("10"), Some("10")), 2);
+    assert_eq!(lines(Some("10:1"), Some("11")), 1);
+    assert_eq!(lines(None, Some("11:30")), 2);
+    assert_eq!(lines(Some("11:31"), Some("11:31")), 1);
+    assert_eq!(lines(Some("12"), None), 0);
+    let header_only = exported(&db, Format::Csv, Some("12"), None);
+    assert_eq!(header_only, "period,thread,index_in_slot,kind,from,to,amount,operation_id\n");
+
+    let mut out = Vec::new();
+    let bounds = (SlotBound::parse("11").ok(), SlotBound::parse("10:31").ok());
+    let err = export::export(&db, Format::Csv, bounds.0, bounds.1, &mut out).unwrap_err();
+    assert!(err.to_string().contains("--from-slot is after --to-slot"), "{}", err);
+}
+
+#[test]
+fn test_export_json() {
+    let db = seeded_database();
+    let text = exported(&db, Format::Json, Some("10:0"), Some("10:0"));
+    assert!(text.ends_with("]\n"));
+    let entries: serde_json::Value = serde_json::from_str(&text).unwrap();
+    let expected = serde_json::json!([
+        {
+            "slot": { "period": 10, "thread": 0 },
+            "index_in_slot": 0,
+            "kind": "mint",
+            "from": null,
+            "to": ALICE,
+            "amount": "1000",
+            "operation_id": "O1op10x0"
+        },
+        {
+            "slot": { "period": 10, "thread": 0 },
+            "index_in_slot": 1,
+            "kind": "transfer",
+            "from": ALICE,
+            "to": BOB,
+            "amount": "300",
+            "operation_id": "O1op10x1"
+        }
+    ]);
+    assert_eq!(entries, expected);
+
+    let entries: serde_json::Value = serde_json::from_str(&exported(&db, Format::Json, None, None)).unwrap();
+    assert_eq!(entries.as_array().unwrap().len(), 3);
+    assert_eq!(entries[2]["kind"], "burn");
+    assert_eq!(entries[2]["to"], serde_json::Value::Null);
+}
+
+#[test]
+fn test_slot_bound_parse() {
+    for valid in ["0", "10", "10:0", "10:31", "18446744073709551615"] {
+        assert!(SlotBound::parse(valid).is_ok(), "{} was rejected", valid);
+    }
+    assert_eq!(SlotBound::parse("10:32").err().unwrap(), "10:32: threads go from 0 to 31");
+    for invalid in ["", ":", "10:", ":3", "-1", "10:-1", "10:1:2", "1.5", "ten"] {
+        let err = SlotBound::parse(invalid).err().unwrap_or_else(|| panic!("{} was accepted", invalid));
+        assert_eq!(err, format!("{} is not PERIOD or PERIOD:THREAD", invalid));
+    }
+}
+
+#[test]
+fn test_metrics() {
+    let db = seeded_database();
+    let metrics = Metrics::default();
+    let value = |text: &str, name: &str| -> f64 {
+        let line = text.lines().find(|line| line.starts_with(&format!("{} ", name))).unwrap();
+        line[name.len() + 1..].parse().unwrap()
+    };
+
+    let text = metrics.render(&db).unwrap();
+    assert!(text.contains("# TYPE mrc20_indexer_events_processed_total counter\n"), "{}", text);
+    assert!(text.contains("# TYPE mrc20_indexer_lag_slots gauge\n"), "{}", text);
+    assert_eq!(value(&text, "mrc20_indexer_events_processed_total"), 0.0);
+    assert_eq!(value(&text, "mrc20_indexer_last_success_timestamp_seconds"), 0.0);
+    assert_eq!(value(&text, "mrc20_indexer_total_supply"), 900.0);
+    assert_eq!(value(&text, "mrc20_indexer_holders"), 2.0);
+
+    // A poll at head 100:0 applied 4 events; the head moved 2 periods and 1 thread while the next one failed
+    metrics.observe_head(Slot { period: 100, thread: 0 });
+    metrics.poll_succeeded(Slot { period: 100, thread: 0 }, 4);
+    metrics.observe_head(Slot { period: 102, thread: 1 });
+    metrics.poll_failed();
+
+    let text = metrics.render(&db).unwrap();
+    assert_eq!(value(&text, "mrc20_indexer_events_processed_total"), 4.0);
+    assert_eq!(value(&text, "mrc20_indexer_poll_errors_total"), 1.0);
+    assert_eq!(value(&text, "mrc20_indexer_head_slot"), (102 * 32 + 1) as f64);
+    assert_eq!(value(&text, "mrc20_indexer_lag_slots"), 65.0);
+    assert!(value(&text, "mrc20_indexer_last_success_timestamp_seconds") > 0.0);
+
+    // Every metric has its help and type lines
+    for line in text.lines().filter(|line| !line.starts_with('#')) {
+        let name = line.split(' ').next().unwrap();
+        assert!(text.contains(&format!("# HELP {} ", name)), "{}", name);
+        assert!(text.contains(&format!("# TYPE {} ", name)), "{}", name);
+    }
+}
@@ -0,0 +1,307 @@
+//! Tests for the MRC721 NFT Contract
+//!
+//! This test suite validates the MRC721 contract functionality using
+//! the massa-testkit runtime, in parallel with the MRC20 suite.
+
+use anyhow::Result;
+use massa_types::{Args, U256};
+use massa_testkit::{TestInterface, TestRuntime};
+
+/// Test addresses for simulating different users
+const DEPLOYER: &str = "AU1deployerAddress123456789012345678901234567890";
+const ALICE: &str = "AU1aliceAddress1234567890123456789012345678901234";
+const BOB: &str = "AU1bobAddress12345678901234567890123456789012345";
+const CHARLIE: &str = "AU1charlieAddress12345678901234567890123456789012";
+
+/// Helper to build WASM path
+fn wasm_path() -> std::path::PathBuf {
+    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../target/wasm32v1-none/release/mrc721.wasm")
+}
+
+/// Deploy the collection as DEPLOYER and mint token 1 to Alice
+fn deploy_with_token(wasm: &[u8], runtime: &TestRuntime) -> Result<()> {
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_NFT".to_string()]);
+    let mut args = Args::new();
+    args.add_string("MassaPunks").add_string("MPUNK").add_string("ipfs://punks/");
+    runtime.execute(wasm, "constructor", &args.into_bytes())?;
+
+    let mut mint_args = Args::new();
+    mint_args.add_string(ALICE).add_u256(U256::from(1u64));
+    runtime.execute(wasm, "mint", &mint_args.into_bytes())?;
+    Ok(())
+}
+
+fn owner_of(wasm: &[u8], runtime: &TestRuntime, token_id: u64) -> Result<String> {
+    let mut args = Args::new();
+    args.add_u256(U256::from(token_id));
+    let response = runtime.execute(wasm, "ownerOf", &args.into_bytes())?;
+    Ok(String::from_utf8(response.ret)?)
+}
+
+fn balance_of(wasm: &[u8], runtime: &TestRuntime, address: &str) -> Result<U256> {
+    let mut args = Args::new();
+    args.add_string(address);
+    let response = runtime.execute(wasm, "balanceOf", &args.into_bytes())?;
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&response.ret[..32]);
+    Ok(U256::from_le_bytes(bytes))
+}
+
+fn transfer_args(from: &str, to: &str, token_id: u64) -> Vec<u8> {
+    let mut args = Args::new();
+    args.add_string(from).add_string(to).add_u256(U256::from(token_id));
+    args.into_bytes()
+}
+
+/// Run a call that must fail and return its error, e.g. `ERR:{CODE}:{fields}:{message}`
+fn execute_expect_failure(wasm: &[u8], runtime: &TestRuntime, function: &str, args: &[u8]) -> String {
+    match runtime.execute(wasm, function, args) {
+        Ok(response) => panic!("{} must fail, returned {:?}", function, response.ret),
+        Err(err) => format!("{:?}", err),
+    }
+}
+
+#[test]
+fn test_constructor_and_metadata() -> Result<()> {
+    let wasm = std::fs::read(wasm_path())?;
+    let runtime = TestRuntime::new();
+
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_NFT".to_string()]);
+    let mut args = Args::new();
+    args.add_string("").add_string("MPUNK");
+    let err = execute_expect_failure(&wasm, &runtime, "constructor", &args.into_bytes());
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=name:"), "Unexpected error: {}", err);
+
+    deploy_with_token(&wasm, &runtime)?;
+
+    let events = runtime.interface.events();
+    assert!(events.contains(&format!("CHANGE_OWNER:{}", DEPLOYER)));
+
+    let response = runtime.execute(&wasm, "name", &[])?;
+    assert_eq!(response.ret, b"MassaPunks".to_vec());
+    let response = runtime.execute(&wasm, "symbol", &[])?;
+    assert_eq!(response.ret, b"MPUNK".to_vec());
+    let response = runtime.execute(&wasm, "ownerAddress", &[])?;
+    assert_eq!(response.ret, DEPLOYER.as_bytes().to_vec());
+
+    let mut uri_args = Args::new();
+    uri_args.add_u256(U256::from(1u64));
+    let response = runtime.execute(&wasm, "tokenURI", &uri_args.into_bytes())?;
+    assert_eq!(response.ret, b"ipfs://punks/1".to_vec());
+
+    Ok(())
+}
+
+#[test]
+fn test_mint() -> Result<()> {
+    let wasm = std::fs::read(wasm_path())?;
+    let runtime = TestRuntime::new();
+    deploy_with_token(&wasm, &runtime)?;
+
+    let events = runtime.interface.events();
+    assert!(events.contains(&format!("TRANSFER:from=,to={},tokenId=1", ALICE)));
+
+    assert_eq!(owner_of(&wasm, &runtime, 1)?, ALICE);
+    assert_eq!(balance_of(&wasm, &runtime, ALICE)?, U256::from(1u64));
+    let response = runtime.execute(&wasm, "totalSupply", &[])?;
+    assert_eq!(response.ret, U256::from(1u64).to_le_bytes().to_vec());
+
+    // Token ids are unique
+    let mut mint_args = Args::new();
+    mint_args.add_string(BOB).add_u256(U256::from(1u64));
+    let err = execute_expect_failure(&wasm, &runtime, "mint", &mint_args.into_bytes());
+    assert!(err.contains("ERR:ALREADY_EXISTS:tokenId=1:"), "Unexpected error: {}", err);
+
+    // Only the owner mints
+    runtime
+        .interface
+        .set_call_stack(vec![ALICE.to_string(), "AS_NFT".to_string()]);
+    let mut mint_args = Args::new();
+    mint_args.add_string(ALICE).add_u256(U256::from(2u64));
+    let err = execute_expect_failure(&wasm, &runtime, "mint", &mint_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_owner_of_unknown_token() -> Result<()> {
+    let wasm = std::fs::read(wasm_path())?;
+    let runtime = TestRuntime::new();
+    deploy_with_token(&wasm, &runtime)?;
+
+    let err = owner_of(&wasm, &runtime, 2).expect_err("Unknown token must fail");
+    assert!(
+        format!("{:?}", err).contains("ERR:NOT_FOUND:tokenId=2:"),
+        "Unexpected error: {:?}",
+        err
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_from_owner() -> Result<()> {
+    let wasm = std::fs::read(wasm_path())?;
+    let runtime = TestRuntime::new();
+    deploy_with_token(&wasm, &runtime)?;
+
+    runtime
+        .interface
+        .set_call_stack(vec![ALICE.to_string(), "AS_NFT".to_string()]);
+    runtime.execute(&wasm, "transferFrom", &transfer_args(ALICE, BOB, 1))?;
+
+    let events = runtime.interface.events();
+    assert!(events.contains(&format!("TRANSFER:from={},to={},tokenId=1", ALICE, BOB)));
+    assert_eq!(owner_of(&wasm, &runtime, 1)?, BOB);
+    assert_eq!(balance_of(&wasm, &runtime, ALICE)?, U256::ZERO);
+    assert_eq!(balance_of(&wasm, &runtime, BOB)?, U256::from(1u64));
+
+    // `from` must be the current owner
+    let err = execute_expect_failure(&wasm, &runtime, "transferFrom", &transfer_args(ALICE, CHARLIE, 1));
+    assert!(err.contains("ERR:INVALID_ARGUMENT:name=from,tokenId=1:"), "Unexpected error: {}", err);
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_from_unauthorized() -> Result<()> {
+    let wasm = std::fs::read(wasm_path())?;
+    let runtime = TestRuntime::new();
+    deploy_with_token(&wasm, &runtime)?;
+
+    runtime
+        .interface
+        .set_call_stack(vec![BOB.to_string(), "AS_NFT".to_string()]);
+    let err = execute_expect_failure(&wasm, &runtime, "transferFrom", &transfer_args(ALICE, BOB, 1));
+    assert!(err.contains(&format!("ERR:UNAUTHORIZED:caller={},tokenId=1:", BOB)), "Unexpected error: {}", err);
+    assert_eq!(owner_of(&wasm, &runtime, 1)?, ALICE);
+
+    Ok(())
+}
+
+#[test]
+fn test_approve() -> Result<()> {
+    let wasm = std::fs::read(wasm_path())?;
+    let runtime = TestRuntime::new();
+    deploy_with_token(&wasm, &runtime)?;
+
+    // Only the owner approves
+    runtime
+        .interface
+        .set_call_stack(vec![BOB.to_string(), "AS_NFT".to_string()]);
+    let mut approve_args = Args::new();
+    approve_args.add_string(BOB).add_u256(U256::from(1u64));
+    let err = execute_expect_failure(&wasm, &runtime, "approve", &approve_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
+
+    runtime
+        .interface
+        .set_call_stack(vec![ALICE.to_string(), "AS_NFT".to_string()]);
+    let mut approve_args = Args::new();
+    approve_args.add_string(BOB).add_u256(U256::from(1u64));
+    runtime.execute(&wasm, "approve", &approve_args.into_bytes())?;
+
+    let events = runtime.interface.events();
+    assert!(events.contains(&format!("APPROVAL:owner={},approved={},tokenId=1", ALICE, BOB)));
+
+    let mut token_args = Args::new();
+    token_args.add_u256(U256::from(1u64));
+    let response = runtime.execute(&wasm, "getApproved", &token_args.into_bytes())?;
+    assert_eq!(response.ret, BOB.as_bytes().to_vec());
+
+    // The approved address transfers, which clears the approval
+    runtime
+        .interface
+        .set_call_stack(vec![BOB.to_string(), "AS_NFT".to_string()]);
+    runtime.execute(&wasm, "transferFrom", &transfer_args(ALICE, CHARLIE, 1))?;
+    assert_eq!(owner_of(&wasm, &runtime, 1)?, CHARLIE);
+
+    let mut token_args = Args::new();
+    token_args.add_u256(U256::from(1u64));
+    let response = runtime.execute(&wasm, "getApproved", &token_args.into_bytes())?;
+    assert!(response.ret.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_set_approval_for_all() -> Result<()> {
+    let wasm = std::fs::read(wasm_path())?;
+    let runtime = TestRuntime::new();
+    deploy_with_token(&wasm, &runtime)?;
+
+    runtime
+        .interface
+        .set_call_stack(vec![ALICE.to_string(), "AS_NFT".to_string()]);
+    let mut operator_args = Args::new();
+    operator_args.add_string(BOB).add_bool(true);
+    runtime.execute(&wasm, "setApprovalForAll", &operator_args.into_bytes())?;
+
+    let events = runtime.interface.events();
+    assert!(events.contains(&format!("APPROVAL_FOR_ALL:owner={},operator={},approved=true", ALICE, BOB)));
+
+    let mut query_args = Args::new();
+    query_args.add_string(ALICE).add_string(BOB);
+    let response = runtime.execute(&wasm, "isApprovedForAll", &query_args.into_bytes())?;
+    assert_eq!(response.ret, vec![1u8]);
+
+    // The operator transfers any of the owner's tokens
+    runtime
+        .interface
+        .set_call_stack(vec![BOB.to_string(), "AS_NFT".to_string()]);
+    runtime.execute(&wasm, "transferFrom", &transfer_args(ALICE, BOB, 1))?;
+    assert_eq!(owner_of(&wasm, &runtime, 1)?, BOB);
+
+    // Revoking removes the approval
+    runtime
+        .interface
+        .set_call_stack(vec![ALICE.to_string(), "AS_NFT".to_string()]);
+    let mut operator_args = Args::new();
+    operator_args.add_string(BOB).add_bool(false);
+    runtime.execute(&wasm, "setApprovalForAll", &operator_args.into_bytes())?;
+
+    let mut query_args = Args::new();
+    query_args.add_string(ALICE).add_string(BOB);
+    let response = runtime.execute(&wasm, "isApprovedForAll", &query_args.into_bytes())?;
+    assert_eq!(response.ret, vec![0u8]);
+
+    Ok(())
+}
+
+#[test]
+fn test_burn() -> Result<()> {
+    let wasm = std::fs::read(wasm_path())?;
+    let runtime = TestRuntime::new();
+    deploy_with_token(&wasm, &runtime)?;
+
+    // Only the holder or an approved address burns
+    runtime
+        .interface
+        .set_call_stack(vec![DEPLOYER.to_string(), "AS_NFT".to_string()]);
+    let mut burn_args = Args::new();
+    burn_args.add_u256(U256::from(1u64));
+    let err = execute_expect_failure(&wasm, &runtime, "burn", &burn_args.into_bytes());
+    assert!(err.contains("ERR:UNAUTHORIZED:"), "Unexpected error: {}", err);
+
+    runtime
+        .interface
+        .set_call_stack(vec![ALICE.to_string(), "AS_NFT".to_string()]);
+    let mut burn_args = Args::new();
+    burn_args.add_u256(U256::from(1u64));
+    runtime.execute(&wasm, "burn", &burn_args.into_bytes())?;
+
+    let events = runtime.interface.events();
+    assert!(events.contains(&format!("TRANSFER:from={},to=,tokenId=1", ALICE)));
+    assert!(owner_of(&wasm, &runtime, 1).is_err());
+    assert_eq!(balance_of(&wasm, &runtime, ALICE)?, U256::ZERO);
+    let response = runtime.execute(&wasm, "totalSupply", &[])?;
+    assert_eq!(response.ret, U256::ZERO.to_le_bytes().to_vec());
+
+    Ok(())
+}
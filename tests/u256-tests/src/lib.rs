@@ -0,0 +1,648 @@
+//! Tests for the contracts' U256 helpers and I256 type
+//!
+//! Checks the arithmetic, conversions and encodings of the shared
+//! `mrc20-core` modules (`math`, `i256`, `encoding`) against
+//! `num-bigint` on edge values (limb boundaries, powers of two, the extremes)
+//! and on pseudo-random operands of every width.
+
+use massa_types::{Args, U256};
+use mrc20_core::i256::I256;
+use mrc20_core::math::Rounding;
+use mrc20_core::{encoding, math};
+use num_bigint::{BigInt, BigUint, Sign};
+
+/// Number of pseudo-random operands per width
+const RANDOM_ROUNDS: usize = 500;
+
+fn to_big(value: U256) -> BigUint {
+    BigUint::from_bytes_le(&value.to_le_bytes())
+}
+
+/// Convert back to U256, or None if the value does not fit in 256 bits.
+fn from_big(value: &BigUint) -> Option<U256> {
+    let bytes = value.to_bytes_le();
+    if bytes.len() > 32 {
+        return None;
+    }
+    let mut padded = [0u8; 32];
+    padded[..bytes.len()].copy_from_slice(&bytes);
+    Some(U256::from_le_bytes(padded))
+}
+
+fn max() -> U256 {
+    U256::from_le_bytes([0xff; 32])
+}
+
+/// Value with only bit `bit` set
+fn pow2(bit: usize) -> U256 {
+    let mut bytes = [0u8; 32];
+    bytes[bit / 8] = 1 << (bit % 8);
+    U256::from_le_bytes(bytes)
+}
+
+/// Edge values: small numbers, limb boundaries and the top of the range
+fn edge_values() -> Vec<U256> {
+    let mut values = vec![U256::ZERO, U256::from(1u64), U256::from(2u64), U256::from(3u64), U256::from(10u64)];
+    for bit in [63, 64, 127, 128, 191, 192, 255] {
+        let power = pow2(bit);
+        values.push(power);
+        values.push(power.saturating_sub(U256::from(1u64)));
+        values.push(power.saturating_add(U256::from(1u64)));
+    }
+    values.push(U256::from(u64::MAX));
+    values.push(U256::from(1_000_000_000_000_000_000u64));
+    values.push(max().saturating_sub(U256::from(1u64)));
+    values.push(max());
+    values
+}
+
+/// Deterministic xorshift generator, so failures are reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Random value using the low `limbs` 64-bit limbs
+    fn value(&mut self, limbs: usize) -> U256 {
+        let mut bytes = [0u8; 32];
+        for chunk in bytes.chunks_exact_mut(8).take(limbs) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes());
+        }
+        U256::from_le_bytes(bytes)
+    }
+}
+
+/// Edge pairs, then random pairs of every combination of widths
+fn operand_pairs() -> Vec<(U256, U256)> {
+    let edges = edge_values();
+    let mut pairs = Vec::new();
+    for a in &edges {
+        for b in &edges {
+            pairs.push((*a, *b));
+        }
+    }
+
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for a_limbs in 1..=4 {
+        for b_limbs in 1..=4 {
+            for _ in 0..RANDOM_ROUNDS {
+                pairs.push((rng.value(a_limbs), rng.value(b_limbs)));
+            }
+        }
+    }
+    pairs
+}
+
+#[test]
+fn test_checked_mul_matches_reference() {
+    for (a, b) in operand_pairs() {
+        let expected = from_big(&(to_big(a) * to_big(b)));
+        assert_eq!(math::checked_mul(a, b), expected, "{} * {}", a, b);
+    }
+}
+
+#[test]
+fn test_saturating_mul_matches_reference() {
+    for (a, b) in operand_pairs() {
+        let expected = from_big(&(to_big(a) * to_big(b))).unwrap_or(max());
+        assert_eq!(math::saturating_mul(a, b), expected, "{} * {}", a, b);
+    }
+}
+
+#[test]
+fn test_checked_div_matches_reference() {
+    for (a, b) in operand_pairs() {
+        let expected = if b == U256::ZERO { None } else { from_big(&(to_big(a) / to_big(b))) };
+        assert_eq!(math::checked_div(a, b), expected, "{} / {}", a, b);
+    }
+}
+
+#[test]
+fn test_checked_rem_matches_reference() {
+    for (a, b) in operand_pairs() {
+        let expected = if b == U256::ZERO { None } else { from_big(&(to_big(a) % to_big(b))) };
+        assert_eq!(math::checked_rem(a, b), expected, "{} % {}", a, b);
+    }
+}
+
+#[test]
+fn test_mul_div_matches_reference() {
+    let edges = edge_values();
+    let mut triples = Vec::new();
+    for a in &edges {
+        for b in &edges {
+            for denominator in &edges {
+                triples.push((*a, *b, *denominator));
+            }
+        }
+    }
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for limbs in 1..=4 {
+        for _ in 0..RANDOM_ROUNDS {
+            triples.push((rng.value(4), rng.value(4), rng.value(limbs)));
+        }
+    }
+
+    for (a, b, denominator) in triples {
+        let expected = if denominator == U256::ZERO {
+            None
+        } else {
+            from_big(&(to_big(a) * to_big(b) / to_big(denominator)))
+        };
+        assert_eq!(math::mul_div(a, b, denominator), expected, "{} * {} / {}", a, b, denominator);
+    }
+}
+
+#[test]
+fn test_mul_div_keeps_full_product() {
+    // MAX * MAX overflows 256 bits, but dividing by MAX brings it back
+    assert_eq!(math::checked_mul(max(), max()), None);
+    assert_eq!(math::mul_div(max(), max(), max()), Some(max()));
+
+    // A 1e18-scaled reward on a large stake, as in the staking contract
+    let stake = pow2(200);
+    let scale = U256::from(1_000_000_000_000_000_000u64);
+    assert_eq!(math::checked_mul(stake, scale), None);
+    assert_eq!(math::mul_div(stake, scale, scale), Some(stake));
+
+    // Results that do not fit are still rejected
+    assert_eq!(math::mul_div(max(), U256::from(2u64), U256::from(1u64)), None);
+}
+
+#[test]
+fn test_division_by_zero() {
+    assert_eq!(math::checked_div(U256::from(1u64), U256::ZERO), None);
+    assert_eq!(math::checked_rem(U256::from(1u64), U256::ZERO), None);
+    assert_eq!(math::mul_div(U256::from(1u64), U256::from(1u64), U256::ZERO), None);
+    assert_eq!(math::checked_div(U256::ZERO, U256::from(7u64)), Some(U256::ZERO));
+}
+
+/// Reference division with the given rounding, or None if it does not fit
+fn rounded_reference(numerator: BigUint, denominator: &BigUint, rounding: Rounding) -> Option<U256> {
+    let quotient = &numerator / denominator;
+    let remainder = &numerator % denominator;
+    let round_up = match rounding {
+        Rounding::Floor => false,
+        Rounding::Ceil => remainder != BigUint::from(0u8),
+        Rounding::HalfUp => remainder * 2u8 >= *denominator,
+    };
+    from_big(&if round_up { quotient + 1u8 } else { quotient })
+}
+
+const ROUNDINGS: [Rounding; 3] = [Rounding::Floor, Rounding::Ceil, Rounding::HalfUp];
+
+#[test]
+fn test_mul_div_rounded_matches_reference() {
+    let edges = edge_values();
+    let mut rng = Rng(0x94d0_49bb_1331_11eb);
+    let mut triples = Vec::new();
+    for a in &edges {
+        for denominator in &edges {
+            triples.push((*a, U256::from(3u64), *denominator));
+            triples.push((*a, *denominator, U256::from(7u64)));
+        }
+    }
+    for limbs in 1..=4 {
+        for _ in 0..RANDOM_ROUNDS {
+            triples.push((rng.value(4), rng.value(limbs), rng.value(limbs)));
+        }
+    }
+
+    for (a, b, denominator) in triples {
+        for rounding in ROUNDINGS {
+            let expected = if denominator == U256::ZERO {
+                None
+            } else {
+                rounded_reference(to_big(a) * to_big(b), &to_big(denominator), rounding)
+            };
+            assert_eq!(
+                math::mul_div_rounded(a, b, denominator, rounding),
+                expected,
+                "{} * {} / {} ({:?})",
+                a,
+                b,
+                denominator,
+                rounding
+            );
+        }
+    }
+}
+
+#[test]
+fn test_bps_and_percent_rounding() {
+    // 0.3% of 1001 is 3.003
+    let amount = U256::from(1001u64);
+    assert_eq!(math::bps(amount, 30, Rounding::Floor), Some(U256::from(3u64)));
+    assert_eq!(math::bps(amount, 30, Rounding::Ceil), Some(U256::from(4u64)));
+    assert_eq!(math::bps(amount, 30, Rounding::HalfUp), Some(U256::from(3u64)));
+
+    // 50% of 5 is 2.5, halves round up
+    let amount = U256::from(5u64);
+    assert_eq!(math::percent_of(amount, 50, Rounding::Floor), Some(U256::from(2u64)));
+    assert_eq!(math::percent_of(amount, 50, Rounding::HalfUp), Some(U256::from(3u64)));
+    assert_eq!(math::percent_of(amount, 50, Rounding::Ceil), Some(U256::from(3u64)));
+
+    // Exact results do not round
+    for rounding in ROUNDINGS {
+        assert_eq!(math::bps(U256::from(20_000u64), 2_500, rounding), Some(U256::from(5_000u64)));
+        assert_eq!(math::bps(max(), math::MAX_BPS, rounding), Some(max()));
+        assert_eq!(math::percent_of(max(), 100, rounding), Some(max()));
+        assert_eq!(math::bps(max(), 0, rounding), Some(U256::ZERO));
+    }
+
+    // The 512-bit intermediate keeps large amounts exact
+    let expected = rounded_reference(to_big(max()) * 9_999u32, &BigUint::from(10_000u32), Rounding::Floor);
+    assert_eq!(math::bps(max(), 9_999, Rounding::Floor), expected);
+
+    // Above 100%, the maximum overflows
+    assert_eq!(math::bps(max(), math::MAX_BPS + 1, Rounding::Floor), None);
+    assert_eq!(math::percent_of(max(), 101, Rounding::Floor), None);
+    // Rounding up can overflow on its own
+    assert_eq!(math::mul_div_rounded(max(), U256::from(3u64), U256::from(2u64), Rounding::Ceil), None);
+}
+
+#[test]
+fn test_isqrt_matches_reference() {
+    let mut values = edge_values();
+    let mut rng = Rng(0x6c8e_9cf5_7093_2bd5);
+    for limbs in 1..=4 {
+        for _ in 0..RANDOM_ROUNDS {
+            values.push(rng.value(limbs));
+        }
+    }
+
+    for value in values {
+        let root = math::isqrt(value);
+        assert_eq!(to_big(root), to_big(value).sqrt(), "sqrt({})", value);
+    }
+}
+
+#[test]
+fn test_isqrt_edges() {
+    assert_eq!(math::isqrt(U256::ZERO), U256::ZERO);
+    assert_eq!(math::isqrt(U256::from(1u64)), U256::from(1u64));
+    assert_eq!(math::isqrt(U256::from(2u64)), U256::from(1u64));
+    assert_eq!(math::isqrt(U256::from(3u64)), U256::from(1u64));
+    assert_eq!(math::isqrt(U256::from(4u64)), U256::from(2u64));
+    // sqrt(2^256 - 1) is just below 2^128
+    assert_eq!(math::isqrt(max()), pow2(128).saturating_sub(U256::from(1u64)));
+
+    // Perfect squares and their neighbours
+    let mut rng = Rng(0x2127_599b_f432_5c37);
+    let mut roots = vec![
+        U256::from(2u64),
+        U256::from(u64::MAX),
+        pow2(127),
+        pow2(128).saturating_sub(U256::from(1u64)),
+    ];
+    for limbs in 1..=2 {
+        for _ in 0..RANDOM_ROUNDS {
+            roots.push(rng.value(limbs));
+        }
+    }
+    for root in roots {
+        let square = math::checked_mul(root, root).unwrap();
+        let one = U256::from(1u64);
+        assert_eq!(math::isqrt(square), root, "sqrt({}^2)", root);
+        assert_eq!(math::isqrt(square.saturating_add(one)), root, "sqrt({}^2 + 1)", root);
+        if root > U256::ZERO {
+            assert_eq!(math::isqrt(square - one), root - one, "sqrt({}^2 - 1)", root);
+        }
+    }
+}
+
+#[test]
+fn test_checked_pow_matches_reference() {
+    let mut bases = edge_values();
+    let mut rng = Rng(0x8cb9_2ba7_2f3d_8dd7);
+    for _ in 0..RANDOM_ROUNDS {
+        bases.push(rng.value(1));
+    }
+
+    for base in bases {
+        for exp in [0u32, 1, 2, 3, 4, 5, 7, 8, 16, 31, 64, 255, 256, 257, u32::MAX] {
+            // Bases above 1 overflow long before large exponents, so cap the reference work
+            let expected = if base > U256::from(1u64) && exp > 256 {
+                None
+            } else {
+                from_big(&to_big(base).pow(exp))
+            };
+            assert_eq!(math::checked_pow(base, exp), expected, "{}^{}", base, exp);
+        }
+    }
+}
+
+#[test]
+fn test_checked_pow_edges() {
+    let two = U256::from(2u64);
+    assert_eq!(math::checked_pow(U256::ZERO, 0), Some(U256::from(1u64)));
+    assert_eq!(math::checked_pow(U256::ZERO, 5), Some(U256::ZERO));
+    assert_eq!(math::checked_pow(U256::from(1u64), u32::MAX), Some(U256::from(1u64)));
+    assert_eq!(math::checked_pow(two, 255), Some(pow2(255)));
+    assert_eq!(math::checked_pow(two, 256), None);
+    assert_eq!(math::checked_pow(max(), 1), Some(max()));
+    assert_eq!(math::checked_pow(max(), 2), None);
+    // 10^77 fits, 10^78 does not
+    assert!(math::checked_pow(U256::from(10u64), 77).is_some());
+    assert_eq!(math::checked_pow(U256::from(10u64), 78), None);
+}
+
+/// 2^256, the modulus of wrapping arithmetic
+fn modulus() -> BigUint {
+    BigUint::from(1u8) << 256
+}
+
+#[test]
+fn test_overflowing_arithmetic_matches_checked() {
+    for (a, b) in operand_pairs() {
+        let (sum, overflow) = math::overflowing_add(a, b);
+        assert_eq!(overflow, a.checked_add(b).is_none(), "{} + {}", a, b);
+        assert_eq!(a.checked_add(b).unwrap_or(sum), sum, "{} + {}", a, b);
+        assert_eq!(to_big(sum), (to_big(a) + to_big(b)) % modulus(), "{} + {}", a, b);
+        assert_eq!(math::wrapping_add(a, b), sum);
+
+        let (difference, underflow) = math::overflowing_sub(a, b);
+        assert_eq!(underflow, a.checked_sub(b).is_none(), "{} - {}", a, b);
+        assert_eq!(a.checked_sub(b).unwrap_or(difference), difference, "{} - {}", a, b);
+        let expected = (to_big(a) + modulus() - to_big(b)) % modulus();
+        assert_eq!(to_big(difference), expected, "{} - {}", a, b);
+        assert_eq!(math::wrapping_sub(a, b), difference);
+
+        let (product, overflow) = math::overflowing_mul(a, b);
+        assert_eq!(overflow, math::checked_mul(a, b).is_none(), "{} * {}", a, b);
+        assert_eq!(math::checked_mul(a, b).unwrap_or(product), product, "{} * {}", a, b);
+        assert_eq!(to_big(product), to_big(a) * to_big(b) % modulus(), "{} * {}", a, b);
+        assert_eq!(math::wrapping_mul(a, b), product);
+    }
+}
+
+#[test]
+fn test_saturating_arithmetic_matches_checked() {
+    for (a, b) in operand_pairs() {
+        assert_eq!(a.saturating_add(b), a.checked_add(b).unwrap_or(max()), "{} + {}", a, b);
+        assert_eq!(a.saturating_sub(b), a.checked_sub(b).unwrap_or(U256::ZERO), "{} - {}", a, b);
+        assert_eq!(math::saturating_mul(a, b), math::checked_mul(a, b).unwrap_or(max()), "{} * {}", a, b);
+    }
+}
+
+#[test]
+fn test_wrapping_edges() {
+    assert_eq!(math::overflowing_add(max(), U256::from(1u64)), (U256::ZERO, true));
+    assert_eq!(math::overflowing_sub(U256::ZERO, U256::from(1u64)), (max(), true));
+    assert_eq!(math::overflowing_mul(pow2(128), pow2(128)), (U256::ZERO, true));
+    assert_eq!(math::wrapping_mul(max(), max()), U256::from(1u64));
+    assert_eq!(math::overflowing_add(max(), U256::ZERO), (max(), false));
+}
+
+#[test]
+fn test_u128_conversions() {
+    let mut rng = Rng(0x5851_f42d_4c95_7f2d);
+    let mut values = vec![0u128, 1, u64::MAX as u128, 1 << 64, u128::MAX - 1, u128::MAX];
+    for _ in 0..RANDOM_ROUNDS {
+        values.push((rng.next_u64() as u128) | ((rng.next_u64() as u128) << 64));
+    }
+
+    for value in values {
+        let converted = math::from_u128(value);
+        assert_eq!(to_big(converted), BigUint::from(value), "{}", value);
+        assert_eq!(math::to_u128(converted), Some(value), "{}", value);
+        let expected = u64::try_from(value).ok();
+        assert_eq!(math::to_u64(converted), expected, "{}", value);
+    }
+}
+
+#[test]
+fn test_narrowing_conversions_reject_large_values() {
+    for value in edge_values() {
+        let big = to_big(value);
+        assert_eq!(math::to_u64(value), u64::try_from(&big).ok(), "{}", value);
+        assert_eq!(math::to_u128(value), u128::try_from(&big).ok(), "{}", value);
+    }
+    assert_eq!(math::to_u64(pow2(64)), None);
+    assert_eq!(math::to_u128(pow2(128)), None);
+    assert_eq!(math::to_u128(max()), None);
+}
+
+// ============================================================================
+// Encodings
+// ============================================================================
+
+fn random_values() -> Vec<U256> {
+    let mut values = edge_values();
+    let mut rng = Rng(0xbf58_476d_1ce4_e5b9);
+    for limbs in 1..=4 {
+        for _ in 0..RANDOM_ROUNDS {
+            values.push(rng.value(limbs));
+        }
+    }
+    values
+}
+
+#[test]
+fn test_big_endian_round_trip() {
+    for value in random_values() {
+        let be = encoding::to_be_bytes(value);
+        assert_eq!(encoding::from_be_bytes(be), value, "{}", value);
+        assert_eq!(BigUint::from_bytes_be(&be), to_big(value), "{}", value);
+
+        let mut le = be;
+        le.reverse();
+        assert_eq!(le, value.to_le_bytes(), "{}", value);
+    }
+    assert_eq!(encoding::to_be_bytes(U256::from(1u64))[31], 1);
+    assert_eq!(encoding::to_be_bytes(pow2(255))[0], 0x80);
+}
+
+#[test]
+fn test_hex_round_trip() {
+    for value in random_values() {
+        let hex = encoding::to_hex(value);
+        assert_eq!(hex, format!("0x{}", hex::encode(encoding::to_be_bytes(value))), "{}", value);
+        assert_eq!(hex.len(), 66);
+        assert_eq!(encoding::from_hex(&hex), Some(value), "{}", value);
+
+        // Minimal, unprefixed and uppercase forms parse to the same value
+        let minimal = to_big(value).to_str_radix(16);
+        assert_eq!(encoding::from_hex(&minimal), Some(value), "{}", minimal);
+        assert_eq!(encoding::from_hex(&format!("0X{}", minimal.to_uppercase())), Some(value), "{}", minimal);
+
+        let bytes = encoding::to_be_bytes(value);
+        assert_eq!(encoding::from_hex(&hex::encode(bytes)), Some(encoding::from_be_bytes(bytes)));
+    }
+}
+
+#[test]
+fn test_hex_edges() {
+    assert_eq!(encoding::to_hex(U256::ZERO), format!("0x{}", "0".repeat(64)));
+    assert_eq!(encoding::to_hex(max()), format!("0x{}", "f".repeat(64)));
+    assert_eq!(encoding::from_hex("0x0"), Some(U256::ZERO));
+    assert_eq!(encoding::from_hex("ff"), Some(U256::from(255u64)));
+    assert_eq!(encoding::from_hex(&"f".repeat(64)), Some(max()));
+
+    // Rejected inputs
+    assert_eq!(encoding::from_hex(""), None);
+    assert_eq!(encoding::from_hex("0x"), None);
+    assert_eq!(encoding::from_hex(&"1".repeat(65)), None);
+    assert_eq!(encoding::from_hex("0xg1"), None);
+    assert_eq!(encoding::from_hex("0x 1"), None);
+    assert_eq!(encoding::from_hex("+1"), None);
+}
+
+// ============================================================================
+// I256
+// ============================================================================
+
+fn to_big_signed(value: I256) -> BigInt {
+    let magnitude = BigInt::from_biguint(Sign::Plus, to_big(value.unsigned_abs()));
+    if value.is_negative() { -magnitude } else { magnitude }
+}
+
+/// Convert back to I256, or None if the value is out of range.
+fn from_big_signed(value: &BigInt) -> Option<I256> {
+    let min = to_big_signed(I256::MIN);
+    let max = to_big_signed(I256::MAX);
+    if *value < min || *value > max {
+        return None;
+    }
+    let magnitude = from_big(value.magnitude())?;
+    let positive = I256::from_bits(magnitude);
+    if value.sign() == Sign::Minus {
+        // -2^255 has no positive counterpart
+        if *value == min {
+            return Some(I256::MIN);
+        }
+        positive.checked_neg()
+    } else {
+        Some(positive)
+    }
+}
+
+/// Signed edge values: around zero, the limb boundaries and both extremes
+fn signed_edge_values() -> Vec<I256> {
+    let mut values = vec![I256::ZERO, I256::MIN, I256::MAX];
+    for value in [1i64, 2, 3, 10, i64::MAX, i64::MIN] {
+        values.push(I256::from(value));
+        values.push(I256::from(value).checked_neg().unwrap_or(I256::MIN));
+    }
+    for bit in [64, 127, 128, 191, 192, 254] {
+        let power = I256::try_from(pow2(bit)).unwrap();
+        values.push(power);
+        values.push(power.checked_neg().unwrap());
+        values.push(power.checked_sub(I256::from(1i64)).unwrap());
+    }
+    values.push(I256::MIN.checked_add(I256::from(1i64)).unwrap());
+    values.push(I256::MAX.checked_sub(I256::from(1i64)).unwrap());
+    values
+}
+
+fn signed_operand_pairs() -> Vec<(I256, I256)> {
+    let edges = signed_edge_values();
+    let mut pairs = Vec::new();
+    for a in &edges {
+        for b in &edges {
+            pairs.push((*a, *b));
+        }
+    }
+
+    let mut rng = Rng(0x1405_7b7e_f767_814f);
+    for a_limbs in 1..=4 {
+        for b_limbs in 1..=4 {
+            for _ in 0..RANDOM_ROUNDS {
+                pairs.push((I256::from_bits(rng.value(a_limbs)), I256::from_bits(rng.value(b_limbs))));
+            }
+        }
+    }
+    pairs
+}
+
+#[test]
+fn test_i256_arithmetic_matches_reference() {
+    for (a, b) in signed_operand_pairs() {
+        let (big_a, big_b) = (to_big_signed(a), to_big_signed(b));
+        assert_eq!(a.checked_add(b), from_big_signed(&(&big_a + &big_b)), "{} + {}", a, b);
+        assert_eq!(a.checked_sub(b), from_big_signed(&(&big_a - &big_b)), "{} - {}", a, b);
+        assert_eq!(a.checked_mul(b), from_big_signed(&(&big_a * &big_b)), "{} * {}", a, b);
+    }
+}
+
+#[test]
+fn test_i256_negation_and_sign() {
+    for value in signed_edge_values() {
+        let big = to_big_signed(value);
+        assert_eq!(value.checked_neg(), from_big_signed(&-&big), "-{}", value);
+        assert_eq!(value.is_negative(), big.sign() == Sign::Minus, "{}", value);
+        assert_eq!(to_big(value.unsigned_abs()), *big.magnitude(), "{}", value);
+        let expected_signum = match big.sign() {
+            Sign::Minus => -1,
+            Sign::NoSign => 0,
+            Sign::Plus => 1,
+        };
+        assert_eq!(value.signum(), expected_signum, "{}", value);
+    }
+    assert_eq!(I256::MIN.checked_neg(), None);
+    assert_eq!(I256::MIN.unsigned_abs(), pow2(255));
+}
+
+#[test]
+fn test_i256_ordering_matches_reference() {
+    let mut values = signed_edge_values();
+    let mut rng = Rng(0xd1b5_4a32_d192_ed03);
+    for limbs in 1..=4 {
+        for _ in 0..RANDOM_ROUNDS {
+            values.push(I256::from_bits(rng.value(limbs)));
+        }
+    }
+
+    let mut expected: Vec<BigInt> = values.iter().map(|value| to_big_signed(*value)).collect();
+    expected.sort();
+    values.sort();
+    let sorted: Vec<BigInt> = values.iter().map(|value| to_big_signed(*value)).collect();
+    assert_eq!(sorted, expected);
+
+    assert!(I256::MIN < I256::from(-1i64));
+    assert!(I256::from(-1i64) < I256::ZERO);
+    assert!(I256::ZERO < I256::MAX);
+}
+
+#[test]
+fn test_i256_u256_conversions() {
+    for value in edge_values() {
+        let expected = from_big_signed(&BigInt::from_biguint(Sign::Plus, to_big(value)));
+        assert_eq!(I256::try_from(value).ok(), expected, "{}", value);
+    }
+    for value in signed_edge_values() {
+        let expected = if value.is_negative() { None } else { Some(value.unsigned_abs()) };
+        assert_eq!(U256::try_from(value).ok(), expected, "{}", value);
+    }
+
+    // Applying deltas to a balance
+    let balance = U256::from(100u64);
+    assert_eq!(I256::from(-30i64).checked_add_to(balance), Some(U256::from(70u64)));
+    assert_eq!(I256::from(30i64).checked_add_to(balance), Some(U256::from(130u64)));
+    assert_eq!(I256::from(-101i64).checked_add_to(balance), None);
+    assert_eq!(I256::MAX.checked_add_to(max()), None);
+}
+
+#[test]
+fn test_i256_serialization() {
+    assert_eq!(I256::from(-1i64).to_le_bytes(), [0xff; 32]);
+    assert_eq!(I256::MIN.to_le_bytes()[31], 0x80);
+    for value in [i128::MIN, -1, 0, 1, i128::MAX] {
+        assert_eq!(to_big_signed(I256::from_i128(value)), BigInt::from(value), "{}", value);
+    }
+
+    for value in signed_edge_values() {
+        assert_eq!(I256::from_le_bytes(value.to_le_bytes()), value, "{}", value);
+        assert_eq!(I256::from_bits(value.to_bits()), value, "{}", value);
+
+        let mut args = Args::new();
+        args.add_u256(value.to_bits());
+        let mut read = Args::from_bytes(args.into_bytes());
+        assert_eq!(I256::from_bits(read.next_u256().unwrap()), value, "{}", value);
+    }
+}
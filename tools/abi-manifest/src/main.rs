@@ -0,0 +1,73 @@
+//! ABI manifests for the contracts' `#[massa_export]` entrypoints.
+//!
+//! `massa_export` lives in the SDK and only generates the wasm export, so
+//! this companion step reads each contract's `src/lib.rs` instead. For every
+//! exported function it records:
+//!
+//! - the arguments, in the order the body reads them from its `Args`
+//!   (`next_<type>()` calls, following helpers that take `&mut Args`), named
+//!   by their `expect_arg("name")` or, for optional ones, by their binding;
+//! - the return encoding, from the function's tail expression.
+//!
+//! The manifest is written as `<artifact>.abi.json` next to the wasm.
+
+mod scan;
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use serde_json::json;
+
+#[derive(Parser)]
+#[command(about = "Write ABI manifests of the contracts' exported entrypoints")]
+struct Cli {
+    /// Contract crates to describe; every crate under contracts/ by default
+    contracts: Vec<PathBuf>,
+    /// Directory the manifests are written to, next to the wasm artifacts
+    #[arg(long, default_value = "target/wasm32v1-none/release")]
+    out_dir: PathBuf,
+}
+
+/// The `name` of the crate's package.
+fn package_name(contract: &Path) -> Result<String> {
+    let manifest_path = contract.join("Cargo.toml");
+    let manifest =
+        std::fs::read_to_string(&manifest_path).with_context(|| format!("reading {}", manifest_path.display()))?;
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("name = \"")?.strip_suffix('"'))
+        .map(String::from)
+        .with_context(|| format!("no package name in {}", manifest_path.display()))
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let mut contracts = cli.contracts;
+    if contracts.is_empty() {
+        for entry in std::fs::read_dir("contracts").context("reading contracts/")? {
+            contracts.push(entry?.path());
+        }
+        contracts.sort();
+    }
+
+    std::fs::create_dir_all(&cli.out_dir)?;
+    for contract in contracts {
+        let package = package_name(&contract)?;
+        let source_path = contract.join("src/lib.rs");
+        let source =
+            std::fs::read_to_string(&source_path).with_context(|| format!("reading {}", source_path.display()))?;
+        let entrypoints = scan::entrypoints(&source).with_context(|| format!("parsing {}", source_path.display()))?;
+
+        let manifest = json!({
+            "contract": package,
+            "entrypoints": entrypoints,
+        });
+        let out = cli.out_dir.join(format!("{}.abi.json", package.replace('-', "_")));
+        std::fs::write(&out, serde_json::to_string_pretty(&manifest)? + "\n")
+            .with_context(|| format!("writing {}", out.display()))?;
+        eprintln!("{}: {} entrypoints → {}", package, entrypoints.len(), out.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,449 @@
+//! Extraction of entrypoint signatures from contract source.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::Serialize;
+use syn::visit::{self, Visit};
+use syn::{Attribute, Block, Expr, ExprCall, ExprMethodCall, FnArg, Item, ItemFn, Lit, Local, Pat, ReturnType, Stmt, Type};
+
+/// How deep helper functions are followed.
+const MAX_DEPTH: usize = 4;
+
+#[derive(Serialize)]
+pub struct Entrypoint {
+    pub name: String,
+    /// First paragraph of the doc comment.
+    pub description: String,
+    pub args: Vec<Arg>,
+    pub returns: Returns,
+}
+
+#[derive(Clone, Serialize)]
+pub struct Arg {
+    pub name: String,
+    /// `Args` type, as in `next_<type>`: `string`, `u8`, `u32`, `u64`, `u256`, `bool` or `bytes`.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// Read with a fallback instead of `expect_arg`, so it may be left out.
+    pub optional: bool,
+    /// Read in a loop, e.g. once per batch item.
+    pub repeated: bool,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "encoding", rename_all = "lowercase")]
+pub enum Returns {
+    /// Empty result.
+    None,
+    /// Raw bytes, e.g. a stored string; see the description.
+    Bytes,
+    /// Raw little-endian integers.
+    U32,
+    U64,
+    U256,
+    /// `Args` with these field types; `[]` marks fields written in a loop.
+    Args { fields: Vec<String> },
+}
+
+/// Every `#[massa_export]` function of `source`, in source order.
+pub fn entrypoints(source: &str) -> Result<Vec<Entrypoint>> {
+    let file = syn::parse_file(source)?;
+    let functions: HashMap<String, &ItemFn> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Fn(function) => Some((function.sig.ident.to_string(), function)),
+            _ => None,
+        })
+        .collect();
+
+    let exported = file.items.iter().filter_map(|item| match item {
+        Item::Fn(function) if function.attrs.iter().any(|attr| attr.path().is_ident("massa_export")) => Some(function),
+        _ => None,
+    });
+    Ok(exported
+        .map(|function| Entrypoint {
+            name: function.sig.ident.to_string(),
+            description: description(&function.attrs),
+            args: read_args(function, &functions),
+            returns: returns(function, &functions, 0),
+        })
+        .collect())
+}
+
+fn description(attrs: &[Attribute]) -> String {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        let syn::Meta::NameValue(doc) = &attr.meta else {
+            continue;
+        };
+        if !doc.path.is_ident("doc") {
+            continue;
+        }
+        let Expr::Lit(syn::ExprLit { lit: Lit::Str(text), .. }) = &doc.value else {
+            continue;
+        };
+        let line = text.value().trim().to_string();
+        if line.is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+    lines.join(" ")
+}
+
+/// The single identifier of a path expression such as `args`.
+fn ident(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(path) => path.path.get_ident().map(|ident| ident.to_string()),
+        _ => None,
+    }
+}
+
+/// The variable bound by `pat`, looking inside `Ok(x)` and `Some(x)`.
+fn pattern_name(pat: &Pat) -> Option<String> {
+    match pat {
+        Pat::Ident(pat) => Some(pat.ident.to_string()),
+        Pat::Type(pat) => pattern_name(&pat.pat),
+        Pat::TupleStruct(pat) if pat.elems.len() == 1 => pattern_name(pat.elems.first()?),
+        _ => None,
+    }
+}
+
+/// The last segment of a type such as `U256` or `Vec<u8>`.
+fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|segment| segment.ident.to_string()),
+        Type::Reference(reference) => type_name(&reference.elem),
+        _ => None,
+    }
+}
+
+fn return_type(function: &ItemFn) -> Option<String> {
+    match &function.sig.output {
+        ReturnType::Type(_, ty) => type_name(ty),
+        ReturnType::Default => None,
+    }
+}
+
+// ============================================================================
+// Arguments
+// ============================================================================
+
+/// Collects the `next_<type>` reads on one `Args` variable.
+struct ArgReader<'a> {
+    functions: &'a HashMap<String, &'a ItemFn>,
+    receiver: String,
+    binding: Option<String>,
+    loop_depth: usize,
+    depth: usize,
+    args: Vec<Arg>,
+}
+
+impl ArgReader<'_> {
+    /// The type read by `call` if it is `<receiver>.next_<type>()`.
+    fn read_type(&self, call: &ExprMethodCall) -> Option<String> {
+        let ty = call.method.to_string().strip_prefix("next_")?.to_string();
+        (ident(&call.receiver)? == self.receiver).then_some(ty)
+    }
+
+    fn push(&mut self, name: Option<String>, ty: String, optional: bool) {
+        let name = name.unwrap_or_else(|| format!("arg{}", self.args.len()));
+        self.args.push(Arg {
+            name,
+            ty,
+            optional,
+            repeated: self.loop_depth > 0,
+        });
+    }
+
+    fn in_loop(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.loop_depth += 1;
+        visit(self);
+        self.loop_depth -= 1;
+    }
+}
+
+impl<'ast> Visit<'ast> for ArgReader<'_> {
+    fn visit_local(&mut self, local: &'ast Local) {
+        let previous = std::mem::replace(&mut self.binding, pattern_name(&local.pat));
+        visit::visit_local(self, local);
+        self.binding = previous;
+    }
+
+    fn visit_expr_let(&mut self, expr: &'ast syn::ExprLet) {
+        let previous = std::mem::replace(&mut self.binding, pattern_name(&expr.pat));
+        visit::visit_expr_let(self, expr);
+        self.binding = previous;
+    }
+
+    /// Name reads in struct literals after their field, e.g. `cap: args.next_u256().ok()`.
+    fn visit_field_value(&mut self, field: &'ast syn::FieldValue) {
+        let name = match &field.member {
+            syn::Member::Named(name) => Some(name.to_string()),
+            syn::Member::Unnamed(_) => None,
+        };
+        let previous = std::mem::replace(&mut self.binding, name);
+        visit::visit_field_value(self, field);
+        self.binding = previous;
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        if call.method == "expect_arg"
+            && let Expr::MethodCall(read) = &*call.receiver
+            && let Some(ty) = self.read_type(read)
+        {
+            let name = match call.args.first() {
+                Some(Expr::Lit(syn::ExprLit { lit: Lit::Str(name), .. })) => Some(name.value()),
+                _ => self.binding.clone(),
+            };
+            self.push(name, ty, false);
+            return;
+        }
+        if let Some(ty) = self.read_type(call) {
+            self.push(self.binding.clone(), ty, true);
+            return;
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    /// Follow helpers called with `&mut <receiver>`, such as `read_optional_config(&mut args, ...)`.
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        let helper = ident(&call.func).and_then(|name| self.functions.get(&name).copied());
+        if let Some(helper) = helper.filter(|_| self.depth < MAX_DEPTH) {
+            for (index, arg) in call.args.iter().enumerate() {
+                let Expr::Reference(reference) = arg else {
+                    continue;
+                };
+                if reference.mutability.is_none() || ident(&reference.expr).as_ref() != Some(&self.receiver) {
+                    continue;
+                }
+                let Some(FnArg::Typed(param)) = helper.sig.inputs.iter().nth(index) else {
+                    continue;
+                };
+                let Some(receiver) = pattern_name(&param.pat) else {
+                    continue;
+                };
+                let mut nested = ArgReader {
+                    functions: self.functions,
+                    receiver,
+                    binding: None,
+                    loop_depth: self.loop_depth,
+                    depth: self.depth + 1,
+                    args: Vec::new(),
+                };
+                nested.visit_block(&helper.block);
+                self.args.extend(nested.args);
+            }
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast syn::ExprForLoop) {
+        self.in_loop(|reader| visit::visit_expr_for_loop(reader, expr));
+    }
+
+    fn visit_expr_while(&mut self, expr: &'ast syn::ExprWhile) {
+        self.in_loop(|reader| visit::visit_expr_while(reader, expr));
+    }
+
+    fn visit_expr_loop(&mut self, expr: &'ast syn::ExprLoop) {
+        self.in_loop(|reader| visit::visit_expr_loop(reader, expr));
+    }
+}
+
+/// The variable holding `Args::from_bytes(...)` in `block`.
+fn args_variable(block: &Block) -> Option<String> {
+    block.stmts.iter().find_map(|stmt| {
+        let Stmt::Local(local) = stmt else {
+            return None;
+        };
+        let Expr::Call(call) = &*local.init.as_ref()?.expr else {
+            return None;
+        };
+        let Expr::Path(func) = &*call.func else {
+            return None;
+        };
+        let segments: Vec<String> = func.path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+        segments.ends_with(&[String::from("Args"), String::from("from_bytes")]).then(|| pattern_name(&local.pat))?
+    })
+}
+
+fn read_args(function: &ItemFn, functions: &HashMap<String, &ItemFn>) -> Vec<Arg> {
+    let Some(receiver) = args_variable(&function.block) else {
+        return Vec::new();
+    };
+    let mut reader = ArgReader {
+        functions,
+        receiver,
+        binding: None,
+        loop_depth: 0,
+        depth: 0,
+        args: Vec::new(),
+    };
+    reader.visit_block(&function.block);
+    reader.args
+}
+
+// ============================================================================
+// Return values
+// ============================================================================
+
+/// Collects the `add_<type>` writes on one `Args` variable.
+struct ArgsWriter {
+    receiver: String,
+    loop_depth: usize,
+    fields: Vec<String>,
+}
+
+/// The variable at the root of a method chain such as `ret.add_bool(true).add_u256(x)`.
+fn chain_root(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::MethodCall(call) => chain_root(&call.receiver),
+        _ => ident(expr),
+    }
+}
+
+impl<'ast> Visit<'ast> for ArgsWriter {
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        // Visit the receiver first so chained writes are recorded in order
+        visit::visit_expr_method_call(self, call);
+        if let Some(ty) = call.method.to_string().strip_prefix("add_")
+            && chain_root(&call.receiver).as_ref() == Some(&self.receiver)
+        {
+            let suffix = if self.loop_depth > 0 { "[]" } else { "" };
+            self.fields.push(format!("{}{}", ty, suffix));
+        }
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast syn::ExprForLoop) {
+        self.loop_depth += 1;
+        visit::visit_expr_for_loop(self, expr);
+        self.loop_depth -= 1;
+    }
+}
+
+/// The initializer of the last `let <name>` in `block`, searching nested blocks.
+fn binding<'a>(block: &'a Block, name: &str) -> Option<&'a Local> {
+    struct Finder<'a, 'n> {
+        name: &'n str,
+        found: Option<&'a Local>,
+    }
+    impl<'a> Visit<'a> for Finder<'a, '_> {
+        fn visit_local(&mut self, local: &'a Local) {
+            if pattern_name(&local.pat).as_deref() == Some(self.name) {
+                self.found = Some(local);
+            }
+            visit::visit_local(self, local);
+        }
+    }
+    let mut finder = Finder { name, found: None };
+    finder.visit_block(block);
+    finder.found
+}
+
+/// The integer type of `expr` when it can be told from the source.
+fn integer_type(expr: &Expr, block: &Block, functions: &HashMap<String, &ItemFn>, depth: usize) -> Option<String> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    match expr {
+        Expr::Call(call) => functions.get(&ident(&call.func)?).and_then(|function| return_type(function)),
+        Expr::MethodCall(call) => match call.method.to_string().strip_prefix("next_") {
+            Some(ty) => Some(ty.to_string()),
+            None => integer_type(&call.receiver, block, functions, depth + 1),
+        },
+        Expr::Path(_) => {
+            let local = binding(block, &ident(expr)?)?;
+            if let Pat::Type(typed) = &local.pat {
+                return type_name(&typed.ty);
+            }
+            integer_type(&local.init.as_ref()?.expr, block, functions, depth + 1)
+        }
+        Expr::Paren(paren) => integer_type(&paren.expr, block, functions, depth + 1),
+        _ => None,
+    }
+}
+
+fn block_returns(block: &Block, functions: &HashMap<String, &ItemFn>, depth: usize) -> Returns {
+    match block.stmts.last() {
+        Some(Stmt::Expr(expr, None)) => expr_returns(expr, block, functions, depth),
+        _ => Returns::Bytes,
+    }
+}
+
+/// The common encoding of every branch, or `Bytes` if they differ.
+fn unify(branches: impl IntoIterator<Item = Returns>) -> Returns {
+    let mut branches = branches.into_iter();
+    let first = branches.next().unwrap_or(Returns::Bytes);
+    if branches.all(|branch| branch == first) { first } else { Returns::Bytes }
+}
+
+fn expr_returns(expr: &Expr, block: &Block, functions: &HashMap<String, &ItemFn>, depth: usize) -> Returns {
+    if depth > MAX_DEPTH {
+        return Returns::Bytes;
+    }
+    match expr {
+        Expr::Call(call) => {
+            let Expr::Path(func) = &*call.func else {
+                return Returns::Bytes;
+            };
+            let segments: Vec<String> = func.path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+            if segments.ends_with(&[String::from("Vec"), String::from("new")]) {
+                return Returns::None;
+            }
+            match ident(&call.func).and_then(|name| functions.get(&name)) {
+                Some(helper) if return_type(helper).as_deref() == Some("Vec") => returns(helper, functions, depth + 1),
+                _ => Returns::Bytes,
+            }
+        }
+        Expr::MethodCall(call) if call.method == "into_bytes" => {
+            let Some(receiver) = ident(&call.receiver) else {
+                return Returns::Bytes;
+            };
+            let mut writer = ArgsWriter {
+                receiver,
+                loop_depth: 0,
+                fields: Vec::new(),
+            };
+            writer.visit_block(block);
+            if writer.fields.is_empty() {
+                Returns::Bytes
+            } else {
+                Returns::Args { fields: writer.fields }
+            }
+        }
+        Expr::MethodCall(call) if call.method == "to_vec" => {
+            let Expr::MethodCall(bytes) = &*call.receiver else {
+                return Returns::Bytes;
+            };
+            if bytes.method != "to_le_bytes" {
+                return Returns::Bytes;
+            }
+            match integer_type(&bytes.receiver, block, functions, depth).as_deref() {
+                Some("u32") => Returns::U32,
+                Some("u64") => Returns::U64,
+                Some("U256") | Some("u256") => Returns::U256,
+                _ => Returns::Bytes,
+            }
+        }
+        Expr::If(expr_if) => {
+            let then_branch = block_returns(&expr_if.then_branch, functions, depth + 1);
+            let else_branch = match &expr_if.else_branch {
+                Some((_, else_expr)) => expr_returns(else_expr, block, functions, depth + 1),
+                None => Returns::None,
+            };
+            unify([then_branch, else_branch])
+        }
+        Expr::Match(expr_match) => {
+            unify(expr_match.arms.iter().map(|arm| expr_returns(&arm.body, block, functions, depth + 1)))
+        }
+        Expr::Block(inner) => block_returns(&inner.block, functions, depth + 1),
+        _ => Returns::Bytes,
+    }
+}
+
+fn returns(function: &ItemFn, functions: &HashMap<String, &ItemFn>, depth: usize) -> Returns {
+    block_returns(&function.block, functions, depth)
+}
@@ -0,0 +1,361 @@
+//! Rust source generation for both binding flavors.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::manifest::{Entrypoint, Manifest, Returns};
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self",
+    "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+];
+
+/// Longest generated signature kept on one line.
+const MAX_WIDTH: usize = 120;
+
+/// Width doc comments are wrapped at.
+const DOC_WIDTH: usize = 100;
+
+/// `erc20-token` → `Erc20Token`.
+pub fn pascal_case(name: &str) -> String {
+    name.split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| word[..1].to_uppercase() + &word[1..])
+        .collect()
+}
+
+/// `balanceOf` → `balance_of`, `tokenURI` → `token_uri`.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase() || previous.is_ascii_digit() || (previous.is_uppercase() && next_is_lower) {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+/// A snake_case identifier that is not a keyword nor one of `taken`.
+fn identifier(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut ident = snake_case(name);
+    if KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    let base = ident.clone();
+    let mut n = 2;
+    while !taken.insert(ident.clone()) {
+        ident = format!("{}_{}", base, n);
+        n += 1;
+    }
+    ident
+}
+
+/// Parameter type of an `Args` type.
+fn param_type(ty: &str) -> &'static str {
+    match ty {
+        "string" => "&str",
+        "bytes" => "&[u8]",
+        "bool" => "bool",
+        "u8" => "u8",
+        "u32" => "u32",
+        "u64" => "u64",
+        _ => "U256",
+    }
+}
+
+/// Owned type of a returned `Args` field.
+fn field_type(ty: &str) -> &'static str {
+    match ty {
+        "string" => "String",
+        "bytes" => "Vec<u8>",
+        other => param_type(other),
+    }
+}
+
+/// How the entrypoint's arguments map to method parameters.
+enum Param {
+    Plain { ident: String, ty: String },
+    /// Trailing argument with a default; `None` leaves it out.
+    Optional { ident: String, ty: String },
+    /// `u32` length written before a batch, filled in from the batch.
+    Count { batch: String },
+    /// Arguments read in a loop, passed as a slice of items.
+    Batch { ident: String, items: Vec<(String, String)> },
+}
+
+fn params(entrypoint: &Entrypoint) -> Vec<Param> {
+    let mut taken = HashSet::from([String::from("args")]);
+    let mut params: Vec<Param> = Vec::new();
+    let mut args = entrypoint.args.iter().peekable();
+    while let Some(arg) = args.next() {
+        if !arg.repeated {
+            let ident = identifier(&arg.name, &mut taken);
+            let ty = arg.ty.clone();
+            params.push(if arg.optional { Param::Optional { ident, ty } } else { Param::Plain { ident, ty } });
+            continue;
+        }
+        let mut group = vec![arg];
+        while let Some(next) = args.next_if(|next| next.repeated) {
+            group.push(next);
+        }
+        let ident = if let [single] = group.as_slice() {
+            identifier(&format!("{}s", single.name), &mut taken)
+        } else {
+            identifier("items", &mut taken)
+        };
+        let items = group.iter().map(|arg| (identifier(&arg.name, &mut taken), arg.ty.clone())).collect();
+        if matches!(params.last(), Some(Param::Plain { ty, .. }) if ty == "u32") {
+            params.pop();
+            params.push(Param::Count { batch: ident.clone() });
+        }
+        params.push(Param::Batch { ident, items });
+    }
+    params
+}
+
+fn signature(params: &[Param]) -> Vec<String> {
+    params
+        .iter()
+        .filter_map(|param| match param {
+            Param::Plain { ident, ty } => Some(format!("{}: {}", ident, param_type(ty))),
+            Param::Optional { ident, ty } => Some(format!("{}: Option<{}>", ident, param_type(ty))),
+            Param::Count { .. } => None,
+            Param::Batch { ident, items } => {
+                let item = match items.as_slice() {
+                    [(_, ty)] => String::from(param_type(ty)),
+                    items => {
+                        let types: Vec<&str> = items.iter().map(|(_, ty)| param_type(ty)).collect();
+                        format!("({})", types.join(", "))
+                    }
+                };
+                Some(format!("{}: &[{}]", ident, item))
+            }
+        })
+        .collect()
+}
+
+/// Statements building `args`, chaining consecutive `add_*` calls.
+fn encode(params: &[Param], out: &mut String) {
+    if params.is_empty() {
+        return;
+    }
+    out.push_str("        let mut args = Args::new();\n");
+    let mut chain: Vec<String> = Vec::new();
+    let flush = |chain: &mut Vec<String>, out: &mut String| {
+        if !chain.is_empty() {
+            let line = format!("        args{};", chain.concat());
+            if line.len() <= MAX_WIDTH {
+                let _ = writeln!(out, "{}", line);
+            } else {
+                let _ = writeln!(out, "        args{};", chain.join("\n            "));
+            }
+            chain.clear();
+        }
+    };
+    for param in params {
+        match param {
+            Param::Plain { ident, ty } => chain.push(format!(".add_{}({})", ty, ident)),
+            Param::Count { batch } => chain.push(format!(".add_u32({}.len() as u32)", batch)),
+            Param::Optional { ident, ty } => {
+                flush(&mut chain, out);
+                let _ = writeln!(out, "        if let Some({0}) = {0} {{", ident);
+                let _ = writeln!(out, "            args.add_{}({});\n        }}", ty, ident);
+            }
+            Param::Batch { ident, items } => {
+                flush(&mut chain, out);
+                let pattern = match items.as_slice() {
+                    [(item, _)] => format!("&{}", item),
+                    items => {
+                        let names: Vec<&str> = items.iter().map(|(item, _)| item.as_str()).collect();
+                        format!("&({})", names.join(", "))
+                    }
+                };
+                let adds: String = items.iter().map(|(item, ty)| format!(".add_{}({})", ty, item)).collect();
+                let _ = writeln!(out, "        for {} in {} {{\n            args{};\n        }}", pattern, ident, adds);
+            }
+        }
+    }
+    flush(&mut chain, out);
+}
+
+fn doc(entrypoint: &Entrypoint, out: &mut String) {
+    if entrypoint.description.is_empty() {
+        let _ = writeln!(out, "    /// `{}`", entrypoint.name);
+    }
+    // Descriptions are single paragraphs; wrap them like hand-written docs
+    let mut line = String::from("    ///");
+    for word in entrypoint.description.split_whitespace() {
+        if line.len() + 1 + word.len() > DOC_WIDTH && line.len() > "    ///".len() {
+            let _ = writeln!(out, "{}", line);
+            line = String::from("    ///");
+        }
+        line.push(' ');
+        line.push_str(word);
+    }
+    if line.len() > "    ///".len() {
+        let _ = writeln!(out, "{}", line);
+    }
+}
+
+fn method_header(ident: &str, signature: &[String], returns: &str, out: &mut String) {
+    let arrow = if returns.is_empty() { String::new() } else { format!(" -> {}", returns) };
+    let params: String = signature.iter().map(|param| format!(", {}", param)).collect();
+    let line = format!("    pub fn {}(&self{}){} {{", ident, params, arrow);
+    if line.len() <= MAX_WIDTH {
+        let _ = writeln!(out, "{}", line);
+        return;
+    }
+    let _ = writeln!(out, "    pub fn {}(\n        &self,", ident);
+    for param in signature {
+        let _ = writeln!(out, "        {},", param);
+    }
+    let _ = writeln!(out, "    ){} {{", arrow);
+}
+
+/// Field types of an `Args` return value, unless one is repeated.
+fn scalar_fields(fields: &[String]) -> Option<Vec<&str>> {
+    fields.iter().map(|field| (!field.ends_with("[]")).then_some(field.as_str())).collect()
+}
+
+/// Methods callable on the struct; constructors only run at deployment.
+fn callable(manifest: &Manifest) -> impl Iterator<Item = &Entrypoint> {
+    manifest.entrypoints.iter().filter(|entrypoint| !entrypoint.name.starts_with("constructor"))
+}
+
+/// `use` lines for the names `body` mentions.
+fn imports(body: &str, candidates: &[(&str, &str)]) -> String {
+    candidates
+        .iter()
+        .filter(|(name, _)| body.contains(name))
+        .map(|(_, line)| format!("{}\n", line))
+        .collect()
+}
+
+/// Off-chain bindings over `erc20_client::Erc20Client`.
+pub fn client(manifest: &Manifest, name: &str, source: &str) -> String {
+    let mut body = String::new();
+    let _ = writeln!(body, "/// Typed bindings for the `{}` contract.", manifest.contract);
+    let _ = writeln!(body, "pub struct {} {{\n    client: Erc20Client,\n}}\n", name);
+    let _ = writeln!(body, "impl {} {{", name);
+    let _ = writeln!(body, "    /// Bindings over `client`, which points at a deployed `{}`.", manifest.contract);
+    let _ = writeln!(body, "    pub fn new(client: Erc20Client) -> Self {{\n        {} {{ client }}\n    }}\n", name);
+    body.push_str("    pub fn client(&self) -> &Erc20Client {\n        &self.client\n    }\n");
+
+    let mut taken = HashSet::from([String::from("new"), String::from("client")]);
+    for entrypoint in callable(manifest) {
+        let params = params(entrypoint);
+        let (returns, expected, decode) = match &entrypoint.returns {
+            Returns::None => (String::from("()"), "none", String::from("bindings::unit")),
+            Returns::Bytes => (String::from("Vec<u8>"), "bytes", String::from("bindings::bytes")),
+            Returns::U32 => (String::from("u32"), "u32", String::from("bindings::u32")),
+            Returns::U64 => (String::from("u64"), "u64", String::from("bindings::u64")),
+            Returns::U256 => (String::from("U256"), "u256", String::from("bindings::u256")),
+            Returns::Args { fields } => match scalar_fields(fields) {
+                Some(fields) => {
+                    let types: Vec<&str> = fields.iter().map(|field| field_type(field)).collect();
+                    let reads: Vec<String> = fields.iter().map(|field| format!("ret.next_{}().ok()", field)).collect();
+                    let (returns, value) = match (types.as_slice(), reads.as_slice()) {
+                        ([ty], [read]) => (String::from(*ty), read.clone()),
+                        _ => {
+                            let reads: Vec<String> = reads.iter().map(|read| format!("{}?", read)).collect();
+                            let value = format!("Some(({}))", reads.join(", "));
+                            let value = if value.len() + 12 <= MAX_WIDTH {
+                                value
+                            } else {
+                                let reads = reads.join(",\n                ");
+                                format!("Some((\n                {},\n            ))", reads)
+                            };
+                            (format!("({})", types.join(", ")), value)
+                        }
+                    };
+                    let decode = format!(
+                        "|ret| {{\n{}{}\n        }}",
+                        "            let mut ret = Args::from_bytes(ret.to_vec());\n            ",
+                        value
+                    );
+                    (returns, "args", decode)
+                }
+                None => (String::from("Args"), "args", String::from("bindings::args")),
+            },
+        };
+
+        body.push('\n');
+        doc(entrypoint, &mut body);
+        let ident = identifier(&entrypoint.name, &mut taken);
+        method_header(&ident, &signature(&params), &format!("ContractCall<'_, {}>", returns), &mut body);
+        encode(&params, &mut body);
+        let args = if params.is_empty() { "Args::new()" } else { "args" };
+        let _ = writeln!(
+            body,
+            "        ContractCall::new(&self.client, \"{}\", {}, \"{}\", {})\n    }}",
+            entrypoint.name, args, expected, decode
+        );
+    }
+    body.push_str("}\n");
+
+    let call = if body.contains("bindings::") { "bindings::{self, ContractCall}" } else { "ContractCall" };
+    let root = if body.contains("U256") { "Args, Erc20Client, U256" } else { "Args, Erc20Client" };
+    format!(
+        "// Generated by abigen from {}. Do not edit.\n\nuse erc20_client::{};\nuse erc20_client::{{{}}};\n\n{}",
+        source, call, root, body
+    )
+}
+
+/// Cross-contract bindings over the shared `calls` module.
+pub fn contract(manifest: &Manifest, name: &str, source: &str) -> String {
+    let mut body = String::new();
+    let _ = writeln!(body, "/// Typed cross-contract calls to a deployed `{}`.", manifest.contract);
+    let _ = writeln!(body, "pub struct {} {{\n    address: String,\n}}\n", name);
+    let _ = writeln!(body, "impl {} {{", name);
+    let _ = writeln!(body, "    pub fn new(address: &str) -> Self {{\n        {} {{", name);
+    body.push_str("            address: String::from(address),\n        }\n    }\n\n");
+    body.push_str("    pub fn address(&self) -> &str {\n        &self.address\n    }\n");
+
+    let mut taken = HashSet::from([String::from("new"), String::from("address")]);
+    for entrypoint in callable(manifest) {
+        let params = params(entrypoint);
+        let (returns, call) = match &entrypoint.returns {
+            Returns::None => ("", "calls::call"),
+            Returns::Bytes => ("Vec<u8>", "calls::call"),
+            Returns::U32 => ("u32", "calls::call_u32"),
+            Returns::U64 => ("u64", "calls::call_u64"),
+            Returns::U256 => ("U256", "calls::call_u256"),
+            Returns::Args { .. } => ("Args", "calls::call"),
+        };
+
+        body.push('\n');
+        doc(entrypoint, &mut body);
+        let ident = identifier(&entrypoint.name, &mut taken);
+        method_header(&ident, &signature(&params), returns, &mut body);
+        encode(&params, &mut body);
+        let args = if params.is_empty() { "Args::new()" } else { "args" };
+        let call = format!("{}(&self.address, \"{}\", {})", call, entrypoint.name, args);
+        let _ = match &entrypoint.returns {
+            Returns::None => writeln!(body, "        {};\n    }}", call),
+            Returns::Args { .. } => writeln!(body, "        Args::from_bytes({})\n    }}", call),
+            _ => writeln!(body, "        {}\n    }}", call),
+        };
+    }
+    body.push_str("}\n");
+
+    let imports = imports(
+        &body,
+        &[
+            ("String", "use alloc::string::String;"),
+            ("Vec<", "use alloc::vec::Vec;"),
+            ("Args", "use massa_sc_sdk::Args;"),
+        ],
+    );
+    let root = if body.contains("U256") { "use super::{calls, U256};" } else { "use super::calls;" };
+    format!(
+        "// Generated by abigen from {}. Do not edit.\n\n{}\n\n{}\n{}\n\n{}",
+        source, "// Callers use a subset of the entrypoints\n#![allow(dead_code)]", imports, root, body
+    )
+}
@@ -0,0 +1,65 @@
+//! Typed Rust bindings from the ABI manifests written by `tools/abi-manifest`.
+//!
+//! Each entrypoint becomes a method with typed parameters that builds the
+//! `Args` itself, so callers never spell entrypoint names or encode arguments
+//! by hand. Two flavors are generated:
+//!
+//! - `client`: a struct over `erc20_client::Erc20Client` for off-chain use,
+//!   whose methods return a `ContractCall` to read or send;
+//! - `contract`: a struct over a contract address for cross-contract calls,
+//!   built on the shared `calls` module.
+
+mod codegen;
+mod manifest;
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+
+use manifest::Manifest;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Target {
+    /// Off-chain bindings over `erc20_client`
+    Client,
+    /// Cross-contract bindings over the `calls` module
+    Contract,
+}
+
+#[derive(Parser)]
+#[command(about = "Generate typed Rust bindings from a contract's ABI manifest")]
+struct Cli {
+    /// Manifest written by abi-manifest, e.g. target/wasm32v1-none/release/erc20_token.abi.json
+    manifest: PathBuf,
+    /// Where the bindings are used
+    #[arg(long, value_enum, default_value = "client")]
+    target: Target,
+    /// Name of the generated struct; the contract's package name in PascalCase by default
+    #[arg(long)]
+    name: Option<String>,
+    /// File to write, instead of stdout
+    #[arg(long, short)]
+    out: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let text =
+        std::fs::read_to_string(&cli.manifest).with_context(|| format!("reading {}", cli.manifest.display()))?;
+    let manifest: Manifest =
+        serde_json::from_str(&text).with_context(|| format!("parsing {}", cli.manifest.display()))?;
+
+    let name = cli.name.unwrap_or_else(|| codegen::pascal_case(&manifest.contract));
+    let source_name = cli.manifest.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+    let code = match cli.target {
+        Target::Client => codegen::client(&manifest, &name, &source_name),
+        Target::Contract => codegen::contract(&manifest, &name, &source_name),
+    };
+
+    match cli.out {
+        Some(out) => std::fs::write(&out, code).with_context(|| format!("writing {}", out.display()))?,
+        None => print!("{}", code),
+    }
+    Ok(())
+}
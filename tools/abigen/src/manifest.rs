@@ -0,0 +1,38 @@
+//! The manifest format written by `tools/abi-manifest`.
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Manifest {
+    /// Package name of the contract, e.g. `erc20-token`.
+    pub contract: String,
+    pub entrypoints: Vec<Entrypoint>,
+}
+
+#[derive(Deserialize)]
+pub struct Entrypoint {
+    pub name: String,
+    pub description: String,
+    pub args: Vec<Arg>,
+    pub returns: Returns,
+}
+
+#[derive(Deserialize)]
+pub struct Arg {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub optional: bool,
+    pub repeated: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "encoding", rename_all = "lowercase")]
+pub enum Returns {
+    None,
+    Bytes,
+    U32,
+    U64,
+    U256,
+    Args { fields: Vec<String> },
+}
@@ -0,0 +1,95 @@
+//! Decode `Args` bytes against a type signature, or encode values into them.
+//!
+//! Useful when a call fails on its arguments, e.g. a deployment whose
+//! constructor rejects what the deployer serialized:
+//!
+//! ```text
+//! args-decode decode --types string,string,u8,u256 0x0500000048656c6c6f...
+//! args-decode encode --types string,string,u8,u256 MassaToken MT 9 1000000000
+//! ```
+
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use clap::{Parser, Subcommand};
+use erc20_client::codec::{self, ArgType, ArgsReader, Value};
+
+#[derive(Parser)]
+#[command(about = "Decode or encode Args bytes")]
+struct Cli {
+    /// Read and write base64 instead of hex
+    #[arg(long, global = true)]
+    base64: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the fields of serialized Args, with their byte offsets
+    Decode {
+        /// Comma-separated field types: string, bool, u8, u32, u64, u256, bytes
+        #[arg(long, short)]
+        types: String,
+        /// The bytes, hex (with or without 0x) or base64
+        data: String,
+    },
+    /// Serialize values as Args; bytes values are hex
+    Encode {
+        /// Comma-separated field types: string, bool, u8, u32, u64, u256, bytes
+        #[arg(long, short)]
+        types: String,
+        values: Vec<String>,
+    },
+}
+
+fn parse_data(data: &str, base64: bool) -> Result<Vec<u8>> {
+    let data: String = data.split_whitespace().collect();
+    if base64 {
+        BASE64.decode(&data).context("invalid base64")
+    } else {
+        hex::decode(data.trim_start_matches("0x")).context("invalid hex; pass --base64 for base64 input")
+    }
+}
+
+fn decode(types: &[ArgType], data: &[u8]) -> Result<()> {
+    let mut reader = ArgsReader::new(data);
+    for (index, ty) in types.iter().enumerate() {
+        let offset = reader.offset();
+        match reader.read(*ty) {
+            Ok(value) => println!("{:>3}  @{:<5} {:<7} {}", index, offset, ty, value),
+            Err(err) => bail!("field {}: {} (undecoded: 0x{})", index, err, hex::encode(&data[offset..])),
+        }
+    }
+    let trailing = reader.remaining();
+    if !trailing.is_empty() {
+        bail!("{} trailing bytes after the last field: 0x{}", trailing.len(), hex::encode(trailing));
+    }
+    Ok(())
+}
+
+fn encode(types: &[ArgType], values: &[String], base64: bool) -> Result<()> {
+    if types.len() != values.len() {
+        bail!("{} types but {} values", types.len(), values.len());
+    }
+    let values = types
+        .iter()
+        .zip(values)
+        .map(|(ty, text)| Value::parse(*ty, text))
+        .collect::<erc20_client::Result<Vec<_>>>()?;
+    let bytes = codec::encode(&values);
+    if base64 {
+        println!("{}", BASE64.encode(bytes));
+    } else {
+        println!("0x{}", hex::encode(bytes));
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Decode { types, data } => decode(&codec::parse_signature(&types)?, &parse_data(&data, cli.base64)?),
+        Command::Encode { types, values } => encode(&codec::parse_signature(&types)?, &values, cli.base64),
+    }
+}
@@ -0,0 +1,110 @@
+//! Deploy the MRC20 token the way the AssemblyScript deployer does.
+//!
+//! Uploads the compiled token with the AS deployer bytecode, calls
+//! `constructor(name, symbol, decimals, totalSupply)` during the deployment
+//! and prints the address of the new contract.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use erc20_client::config::Config;
+use erc20_client::rpc::RpcClient;
+use erc20_client::{deploy, units, Args, CallOptions, KeyPair};
+
+#[derive(Parser)]
+#[command(about = "Deploy the MRC20 token and call its constructor")]
+struct Cli {
+    /// Network profile: buildnet, mainnet, local or one from the configuration file
+    #[arg(long, env = "MRC20_NETWORK", default_value = "buildnet")]
+    network: String,
+    /// Configuration file with network profiles
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// JSON-RPC endpoint, overriding the profile's
+    #[arg(long)]
+    rpc: Option<String>,
+    /// Secret key (S...) of the deploying account, which becomes the owner
+    #[arg(long, env = "MASSA_SECRET_KEY", hide_env_values = true)]
+    secret_key: String,
+    /// Bytecode of the AS deployer from @massalabs/massa-sc-deployer
+    #[arg(long)]
+    deployer: PathBuf,
+    /// Compiled token
+    #[arg(long, default_value = "target/wasm32v1-none/release/erc20_token.wasm")]
+    wasm: PathBuf,
+    #[arg(long)]
+    name: String,
+    #[arg(long)]
+    symbol: String,
+    #[arg(long, default_value_t = 18)]
+    decimals: u8,
+    /// Initial supply in whole tokens, e.g. 1000000 or 0.5
+    #[arg(long)]
+    supply: String,
+    /// MAS sent to the constructor
+    #[arg(long, default_value = "0")]
+    coins: String,
+    /// Most MAS the deployment may spend, covering the coins and storage costs
+    #[arg(long, default_value = "1")]
+    max_coins: String,
+    /// Fee in MAS, overriding the profile's
+    #[arg(long)]
+    fee: Option<String>,
+    #[arg(long, default_value_t = 3_000_000_000)]
+    max_gas: u64,
+    /// Seconds to wait for the deployment to execute
+    #[arg(long, default_value_t = 120)]
+    timeout: u64,
+}
+
+fn mas(amount: &str, flag: &str) -> Result<u64> {
+    units::parse_mas(amount).with_context(|| format!("--{} is not a MAS amount: {}", flag, amount))
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let supply = units::parse_units(&cli.supply, cli.decimals)
+        .with_context(|| format!("--supply is not an amount with {} decimals: {}", cli.decimals, cli.supply))?;
+    let mut constructor_args = Args::new();
+    constructor_args
+        .add_string(&cli.name)
+        .add_string(&cli.symbol)
+        .add_u8(cli.decimals)
+        .add_u256(supply);
+
+    let deployer = std::fs::read(&cli.deployer).with_context(|| format!("reading {}", cli.deployer.display()))?;
+    let bytecode = std::fs::read(&cli.wasm).with_context(|| format!("reading {}", cli.wasm.display()))?;
+    let keypair = KeyPair::from_secret_key(&cli.secret_key)?;
+    let profile = Config::load(cli.config.as_deref())?.profile(&cli.network)?;
+    let rpc = match &cli.rpc {
+        Some(url) => RpcClient::new(url).with_chain_id(profile.chain_id),
+        None => profile.rpc(),
+    };
+    let options = CallOptions {
+        fee: cli.fee.as_deref().map_or(Ok(profile.fee), |fee| mas(fee, "fee"))?,
+        max_gas: cli.max_gas,
+        coins: mas(&cli.max_coins, "max-coins")?,
+        ..CallOptions::default()
+    };
+
+    eprintln!("Deploying {} ({}) on {} from {}", cli.name, cli.symbol, rpc.url(), keypair.address());
+    let deployment = deploy::deploy(
+        &rpc,
+        &keypair,
+        &deployer,
+        &bytecode,
+        &constructor_args.into_bytes(),
+        mas(&cli.coins, "coins")?,
+        &options,
+        Duration::from_secs(cli.timeout),
+    )?;
+    for event in &deployment.events {
+        eprintln!("  {}", event);
+    }
+    eprintln!("Operation {}", deployment.operation_id);
+    println!("{}", deployment.address);
+    Ok(())
+}
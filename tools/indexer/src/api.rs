@@ -0,0 +1,75 @@
+//! Read-only HTTP JSON API over the indexed data.
+//!
+//! - `GET /status` → token address and position of the last indexed event
+//! - `GET /balances/{address}` → current balance
+//! - `GET /balances/{address}/history` → balance after each change, oldest first
+//! - `GET /allowances/{owner}/{spender}` → current allowance
+//! - `GET /metrics` → Prometheus metrics, see [`crate::metrics`]
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::db::Database;
+use crate::metrics::Metrics;
+
+/// Version 0.0.4 of the Prometheus text format.
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+fn route(db: &Database, token: &str, path: &str) -> Result<Option<Value>> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let body = match segments.as_slice() {
+        ["status"] => json!({ "token": token, "cursor": db.cursor()? }),
+        ["balances", address] => json!({
+            "address": address,
+            "balance": db.balance(address)?.to_string(),
+        }),
+        ["balances", address, "history"] => json!({
+            "address": address,
+            "history": db.balance_history(address)?,
+        }),
+        ["allowances", owner, spender] => json!({
+            "owner": owner,
+            "spender": spender,
+            "amount": db.allowance(owner, spender)?.to_string(),
+        }),
+        _ => return Ok(None),
+    };
+    Ok(Some(body))
+}
+
+/// Serve the API on `listen` until the process exits.
+pub fn serve(db: Database, metrics: &Metrics, token: &str, listen: &str) -> Result<()> {
+    let server = Server::http(listen).map_err(|err| anyhow!("listening on {}: {}", listen, err))?;
+    let content_type = Header::from_bytes("Content-Type", "application/json").expect("Valid header");
+    let metrics_type = Header::from_bytes("Content-Type", METRICS_CONTENT_TYPE).expect("Valid header");
+    for request in server.incoming_requests() {
+        let path = request.url().split('?').next().unwrap_or_default().to_string();
+        if *request.method() == Method::Get && path == "/metrics" {
+            let response = match metrics.render(&db) {
+                Ok(text) => Response::from_string(text).with_header(metrics_type.clone()),
+                Err(err) => Response::from_string(err.to_string()).with_status_code(500),
+            };
+            if let Err(err) = request.respond(response) {
+                eprintln!("Failed to answer {}: {}", path, err);
+            }
+            continue;
+        }
+        let (status, body) = if *request.method() != Method::Get {
+            (405, json!({ "error": "only GET is supported" }))
+        } else {
+            match route(&db, token, &path) {
+                Ok(Some(body)) => (200, body),
+                Ok(None) => (404, json!({ "error": "not found" })),
+                Err(err) => (500, json!({ "error": err.to_string() })),
+            }
+        };
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        if let Err(err) = request.respond(response) {
+            eprintln!("Failed to answer {}: {}", path, err);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,295 @@
+//! SQLite storage of indexed events, balances and allowances.
+//!
+//! Amounts are stored as decimal text, since SQLite integers stop at 64 bits.
+
+use anyhow::{bail, Context, Result};
+use erc20_client::rpc::Slot;
+use erc20_client::{units, U256};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::events::TokenEvent;
+
+/// Threads per period, so the last thread of a period is `THREAD_COUNT - 1`.
+pub const THREAD_COUNT: u8 = 32;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    period INTEGER NOT NULL,
+    thread INTEGER NOT NULL,
+    index_in_slot INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS events (
+    period INTEGER NOT NULL,
+    thread INTEGER NOT NULL,
+    index_in_slot INTEGER NOT NULL,
+    operation_id TEXT,
+    kind TEXT NOT NULL,
+    from_address TEXT,
+    to_address TEXT,
+    owner TEXT,
+    spender TEXT,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (period, thread, index_in_slot)
+);
+CREATE TABLE IF NOT EXISTS balances (
+    address TEXT PRIMARY KEY,
+    balance TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS balance_history (
+    address TEXT NOT NULL,
+    period INTEGER NOT NULL,
+    thread INTEGER NOT NULL,
+    index_in_slot INTEGER NOT NULL,
+    balance TEXT NOT NULL,
+    PRIMARY KEY (address, period, thread, index_in_slot)
+);
+CREATE TABLE IF NOT EXISTS allowances (
+    owner TEXT NOT NULL,
+    spender TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (owner, spender)
+);
+";
+
+/// Where an event was emitted; events are ordered by slot, then index.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Position {
+    pub slot: Slot,
+    pub index_in_slot: u64,
+}
+
+/// An event ready to be applied.
+pub struct IndexedEvent {
+    pub position: Position,
+    pub operation_id: Option<String>,
+    pub event: TokenEvent,
+}
+
+/// A balance after one of the events that changed it.
+#[derive(Serialize)]
+pub struct BalanceChange {
+    #[serde(flatten)]
+    pub position: Position,
+    pub balance: String,
+}
+
+/// A transfer, mint or burn; mints have no sender and burns no recipient.
+#[derive(Serialize)]
+pub struct LedgerEntry {
+    #[serde(flatten)]
+    pub position: Position,
+    pub kind: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub amount: String,
+    pub operation_id: Option<String>,
+}
+
+pub struct Database {
+    connection: Connection,
+}
+
+fn parse_amount(text: &str) -> Result<U256> {
+    units::parse_units(text, 0).with_context(|| format!("invalid stored amount {}", text))
+}
+
+impl Database {
+    pub fn open(path: &str) -> Result<Self> {
+        let connection = Connection::open(path).with_context(|| format!("opening {}", path))?;
+        // Lets the API read while the poller writes
+        connection.pragma_update(None, "journal_mode", "WAL")?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Database { connection })
+    }
+
+    /// Position of the last applied event, `None` before the first one.
+    pub fn cursor(&self) -> Result<Option<Position>> {
+        let position = self
+            .connection
+            .query_row("SELECT period, thread, index_in_slot FROM cursor WHERE id = 0", [], |row| {
+                Ok(Position {
+                    slot: Slot {
+                        period: row.get(0)?,
+                        thread: row.get(1)?,
+                    },
+                    index_in_slot: row.get(2)?,
+                })
+            })
+            .optional()?;
+        Ok(position)
+    }
+
+    pub fn balance(&self, address: &str) -> Result<U256> {
+        stored_balance(&self.connection, address)
+    }
+
+    /// Sum of all balances, which matches the token's total supply once fully indexed.
+    pub fn total_supply(&self) -> Result<U256> {
+        let mut statement = self.connection.prepare("SELECT balance FROM balances")?;
+        let balances = statement.query_map([], |row| row.get::<_, String>(0))?;
+        let mut total = U256::ZERO;
+        for balance in balances {
+            total = total.checked_add(parse_amount(&balance?)?).context("total supply above U256::MAX")?;
+        }
+        Ok(total)
+    }
+
+    /// Number of addresses with a non-zero balance.
+    pub fn holder_count(&self) -> Result<u64> {
+        let count = self
+            .connection
+            .query_row("SELECT COUNT(*) FROM balances WHERE balance != '0'", [], |row| row.get(0))?;
+        Ok(count)
+    }
+
+    pub fn allowance(&self, owner: &str, spender: &str) -> Result<U256> {
+        let amount: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT amount FROM allowances WHERE owner = ?1 AND spender = ?2",
+                [owner, spender],
+                |row| row.get(0),
+            )
+            .optional()?;
+        amount.map_or(Ok(U256::ZERO), |amount| parse_amount(&amount))
+    }
+
+    /// Every balance `address` had, oldest first.
+    pub fn balance_history(&self, address: &str) -> Result<Vec<BalanceChange>> {
+        let mut statement = self.connection.prepare(
+            "SELECT period, thread, index_in_slot, balance FROM balance_history
+             WHERE address = ?1 ORDER BY period, thread, index_in_slot",
+        )?;
+        let rows = statement.query_map([address], |row| {
+            Ok(BalanceChange {
+                position: Position {
+                    slot: Slot {
+                        period: row.get(0)?,
+                        thread: row.get(1)?,
+                    },
+                    index_in_slot: row.get(2)?,
+                },
+                balance: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Every transfer, mint and burn from slot `start` to slot `end` included, in execution order.
+    pub fn ledger(&self, start: Slot, end: Slot) -> Result<Vec<LedgerEntry>> {
+        let mut statement = self.connection.prepare(
+            "SELECT period, thread, index_in_slot, kind, from_address, to_address, amount, operation_id FROM events
+             WHERE kind != 'approval' AND (period, thread) >= (?1, ?2) AND (period, thread) <= (?3, ?4)
+             ORDER BY period, thread, index_in_slot",
+        )?;
+        let rows = statement.query_map(params![start.period, start.thread, end.period, end.thread], |row| {
+            Ok(LedgerEntry {
+                position: Position {
+                    slot: Slot {
+                        period: row.get(0)?,
+                        thread: row.get(1)?,
+                    },
+                    index_in_slot: row.get(2)?,
+                },
+                kind: row.get(3)?,
+                from: row.get(4)?,
+                to: row.get(5)?,
+                amount: row.get(6)?,
+                operation_id: row.get(7)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Apply `events` and move the cursor to `cursor` in one transaction.
+    pub fn apply(&mut self, events: &[IndexedEvent], cursor: Position) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        for indexed in events {
+            apply_event(&transaction, indexed)?;
+        }
+        transaction.execute(
+            "INSERT INTO cursor (id, period, thread, index_in_slot) VALUES (0, ?1, ?2, ?3)
+             ON CONFLICT (id) DO UPDATE SET period = ?1, thread = ?2, index_in_slot = ?3",
+            params![cursor.slot.period, cursor.slot.thread, cursor.index_in_slot],
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+fn apply_event(connection: &Connection, indexed: &IndexedEvent) -> Result<()> {
+    let (from, to, owner, spender, amount) = match &indexed.event {
+        TokenEvent::Transfer { from, to, amount } => (Some(from), Some(to), None, None, *amount),
+        TokenEvent::Approval { owner, spender, amount } => (None, None, Some(owner), Some(spender), *amount),
+        TokenEvent::Mint { to, amount } => (None, Some(to), None, None, *amount),
+        TokenEvent::Burn { from, amount } => (Some(from), None, None, None, *amount),
+    };
+    let position = indexed.position;
+    connection.execute(
+        "INSERT INTO events (period, thread, index_in_slot, operation_id, kind,
+                             from_address, to_address, owner, spender, amount)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            position.slot.period,
+            position.slot.thread,
+            position.index_in_slot,
+            indexed.operation_id,
+            indexed.event.kind(),
+            from,
+            to,
+            owner,
+            spender,
+            amount.to_string(),
+        ],
+    )?;
+
+    if let (Some(owner), Some(spender)) = (owner, spender) {
+        connection.execute(
+            "INSERT INTO allowances (owner, spender, amount) VALUES (?1, ?2, ?3)
+             ON CONFLICT (owner, spender) DO UPDATE SET amount = ?3",
+            params![owner, spender, amount.to_string()],
+        )?;
+    }
+    if let Some(from) = from {
+        let balance = stored_balance(connection, from)?;
+        let Some(balance) = balance.checked_sub(amount) else {
+            bail!("{} spends {} but only has {}; events before the start slot are missing", from, amount, balance);
+        };
+        set_balance(connection, from, balance, position)?;
+    }
+    if let Some(to) = to {
+        let balance = stored_balance(connection, to)?;
+        let balance = balance.checked_add(amount).context("balance above U256::MAX")?;
+        set_balance(connection, to, balance, position)?;
+    }
+    Ok(())
+}
+
+fn stored_balance(connection: &Connection, address: &str) -> Result<U256> {
+    let balance: Option<String> = connection
+        .query_row("SELECT balance FROM balances WHERE address = ?1", [address], |row| row.get(0))
+        .optional()?;
+    balance.map_or(Ok(U256::ZERO), |balance| parse_amount(&balance))
+}
+
+fn set_balance(connection: &Connection, address: &str, balance: U256, position: Position) -> Result<()> {
+    connection.execute(
+        "INSERT INTO balances (address, balance) VALUES (?1, ?2)
+         ON CONFLICT (address) DO UPDATE SET balance = ?2",
+        params![address, balance.to_string()],
+    )?;
+    connection.execute(
+        "INSERT OR REPLACE INTO balance_history (address, period, thread, index_in_slot, balance)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            address,
+            position.slot.period,
+            position.slot.thread,
+            position.index_in_slot,
+            balance.to_string()
+        ],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! Parsing of the token's structured event payloads.
+//!
+//! Transfer, approval, mint and burn events are `NAME:key=value,...` with
+//! decimal amounts, as listed in the README's Events section.
+//!
+//! Only final events are indexed, so an applied event is never reverted. The
+//! node is asked for events from the cursor's slot on, which it includes, so
+//! `new_events` drops the ones already applied.
+
+use erc20_client::rpc::{EventFilter, OutputEvent, Slot};
+use erc20_client::{units, U256};
+
+use crate::db::{IndexedEvent, Position};
+
+const TRANSFER_EVENT: &str = "TRANSFER SUCCESS";
+const APPROVAL_EVENT: &str = "APPROVAL SUCCESS";
+const MINT_EVENT: &str = "MINT SUCCESS";
+const BURN_EVENT: &str = "BURN_SUCCESS";
+
+/// A balance or allowance change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TokenEvent {
+    Transfer { from: String, to: String, amount: U256 },
+    Approval { owner: String, spender: String, amount: U256 },
+    Mint { to: String, amount: U256 },
+    Burn { from: String, amount: U256 },
+}
+
+impl TokenEvent {
+    /// Parse an event payload, `None` for other events or malformed payloads.
+    pub fn parse(data: &str) -> Option<TokenEvent> {
+        let (name, payload) = data.split_once(':')?;
+        let field = |key: &str| {
+            payload
+                .split(',')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value)
+        };
+        let address = |key: &str| field(key).map(String::from);
+        let amount = || field("amount").and_then(|amount| units::parse_units(amount, 0));
+
+        match name {
+            TRANSFER_EVENT => Some(TokenEvent::Transfer {
+                from: address("from")?,
+                to: address("to")?,
+                amount: amount()?,
+            }),
+            APPROVAL_EVENT => Some(TokenEvent::Approval {
+                owner: address("owner")?,
+                spender: address("spender")?,
+                amount: amount()?,
+            }),
+            MINT_EVENT => Some(TokenEvent::Mint {
+                to: address("to")?,
+                amount: amount()?,
+            }),
+            BURN_EVENT => Some(TokenEvent::Burn {
+                from: address("from")?,
+                amount: amount()?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The event name as stored and served, e.g. `transfer`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TokenEvent::Transfer { .. } => "transfer",
+            TokenEvent::Approval { .. } => "approval",
+            TokenEvent::Mint { .. } => "mint",
+            TokenEvent::Burn { .. } => "burn",
+        }
+    }
+}
+
+/// Filter for the final events of `token` from the cursor's slot, or from `start` without a cursor.
+pub fn event_filter(token: &str, start: Slot, cursor: Option<Position>) -> EventFilter {
+    EventFilter {
+        start: Some(cursor.map_or(start, |cursor| cursor.slot)),
+        emitter_address: Some(String::from(token)),
+        is_final: Some(true),
+        ..EventFilter::default()
+    }
+}
+
+/// The token events after `cursor`, in execution order, and the position of
+/// the last event after it, token event or not.
+pub fn new_events(events: Vec<OutputEvent>, cursor: Option<Position>) -> (Option<Position>, Vec<IndexedEvent>) {
+    let mut latest = cursor;
+    let mut indexed = Vec::new();
+    for event in events {
+        let position = Position {
+            slot: event.context.slot,
+            index_in_slot: event.context.index_in_slot,
+        };
+        // The start slot is inclusive, so skip what was already applied
+        if cursor.is_some_and(|cursor| position <= cursor) {
+            continue;
+        }
+        latest = latest.max(Some(position));
+        if let Some(token_event) = TokenEvent::parse(&event.data) {
+            indexed.push(IndexedEvent {
+                position,
+                operation_id: event.context.origin_operation_id,
+                event: token_event,
+            });
+        }
+    }
+    indexed.sort_by_key(|event| event.position);
+    (latest, indexed)
+}
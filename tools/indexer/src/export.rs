@@ -0,0 +1,116 @@
+//! Transfer ledger export for accounting.
+//!
+//! One row per transfer, mint or burn, in execution order. Mints have an
+//! empty sender and burns an empty recipient; approvals move no tokens and are
+//! left out. Amounts are decimal strings in base units.
+
+use std::io::Write;
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use erc20_client::rpc::Slot;
+
+use crate::db::{Database, LedgerEntry, THREAD_COUNT};
+
+/// Largest period SQLite can compare, its integers being signed.
+const LAST_PERIOD: u64 = i64::MAX as u64;
+const CSV_HEADER: &str = "period,thread,index_in_slot,kind,from,to,amount,operation_id";
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// Comma-separated values with a header row
+    Csv,
+    /// A JSON array of entries, as served by the HTTP API
+    Json,
+}
+
+/// A `PERIOD` or `PERIOD:THREAD` slot bound; a bare period covers all its threads.
+#[derive(Clone, Copy)]
+pub struct SlotBound {
+    period: u64,
+    thread: Option<u8>,
+}
+
+impl SlotBound {
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        let invalid = || format!("{} is not PERIOD or PERIOD:THREAD", text);
+        let (period, thread) = match text.split_once(':') {
+            Some((period, thread)) => (period, Some(thread.parse::<u8>().map_err(|_| invalid())?)),
+            None => (text, None),
+        };
+        if thread.is_some_and(|thread| thread >= THREAD_COUNT) {
+            return Err(format!("{}: threads go from 0 to {}", text, THREAD_COUNT - 1));
+        }
+        Ok(SlotBound {
+            period: period.parse().map_err(|_| invalid())?,
+            thread,
+        })
+    }
+
+    fn first(self) -> Slot {
+        Slot {
+            period: self.period,
+            thread: self.thread.unwrap_or(0),
+        }
+    }
+
+    fn last(self) -> Slot {
+        Slot {
+            period: self.period,
+            thread: self.thread.unwrap_or(THREAD_COUNT - 1),
+        }
+    }
+}
+
+/// Write the ledger from `from` to `to`, both included, to `out`.
+pub fn export(
+    db: &Database,
+    format: Format,
+    from: Option<SlotBound>,
+    to: Option<SlotBound>,
+    out: impl Write,
+) -> Result<()> {
+    let start = from.map_or(Slot::default(), SlotBound::first);
+    let last = SlotBound {
+        period: LAST_PERIOD,
+        thread: None,
+    };
+    let end = to.unwrap_or(last).last();
+    if start > end {
+        bail!("--from-slot is after --to-slot");
+    }
+    let entries = db.ledger(start, end)?;
+    match format {
+        Format::Csv => write_csv(&entries, out),
+        Format::Json => write_json(&entries, out),
+    }
+    .context("writing the export")
+}
+
+fn write_csv(entries: &[LedgerEntry], mut out: impl Write) -> Result<()> {
+    writeln!(out, "{}", CSV_HEADER)?;
+    // Addresses, operation ids and amounts never contain commas or quotes
+    for entry in entries {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{}",
+            entry.position.slot.period,
+            entry.position.slot.thread,
+            entry.position.index_in_slot,
+            entry.kind,
+            entry.from.as_deref().unwrap_or_default(),
+            entry.to.as_deref().unwrap_or_default(),
+            entry.amount,
+            entry.operation_id.as_deref().unwrap_or_default(),
+        )?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn write_json(entries: &[LedgerEntry], mut out: impl Write) -> Result<()> {
+    serde_json::to_writer_pretty(&mut out, entries)?;
+    writeln!(out)?;
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,10 @@
+//! Storage, parsing, export and serving of the indexer's data.
+//!
+//! The `indexer` binary polls the node and wires these together; they are a
+//! library so `tests/indexer-tests` can check them without a node.
+
+pub mod api;
+pub mod db;
+pub mod events;
+pub mod export;
+pub mod metrics;
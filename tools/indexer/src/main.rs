@@ -0,0 +1,160 @@
+//! Indexer for the token's transfer, approval, mint and burn events.
+//!
+//! Polls the node for the token's final events, keeps balances, allowances
+//! and per-address balance history in SQLite, and serves them over HTTP.
+//! `export` writes the transfer ledger as CSV or JSON for accounting.
+
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use erc20_client::config::Config;
+use erc20_client::rpc::{RpcClient, Slot};
+
+use indexer::db::{Database, Position};
+use indexer::export::{self, Format, SlotBound};
+use indexer::metrics::Metrics;
+use indexer::{api, events};
+
+#[derive(Parser)]
+#[command(about = "Index MRC20 token events into SQLite")]
+struct Cli {
+    /// SQLite database file
+    #[arg(long, global = true, default_value = "indexer.db")]
+    database: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Poll the node for new events and serve the HTTP API
+    Run {
+        /// Address (AS...) of the token, or its name in the network profile
+        #[arg(long, env = "MRC20_TOKEN")]
+        token: String,
+        /// Network profile: buildnet, mainnet, local or one from the configuration file
+        #[arg(long, env = "MRC20_NETWORK", default_value = "buildnet")]
+        network: String,
+        /// Configuration file with network profiles
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// JSON-RPC endpoint, overriding the profile's
+        #[arg(long)]
+        rpc: Option<String>,
+        /// Address the HTTP API listens on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+        /// Seconds between polls
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+        /// Period to start from on an empty database, e.g. the deployment period
+        #[arg(long, default_value_t = 0)]
+        start_period: u64,
+    },
+    /// Write the transfer ledger (transfers, mints and burns) to stdout
+    Export {
+        #[arg(long, value_enum, default_value = "csv")]
+        format: Format,
+        /// First slot included, as PERIOD or PERIOD:THREAD
+        #[arg(long, value_parser = SlotBound::parse)]
+        from_slot: Option<SlotBound>,
+        /// Last slot included, as PERIOD (all its threads) or PERIOD:THREAD
+        #[arg(long, value_parser = SlotBound::parse)]
+        to_slot: Option<SlotBound>,
+    },
+}
+
+/// Fetch the final events after `cursor`, apply them, and return the new cursor
+/// and the number of token events applied.
+///
+/// Without a cursor, indexing starts at `start`.
+fn poll(
+    rpc: &RpcClient,
+    db: &mut Database,
+    token: &str,
+    start: Slot,
+    cursor: Option<Position>,
+) -> Result<(Option<Position>, usize)> {
+    let events = rpc.get_events(&events::event_filter(token, start, cursor))?;
+    let (latest, indexed) = events::new_events(events, cursor);
+    if let Some(latest) = latest.filter(|latest| Some(*latest) != cursor) {
+        db.apply(&indexed, latest)?;
+    }
+    Ok((latest, indexed.len()))
+}
+
+fn run(database: &str, token: String, rpc: RpcClient, listen: String, interval: u64, start_period: u64) -> Result<()> {
+    // Fail now rather than index another chain's events
+    rpc.get_status()?;
+    let mut db = Database::open(database)?;
+    let api_db = Database::open(database)?;
+    let api_token = token.clone();
+    let metrics = Arc::new(Metrics::default());
+    let api_metrics = Arc::clone(&metrics);
+    std::thread::spawn(move || {
+        if let Err(err) = api::serve(api_db, &api_metrics, &api_token, &listen) {
+            eprintln!("HTTP API stopped: {:#}", err);
+            std::process::exit(1);
+        }
+    });
+
+    let start = Slot {
+        period: start_period,
+        thread: 0,
+    };
+    let mut cursor = db.cursor()?;
+    loop {
+        let polled = rpc.get_status().map_err(anyhow::Error::from).and_then(|status| {
+            metrics.observe_head(status.next_slot);
+            let (next, applied) = poll(&rpc, &mut db, &token, start, cursor)?;
+            metrics.poll_succeeded(status.next_slot, applied);
+            Ok(next)
+        });
+        match polled {
+            Ok(next) => cursor = next,
+            Err(err) => {
+                metrics.poll_failed();
+                eprintln!("Poll failed: {:#}", err);
+            }
+        }
+        std::thread::sleep(Duration::from_secs(interval));
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Run {
+            token,
+            network,
+            config,
+            rpc,
+            listen,
+            interval,
+            start_period,
+        } => {
+            let profile = Config::load(config.as_deref())?.profile(&network)?;
+            let rpc = match rpc {
+                Some(url) => RpcClient::new(&url).with_chain_id(profile.chain_id),
+                None => profile.rpc(),
+            };
+            let token = String::from(profile.token_address(&token));
+            run(&cli.database, token, rpc, listen, interval, start_period)
+        }
+        Command::Export {
+            format,
+            from_slot,
+            to_slot,
+        } => {
+            if !Path::new(&cli.database).exists() {
+                bail!("{} does not exist", cli.database);
+            }
+            let db = Database::open(&cli.database)?;
+            export::export(&db, format, from_slot, to_slot, BufWriter::new(std::io::stdout().lock()))
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Prometheus metrics of the indexer, served on `GET /metrics`.
+//!
+//! The poller updates the counters; the supply and holder gauges are read
+//! from the database on each scrape. The node only reports its head, not how
+//! far the indexer has caught up, so the lag is the number of slots the head
+//! has advanced since the last successful poll. It stays near zero while the
+//! indexer keeps up and grows while polls fail.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use erc20_client::rpc::Slot;
+
+use crate::db::{Database, THREAD_COUNT};
+
+/// Counters and slots shared by the poller and the HTTP API.
+#[derive(Default)]
+pub struct Metrics {
+    events_processed: AtomicU64,
+    poll_errors: AtomicU64,
+    /// Head slot at the last poll attempt, as a slot number.
+    head_slot: AtomicU64,
+    /// Head slot at the last successful poll.
+    synced_slot: AtomicU64,
+    last_success: AtomicU64,
+}
+
+fn slot_number(slot: Slot) -> u64 {
+    slot.period * u64::from(THREAD_COUNT) + u64::from(slot.thread)
+}
+
+impl Metrics {
+    pub fn observe_head(&self, head: Slot) {
+        self.head_slot.store(slot_number(head), Ordering::Relaxed);
+    }
+
+    /// Record a successful poll that applied `events` events, started when the head was at `head`.
+    pub fn poll_succeeded(&self, head: Slot, events: usize) {
+        self.events_processed.fetch_add(events as u64, Ordering::Relaxed);
+        self.synced_slot.store(slot_number(head), Ordering::Relaxed);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        self.last_success.store(now.as_secs(), Ordering::Relaxed);
+    }
+
+    pub fn poll_failed(&self) {
+        self.poll_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The metrics in the Prometheus text exposition format.
+    pub fn render(&self, db: &Database) -> Result<String> {
+        let head = self.head_slot.load(Ordering::Relaxed);
+        let synced = self.synced_slot.load(Ordering::Relaxed);
+        // Prometheus values are floats, so a large supply loses its lowest digits
+        let total_supply: f64 = db.total_supply()?.to_string().parse()?;
+        let metrics = [
+            (
+                "mrc20_indexer_events_processed_total",
+                "counter",
+                "Token events applied since the indexer started.",
+                self.events_processed.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "mrc20_indexer_poll_errors_total",
+                "counter",
+                "Polls that failed since the indexer started.",
+                self.poll_errors.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "mrc20_indexer_total_supply",
+                "gauge",
+                "Sum of the indexed balances, in base units.",
+                total_supply,
+            ),
+            (
+                "mrc20_indexer_holders",
+                "gauge",
+                "Addresses with a non-zero indexed balance.",
+                db.holder_count()? as f64,
+            ),
+            (
+                "mrc20_indexer_head_slot",
+                "gauge",
+                "Slot number (period * 32 + thread) of the node's head at the last poll.",
+                head as f64,
+            ),
+            (
+                "mrc20_indexer_lag_slots",
+                "gauge",
+                "Slots the node's head has advanced since the last successful poll.",
+                head.saturating_sub(synced) as f64,
+            ),
+            (
+                "mrc20_indexer_last_success_timestamp_seconds",
+                "gauge",
+                "Unix time of the last successful poll, 0 before the first.",
+                self.last_success.load(Ordering::Relaxed) as f64,
+            ),
+        ];
+        let mut text = String::new();
+        for (name, kind, help, value) in metrics {
+            writeln!(text, "# HELP {} {}", name, help)?;
+            writeln!(text, "# TYPE {} {}", name, kind)?;
+            writeln!(text, "{} {}", name, value)?;
+        }
+        Ok(text)
+    }
+}
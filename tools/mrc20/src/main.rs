@@ -0,0 +1,127 @@
+//! Command-line access to a deployed MRC20 token.
+//!
+//! Amounts are read and printed in whole tokens and scaled with the token's
+//! `decimals()`, so `transfer AU12... 1.5` sends 1.5 tokens whatever the
+//! token's precision.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use erc20_client::config::Config;
+use erc20_client::holders::MAX_PAGE_SIZE;
+use erc20_client::rpc::RpcClient;
+use erc20_client::{units, CallOptions, CallReceipt, Erc20Client, KeyPair, U256};
+
+#[derive(Parser)]
+#[command(about = "Read and operate an MRC20 token")]
+struct Cli {
+    /// Network profile: buildnet, mainnet, local or one from the configuration file
+    #[arg(long, global = true, env = "MRC20_NETWORK", default_value = "buildnet")]
+    network: String,
+    /// Configuration file with network profiles
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// JSON-RPC endpoint, overriding the profile's
+    #[arg(long, global = true)]
+    rpc: Option<String>,
+    /// Address (AS...) of the token, or its name in the network profile
+    #[arg(long, global = true, env = "MRC20_TOKEN")]
+    token: String,
+    /// Secret key (S...) signing state-changing commands
+    #[arg(long, global = true, env = "MASSA_SECRET_KEY", hide_env_values = true)]
+    secret_key: Option<String>,
+    /// Fee in MAS, overriding the profile's
+    #[arg(long, global = true)]
+    fee: Option<String>,
+    /// Gas limit; when omitted, each call is simulated first and sent with its gas cost plus a margin
+    #[arg(long, global = true)]
+    max_gas: Option<u64>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Name, symbol, decimals and total supply
+    Info,
+    /// Balance of an address
+    Balance { address: String },
+    /// Every holder and their final balance, read from the token's datastore
+    Holders,
+    /// Send tokens from the signing account
+    Transfer { to: String, amount: String },
+    /// Set the allowance of a spender
+    Approve { spender: String, amount: String },
+    /// Create tokens; the signing account needs the MINTER role
+    Mint { to: String, amount: String },
+    /// Destroy tokens of the signing account
+    Burn { amount: String },
+}
+
+fn amount(token: &Erc20Client, amount: &str) -> Result<U256> {
+    let decimals = token.decimals()?;
+    units::parse_units(amount, decimals)
+        .with_context(|| format!("{} is not an amount with at most {} decimals", amount, decimals))
+}
+
+fn print_receipt(receipt: &CallReceipt) {
+    println!("Operation {}", receipt.operation_id);
+    for event in &receipt.events {
+        println!("  {}", event);
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let profile = Config::load(cli.config.as_deref())?.profile(&cli.network)?;
+    let rpc = match &cli.rpc {
+        Some(url) => RpcClient::new(url).with_chain_id(profile.chain_id),
+        None => profile.rpc(),
+    };
+    let fee = match &cli.fee {
+        Some(fee) => units::parse_mas(fee).with_context(|| format!("--fee is not a MAS amount: {}", fee))?,
+        None => profile.fee,
+    };
+    let mut token = Erc20Client::with_rpc(rpc, profile.token_address(&cli.token)).with_options(CallOptions {
+        fee,
+        max_gas: cli.max_gas.unwrap_or(CallOptions::default().max_gas),
+        estimate_gas: cli.max_gas.is_none(),
+        ..CallOptions::default()
+    });
+    if let Some(secret_key) = &cli.secret_key {
+        token = token.with_keypair(KeyPair::from_secret_key(secret_key)?);
+    }
+
+    let receipt = match &cli.command {
+        Command::Info => {
+            let decimals = token.decimals()?;
+            println!("Name:         {}", token.name()?);
+            println!("Symbol:       {}", token.symbol()?);
+            println!("Decimals:     {}", decimals);
+            println!("Total supply: {}", units::format_units(token.total_supply()?, decimals));
+            return Ok(());
+        }
+        Command::Balance { address } => {
+            let balance = token.balance_of(address)?;
+            println!("{} {}", units::format_units(balance, token.decimals()?), token.symbol()?);
+            return Ok(());
+        }
+        Command::Holders => {
+            let decimals = token.decimals()?;
+            for page in token.holder_pages(MAX_PAGE_SIZE)? {
+                for holder in page? {
+                    println!("{} {}", holder.address, units::format_units(holder.balance, decimals));
+                }
+            }
+            return Ok(());
+        }
+        Command::Transfer { to, amount: value } => token.transfer(to, amount(&token, value)?)?,
+        Command::Approve { spender, amount: value } => token.approve(spender, amount(&token, value)?)?,
+        Command::Mint { to, amount: value } => token.mint(to, amount(&token, value)?)?,
+        Command::Burn { amount: value } => token.burn(amount(&token, value)?)?,
+    };
+    print_receipt(&receipt);
+    Ok(())
+}